@@ -0,0 +1,3 @@
+drop index idx_tweet_user_id_created_at_id;
+drop index idx_tweet_created_at_id;
+drop table subscriptions;
@@ -0,0 +1,17 @@
+create table subscriptions
+(
+    subscriber_id uuid not null,
+    target_id     uuid not null,
+    created_at    timestamptz default now(),
+    primary key (subscriber_id, target_id),
+    foreign key (subscriber_id) references "users" (id) on delete cascade,
+    foreign key (target_id) references "users" (id) on delete cascade,
+    check (subscriber_id <> target_id)
+);
+
+-- Add index for looking up the subscribers of a user
+create index idx_subscriptions_target_id on subscriptions(target_id);
+
+-- Add indexes backing the (created_at, id) keyset cursor of the home timeline
+create index idx_tweet_created_at_id on tweet(created_at desc, id desc);
+create index idx_tweet_user_id_created_at_id on tweet(user_id, created_at desc, id desc);
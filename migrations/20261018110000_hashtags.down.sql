@@ -0,0 +1 @@
+drop table tweet_hashtags, hashtags;
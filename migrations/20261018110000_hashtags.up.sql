@@ -0,0 +1,19 @@
+create table hashtags
+(
+    id         uuid primary key default gen_random_uuid(),
+    tag        varchar(255) not null unique,
+    created_at timestamptz default now()
+);
+
+create table tweet_hashtags
+(
+    tweet_id   uuid not null,
+    hashtag_id uuid not null,
+    created_at timestamptz default now(),
+    primary key (tweet_id, hashtag_id),
+    foreign key (tweet_id) references tweet (id) on delete cascade,
+    foreign key (hashtag_id) references hashtags (id) on delete cascade
+);
+
+-- Add index for listing the tweets of a hashtag
+create index idx_tweet_hashtags_hashtag_id on tweet_hashtags(hashtag_id);
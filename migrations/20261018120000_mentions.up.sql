@@ -0,0 +1,14 @@
+create table tweet_mentions
+(
+    tweet_id    uuid not null,
+    user_id     uuid not null,
+    start_index int  not null,
+    end_index   int  not null,
+    created_at  timestamptz default now(),
+    primary key (tweet_id, start_index),
+    foreign key (tweet_id) references tweet (id) on delete cascade,
+    foreign key (user_id) references "users" (id) on delete cascade
+);
+
+-- Add index for listing the mentions of a user
+create index idx_tweet_mentions_user_id on tweet_mentions(user_id);
@@ -0,0 +1,3 @@
+drop index idx_tweet_search_vector;
+
+alter table tweet drop column search_vector;
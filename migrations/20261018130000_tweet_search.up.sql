@@ -0,0 +1,5 @@
+alter table tweet
+    add column search_vector tsvector generated always as (to_tsvector('english', content)) stored;
+
+-- Add index for full-text search over tweet content
+create index idx_tweet_search_vector on tweet using gin (search_vector);
@@ -0,0 +1,4 @@
+create extension if not exists pg_trgm;
+
+-- Add index for fuzzy username search
+create index idx_users_username_trgm on users using gin (username gin_trgm_ops);
@@ -0,0 +1,2 @@
+drop table tweet_media;
+drop table media;
@@ -0,0 +1,28 @@
+create table media
+(
+    id          uuid primary key default gen_random_uuid(),
+    user_id     uuid         not null,
+    sha256      char(64)     not null,
+    mime_type   varchar(100) not null,
+    size_bytes  bigint       not null,
+    storage_key varchar(255) not null,
+    created_at  timestamptz default now(),
+    unique (user_id, sha256),
+    foreign key (user_id) references "users" (id) on delete cascade
+);
+
+create table tweet_media
+(
+    tweet_id   uuid not null,
+    media_id   uuid not null,
+    position   int  not null,
+    created_at timestamptz default now(),
+    primary key (tweet_id, media_id),
+    foreign key (tweet_id) references tweet (id) on delete cascade,
+    foreign key (media_id) references media (id) on delete cascade
+);
+
+-- Add index for finding already stored content by hash
+create index idx_media_sha256 on media(sha256);
+-- Add index for listing the tweets a media item is attached to
+create index idx_tweet_media_media_id on tweet_media(media_id);
@@ -0,0 +1,13 @@
+create table media_variants
+(
+    media_id    uuid         not null,
+    name        varchar(50)  not null,
+    storage_key varchar(255) not null,
+    mime_type   varchar(100) not null,
+    width       int          not null,
+    height      int          not null,
+    size_bytes  bigint       not null,
+    created_at  timestamptz default now(),
+    primary key (media_id, name),
+    foreign key (media_id) references media (id) on delete cascade
+);
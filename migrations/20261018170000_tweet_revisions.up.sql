@@ -0,0 +1,11 @@
+create table tweet_revisions
+(
+    id          uuid primary key default gen_random_uuid(),
+    tweet_id    uuid not null,
+    revision    int  not null,
+    content     text not null,
+    created_at  timestamptz,
+    replaced_at timestamptz default now(),
+    unique (tweet_id, revision),
+    foreign key (tweet_id) references tweet (id) on delete cascade
+);
@@ -0,0 +1,5 @@
+drop index idx_tweet_deleted_at;
+drop index idx_users_deleted_at;
+
+alter table tweet drop column deleted_at;
+alter table users drop column deleted_at;
@@ -0,0 +1,6 @@
+alter table users add column deleted_at timestamptz;
+alter table tweet add column deleted_at timestamptz;
+
+-- Add indexes for the retention job, which scans soft-deleted rows only
+create index idx_users_deleted_at on users(deleted_at) where deleted_at is not null;
+create index idx_tweet_deleted_at on tweet(deleted_at) where deleted_at is not null;
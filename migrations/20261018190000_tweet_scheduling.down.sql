@@ -0,0 +1,4 @@
+drop index idx_tweet_publish_at;
+
+alter table tweet drop column publish_at;
+alter table tweet drop column status;
@@ -0,0 +1,6 @@
+alter table tweet add column status varchar(16) not null default 'published'
+    check (status in ('draft', 'scheduled', 'published'));
+alter table tweet add column publish_at timestamptz;
+
+-- Add index for the scheduler, which only scans tweets waiting to be published
+create index idx_tweet_publish_at on tweet(publish_at) where status = 'scheduled';
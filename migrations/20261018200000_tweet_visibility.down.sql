@@ -0,0 +1,3 @@
+drop function tweet_visible_to(uuid, varchar, uuid);
+
+alter table tweet drop column visibility;
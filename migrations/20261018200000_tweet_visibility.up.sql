@@ -0,0 +1,16 @@
+alter table tweet add column visibility varchar(16) not null default 'public'
+    check (visibility in ('public', 'group', 'private'));
+
+-- Whether a tweet by author_id with the given visibility may be read by
+-- viewer_id. A null viewer is an anonymous visitor and only sees public tweets.
+create function tweet_visible_to(author_id uuid, visibility varchar, viewer_id uuid)
+returns boolean as $$
+    select visibility = 'public'
+        or author_id = viewer_id
+        or (visibility = 'group' and exists(
+            select 1
+            from users author
+            join users viewer on viewer.user_group_id = author.user_group_id
+            where author.id = author_id and viewer.id = viewer_id
+        ));
+$$ language sql stable;
@@ -0,0 +1,3 @@
+drop table if exists user_list_members;
+drop table if exists user_lists;
+drop table if exists bookmarks;
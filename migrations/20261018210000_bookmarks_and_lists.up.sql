@@ -0,0 +1,38 @@
+create table bookmarks
+(
+    user_id    uuid not null,
+    tweet_id   uuid not null,
+    created_at timestamptz default now(),
+    primary key (user_id, tweet_id),
+    foreign key (user_id) references "users" (id) on delete cascade,
+    foreign key (tweet_id) references tweet (id) on delete cascade
+);
+
+-- Add index backing the most-recent-first bookmark listing
+create index idx_bookmarks_user_id_created_at on bookmarks(user_id, created_at desc);
+
+create table user_lists
+(
+    id          uuid primary key default gen_random_uuid(),
+    owner_id    uuid        not null,
+    name        varchar(64) not null,
+    description text,
+    created_at  timestamptz default now(),
+    updated_at  timestamptz default now(),
+    foreign key (owner_id) references "users" (id) on delete cascade
+);
+
+create index idx_user_lists_owner_id on user_lists(owner_id);
+
+create table user_list_members
+(
+    list_id    uuid not null,
+    user_id    uuid not null,
+    created_at timestamptz default now(),
+    primary key (list_id, user_id),
+    foreign key (list_id) references user_lists (id) on delete cascade,
+    foreign key (user_id) references "users" (id) on delete cascade
+);
+
+-- Add index for looking up the lists a user belongs to
+create index idx_user_list_members_user_id on user_list_members(user_id);
@@ -0,0 +1 @@
+alter table users drop column pinned_tweet_id;
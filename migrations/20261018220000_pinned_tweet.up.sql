@@ -0,0 +1,3 @@
+-- Add the tweet shown first on a user's profile; purging the tweet unpins it
+alter table users
+    add column pinned_tweet_id uuid references tweet (id) on delete set null;
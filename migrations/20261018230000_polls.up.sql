@@ -0,0 +1,32 @@
+create table polls
+(
+    tweet_id   uuid primary key,
+    closes_at  timestamptz not null,
+    created_at timestamptz default now(),
+    foreign key (tweet_id) references tweet (id) on delete cascade
+);
+
+create table poll_options
+(
+    id       uuid primary key default gen_random_uuid(),
+    tweet_id uuid        not null,
+    position smallint    not null,
+    label    varchar(64) not null,
+    unique (tweet_id, position),
+    foreign key (tweet_id) references polls (tweet_id) on delete cascade
+);
+
+-- One vote per user and poll
+create table poll_votes
+(
+    tweet_id   uuid not null,
+    user_id    uuid not null,
+    option_id  uuid not null,
+    created_at timestamptz default now(),
+    primary key (tweet_id, user_id),
+    foreign key (tweet_id) references polls (tweet_id) on delete cascade,
+    foreign key (user_id) references "users" (id) on delete cascade,
+    foreign key (option_id) references poll_options (id) on delete cascade
+);
+
+create index idx_poll_votes_option_id on poll_votes(option_id);
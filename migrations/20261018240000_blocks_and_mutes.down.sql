@@ -0,0 +1,17 @@
+create or replace function tweet_visible_to(author_id uuid, visibility varchar, viewer_id uuid)
+returns boolean as $$
+    select visibility = 'public'
+        or author_id = viewer_id
+        or (visibility = 'group' and exists(
+            select 1
+            from users author
+            join users viewer on viewer.user_group_id = author.user_group_id
+            where author.id = author_id and viewer.id = viewer_id
+        ));
+$$ language sql stable;
+
+drop function user_muted_by(uuid, uuid);
+drop function users_blocked(uuid, uuid);
+
+drop table if exists mutes;
+drop table if exists blocks;
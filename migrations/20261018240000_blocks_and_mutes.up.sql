@@ -0,0 +1,55 @@
+create table blocks
+(
+    blocker_id uuid not null,
+    blocked_id uuid not null,
+    created_at timestamptz default now(),
+    primary key (blocker_id, blocked_id),
+    foreign key (blocker_id) references "users" (id) on delete cascade,
+    foreign key (blocked_id) references "users" (id) on delete cascade,
+    check (blocker_id <> blocked_id)
+);
+
+-- Add index for checking blocks in the reverse direction
+create index idx_blocks_blocked_id on blocks(blocked_id);
+
+create table mutes
+(
+    muter_id   uuid not null,
+    muted_id   uuid not null,
+    created_at timestamptz default now(),
+    primary key (muter_id, muted_id),
+    foreign key (muter_id) references "users" (id) on delete cascade,
+    foreign key (muted_id) references "users" (id) on delete cascade,
+    check (muter_id <> muted_id)
+);
+
+-- Whether either user has blocked the other. Always false for a null user.
+create function users_blocked(user_a uuid, user_b uuid)
+returns boolean as $$
+    select exists(
+        select 1
+        from blocks
+        where (blocker_id = user_a and blocked_id = user_b)
+           or (blocker_id = user_b and blocked_id = user_a)
+    );
+$$ language sql stable;
+
+-- Whether muter_id has muted author_id. Always false for a null muter.
+create function user_muted_by(author_id uuid, muter_id uuid)
+returns boolean as $$
+    select exists(select 1 from mutes m where m.muter_id = $2 and m.muted_id = $1);
+$$ language sql stable;
+
+-- Blocked users cannot read each other's tweets, whatever their visibility.
+create or replace function tweet_visible_to(author_id uuid, visibility varchar, viewer_id uuid)
+returns boolean as $$
+    select (visibility = 'public'
+        or author_id = viewer_id
+        or (visibility = 'group' and exists(
+            select 1
+            from users author
+            join users viewer on viewer.user_group_id = author.user_group_id
+            where author.id = author_id and viewer.id = viewer_id
+        )))
+        and not users_blocked(author_id, viewer_id);
+$$ language sql stable;
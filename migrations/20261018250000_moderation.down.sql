@@ -0,0 +1,4 @@
+alter table users drop column suspended_until;
+
+drop table if exists moderation_actions;
+drop table if exists reports;
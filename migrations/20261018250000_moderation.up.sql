@@ -0,0 +1,42 @@
+create table reports
+(
+    id          uuid primary key default gen_random_uuid(),
+    reporter_id uuid        not null,
+    tweet_id    uuid,
+    user_id     uuid,
+    reason      text        not null,
+    status      varchar(16) not null default 'open'
+        check (status in ('open', 'actioned', 'dismissed')),
+    resolved_by uuid,
+    resolved_at timestamptz,
+    created_at  timestamptz default now(),
+    foreign key (reporter_id) references "users" (id) on delete cascade,
+    foreign key (tweet_id) references tweet (id) on delete cascade,
+    foreign key (user_id) references "users" (id) on delete cascade,
+    foreign key (resolved_by) references "users" (id) on delete set null,
+    -- A report targets either a tweet or a user
+    check ((tweet_id is null) <> (user_id is null))
+);
+
+-- Add index backing the oldest-first moderation queue
+create index idx_reports_status_created_at on reports(status, created_at);
+
+-- Append-only log of moderator actions. The targeted tweet and user are kept
+-- as plain ids so entries outlive purged rows.
+create table moderation_actions
+(
+    id           uuid primary key default gen_random_uuid(),
+    moderator_id uuid,
+    action       varchar(32) not null,
+    report_id    uuid,
+    tweet_id     uuid,
+    user_id      uuid,
+    note         text,
+    created_at   timestamptz default now(),
+    foreign key (moderator_id) references "users" (id) on delete set null,
+    foreign key (report_id) references reports (id) on delete set null
+);
+
+create index idx_moderation_actions_created_at on moderation_actions(created_at desc);
+
+alter table users add column suspended_until timestamptz;
@@ -0,0 +1,9 @@
+drop index if exists idx_tweet_held_created_at;
+
+-- Held tweets go back to their authors as drafts
+update tweet set status = 'draft' where status = 'held';
+alter table tweet drop constraint tweet_status_check;
+alter table tweet add constraint tweet_status_check
+    check (status in ('draft', 'scheduled', 'published'));
+
+drop table if exists content_rules;
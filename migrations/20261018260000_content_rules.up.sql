@@ -0,0 +1,23 @@
+-- Admin-managed rules checked against tweet content when it is posted or
+-- edited. `hit_count` and `last_hit_at` count the tweets each rule matched.
+create table content_rules
+(
+    id          uuid primary key default gen_random_uuid(),
+    kind        varchar(16)  not null check (kind in ('word', 'regex', 'domain')),
+    pattern     varchar(255) not null,
+    action      varchar(16)  not null check (action in ('reject', 'hold', 'mask')),
+    enabled     boolean      not null default true,
+    hit_count   bigint       not null default 0,
+    last_hit_at timestamptz,
+    created_at  timestamptz default now(),
+    updated_at  timestamptz default now(),
+    unique (kind, pattern)
+);
+
+-- Tweets matching a `hold` rule wait for a moderator before being published
+alter table tweet drop constraint tweet_status_check;
+alter table tweet add constraint tweet_status_check
+    check (status in ('draft', 'scheduled', 'published', 'held'));
+
+-- Add index backing the oldest-first queue of held tweets
+create index idx_tweet_held_created_at on tweet(created_at) where status = 'held';
@@ -0,0 +1,18 @@
+create or replace function tweet_visible_to(author_id uuid, visibility varchar, viewer_id uuid)
+returns boolean as $$
+    select (visibility = 'public'
+        or author_id = viewer_id
+        or (visibility = 'group' and exists(
+            select 1
+            from users author
+            join users viewer on viewer.user_group_id = author.user_group_id
+            where author.id = author_id and viewer.id = viewer_id
+        )))
+        and not users_blocked(author_id, viewer_id);
+$$ language sql stable;
+
+drop function if exists user_banned(uuid);
+
+alter table users drop column if exists status_changed_at;
+alter table users drop column if exists status_reason;
+alter table users drop column if exists status;
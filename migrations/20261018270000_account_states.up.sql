@@ -0,0 +1,30 @@
+alter table users add column status varchar(16) not null default 'active'
+    check (status in ('active', 'deactivated', 'suspended', 'banned'));
+alter table users add column status_reason text;
+alter table users add column status_changed_at timestamptz;
+
+-- Suspensions recorded before account states existed
+update users
+set status = 'suspended', status_changed_at = now()
+where suspended_until > now();
+
+-- Whether user_id is banned. Always false for a null user.
+create function user_banned(user_id uuid)
+returns boolean as $$
+    select exists(select 1 from users u where u.id = $1 and u.status = 'banned');
+$$ language sql stable;
+
+-- Tweets of banned users are hidden from everyone.
+create or replace function tweet_visible_to(author_id uuid, visibility varchar, viewer_id uuid)
+returns boolean as $$
+    select (visibility = 'public'
+        or author_id = viewer_id
+        or (visibility = 'group' and exists(
+            select 1
+            from users author
+            join users viewer on viewer.user_group_id = author.user_group_id
+            where author.id = author_id and viewer.id = viewer_id
+        )))
+        and not users_blocked(author_id, viewer_id)
+        and not user_banned(author_id);
+$$ language sql stable;
@@ -0,0 +1,10 @@
+-- Add the profile a user edits about themselves; purging a media item clears
+-- the avatar or banner that uses it
+alter table users add column display_name varchar(50);
+alter table users add column bio varchar(160);
+alter table users add column location varchar(30);
+alter table users add column website varchar(100);
+alter table users
+    add column avatar_media_id uuid references media (id) on delete set null;
+alter table users
+    add column banner_media_id uuid references media (id) on delete set null;
@@ -0,0 +1,32 @@
+-- One row per notification shown to user_id. Similar events share a
+-- group_key and are folded into the recipient's unread row of that key, so
+-- actor_ids lists everyone involved, most recent first.
+create table notifications
+(
+    id            uuid primary key default gen_random_uuid(),
+    user_id       uuid        not null,
+    kind          varchar(32) not null
+        check (kind in ('mention', 'permission_granted', 'permission_revoked',
+                        'user_group_changed')),
+    group_key     varchar(64),
+    actor_ids     uuid[]      not null default '{}',
+    event_count   integer     not null default 1,
+    tweet_id      uuid,
+    permission_id uuid,
+    user_group_id uuid,
+    read_at       timestamptz,
+    created_at    timestamptz default now(),
+    updated_at    timestamptz default now(),
+    foreign key (user_id) references "users" (id) on delete cascade,
+    foreign key (tweet_id) references tweet (id) on delete set null,
+    foreign key (permission_id) references permissions (id) on delete cascade,
+    foreign key (user_group_id) references user_groups (id) on delete cascade
+);
+
+-- A recipient has at most one unread notification per group
+create unique index idx_notifications_unread_group
+    on notifications(user_id, group_key) where read_at is null;
+
+-- Add index backing the newest-first notification listing
+create index idx_notifications_user_id_updated_at
+    on notifications(user_id, updated_at desc, id desc);
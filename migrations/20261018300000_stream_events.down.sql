@@ -0,0 +1,6 @@
+drop trigger if exists stream_notification on notifications;
+drop trigger if exists stream_newly_published_tweet on tweet;
+drop trigger if exists stream_published_tweet on tweet;
+drop function if exists record_stream_event();
+drop function if exists tweet_on_home_timeline(uuid, uuid);
+drop table if exists stream_events;
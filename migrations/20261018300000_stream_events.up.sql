@@ -0,0 +1,71 @@
+-- Short-lived log of what the event stream pushes to connected clients. Ids
+-- double as SSE event ids, so a reconnecting client resumes after the last id
+-- it saw. Each insert is announced on the stream_events channel and delivered
+-- once the inserting transaction commits.
+create table stream_events
+(
+    id              bigserial primary key,
+    kind            varchar(16) not null check (kind in ('tweet', 'notification')),
+    tweet_id        uuid,
+    notification_id uuid,
+    created_at      timestamptz default now(),
+    foreign key (tweet_id) references tweet (id) on delete cascade,
+    foreign key (notification_id) references notifications (id) on delete cascade,
+    check ((tweet_id is null) <> (notification_id is null))
+);
+
+-- Add index for the retention job purging old events
+create index idx_stream_events_created_at on stream_events(created_at);
+
+-- Whether tweets of author_id go to the home timeline of viewer_id: their own,
+-- those of members of their user_group and those of accounts they subscribe to.
+create function tweet_on_home_timeline(author_id uuid, viewer_id uuid)
+returns boolean as $$
+    select author_id = viewer_id
+        or exists(
+            select 1
+            from users author
+            join users viewer on viewer.user_group_id = author.user_group_id
+            where author.id = $1 and viewer.id = $2
+        )
+        or exists(
+            select 1 from subscriptions where subscriber_id = $2 and target_id = $1
+        );
+$$ language sql stable;
+
+create function record_stream_event()
+returns trigger as $$
+declare
+    event_id bigint;
+begin
+    if tg_table_name = 'tweet' then
+        insert into stream_events (kind, tweet_id) values ('tweet', new.id)
+        returning id into event_id;
+    else
+        insert into stream_events (kind, notification_id) values ('notification', new.id)
+        returning id into event_id;
+    end if;
+    perform pg_notify('stream_events', event_id::text);
+    return new;
+end;
+$$ language plpgsql;
+
+-- A tweet is streamed once it is published, whether posted directly, by the
+-- scheduler or released by a moderator
+create trigger stream_published_tweet
+after insert on tweet
+for each row
+when (new.status = 'published')
+execute function record_stream_event();
+
+create trigger stream_newly_published_tweet
+after update of status on tweet
+for each row
+when (new.status = 'published' and old.status <> 'published')
+execute function record_stream_event();
+
+-- Folding another event into a grouped notification streams it again
+create trigger stream_notification
+after insert or update of event_count on notifications
+for each row
+execute function record_stream_event();
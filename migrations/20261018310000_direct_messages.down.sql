@@ -0,0 +1,4 @@
+drop table if exists messages;
+drop table if exists conversation_participants;
+drop table if exists conversations;
+alter table users drop column dm_policy;
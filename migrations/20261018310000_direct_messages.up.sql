@@ -0,0 +1,47 @@
+-- Who may start a conversation with a user: everyone, only the accounts they
+-- subscribe to, or nobody
+alter table users add column dm_policy varchar(16) not null default 'everyone'
+    check (dm_policy in ('everyone', 'subscriptions', 'nobody'));
+
+-- A one-to-one or small group conversation. updated_at moves with every
+-- message so inboxes list the most recently active conversations first.
+create table conversations
+(
+    id         uuid primary key default gen_random_uuid(),
+    creator_id uuid,
+    is_group   boolean not null default false,
+    created_at timestamptz default now(),
+    updated_at timestamptz default now(),
+    foreign key (creator_id) references "users" (id) on delete set null
+);
+
+-- last_read_at is the read receipt of the participant: every message sent up
+-- to then counts as read
+create table conversation_participants
+(
+    conversation_id uuid not null,
+    user_id         uuid not null,
+    last_read_at    timestamptz,
+    created_at      timestamptz default now(),
+    primary key (conversation_id, user_id),
+    foreign key (conversation_id) references conversations (id) on delete cascade,
+    foreign key (user_id) references "users" (id) on delete cascade
+);
+
+-- Add index for listing the conversations of a user
+create index idx_conversation_participants_user_id on conversation_participants(user_id);
+
+create table messages
+(
+    id              uuid primary key default gen_random_uuid(),
+    conversation_id uuid          not null,
+    sender_id       uuid          not null,
+    content         varchar(1000) not null,
+    created_at      timestamptz default now(),
+    foreign key (conversation_id) references conversations (id) on delete cascade,
+    foreign key (sender_id) references "users" (id) on delete cascade
+);
+
+-- Add index backing the newest-first message listing
+create index idx_messages_conversation_id_created_at
+    on messages(conversation_id, created_at desc, id desc);
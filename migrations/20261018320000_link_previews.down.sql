@@ -0,0 +1,2 @@
+drop table if exists tweet_links;
+drop table if exists link_previews;
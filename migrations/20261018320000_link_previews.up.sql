@@ -0,0 +1,34 @@
+-- Preview cards of the links found in tweets, shared by every tweet linking
+-- the same normalized url. Rows start out pending and are filled in by the
+-- link preview job; failed fetches are retried until attempts runs out.
+create table link_previews
+(
+    url             varchar(2048) primary key,
+    status          varchar(16)   not null default 'pending'
+        check (status in ('pending', 'ready', 'failed')),
+    title           varchar(300),
+    description     varchar(1000),
+    image_url       varchar(2048),
+    site_name       varchar(100),
+    attempts        int           not null default 0,
+    next_attempt_at timestamptz   not null default now(),
+    fetched_at      timestamptz,
+    created_at      timestamptz default now()
+);
+
+-- Add index for the job looking for previews to fetch
+create index idx_link_previews_pending on link_previews(next_attempt_at) where status = 'pending';
+
+create table tweet_links
+(
+    tweet_id   uuid          not null,
+    url        varchar(2048) not null,
+    position   int           not null,
+    created_at timestamptz default now(),
+    primary key (tweet_id, url),
+    foreign key (tweet_id) references tweet (id) on delete cascade,
+    foreign key (url) references link_previews (url) on delete cascade
+);
+
+-- Add index for finding the tweets linking a url
+create index idx_tweet_links_url on tweet_links(url);
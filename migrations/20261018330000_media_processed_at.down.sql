@@ -0,0 +1 @@
+alter table media drop column processed_at;
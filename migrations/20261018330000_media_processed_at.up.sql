@@ -0,0 +1,7 @@
+-- Mark media whose upload has been processed. Until then the stored original
+-- may still carry EXIF metadata and must not be cached for good. Existing
+-- media were processed when they were uploaded.
+alter table media
+    add column processed_at timestamptz;
+update media
+set processed_at = created_at;
@@ -0,0 +1 @@
+drop function if exists html_escape(text);
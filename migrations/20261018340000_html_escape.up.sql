@@ -0,0 +1,7 @@
+-- Escapes text for inclusion in HTML, used for search snippets that carry
+-- `<mark>` tags around the matched words.
+create function html_escape(content text)
+returns text as $$
+    select replace(replace(replace(replace(replace(content,
+        '&', '&amp;'), '<', '&lt;'), '>', '&gt;'), '"', '&quot;'), '''', '&#39;');
+$$ language sql immutable;
@@ -26,6 +26,11 @@ use utoipa::OpenApi;
         crate::routes::user::update_user_route,
         crate::routes::user::delete_user_route,
         crate::routes::user::delete_users_route,
+        crate::routes::user::get_user_mentions_route,
+        crate::routes::user::restore_user_route,
+        crate::routes::user::get_account_status_route,
+        crate::routes::user::update_account_status_route,
+        crate::routes::auth::deactivate_account,
 
         // User Group routes
         crate::routes::user_group::insert_user_group_route,
@@ -41,6 +46,105 @@ use utoipa::OpenApi;
         crate::routes::tweet::update_tweet_route,
         crate::routes::tweet::delete_tweet_route,
         crate::routes::tweet::delete_tweets_route,
+        crate::routes::tweet::get_tweet_revisions_route,
+        crate::routes::tweet::restore_tweet_route,
+        crate::routes::tweet::pin_tweet_route,
+        crate::routes::tweet::unpin_tweet_route,
+        crate::routes::tweet::vote_poll_route,
+
+        // Subscription routes
+        crate::routes::subscription::insert_subscription_route,
+        crate::routes::subscription::get_subscriptions_route,
+        crate::routes::subscription::delete_subscription_route,
+
+        // Timeline routes
+        crate::routes::timeline::get_home_timeline_route,
+
+        // Hashtag routes
+        crate::routes::hashtag::get_hashtag_tweets_route,
+        crate::routes::hashtag::get_trending_hashtags_route,
+
+        // Search routes
+        crate::routes::search::search_tweets_route,
+        crate::routes::search::search_users_route,
+
+        // Media routes
+        crate::routes::media::upload_media_route,
+        crate::routes::media::get_media_route,
+        crate::routes::media::get_media_content_route,
+        crate::routes::media::get_media_variant_content_route,
+
+        // Draft routes
+        crate::routes::draft::insert_draft_route,
+        crate::routes::draft::get_drafts_route,
+        crate::routes::draft::get_draft_route,
+        crate::routes::draft::update_draft_route,
+        crate::routes::draft::delete_draft_route,
+        crate::routes::draft::publish_draft_route,
+
+        // Bookmark routes
+        crate::routes::bookmark::insert_bookmark_route,
+        crate::routes::bookmark::delete_bookmark_route,
+        crate::routes::bookmark::get_bookmarks_route,
+
+        // List routes
+        crate::routes::user_list::insert_user_list_route,
+        crate::routes::user_list::get_user_lists_route,
+        crate::routes::user_list::get_user_list_route,
+        crate::routes::user_list::update_user_list_route,
+        crate::routes::user_list::delete_user_list_route,
+        crate::routes::user_list::get_user_list_members_route,
+        crate::routes::user_list::insert_user_list_member_route,
+        crate::routes::user_list::delete_user_list_member_route,
+        crate::routes::user_list::get_user_list_timeline_route,
+
+        // Block and mute routes
+        crate::routes::block::insert_block_route,
+        crate::routes::block::delete_block_route,
+        crate::routes::block::get_blocks_route,
+        crate::routes::mute::insert_mute_route,
+        crate::routes::mute::delete_mute_route,
+        crate::routes::mute::get_mutes_route,
+
+        // Report and moderation routes
+        crate::routes::report::insert_report_route,
+        crate::routes::report::get_reports_route,
+        crate::routes::report::get_report_route,
+        crate::routes::report::update_report_route,
+        crate::routes::moderation::hide_tweet_route,
+        crate::routes::moderation::suspend_user_route,
+        crate::routes::moderation::get_moderation_actions_route,
+        crate::routes::moderation::get_held_tweets_route,
+        crate::routes::moderation::release_tweet_route,
+        // Content rule routes
+        crate::routes::content_rule::insert_content_rule_route,
+        crate::routes::content_rule::get_content_rules_route,
+        crate::routes::content_rule::get_content_rule_route,
+        crate::routes::content_rule::update_content_rule_route,
+        crate::routes::content_rule::delete_content_rule_route,
+        crate::routes::content_rule::test_content_rules_route,
+        // Profile routes
+        crate::routes::profile::get_profile_route,
+        crate::routes::profile::update_profile_route,
+        crate::routes::profile::get_messaging_settings_route,
+        crate::routes::profile::update_messaging_settings_route,
+        // Notification routes
+        crate::routes::notification::get_notifications_route,
+        crate::routes::notification::get_unread_notification_count_route,
+        crate::routes::notification::mark_notification_read_route,
+        crate::routes::notification::mark_all_notifications_read_route,
+        // Stream routes
+        crate::routes::stream::stream_route,
+        // Gateway routes
+        crate::routes::gateway::gateway_route,
+        // Conversation routes
+        crate::routes::conversation::insert_conversation_route,
+        crate::routes::conversation::get_conversations_route,
+        crate::routes::conversation::get_conversation_route,
+        crate::routes::conversation::mark_conversation_read_route,
+        crate::routes::conversation::get_messages_route,
+        crate::routes::conversation::insert_message_route,
+        crate::routes::conversation::delete_message_route,
 
         // Health Check route
         crate::routes::health_check::health_check,
@@ -60,6 +164,8 @@ use utoipa::OpenApi;
             // User schemas
             crate::models::user::User,
             crate::models::user::UserResponse,
+            crate::models::user::UserProfile,
+            crate::models::user::AuthorResponse,
             crate::routes::user::CreateUserFormData,
             crate::routes::user::PutUserFormData,
             crate::dto::response::DtoResponse<crate::models::user::UserResponse>,
@@ -70,9 +176,94 @@ use utoipa::OpenApi;
             crate::dto::response::DtoResponse<crate::models::user_group::UserGroup>,
             // Tweet schemas
             crate::models::tweet::TweetResponse,
+            crate::models::tweet::TweetVisibility,
+            crate::models::mention::MentionEntity,
             crate::routes::tweet::CreateTweetFormData,
             crate::routes::tweet::PutTweetFormData,
+            crate::models::poll::PollResponse,
+            crate::models::poll::PollOptionResponse,
+            crate::routes::tweet::CreatePollFormData,
+            crate::routes::tweet::VotePollFormData,
             crate::dto::response::DtoResponse<crate::models::tweet::TweetResponse>,
+            crate::models::tweet_revision::TweetRevisionResponse,
+            crate::dto::response::DtoResponse<crate::models::tweet_revision::TweetRevisionResponse>,
+            // Subscription schemas
+            crate::models::subscription::SubscriptionResponse,
+            crate::routes::subscription::CreateSubscriptionFormData,
+            crate::dto::response::DtoResponse<crate::models::subscription::SubscriptionResponse>,
+            // Timeline schemas
+            crate::dto::cursor::CursorResponse<crate::models::tweet::TweetResponse>,
+            // Hashtag schemas
+            crate::models::hashtag::TrendingHashtag,
+            crate::dto::response::DtoResponse<crate::models::hashtag::TrendingHashtag>,
+            // Search schemas
+            crate::models::search::TweetSearchResponse,
+            crate::dto::response::DtoResponse<crate::models::search::TweetSearchResponse>,
+            // Media schemas
+            crate::models::media::MediaResponse,
+            crate::models::media::MediaVariantResponse,
+            // Draft schemas
+            crate::models::draft::TweetStatus,
+            crate::models::draft::TweetDraftResponse,
+            crate::routes::draft::CreateDraftFormData,
+            crate::routes::draft::PutDraftFormData,
+            crate::dto::response::DtoResponse<crate::models::draft::TweetDraftResponse>,
+            // Bookmark schemas
+            crate::models::bookmark::BookmarkResponse,
+            // List schemas
+            crate::models::user_list::UserListResponse,
+            crate::models::user_list::UserListMemberResponse,
+            crate::routes::user_list::UserListFormData,
+            crate::dto::response::DtoResponse<crate::models::user_list::UserListResponse>,
+            // Block and mute schemas
+            crate::models::block::BlockResponse,
+            crate::models::mute::MuteResponse,
+            // Report and moderation schemas
+            crate::models::report::ReportStatus,
+            crate::models::report::ReportResponse,
+            crate::routes::report::CreateReportFormData,
+            crate::routes::report::UpdateReportFormData,
+            crate::dto::response::DtoResponse<crate::models::report::ReportResponse>,
+            crate::models::moderation::ModerationActionKind,
+            crate::models::moderation::ModerationActionResponse,
+            crate::routes::moderation::HideTweetFormData,
+            crate::routes::moderation::SuspendUserFormData,
+            crate::dto::response::DtoResponse<crate::models::moderation::ModerationActionResponse>,
+            crate::routes::moderation::ReleaseTweetFormData,
+            // Account status schemas
+            crate::models::account::AccountStatus,
+            crate::models::account::AccountStateResponse,
+            crate::routes::user::UpdateAccountStatusFormData,
+            crate::routes::auth::DeactivateAccountFormData,
+            // Content rule schemas
+            crate::models::content_rule::ContentRuleKind,
+            crate::models::content_rule::ContentRuleAction,
+            crate::models::content_rule::ContentRuleResponse,
+            crate::models::content_rule::ContentRuleEvaluationResponse,
+            crate::routes::content_rule::ContentRuleFormData,
+            crate::routes::content_rule::TestContentRulesFormData,
+            crate::dto::response::DtoResponse<crate::models::content_rule::ContentRuleResponse>,
+            // Notification schemas
+            crate::models::notification::NotificationKind,
+            crate::models::notification::NotificationResponse,
+            crate::models::notification::NotificationCountResponse,
+            crate::dto::cursor::CursorResponse<crate::models::notification::NotificationResponse>,
+            // Stream schemas
+            crate::models::stream::StreamEventKind,
+            // Link preview schemas
+            crate::models::link_preview::LinkPreviewResponse,
+            // Gateway schemas
+            crate::models::gateway::ClientEvent,
+            crate::models::gateway::ServerEvent,
+            // Conversation schemas
+            crate::models::conversation::DmPolicy,
+            crate::models::conversation::MessagingSettings,
+            crate::models::conversation::ConversationResponse,
+            crate::models::conversation::DirectMessageResponse,
+            crate::routes::conversation::ConversationFormData,
+            crate::routes::conversation::MessageFormData,
+            crate::dto::cursor::CursorResponse<crate::models::conversation::ConversationResponse>,
+            crate::dto::cursor::CursorResponse<crate::models::conversation::DirectMessageResponse>,
             // Common schemas
             crate::dto::pagination::Pagination,
             crate::error::AlohaError,
@@ -84,6 +275,24 @@ use utoipa::OpenApi;
         (name = "users", description = "User Management API"),
         (name = "user-groups", description = "User Group Management API"),
         (name = "tweets", description = "Tweet Management API"),
+        (name = "subscriptions", description = "Subscription Management API"),
+        (name = "timeline", description = "Timeline API"),
+        (name = "hashtags", description = "Hashtag API"),
+        (name = "search", description = "Search API"),
+        (name = "media", description = "Media Upload API"),
+        (name = "drafts", description = "Drafts and Scheduled Tweets API"),
+        (name = "bookmarks", description = "Bookmark API"),
+        (name = "lists", description = "User List API"),
+        (name = "blocks", description = "Block API"),
+        (name = "mutes", description = "Mute API"),
+        (name = "reports", description = "Content Reporting API"),
+        (name = "moderation", description = "Moderation API"),
+        (name = "content-rules", description = "Content Rule API"),
+        (name = "profile", description = "User Profile API"),
+        (name = "notifications", description = "Notification API"),
+        (name = "stream", description = "Server-Sent Events API"),
+        (name = "gateway", description = "WebSocket Gateway API"),
+        (name = "conversations", description = "Direct Message API"),
         (name = "health", description = "Health Check API")
     )
 )]
@@ -12,6 +12,14 @@ pub struct Settings {
     pub routes: Routes,
     pub redis_uri: SecretString,
     pub log_level: String,
+    pub hashtags: HashtagSettings,
+    pub media: MediaSettings,
+    pub tweets: TweetSettings,
+    pub retention: RetentionSettings,
+    pub stream: StreamSettings,
+    pub gateway: GatewaySettings,
+    pub link_previews: LinkPreviewSettings,
+    pub pagination: PaginationSettings,
 }
 #[derive(Deserialize, Debug, Clone)]
 pub struct DatabaseSettings {
@@ -32,6 +40,139 @@ pub struct ApplicationSettings {
     pub hmac_secret: SecretString,
     pub endpoint: String,
 }
+#[derive(serde::Deserialize, Debug, Clone)]
+pub struct HashtagSettings {
+    /// Only tweets newer than this take part in trending.
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub trending_window_hours: u32,
+    /// Age after which a tweet counts half as much towards a trend. Must be
+    /// positive.
+    #[serde(deserialize_with = "deserialize_positive_number_from_string")]
+    pub trending_half_life_hours: f64,
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub trending_limit: u32,
+}
+#[derive(serde::Deserialize, Debug, Clone)]
+pub struct TweetSettings {
+    /// How long after posting a tweet can still be edited.
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub edit_window_minutes: u32,
+    /// How often the scheduler looks for scheduled tweets that are due.
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub publish_interval_seconds: u32,
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub publish_batch_size: u32,
+}
+#[derive(serde::Deserialize, Debug, Clone)]
+pub struct RetentionSettings {
+    /// How long soft-deleted users and tweets are kept before being purged.
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub grace_period_days: u32,
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub purge_interval_minutes: u32,
+    /// How long streamed events stay available to reconnecting clients.
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub stream_event_hours: u32,
+}
+#[derive(serde::Deserialize, Debug, Clone)]
+pub struct StreamSettings {
+    /// Interval between comments sent to keep idle connections open.
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub heartbeat_seconds: u32,
+    /// Most events replayed to a client resuming from `Last-Event-ID`.
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub replay_limit: u32,
+    /// Events buffered per connection before a slow client starts missing them.
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub channel_capacity: u32,
+}
+#[derive(serde::Deserialize, Debug, Clone)]
+pub struct GatewaySettings {
+    /// Interval between pings sent to keep idle WebSocket connections open.
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub ping_seconds: u32,
+    /// Frames queued per connection before a slow client is disconnected.
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub send_queue: u32,
+    /// Most channels a single connection may subscribe to.
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub max_channels: u32,
+}
+#[derive(serde::Deserialize, Debug, Clone)]
+pub struct PaginationSettings {
+    /// Largest page served by the cursor-paginated listings: the home
+    /// timeline, notifications, conversations and messages.
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub max_cursor_size: u32,
+}
+#[derive(serde::Deserialize, Debug, Clone)]
+pub struct LinkPreviewSettings {
+    /// Whether the link preview job runs on this instance.
+    pub enabled: bool,
+    /// How often the job looks for links waiting for a preview.
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub fetch_interval_seconds: u32,
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub batch_size: u32,
+    /// Limit on resolving, connecting to and reading each page.
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub timeout_seconds: u32,
+    /// Pages are only read up to this size; the tags are in the head anyway.
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub max_body_bytes: u64,
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub max_redirects: u32,
+    /// Fetches of a link before it is given up on.
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub max_attempts: u32,
+    /// Delay before a failed fetch is retried, multiplied by the attempts made.
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub retry_seconds: u32,
+    /// Lets links point at loopback and private addresses. Only meant for
+    /// local development and tests.
+    #[serde(default)]
+    pub allow_private_networks: bool,
+}
+#[derive(serde::Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum MediaStorage {
+    Local,
+    S3,
+}
+#[derive(serde::Deserialize, Debug, Clone)]
+pub struct MediaSettings {
+    pub storage: MediaStorage,
+    /// Directory used by the `local` storage.
+    pub local_path: String,
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub max_upload_bytes: u64,
+    /// Maximum number of media items attached to one tweet.
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub max_attachments: u32,
+    /// Required when `storage` is `s3`.
+    pub s3: Option<S3Settings>,
+    /// Resized copies generated for every uploaded image.
+    #[serde(default)]
+    pub variants: Vec<MediaVariantSettings>,
+}
+#[derive(serde::Deserialize, Debug, Clone)]
+pub struct MediaVariantSettings {
+    pub name: String,
+    /// Images are scaled down, keeping their aspect ratio, to fit these bounds.
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub max_width: u32,
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub max_height: u32,
+}
+#[derive(serde::Deserialize, Debug, Clone)]
+pub struct S3Settings {
+    /// Base URL of the S3 compatible service; buckets are addressed path-style.
+    pub endpoint: String,
+    pub bucket: String,
+    pub region: String,
+    pub access_key_id: String,
+    pub secret_access_key: SecretString,
+}
 pub enum Environment {
     Development,
     Production,
@@ -78,6 +219,23 @@ impl TryFrom<String> for Environment {
         }
     }
 }
+/// Like `deserialize_number_from_string`, but refuses zero, negative and
+/// non-finite values.
+fn deserialize_positive_number_from_string<'de, D>(deserializer: D) -> Result<f64, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let value: f64 = deserialize_number_from_string(deserializer)?;
+    if value.is_finite() && value > 0.0 {
+        Ok(value)
+    } else {
+        Err(serde::de::Error::custom(format!(
+            "expected a positive number, got {}",
+            value
+        )))
+    }
+}
+
 pub fn get_configuration() -> Result<Settings, config::ConfigError> {
     let base_path = std::env::current_dir().expect("Failed to determine the current directory");
     let configuration_directory = base_path.join("configuration");
@@ -108,7 +266,7 @@ pub fn get_configuration() -> Result<Settings, config::ConfigError> {
 
 #[cfg(test)]
 mod tests {
-    use crate::configuration::{get_configuration, ApplicationSettings};
+    use crate::configuration::{get_configuration, ApplicationSettings, HashtagSettings};
     use secrecy::{ExposeSecret, SecretString};
 
     #[test]
@@ -147,4 +305,23 @@ mod tests {
         assert_eq!(settings.application.host, "127.0.0.1");
         // assert_eq!(settings.application.port, 0);
     }
+
+    #[test]
+    fn test_trending_half_life_must_be_positive() {
+        let settings = |half_life: serde_json::Value| {
+            serde_json::from_value::<HashtagSettings>(serde_json::json!({
+                "trending_window_hours": 24,
+                "trending_half_life_hours": half_life,
+                "trending_limit": 10
+            }))
+        };
+        assert_eq!(
+            settings(serde_json::json!("1.5"))
+                .unwrap()
+                .trending_half_life_hours,
+            1.5
+        );
+        assert!(settings(serde_json::json!(0)).is_err());
+        assert!(settings(serde_json::json!(-6)).is_err());
+    }
 }
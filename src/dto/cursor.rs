@@ -0,0 +1,117 @@
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+/// Keyset position in a listing ordered by `(created_at, id)` descending.
+///
+/// Encoded for clients as `<unix microseconds>_<uuid>`; the value is opaque to
+/// them and only ever handed back as the `cursor` query parameter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cursor {
+    pub created_at: OffsetDateTime,
+    pub id: Uuid,
+}
+
+impl Cursor {
+    pub fn new(created_at: OffsetDateTime, id: Uuid) -> Self {
+        Self { created_at, id }
+    }
+
+    pub fn encode(&self) -> String {
+        format!(
+            "{}_{}",
+            self.created_at.unix_timestamp_nanos() / 1_000,
+            self.id
+        )
+    }
+
+    pub fn decode(value: &str) -> Option<Self> {
+        let (micros, id) = value.split_once('_')?;
+        let micros = micros.parse::<i128>().ok()?;
+        let created_at = OffsetDateTime::from_unix_timestamp_nanos(micros * 1_000).ok()?;
+        let id = Uuid::parse_str(id).ok()?;
+        Some(Self { created_at, id })
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CursorQuery {
+    pub cursor: Option<String>,
+    pub size: Option<usize>,
+}
+
+impl CursorQuery {
+    pub fn default_query() -> Self {
+        CursorQuery {
+            cursor: None,
+            size: Some(10),
+        }
+    }
+
+    pub fn size(&self) -> usize {
+        self.size.unwrap_or(10)
+    }
+
+    /// Limits the requested page size to `max_size`.
+    pub fn capped(mut self, max_size: usize) -> Self {
+        self.size = Some(self.size().min(max_size));
+        self
+    }
+
+    /// Returns `Ok(None)` for the first page and `Err` for a malformed cursor.
+    pub fn cursor(&self) -> Result<Option<Cursor>, String> {
+        match &self.cursor {
+            None => Ok(None),
+            Some(value) => Cursor::decode(value)
+                .map(Some)
+                .ok_or_else(|| format!("Cursor `{}` is invalid.", value)),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct CursorResponse<T> {
+    pub next_cursor: Option<String>,
+    pub data: T,
+}
+
+impl<T> CursorResponse<T> {
+    pub fn new(data: T, next_cursor: Option<String>) -> Self {
+        CursorResponse { next_cursor, data }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::dto::cursor::{Cursor, CursorQuery};
+    use time::OffsetDateTime;
+    use uuid::Uuid;
+
+    #[test]
+    fn test_cursor_round_trip() {
+        let created_at =
+            OffsetDateTime::from_unix_timestamp_nanos(1_700_000_000_123_456_000).unwrap();
+        let cursor = Cursor::new(created_at, Uuid::new_v4());
+        assert_eq!(Cursor::decode(&cursor.encode()), Some(cursor));
+    }
+
+    #[test]
+    fn test_cursor_query_rejects_garbage() {
+        let query = CursorQuery {
+            cursor: Some(String::from("not-a-cursor")),
+            size: None,
+        };
+        assert!(query.cursor().is_err());
+        assert_eq!(CursorQuery::default_query().cursor(), Ok(None));
+    }
+
+    #[test]
+    fn test_cursor_query_caps_size() {
+        let query = CursorQuery {
+            cursor: None,
+            size: Some(1_000_000),
+        };
+        assert_eq!(query.capped(100).size(), 100);
+        assert_eq!(CursorQuery::default_query().capped(100).size(), 10);
+    }
+}
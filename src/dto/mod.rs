@@ -1,3 +1,4 @@
+pub mod cursor;
 pub mod pagination;
 pub mod query;
 pub mod response;
@@ -1,3 +1,6 @@
+use crate::models::content_rule::{ContentRuleAction, ContentRuleKind};
+use crate::models::draft::TweetStatus;
+use crate::models::report::ReportStatus;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
@@ -56,3 +59,93 @@ pub struct TweetFilterQuery {
     #[serde(rename = "user_id")]
     pub user_id: Option<Uuid>,
 }
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DraftFilterQuery {
+    pub status: Option<TweetStatus>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UserListFilterQuery {}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BlockFilterQuery {}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MuteFilterQuery {}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ReportFilterQuery {
+    pub status: Option<ReportStatus>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ModerationActionFilterQuery {}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ContentRuleFilterQuery {
+    pub kind: Option<ContentRuleKind>,
+    pub action: Option<ContentRuleAction>,
+    pub enabled: Option<bool>,
+}
+
+/// Query string of the search endpoints: the raw `q` expression plus the same
+/// `page`/`size` pagination as `DtoQuery`.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SearchQuery {
+    pub q: String,
+    pub page: Option<usize>,
+    pub size: Option<usize>,
+}
+
+impl SearchQuery {
+    /// Largest page a single search request may ask for.
+    pub const MAX_SIZE: usize = 100;
+
+    pub fn new(q: &str) -> Self {
+        SearchQuery {
+            q: q.to_string(),
+            page: Some(1),
+            size: Some(10),
+        }
+    }
+
+    /// The requested page, `page=0` being read as the first one.
+    pub fn page(&self) -> usize {
+        self.page.unwrap_or(1).max(1)
+    }
+
+    pub fn size(&self) -> usize {
+        self.size.unwrap_or(10).clamp(1, Self::MAX_SIZE)
+    }
+
+    /// Saturates rather than overflowing, and always fits an SQL `OFFSET`.
+    pub fn offset(&self) -> usize {
+        (self.page() - 1)
+            .saturating_mul(self.size())
+            .min(i64::MAX as usize)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::dto::query::SearchQuery;
+
+    #[test]
+    fn test_search_query_clamps_page_and_size() {
+        let query = SearchQuery {
+            page: Some(0),
+            size: Some(1_000_000),
+            ..SearchQuery::new("rust")
+        };
+        assert_eq!(query.page(), 1);
+        assert_eq!(query.size(), SearchQuery::MAX_SIZE);
+        assert_eq!(query.offset(), 0);
+
+        let query = SearchQuery {
+            page: Some(usize::MAX),
+            ..SearchQuery::new("rust")
+        };
+        assert_eq!(query.offset() as i64, i64::MAX);
+    }
+}
@@ -12,6 +12,8 @@ pub enum AlohaError {
     UserPasswordInvalid,
     UserNameInvalid,
     UserUnauthentication,
+    PermissionDenied,
+    MediaStorageError(String),
 }
 
 impl std::error::Error for AlohaError {
@@ -29,6 +31,8 @@ impl ResponseError for AlohaError {
             AlohaError::UserPasswordInvalid => StatusCode::BAD_REQUEST,
             AlohaError::UserNameInvalid => StatusCode::BAD_REQUEST,
             AlohaError::UserUnauthentication => StatusCode::UNAUTHORIZED,
+            AlohaError::PermissionDenied => StatusCode::FORBIDDEN,
+            AlohaError::MediaStorageError(_) => StatusCode::INTERNAL_SERVER_ERROR,
         }
     }
 }
@@ -42,6 +46,8 @@ impl Display for AlohaError {
             AlohaError::UserPasswordInvalid => write!(f, "User password is invalid."),
             AlohaError::UserNameInvalid => write!(f, "User name is invalid."),
             AlohaError::UserUnauthentication => write!(f, "User is unauthenticated."),
+            AlohaError::PermissionDenied => write!(f, "Permission denied."),
+            AlohaError::MediaStorageError(msg) => write!(f, "{}", msg),
         }
     }
 }
@@ -71,6 +77,12 @@ impl Serialize for AlohaError {
             AlohaError::UserUnauthentication => {
                 s.serialize_field("code", &StatusCode::UNAUTHORIZED.as_u16())?
             }
+            AlohaError::PermissionDenied => {
+                s.serialize_field("code", &StatusCode::FORBIDDEN.as_u16())?
+            }
+            AlohaError::MediaStorageError(_) => {
+                s.serialize_field("code", &StatusCode::INTERNAL_SERVER_ERROR.as_u16())?
+            }
         };
         s.serialize_field("error", &format!("{}", self))?;
         s.end()
@@ -0,0 +1,303 @@
+use crate::mappers::gateway::{get_tweet_channels, get_tweet_viewers};
+use crate::mappers::tweet::get_tweet_by_id;
+use crate::models::gateway::{GatewayChannel, ServerEvent, TypingSignal};
+use crate::routes::tweet::build_tweet_responses;
+use sqlx::PgPool;
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use tokio::sync::mpsc;
+use uuid::Uuid;
+
+/// Postgres channel typing indicators are announced on, so they reach the
+/// subscribers connected to any server instance.
+pub const GATEWAY_CHANNEL: &str = "gateway_events";
+
+/// How a frame is handed to a connection whose send queue is full.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Delivery {
+    /// The connection is closed, since the client would miss the frame.
+    Reliable,
+    /// The frame is dropped; used for short-lived state like typing.
+    Droppable,
+}
+
+struct GatewayConnection {
+    user_id: Uuid,
+    channels: HashSet<GatewayChannel>,
+    sender: mpsc::Sender<Arc<str>>,
+}
+
+/// Keeps the WebSocket connections open on this server instance together
+/// with their channel subscriptions. Frames go through a bounded queue per
+/// connection, so a slow client never holds up the others.
+pub struct Gateway {
+    next_id: AtomicU64,
+    send_queue: usize,
+    connections: Mutex<HashMap<u64, GatewayConnection>>,
+}
+
+impl Gateway {
+    pub fn new(send_queue: usize) -> Self {
+        Self {
+            next_id: AtomicU64::new(0),
+            send_queue: send_queue.max(1),
+            connections: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Registers a connection of `user_id` until the returned handle is
+    /// dropped.
+    pub fn connect(self: &Arc<Self>, user_id: Uuid) -> GatewayHandle {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let (sender, receiver) = mpsc::channel(self.send_queue);
+        self.connections.lock().unwrap().insert(
+            id,
+            GatewayConnection {
+                user_id,
+                channels: HashSet::new(),
+                sender,
+            },
+        );
+        GatewayHandle {
+            gateway: self.clone(),
+            id,
+            user_id,
+            receiver: Some(receiver),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.connections.lock().unwrap().is_empty()
+    }
+
+    /// Adds `channel` to the subscriptions of connection `id`. Fails when the
+    /// connection already has `max_channels` subscriptions.
+    pub fn subscribe(
+        &self,
+        id: u64,
+        channel: GatewayChannel,
+        max_channels: usize,
+    ) -> Result<(), String> {
+        let mut connections = self.connections.lock().unwrap();
+        let Some(connection) = connections.get_mut(&id) else {
+            return Ok(());
+        };
+        if !connection.channels.contains(&channel) && connection.channels.len() >= max_channels {
+            return Err(format!(
+                "At most {} channels can be subscribed to.",
+                max_channels
+            ));
+        }
+        connection.channels.insert(channel);
+        Ok(())
+    }
+
+    pub fn unsubscribe(&self, id: u64, channel: &GatewayChannel) {
+        if let Some(connection) = self.connections.lock().unwrap().get_mut(&id) {
+            connection.channels.remove(channel);
+        }
+    }
+
+    pub fn is_subscribed(&self, id: u64, channel: &GatewayChannel) -> bool {
+        self.connections
+            .lock()
+            .unwrap()
+            .get(&id)
+            .is_some_and(|connection| connection.channels.contains(channel))
+    }
+
+    /// Users with a connection subscribed to any of `channels`.
+    pub fn subscribers(&self, channels: &[GatewayChannel]) -> Vec<Uuid> {
+        let connections = self.connections.lock().unwrap();
+        let users: HashSet<Uuid> = connections
+            .values()
+            .filter(|connection| channels.iter().any(|c| connection.channels.contains(c)))
+            .map(|connection| connection.user_id)
+            .collect();
+        users.into_iter().collect()
+    }
+
+    /// Queues `frame` for connection `id` alone.
+    pub fn send(&self, id: u64, frame: &ServerEvent) {
+        let frame: Arc<str> = frame.to_json().into();
+        let mut connections = self.connections.lock().unwrap();
+        if let Some(connection) = connections.get(&id) {
+            if !Self::enqueue(connection, &frame, Delivery::Reliable) {
+                connections.remove(&id);
+            }
+        }
+    }
+
+    /// Queues `frame` for the connections subscribed to `channel` whose user
+    /// passes `recipient`.
+    pub fn publish(
+        &self,
+        channel: &GatewayChannel,
+        frame: &ServerEvent,
+        recipient: impl Fn(Uuid) -> bool,
+        delivery: Delivery,
+    ) {
+        let frame: Arc<str> = frame.to_json().into();
+        let mut connections = self.connections.lock().unwrap();
+        connections.retain(|_, connection| {
+            if !connection.channels.contains(channel) || !recipient(connection.user_id) {
+                return true;
+            }
+            Self::enqueue(connection, &frame, delivery)
+        });
+    }
+
+    /// Returns whether the connection can stay open. Dropping a connection
+    /// closes its queue, which makes its writer close the socket.
+    fn enqueue(connection: &GatewayConnection, frame: &Arc<str>, delivery: Delivery) -> bool {
+        match connection.sender.try_send(frame.clone()) {
+            Ok(()) => true,
+            Err(mpsc::error::TrySendError::Full(_)) if delivery == Delivery::Droppable => true,
+            Err(mpsc::error::TrySendError::Full(_)) => {
+                tracing::log::warn!(
+                    "Disconnecting slow gateway client of user {}",
+                    connection.user_id
+                );
+                false
+            }
+            Err(mpsc::error::TrySendError::Closed(_)) => false,
+        }
+    }
+}
+
+/// A connection registered with the [`Gateway`]; unregisters it when dropped.
+pub struct GatewayHandle {
+    gateway: Arc<Gateway>,
+    pub id: u64,
+    pub user_id: Uuid,
+    receiver: Option<mpsc::Receiver<Arc<str>>>,
+}
+
+impl GatewayHandle {
+    /// The queue of frames to write to the socket, which ends once the
+    /// gateway dropped the connection. Can only be taken once.
+    pub fn take_receiver(&mut self) -> Option<mpsc::Receiver<Arc<str>>> {
+        self.receiver.take()
+    }
+}
+
+impl Drop for GatewayHandle {
+    fn drop(&mut self) {
+        self.gateway.connections.lock().unwrap().remove(&self.id);
+    }
+}
+
+/// Publishes the tweet `tweet_id` on the channels it belongs to, to the
+/// subscribers allowed to read it.
+pub async fn dispatch_tweet_to_channels(
+    pool: &PgPool,
+    gateway: &Gateway,
+    tweet_id: Uuid,
+) -> Result<(), anyhow::Error> {
+    if gateway.is_empty() {
+        return Ok(());
+    }
+    let transaction = pool.begin().await?;
+    let channels = get_tweet_channels(transaction, tweet_id).await?;
+    let subscribers = gateway.subscribers(&channels);
+    if subscribers.is_empty() {
+        return Ok(());
+    }
+    let transaction = pool.begin().await?;
+    let recipients = get_tweet_viewers(transaction, tweet_id, &subscribers).await?;
+    let Some(&viewer_id) = recipients.first() else {
+        return Ok(());
+    };
+    let transaction = pool.begin().await?;
+    let Some(tweet) = get_tweet_by_id(transaction, tweet_id, Some(viewer_id)).await? else {
+        return Ok(());
+    };
+    let tweet = build_tweet_responses(pool, vec![tweet], None)
+        .await?
+        .remove(0);
+    for channel in channels {
+        let frame = ServerEvent::Tweet {
+            channel: channel.to_string(),
+            tweet: Box::new(tweet.clone()),
+        };
+        gateway.publish(
+            &channel,
+            &frame,
+            |user_id| recipients.contains(&user_id),
+            Delivery::Reliable,
+        );
+    }
+    Ok(())
+}
+
+/// Hands a typing indicator announced on [`GATEWAY_CHANNEL`] to the other
+/// subscribers of its channel.
+pub fn dispatch_gateway_signal(gateway: &Gateway, payload: &str) -> Result<(), anyhow::Error> {
+    let signal: TypingSignal = serde_json::from_str(payload)?;
+    let channel: GatewayChannel = signal.channel.parse().map_err(anyhow::Error::msg)?;
+    let frame = ServerEvent::Typing {
+        channel: signal.channel,
+        user_id: signal.user_id,
+        username: signal.username,
+    };
+    gateway.publish(
+        &channel,
+        &frame,
+        |user_id| user_id != signal.user_id,
+        Delivery::Droppable,
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::jobs::gateway::{Delivery, Gateway};
+    use crate::models::gateway::{GatewayChannel, ServerEvent};
+    use std::sync::Arc;
+    use tokio::sync::mpsc::error::TryRecvError;
+    use uuid::Uuid;
+
+    fn typing(username: &str) -> ServerEvent {
+        ServerEvent::Typing {
+            channel: "conversation".to_string(),
+            user_id: Uuid::new_v4(),
+            username: username.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_full_queue_drops_droppable_frames() {
+        let gateway = Arc::new(Gateway::new(1));
+        let channel = GatewayChannel::Conversation(Uuid::new_v4());
+        let mut handle = gateway.connect(Uuid::new_v4());
+        let mut receiver = handle.take_receiver().unwrap();
+        gateway.subscribe(handle.id, channel.clone(), 1).unwrap();
+
+        for username in ["first", "second"] {
+            gateway.publish(&channel, &typing(username), |_| true, Delivery::Droppable);
+        }
+        assert!(gateway.is_subscribed(handle.id, &channel));
+        assert!(receiver.try_recv().unwrap().contains("first"));
+        assert_eq!(receiver.try_recv(), Err(TryRecvError::Empty));
+
+        gateway.publish(&channel, &typing("third"), |_| true, Delivery::Droppable);
+        assert!(receiver.try_recv().unwrap().contains("third"));
+    }
+
+    #[test]
+    fn test_full_queue_disconnects_reliable_clients() {
+        let gateway = Arc::new(Gateway::new(1));
+        let channel = GatewayChannel::Conversation(Uuid::new_v4());
+        let mut handle = gateway.connect(Uuid::new_v4());
+        let mut receiver = handle.take_receiver().unwrap();
+        gateway.subscribe(handle.id, channel.clone(), 1).unwrap();
+
+        for username in ["first", "second"] {
+            gateway.publish(&channel, &typing(username), |_| true, Delivery::Reliable);
+        }
+        assert!(gateway.is_empty());
+        assert!(receiver.try_recv().unwrap().contains("first"));
+        assert_eq!(receiver.try_recv(), Err(TryRecvError::Disconnected));
+    }
+}
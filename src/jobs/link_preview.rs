@@ -0,0 +1,298 @@
+use crate::configuration::LinkPreviewSettings;
+use crate::mappers::link_preview::{
+    claim_link_previews, fail_link_preview, save_link_preview, ClaimedLinkPreview,
+};
+use crate::models::link_preview::{is_public_ip, parse_preview_metadata, PreviewMetadata};
+use actix_web::rt::time::timeout;
+use futures_util::future::join_all;
+use reqwest::header::{CONTENT_TYPE, LOCATION};
+use reqwest::{redirect, StatusCode, Url};
+use sqlx::PgPool;
+use std::fmt::{Display, Formatter};
+use std::net::{IpAddr, SocketAddr};
+use std::time::Duration;
+
+const USER_AGENT: &str = "AlohaBot/1.0 (link previews)";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PreviewFetchError {
+    /// Fetching again would not help: the address is not allowed, the page
+    /// is missing or has nothing to preview.
+    Refused(String),
+    /// Network trouble or a server error, worth retrying later.
+    Failed(String),
+}
+
+impl Display for PreviewFetchError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PreviewFetchError::Refused(msg) => write!(f, "Refused: {}", msg),
+            PreviewFetchError::Failed(msg) => write!(f, "Failed: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for PreviewFetchError {}
+
+/// Fetches the pages behind links to read their preview metadata. Every hop
+/// of a redirect chain is resolved up front and refused unless all its
+/// addresses are public; the connection is then pinned to the checked address
+/// so the name cannot be rebound to an internal one in between.
+pub struct LinkPreviewFetcher {
+    timeout: Duration,
+    max_body_bytes: u64,
+    max_redirects: u32,
+    allow_private_networks: bool,
+}
+
+impl LinkPreviewFetcher {
+    pub fn new(settings: &LinkPreviewSettings) -> Self {
+        Self {
+            timeout: Duration::from_secs(settings.timeout_seconds.max(1) as u64),
+            max_body_bytes: settings.max_body_bytes,
+            max_redirects: settings.max_redirects,
+            allow_private_networks: settings.allow_private_networks,
+        }
+    }
+
+    /// The address to connect to for `url`, once every address its host
+    /// resolves to passed the network policy.
+    async fn resolve(&self, url: &Url) -> Result<SocketAddr, PreviewFetchError> {
+        let host = url
+            .host_str()
+            .ok_or_else(|| PreviewFetchError::Refused(format!("{} has no host", url)))?;
+        let port = url
+            .port_or_known_default()
+            .ok_or_else(|| PreviewFetchError::Refused(format!("{} has no port", url)))?;
+        let addresses: Vec<SocketAddr> = match host
+            .trim_start_matches('[')
+            .trim_end_matches(']')
+            .parse::<IpAddr>()
+        {
+            Ok(ip) => vec![SocketAddr::new(ip, port)],
+            Err(_) => timeout(self.timeout, tokio::net::lookup_host((host, port)))
+                .await
+                .map_err(|_| PreviewFetchError::Failed(format!("Resolving {} timed out", host)))?
+                .map_err(|e| PreviewFetchError::Failed(format!("Resolving {}: {}", host, e)))?
+                .collect(),
+        };
+        if !self.allow_private_networks && addresses.iter().any(|a| !is_public_ip(a.ip())) {
+            return Err(PreviewFetchError::Refused(format!(
+                "{} resolves to a non-public address",
+                host
+            )));
+        }
+        addresses
+            .into_iter()
+            .next()
+            .ok_or_else(|| PreviewFetchError::Failed(format!("{} has no address", host)))
+    }
+
+    fn client(&self, url: &Url, address: SocketAddr) -> Result<reqwest::Client, PreviewFetchError> {
+        let mut builder = reqwest::Client::builder()
+            .redirect(redirect::Policy::none())
+            .no_proxy()
+            .user_agent(USER_AGENT)
+            .connect_timeout(self.timeout)
+            .timeout(self.timeout);
+        if let Some(domain) = url.domain() {
+            builder = builder.resolve(domain, address);
+        }
+        builder
+            .build()
+            .map_err(|e| PreviewFetchError::Failed(e.to_string()))
+    }
+
+    /// Reads the preview metadata of the html page at `url`, following up to
+    /// `max_redirects` redirects and reading at most `max_body_bytes`.
+    pub async fn fetch(&self, url: &str) -> Result<PreviewMetadata, PreviewFetchError> {
+        let mut url =
+            Url::parse(url).map_err(|e| PreviewFetchError::Refused(format!("{}: {}", url, e)))?;
+        let mut redirects = 0;
+        loop {
+            if !matches!(url.scheme(), "http" | "https") {
+                return Err(PreviewFetchError::Refused(format!(
+                    "{} is not an http(s) url",
+                    url
+                )));
+            }
+            let address = self.resolve(&url).await?;
+            let mut response = self
+                .client(&url, address)?
+                .get(url.clone())
+                .header("Accept", "text/html,application/xhtml+xml")
+                .send()
+                .await
+                .map_err(|e| PreviewFetchError::Failed(e.to_string()))?;
+
+            let status = response.status();
+            if status.is_redirection() {
+                let location = response
+                    .headers()
+                    .get(LOCATION)
+                    .and_then(|location| location.to_str().ok())
+                    .and_then(|location| url.join(location).ok());
+                match location {
+                    Some(location) if redirects < self.max_redirects => {
+                        redirects += 1;
+                        url = location;
+                        continue;
+                    }
+                    _ => {
+                        return Err(PreviewFetchError::Refused(format!(
+                            "Too many or invalid redirects from {}",
+                            url
+                        )))
+                    }
+                }
+            }
+            if status.is_server_error() || status == StatusCode::TOO_MANY_REQUESTS {
+                return Err(PreviewFetchError::Failed(format!(
+                    "{} answered {}",
+                    url, status
+                )));
+            }
+            if !status.is_success() {
+                return Err(PreviewFetchError::Refused(format!(
+                    "{} answered {}",
+                    url, status
+                )));
+            }
+            let is_html = response
+                .headers()
+                .get(CONTENT_TYPE)
+                .and_then(|content_type| content_type.to_str().ok())
+                .is_some_and(|content_type| {
+                    let content_type = content_type.to_ascii_lowercase();
+                    content_type.starts_with("text/html")
+                        || content_type.starts_with("application/xhtml+xml")
+                });
+            if !is_html {
+                return Err(PreviewFetchError::Refused(format!(
+                    "{} is not an html page",
+                    url
+                )));
+            }
+
+            let mut body: Vec<u8> = Vec::new();
+            while (body.len() as u64) < self.max_body_bytes {
+                match response.chunk().await {
+                    Ok(Some(chunk)) => body.extend_from_slice(&chunk),
+                    Ok(None) => break,
+                    Err(e) => return Err(PreviewFetchError::Failed(e.to_string())),
+                }
+            }
+            body.truncate(self.max_body_bytes as usize);
+
+            let metadata = parse_preview_metadata(&String::from_utf8_lossy(&body), &url);
+            if metadata.is_empty() {
+                return Err(PreviewFetchError::Refused(format!(
+                    "{} has nothing to preview",
+                    url
+                )));
+            }
+            return Ok(metadata);
+        }
+    }
+}
+
+/// How a run of the link preview job went.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LinkPreviewCounts {
+    pub ready: usize,
+    pub failed: usize,
+    /// Fetches that failed but will be retried.
+    pub retrying: usize,
+}
+
+/// Fetches one claimed preview and records the outcome. Metadata that cannot
+/// be stored gives up on the preview like a refused fetch would, so the row
+/// is not claimed again forever.
+async fn process_link_preview(
+    pool: &PgPool,
+    fetcher: &LinkPreviewFetcher,
+    settings: &LinkPreviewSettings,
+    claimed: ClaimedLinkPreview,
+) -> Result<LinkPreviewCounts, anyhow::Error> {
+    let mut counts = LinkPreviewCounts::default();
+    let fetched = match fetcher.fetch(&claimed.url).await {
+        Ok(metadata) => {
+            let transaction = pool.begin().await?;
+            save_link_preview(transaction, &claimed.url, &metadata)
+                .await
+                .map(|_| ())
+                .map_err(|e| PreviewFetchError::Refused(format!("{:?}", e)))
+        }
+        Err(e) => Err(e),
+    };
+    match fetched {
+        Ok(()) => counts.ready += 1,
+        Err(PreviewFetchError::Failed(e)) if claimed.attempts < settings.max_attempts as i32 => {
+            tracing::log::debug!("Retrying link preview of {} later: {}", claimed.url, e);
+            counts.retrying += 1;
+        }
+        Err(e) => {
+            tracing::log::debug!("Giving up link preview of {}: {}", claimed.url, e);
+            let transaction = pool.begin().await?;
+            fail_link_preview(transaction, &claimed.url).await?;
+            counts.failed += 1;
+        }
+    }
+    Ok(counts)
+}
+
+/// Fetches one batch of pending link previews concurrently.
+pub async fn run_link_preview_once(
+    pool: &PgPool,
+    fetcher: &LinkPreviewFetcher,
+    settings: &LinkPreviewSettings,
+) -> Result<LinkPreviewCounts, anyhow::Error> {
+    let transaction = pool.begin().await?;
+    let claimed = claim_link_previews(
+        transaction,
+        settings.batch_size.max(1) as i64,
+        settings.retry_seconds as i32,
+    )
+    .await?;
+    let results = join_all(
+        claimed
+            .into_iter()
+            .map(|claimed| process_link_preview(pool, fetcher, settings, claimed)),
+    )
+    .await;
+
+    let mut counts = LinkPreviewCounts::default();
+    for result in results {
+        match result {
+            Ok(result) => {
+                counts.ready += result.ready;
+                counts.failed += result.failed;
+                counts.retrying += result.retrying;
+            }
+            Err(e) => tracing::log::error!("Failed to record a link preview: {:?}", e),
+        }
+    }
+    Ok(counts)
+}
+
+/// Runs [`run_link_preview_once`] every `fetch_interval_seconds`, starting
+/// one interval after boot, until the runtime shuts down.
+pub async fn run_link_preview_job(pool: PgPool, settings: LinkPreviewSettings) {
+    let fetcher = LinkPreviewFetcher::new(&settings);
+    let period = Duration::from_secs(settings.fetch_interval_seconds.max(1) as u64);
+    let start = actix_web::rt::time::Instant::now() + period;
+    let mut interval = actix_web::rt::time::interval_at(start, period);
+    loop {
+        interval.tick().await;
+        match run_link_preview_once(&pool, &fetcher, &settings).await {
+            Ok(counts) if counts != LinkPreviewCounts::default() => tracing::log::info!(
+                "Fetched {} link previews, {} failed, {} to retry",
+                counts.ready,
+                counts.failed,
+                counts.retrying
+            ),
+            Ok(_) => {}
+            Err(e) => tracing::log::error!("Failed to fetch link previews: {:?}", e),
+        }
+    }
+}
@@ -0,0 +1,7 @@
+//! Background jobs spawned alongside the HTTP server.
+
+pub mod gateway;
+pub mod link_preview;
+pub mod retention;
+pub mod scheduler;
+pub mod stream;
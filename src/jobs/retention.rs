@@ -0,0 +1,52 @@
+use crate::configuration::RetentionSettings;
+use crate::mappers::retention::{purge_deleted, PurgeCounts};
+use crate::mappers::stream::purge_stream_events;
+use sqlx::PgPool;
+use time::{Duration, OffsetDateTime};
+
+/// Purges rows whose grace period ended before `now`.
+pub async fn run_retention_once(
+    pool: &PgPool,
+    settings: &RetentionSettings,
+    now: OffsetDateTime,
+) -> Result<PurgeCounts, anyhow::Error> {
+    let cutoff = now - Duration::days(settings.grace_period_days as i64);
+    let transaction = pool.begin().await?;
+    purge_deleted(transaction, cutoff).await
+}
+
+/// Purges stream events older than `stream_event_hours` before `now`.
+pub async fn purge_stream_events_once(
+    pool: &PgPool,
+    settings: &RetentionSettings,
+    now: OffsetDateTime,
+) -> Result<u64, anyhow::Error> {
+    let cutoff = now - Duration::hours(settings.stream_event_hours as i64);
+    let transaction = pool.begin().await?;
+    purge_stream_events(transaction, cutoff).await
+}
+
+/// Runs [`run_retention_once`] and [`purge_stream_events_once`] every
+/// `purge_interval_minutes`, starting one interval after boot, until the
+/// runtime shuts down. Failures are logged and retried on the next tick.
+pub async fn run_retention_job(pool: PgPool, settings: RetentionSettings) {
+    let period = std::time::Duration::from_secs(settings.purge_interval_minutes.max(1) as u64 * 60);
+    let start = actix_web::rt::time::Instant::now() + period;
+    let mut interval = actix_web::rt::time::interval_at(start, period);
+    loop {
+        interval.tick().await;
+        match run_retention_once(&pool, &settings, OffsetDateTime::now_utc()).await {
+            Ok(counts) if counts != PurgeCounts::default() => tracing::log::info!(
+                "Purged {} deleted users and {} deleted tweets",
+                counts.users,
+                counts.tweets
+            ),
+            Ok(_) => {}
+            Err(e) => tracing::log::error!("Failed to purge deleted rows: {:?}", e),
+        }
+        if let Err(e) = purge_stream_events_once(&pool, &settings, OffsetDateTime::now_utc()).await
+        {
+            tracing::log::error!("Failed to purge stream events: {:?}", e);
+        }
+    }
+}
@@ -0,0 +1,42 @@
+use crate::configuration::TweetSettings;
+use crate::mappers::draft::publish_due_tweets;
+use crate::models::tweet::Tweet;
+use sqlx::PgPool;
+use time::OffsetDateTime;
+
+/// Publishes every scheduled tweet due at `now`, one batch per transaction.
+pub async fn run_scheduler_once(
+    pool: &PgPool,
+    settings: &TweetSettings,
+    now: OffsetDateTime,
+) -> Result<Vec<Tweet>, anyhow::Error> {
+    let batch_size = settings.publish_batch_size.max(1) as i64;
+    let mut published = Vec::new();
+    loop {
+        let transaction = pool.begin().await?;
+        let batch = publish_due_tweets(transaction, now, batch_size).await?;
+        let done = (batch.len() as i64) < batch_size;
+        published.extend(batch);
+        if done {
+            return Ok(published);
+        }
+    }
+}
+
+/// Runs [`run_scheduler_once`] every `publish_interval_seconds`, starting one
+/// interval after boot, until the runtime shuts down.
+pub async fn run_scheduler_job(pool: PgPool, settings: TweetSettings) {
+    let period = std::time::Duration::from_secs(settings.publish_interval_seconds.max(1) as u64);
+    let start = actix_web::rt::time::Instant::now() + period;
+    let mut interval = actix_web::rt::time::interval_at(start, period);
+    loop {
+        interval.tick().await;
+        match run_scheduler_once(&pool, &settings, OffsetDateTime::now_utc()).await {
+            Ok(published) if !published.is_empty() => {
+                tracing::log::info!("Published {} scheduled tweets", published.len())
+            }
+            Ok(_) => {}
+            Err(e) => tracing::log::error!("Failed to publish scheduled tweets: {:?}", e),
+        }
+    }
+}
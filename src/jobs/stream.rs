@@ -0,0 +1,139 @@
+use crate::jobs::gateway::{
+    dispatch_gateway_signal, dispatch_tweet_to_channels, Gateway, GATEWAY_CHANNEL,
+};
+use crate::mappers::stream::get_stream_event_by_id;
+use crate::models::stream::{StreamEventKind, StreamMessage};
+use crate::routes::stream::render_stream_event;
+use sqlx::postgres::PgListener;
+use sqlx::PgPool;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tokio::sync::broadcast;
+use uuid::Uuid;
+
+/// Postgres channel the `record_stream_event` trigger announces event ids on.
+pub const STREAM_CHANNEL: &str = "stream_events";
+
+/// Hands stream messages to the connections open on this server instance.
+/// Every instance runs its own [`run_stream_job`], so a message reaches its
+/// recipients wherever they are connected.
+pub struct StreamBroker {
+    sender: broadcast::Sender<Arc<StreamMessage>>,
+    connected: Mutex<HashMap<Uuid, usize>>,
+}
+
+impl StreamBroker {
+    pub fn new(capacity: usize) -> Self {
+        let (sender, _) = broadcast::channel(capacity.max(1));
+        Self {
+            sender,
+            connected: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Registers a connection of `user_id` until the subscription is dropped.
+    pub fn subscribe(self: &Arc<Self>, user_id: Uuid) -> StreamSubscription {
+        *self.connected.lock().unwrap().entry(user_id).or_default() += 1;
+        StreamSubscription {
+            broker: self.clone(),
+            user_id,
+            receiver: self.sender.subscribe(),
+        }
+    }
+
+    /// Users with at least one open connection.
+    pub fn connected_user_ids(&self) -> Vec<Uuid> {
+        self.connected.lock().unwrap().keys().copied().collect()
+    }
+
+    pub fn publish(&self, message: StreamMessage) {
+        // Sending only fails when nobody is connected.
+        let _ = self.sender.send(Arc::new(message));
+    }
+}
+
+pub struct StreamSubscription {
+    broker: Arc<StreamBroker>,
+    pub user_id: Uuid,
+    pub receiver: broadcast::Receiver<Arc<StreamMessage>>,
+}
+
+impl Drop for StreamSubscription {
+    fn drop(&mut self) {
+        let mut connected = self.broker.connected.lock().unwrap();
+        if let Some(count) = connected.get_mut(&self.user_id) {
+            *count -= 1;
+            if *count == 0 {
+                connected.remove(&self.user_id);
+            }
+        }
+    }
+}
+
+/// Renders the stream event `id` for the users connected to this instance
+/// and publishes it to those it is meant for, over Server-Sent Events and on
+/// the gateway channels of its tweet.
+pub async fn dispatch_stream_event(
+    pool: &PgPool,
+    broker: &StreamBroker,
+    gateway: &Gateway,
+    id: i64,
+) -> Result<(), anyhow::Error> {
+    let connected = broker.connected_user_ids();
+    if connected.is_empty() && gateway.is_empty() {
+        return Ok(());
+    }
+    let transaction = pool.begin().await?;
+    let Some(event) = get_stream_event_by_id(transaction, id).await? else {
+        return Ok(());
+    };
+    if !connected.is_empty() {
+        if let Some(message) = render_stream_event(pool, &event, &connected).await? {
+            broker.publish(message);
+        }
+    }
+    if let (StreamEventKind::Tweet, Some(tweet_id)) = (event.kind, event.tweet_id) {
+        dispatch_tweet_to_channels(pool, gateway, tweet_id).await?;
+    }
+    Ok(())
+}
+
+async fn listen_for_stream_events(
+    pool: &PgPool,
+    broker: &StreamBroker,
+    gateway: &Gateway,
+) -> Result<(), anyhow::Error> {
+    let mut listener = PgListener::connect_with(pool).await?;
+    listener
+        .listen_all([STREAM_CHANNEL, GATEWAY_CHANNEL])
+        .await?;
+    loop {
+        let notification = listener.recv().await?;
+        if notification.channel() == GATEWAY_CHANNEL {
+            if let Err(e) = dispatch_gateway_signal(gateway, notification.payload()) {
+                tracing::log::warn!("Ignoring gateway signal: {:?}", e);
+            }
+            continue;
+        }
+        let Ok(id) = notification.payload().parse::<i64>() else {
+            tracing::log::warn!("Ignoring stream event `{}`", notification.payload());
+            continue;
+        };
+        if let Err(e) = dispatch_stream_event(pool, broker, gateway, id).await {
+            tracing::log::error!("Failed to dispatch stream event {}: {:?}", id, e);
+        }
+    }
+}
+
+/// Listens on [`STREAM_CHANNEL`] and [`GATEWAY_CHANNEL`] and dispatches
+/// everything announced there until the runtime shuts down, reconnecting
+/// after a second when the listener fails. Events announced while it is down
+/// only reach clients reconnecting with `Last-Event-ID`.
+pub async fn run_stream_job(pool: PgPool, broker: Arc<StreamBroker>, gateway: Arc<Gateway>) {
+    loop {
+        if let Err(e) = listen_for_stream_events(&pool, &broker, &gateway).await {
+            tracing::log::error!("Stream listener failed: {:?}", e);
+        }
+        actix_web::rt::time::sleep(std::time::Duration::from_secs(1)).await;
+    }
+}
@@ -5,5 +5,7 @@ pub mod models;
 pub mod api_doc;
 pub mod dto;
 pub mod error;
+pub mod jobs;
 pub mod routes;
 pub mod startup;
+pub mod storage;
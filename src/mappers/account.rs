@@ -0,0 +1,88 @@
+use crate::error::AlohaError;
+use crate::models::account::{AccountState, AccountStatus};
+use anyhow::Context;
+use sqlx::{Postgres, Transaction};
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+/// The account state of a user that is not deleted.
+pub async fn get_account_state(
+    transaction: &mut Transaction<'_, Postgres>,
+    user_id: Uuid,
+) -> Result<Option<AccountState>, anyhow::Error> {
+    let row = sqlx::query_as!(
+        AccountState,
+        r#"
+        SELECT id AS user_id, status AS "status: AccountStatus", status_reason AS reason,
+               suspended_until, status_changed_at AS changed_at
+        FROM users
+        WHERE id = $1 AND deleted_at IS NULL
+        "#,
+        user_id
+    )
+    .fetch_optional(&mut **transaction)
+    .await
+    .context("Failed to fetch account state")?;
+
+    Ok(row)
+}
+
+/// Moves the account of `user_id` to `status` inside the caller's
+/// transaction. `suspended_until` is only kept for suspensions. Returns
+/// `None` for unknown users and `RequestParameterInvalid` when the current
+/// status cannot change to `status`.
+pub async fn set_account_status(
+    transaction: &mut Transaction<'_, Postgres>,
+    user_id: Uuid,
+    status: AccountStatus,
+    reason: Option<String>,
+    suspended_until: Option<OffsetDateTime>,
+) -> Result<Option<AccountState>, anyhow::Error> {
+    let current = sqlx::query_as!(
+        AccountState,
+        r#"
+        SELECT id AS user_id, status AS "status: AccountStatus", status_reason AS reason,
+               suspended_until, status_changed_at AS changed_at
+        FROM users
+        WHERE id = $1 AND deleted_at IS NULL
+        FOR UPDATE
+        "#,
+        user_id
+    )
+    .fetch_optional(&mut **transaction)
+    .await
+    .context("Failed to lock account state")?;
+
+    let Some(current) = current else {
+        return Ok(None);
+    };
+    let current_status = current.effective_status(OffsetDateTime::now_utc());
+    if !current_status.can_transition_to(status) {
+        return Err(AlohaError::RequestParameterInvalid(format!(
+            "A {:?} account cannot become {:?}.",
+            current_status, status
+        ))
+        .into());
+    }
+    let suspended_until = suspended_until.filter(|_| status == AccountStatus::Suspended);
+
+    let row = sqlx::query_as!(
+        AccountState,
+        r#"
+        UPDATE users
+        SET status = $2, status_reason = $3, suspended_until = $4, status_changed_at = now()
+        WHERE id = $1
+        RETURNING id AS user_id, status AS "status: AccountStatus", status_reason AS reason,
+                  suspended_until, status_changed_at AS changed_at
+        "#,
+        user_id,
+        status as AccountStatus,
+        reason,
+        suspended_until
+    )
+    .fetch_one(&mut **transaction)
+    .await
+    .context("Failed to update account state")?;
+
+    Ok(Some(row))
+}
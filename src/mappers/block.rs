@@ -0,0 +1,132 @@
+use crate::dto::pagination::Pagination;
+use crate::dto::query::{BlockFilterQuery, DtoQuery};
+use crate::dto::response::DtoResponse;
+use crate::error::AlohaError;
+use crate::models::block::Block;
+use crate::models::user::User;
+use anyhow::Context;
+use sqlx::{Postgres, Transaction};
+use uuid::Uuid;
+
+use super::user::check_user_id_is_valid;
+
+/// Blocks `block.blocked_id` for `block.blocker_id` and drops the
+/// subscriptions between the two users. Blocking twice is a no-op.
+pub async fn insert_block(
+    mut transaction: Transaction<'_, Postgres>,
+    block: &Block,
+) -> Result<Block, anyhow::Error> {
+    let is_user_valid = check_user_id_is_valid(&mut transaction, block.blocked_id).await?;
+    if !is_user_valid {
+        return Err(AlohaError::UserIdInvalid.into());
+    }
+
+    let row = sqlx::query_as!(
+        Block,
+        r#"
+        INSERT INTO blocks (blocker_id, blocked_id)
+        VALUES ($1, $2)
+        ON CONFLICT (blocker_id, blocked_id)
+        DO UPDATE SET blocker_id = EXCLUDED.blocker_id
+        RETURNING blocker_id, blocked_id, created_at
+        "#,
+        block.blocker_id,
+        block.blocked_id
+    )
+    .fetch_one(&mut *transaction)
+    .await
+    .context("Failed to insert block")?;
+
+    sqlx::query!(
+        r#"
+        DELETE FROM subscriptions
+        WHERE (subscriber_id = $1 AND target_id = $2)
+           OR (subscriber_id = $2 AND target_id = $1)
+        "#,
+        block.blocker_id,
+        block.blocked_id
+    )
+    .execute(&mut *transaction)
+    .await
+    .context("Failed to delete subscriptions between blocked users")?;
+
+    transaction
+        .commit()
+        .await
+        .context("Failed to commit SQL transaction to insert a new block.")?;
+
+    Ok(row)
+}
+
+pub async fn delete_block(
+    mut transaction: Transaction<'_, Postgres>,
+    blocker_id: Uuid,
+    blocked_id: Uuid,
+) -> Result<Option<Block>, anyhow::Error> {
+    let row = sqlx::query_as!(
+        Block,
+        r#"
+        DELETE FROM blocks
+        WHERE blocker_id = $1 AND blocked_id = $2
+        RETURNING blocker_id, blocked_id, created_at
+        "#,
+        blocker_id,
+        blocked_id
+    )
+    .fetch_optional(&mut *transaction)
+    .await
+    .context("Failed to delete block")?;
+
+    transaction
+        .commit()
+        .await
+        .context("Failed to commit SQL transaction to delete a block.")?;
+
+    Ok(row)
+}
+
+/// Users blocked by `blocker_id`, most recently blocked first.
+pub async fn get_blocked_users(
+    mut transaction: Transaction<'_, Postgres>,
+    blocker_id: Uuid,
+    dto_query: DtoQuery<BlockFilterQuery>,
+) -> Result<DtoResponse<Vec<User>>, anyhow::Error> {
+    let offset = dto_query.offset() as i64;
+    let limit = dto_query.size() as i64;
+
+    let total = sqlx::query!(
+        r#"
+        SELECT COUNT(*)
+        FROM blocks b
+        JOIN users u ON u.id = b.blocked_id
+        WHERE b.blocker_id = $1 AND u.deleted_at IS NULL
+        "#,
+        blocker_id
+    )
+    .fetch_one(&mut *transaction)
+    .await?
+    .count;
+
+    let users = sqlx::query_as!(
+        User,
+        r#"
+        SELECT u.id, u.username, u.password_hash, u.created_at, u.user_group_id,
+               u.display_name, u.bio, u.location, u.website, u.avatar_media_id,
+               u.banner_media_id
+        FROM blocks b
+        JOIN users u ON u.id = b.blocked_id
+        WHERE b.blocker_id = $1 AND u.deleted_at IS NULL
+        ORDER BY b.created_at DESC, u.id
+        LIMIT $2 OFFSET $3
+        "#,
+        blocker_id,
+        limit,
+        offset
+    )
+    .fetch_all(&mut *transaction)
+    .await
+    .context("Failed to fetch blocked users")?;
+
+    let pagination = Pagination::new(Some(dto_query.page()), Some(dto_query.size()), total);
+    Ok(DtoResponse::new(users, Some(pagination)))
+}
@@ -0,0 +1,132 @@
+use crate::dto::pagination::Pagination;
+use crate::dto::query::{DtoQuery, TweetFilterQuery};
+use crate::dto::response::DtoResponse;
+use crate::models::bookmark::Bookmark;
+use crate::models::tweet::{Tweet, TweetVisibility};
+use anyhow::Context;
+use sqlx::{Postgres, Transaction};
+use uuid::Uuid;
+
+/// Bookmarks a tweet for `bookmark.user_id`. Bookmarking twice is a no-op.
+/// Returns `None` when the tweet does not exist or the user cannot read it.
+pub async fn insert_bookmark(
+    mut transaction: Transaction<'_, Postgres>,
+    bookmark: &Bookmark,
+) -> Result<Option<Bookmark>, anyhow::Error> {
+    let row = sqlx::query_as!(
+        Bookmark,
+        r#"
+        INSERT INTO bookmarks (user_id, tweet_id)
+        SELECT $1, t.id
+        FROM tweet t
+        WHERE t.id = $2 AND t.deleted_at IS NULL AND t.status = 'published'
+        AND tweet_visible_to(t.user_id, t.visibility, $1)
+        ON CONFLICT (user_id, tweet_id)
+        DO UPDATE SET user_id = EXCLUDED.user_id
+        RETURNING user_id, tweet_id, created_at
+        "#,
+        bookmark.user_id,
+        bookmark.tweet_id
+    )
+    .fetch_optional(&mut *transaction)
+    .await
+    .context("Failed to insert bookmark")?;
+
+    transaction
+        .commit()
+        .await
+        .context("Failed to commit SQL transaction to insert a new bookmark.")?;
+
+    Ok(row)
+}
+
+pub async fn delete_bookmark(
+    mut transaction: Transaction<'_, Postgres>,
+    user_id: Uuid,
+    tweet_id: Uuid,
+) -> Result<Option<Bookmark>, anyhow::Error> {
+    let row = sqlx::query_as!(
+        Bookmark,
+        r#"
+        DELETE FROM bookmarks
+        WHERE user_id = $1 AND tweet_id = $2
+        RETURNING user_id, tweet_id, created_at
+        "#,
+        user_id,
+        tweet_id
+    )
+    .fetch_optional(&mut *transaction)
+    .await
+    .context("Failed to delete bookmark")?;
+
+    transaction
+        .commit()
+        .await
+        .context("Failed to commit SQL transaction to delete a bookmark.")?;
+
+    Ok(row)
+}
+
+/// Tweets bookmarked by `user_id`, most recently bookmarked first. Tweets that
+/// were deleted or are no longer readable by the user are left out.
+pub async fn get_bookmarked_tweets(
+    mut transaction: Transaction<'_, Postgres>,
+    user_id: Uuid,
+    dto_query: DtoQuery<TweetFilterQuery>,
+) -> Result<DtoResponse<Vec<Tweet>>, anyhow::Error> {
+    let offset = dto_query.offset() as i64;
+    let limit = dto_query.size() as i64;
+    let author_id = dto_query.filter.as_ref().and_then(|f| f.user_id);
+
+    let total = sqlx::query!(
+        r#"
+        SELECT COUNT(*)
+        FROM bookmarks b
+        JOIN tweet t ON t.id = b.tweet_id
+        WHERE b.user_id = $1 AND ($2::uuid IS NULL OR t.user_id = $2)
+        AND t.deleted_at IS NULL AND t.status = 'published'
+        AND tweet_visible_to(t.user_id, t.visibility, $1)
+        "#,
+        user_id,
+        author_id
+    )
+    .fetch_one(&mut *transaction)
+    .await?
+    .count;
+
+    let rows = sqlx::query!(
+        r#"
+        SELECT t.id, t.content, t.created_at, t.updated_at, t.user_id,
+               t.visibility AS "visibility: TweetVisibility"
+        FROM bookmarks b
+        JOIN tweet t ON t.id = b.tweet_id
+        WHERE b.user_id = $1 AND ($2::uuid IS NULL OR t.user_id = $2)
+        AND t.deleted_at IS NULL AND t.status = 'published'
+        AND tweet_visible_to(t.user_id, t.visibility, $1)
+        ORDER BY b.created_at DESC, t.id DESC
+        LIMIT $3 OFFSET $4
+        "#,
+        user_id,
+        author_id,
+        limit,
+        offset
+    )
+    .fetch_all(&mut *transaction)
+    .await
+    .context("Failed to fetch bookmarked tweets")?;
+
+    let data: Vec<Tweet> = rows
+        .into_iter()
+        .map(|row| Tweet {
+            id: row.id,
+            content: row.content,
+            created_at: row.created_at,
+            updated_at: row.updated_at,
+            user_id: row.user_id,
+            visibility: row.visibility,
+        })
+        .collect();
+
+    let pagination = Pagination::new(Some(dto_query.page()), Some(dto_query.size()), total);
+    Ok(DtoResponse::new(data, Some(pagination)))
+}
@@ -0,0 +1,232 @@
+use crate::dto::pagination::Pagination;
+use crate::dto::query::{ContentRuleFilterQuery, DtoQuery};
+use crate::dto::response::DtoResponse;
+use crate::models::content_rule::{
+    evaluate_content_rules, ContentRule, ContentRuleAction, ContentRuleEvaluation, ContentRuleKind,
+};
+use anyhow::Context;
+use sqlx::{Postgres, Transaction};
+use uuid::Uuid;
+
+pub async fn insert_content_rule(
+    mut transaction: Transaction<'_, Postgres>,
+    rule: &ContentRule,
+) -> Result<ContentRule, anyhow::Error> {
+    let row = sqlx::query_as!(
+        ContentRule,
+        r#"
+        INSERT INTO content_rules (id, kind, pattern, action, enabled)
+        VALUES ($1, $2, $3, $4, $5)
+        RETURNING id, kind AS "kind: ContentRuleKind", pattern,
+                  action AS "action: ContentRuleAction", enabled, hit_count, last_hit_at,
+                  created_at, updated_at
+        "#,
+        rule.id,
+        rule.kind as ContentRuleKind,
+        rule.pattern,
+        rule.action as ContentRuleAction,
+        rule.enabled
+    )
+    .fetch_one(&mut *transaction)
+    .await
+    .context("Failed to insert content rule")?;
+
+    transaction
+        .commit()
+        .await
+        .context("Failed to commit SQL transaction to insert a new content rule.")?;
+
+    Ok(row)
+}
+
+/// Lists the rules matching the filter, oldest first.
+pub async fn get_content_rules(
+    mut transaction: Transaction<'_, Postgres>,
+    dto_query: DtoQuery<ContentRuleFilterQuery>,
+) -> Result<DtoResponse<Vec<ContentRule>>, anyhow::Error> {
+    let offset = dto_query.offset() as i64;
+    let limit = dto_query.size() as i64;
+    let filter = dto_query.filter.as_ref();
+    let kind = filter.and_then(|f| f.kind);
+    let action = filter.and_then(|f| f.action);
+    let enabled = filter.and_then(|f| f.enabled);
+
+    let total = sqlx::query!(
+        r#"
+        SELECT COUNT(*)
+        FROM content_rules
+        WHERE ($1::varchar IS NULL OR kind = $1)
+        AND ($2::varchar IS NULL OR action = $2)
+        AND ($3::boolean IS NULL OR enabled = $3)
+        "#,
+        kind as Option<ContentRuleKind>,
+        action as Option<ContentRuleAction>,
+        enabled
+    )
+    .fetch_one(&mut *transaction)
+    .await?
+    .count;
+
+    let data = sqlx::query_as!(
+        ContentRule,
+        r#"
+        SELECT id, kind AS "kind: ContentRuleKind", pattern,
+               action AS "action: ContentRuleAction", enabled, hit_count, last_hit_at,
+               created_at, updated_at
+        FROM content_rules
+        WHERE ($1::varchar IS NULL OR kind = $1)
+        AND ($2::varchar IS NULL OR action = $2)
+        AND ($3::boolean IS NULL OR enabled = $3)
+        ORDER BY created_at, id
+        LIMIT $4 OFFSET $5
+        "#,
+        kind as Option<ContentRuleKind>,
+        action as Option<ContentRuleAction>,
+        enabled,
+        limit,
+        offset
+    )
+    .fetch_all(&mut *transaction)
+    .await
+    .context("Failed to fetch content rules")?;
+
+    let pagination = Pagination::new(Some(dto_query.page()), Some(dto_query.size()), total);
+    Ok(DtoResponse::new(data, Some(pagination)))
+}
+
+pub async fn get_content_rule_by_id(
+    mut transaction: Transaction<'_, Postgres>,
+    id: Uuid,
+) -> Result<Option<ContentRule>, anyhow::Error> {
+    let row = sqlx::query_as!(
+        ContentRule,
+        r#"
+        SELECT id, kind AS "kind: ContentRuleKind", pattern,
+               action AS "action: ContentRuleAction", enabled, hit_count, last_hit_at,
+               created_at, updated_at
+        FROM content_rules
+        WHERE id = $1
+        "#,
+        id
+    )
+    .fetch_optional(&mut *transaction)
+    .await
+    .context("Failed to fetch content rule by id")?;
+
+    Ok(row)
+}
+
+/// Replaces the pattern, action and enabled flag of a rule. Hit statistics
+/// are kept.
+pub async fn update_content_rule(
+    mut transaction: Transaction<'_, Postgres>,
+    rule: &ContentRule,
+) -> Result<Option<ContentRule>, anyhow::Error> {
+    let row = sqlx::query_as!(
+        ContentRule,
+        r#"
+        UPDATE content_rules
+        SET kind = $2, pattern = $3, action = $4, enabled = $5, updated_at = now()
+        WHERE id = $1
+        RETURNING id, kind AS "kind: ContentRuleKind", pattern,
+                  action AS "action: ContentRuleAction", enabled, hit_count, last_hit_at,
+                  created_at, updated_at
+        "#,
+        rule.id,
+        rule.kind as ContentRuleKind,
+        rule.pattern,
+        rule.action as ContentRuleAction,
+        rule.enabled
+    )
+    .fetch_optional(&mut *transaction)
+    .await
+    .context("Failed to update content rule")?;
+
+    transaction
+        .commit()
+        .await
+        .context("Failed to commit SQL transaction to update a content rule.")?;
+
+    Ok(row)
+}
+
+pub async fn delete_content_rule(
+    mut transaction: Transaction<'_, Postgres>,
+    id: Uuid,
+) -> Result<Option<ContentRule>, anyhow::Error> {
+    let row = sqlx::query_as!(
+        ContentRule,
+        r#"
+        DELETE FROM content_rules
+        WHERE id = $1
+        RETURNING id, kind AS "kind: ContentRuleKind", pattern,
+                  action AS "action: ContentRuleAction", enabled, hit_count, last_hit_at,
+                  created_at, updated_at
+        "#,
+        id
+    )
+    .fetch_optional(&mut *transaction)
+    .await
+    .context("Failed to delete content rule")?;
+
+    transaction
+        .commit()
+        .await
+        .context("Failed to commit SQL transaction to delete a content rule.")?;
+
+    Ok(row)
+}
+
+async fn get_enabled_content_rules(
+    transaction: &mut Transaction<'_, Postgres>,
+) -> Result<Vec<ContentRule>, anyhow::Error> {
+    let rows = sqlx::query_as!(
+        ContentRule,
+        r#"
+        SELECT id, kind AS "kind: ContentRuleKind", pattern,
+               action AS "action: ContentRuleAction", enabled, hit_count, last_hit_at,
+               created_at, updated_at
+        FROM content_rules
+        WHERE enabled
+        ORDER BY created_at, id
+        "#
+    )
+    .fetch_all(&mut **transaction)
+    .await
+    .context("Failed to fetch enabled content rules")?;
+
+    Ok(rows)
+}
+
+/// Checks `content` against the enabled rules without recording hits.
+pub async fn test_content_rules(
+    mut transaction: Transaction<'_, Postgres>,
+    content: &str,
+) -> Result<ContentRuleEvaluation, anyhow::Error> {
+    let rules = get_enabled_content_rules(&mut transaction).await?;
+    Ok(evaluate_content_rules(&rules, content))
+}
+
+/// Checks `content` against the enabled rules inside the caller's
+/// transaction and counts a hit on every matched rule.
+pub async fn apply_content_rules(
+    transaction: &mut Transaction<'_, Postgres>,
+    content: &str,
+) -> Result<ContentRuleEvaluation, anyhow::Error> {
+    let rules = get_enabled_content_rules(transaction).await?;
+    let evaluation = evaluate_content_rules(&rules, content);
+    if !evaluation.matched_rule_ids.is_empty() {
+        sqlx::query!(
+            r#"
+            UPDATE content_rules
+            SET hit_count = hit_count + 1, last_hit_at = now()
+            WHERE id = ANY($1)
+            "#,
+            &evaluation.matched_rule_ids
+        )
+        .execute(&mut **transaction)
+        .await
+        .context("Failed to record content rule hits")?;
+    }
+    Ok(evaluation)
+}
@@ -0,0 +1,455 @@
+use crate::dto::cursor::{Cursor, CursorQuery, CursorResponse};
+use crate::error::AlohaError;
+use crate::models::conversation::{Conversation, DirectMessage};
+use anyhow::Context;
+use sqlx::{Postgres, Transaction};
+use uuid::Uuid;
+
+async fn check_participant(
+    transaction: &mut Transaction<'_, Postgres>,
+    conversation_id: Uuid,
+    user_id: Uuid,
+) -> Result<bool, anyhow::Error> {
+    let row = sqlx::query!(
+        r#"
+        SELECT EXISTS(
+            SELECT 1 FROM conversation_participants
+            WHERE conversation_id = $1 AND user_id = $2
+        ) AS "exists!"
+        "#,
+        conversation_id,
+        user_id
+    )
+    .fetch_one(&mut **transaction)
+    .await
+    .context("Failed to check conversation participant")?;
+
+    Ok(row.exists)
+}
+
+/// The conversation `id` as seen by its participant `user_id`.
+async fn fetch_conversation(
+    transaction: &mut Transaction<'_, Postgres>,
+    user_id: Uuid,
+    id: Uuid,
+) -> Result<Option<Conversation>, anyhow::Error> {
+    let row = sqlx::query_as!(
+        Conversation,
+        r#"
+        SELECT c.id, c.creator_id, c.is_group,
+               ARRAY(
+                   SELECT p.user_id FROM conversation_participants p
+                   WHERE p.conversation_id = c.id
+                   ORDER BY p.created_at, p.user_id
+               ) AS "participant_ids!",
+               (
+                   SELECT COUNT(*) FROM messages m
+                   WHERE m.conversation_id = c.id AND m.sender_id <> $1
+                   AND (me.last_read_at IS NULL OR m.created_at > me.last_read_at)
+                   AND NOT users_blocked(m.sender_id, $1)
+               ) AS "unread_count!",
+               c.created_at, c.updated_at
+        FROM conversations c
+        JOIN conversation_participants me ON me.conversation_id = c.id AND me.user_id = $1
+        WHERE c.id = $2
+        "#,
+        user_id,
+        id
+    )
+    .fetch_optional(&mut **transaction)
+    .await
+    .context("Failed to fetch conversation")?;
+
+    Ok(row)
+}
+
+/// Starts a conversation between `creator_id` and `participant_ids`, which
+/// must not contain the creator. Every participant has to accept messages
+/// from the creator under their `dm_policy` and neither may block the other.
+/// Starting a one-to-one conversation that already exists returns it.
+pub async fn insert_conversation(
+    mut transaction: Transaction<'_, Postgres>,
+    creator_id: Uuid,
+    participant_ids: &[Uuid],
+) -> Result<Conversation, anyhow::Error> {
+    let existing = sqlx::query!(
+        r#"
+        SELECT COUNT(*) AS "count!" FROM users WHERE id = ANY($1) AND deleted_at IS NULL
+        "#,
+        participant_ids
+    )
+    .fetch_one(&mut *transaction)
+    .await
+    .context("Failed to check conversation participants")?
+    .count;
+    if existing as usize != participant_ids.len() {
+        return Err(AlohaError::UserIdInvalid.into());
+    }
+
+    let refused = sqlx::query!(
+        r#"
+        SELECT u.username
+        FROM users u
+        WHERE u.id = ANY($1)
+        AND (
+            users_blocked(u.id, $2)
+            OR u.dm_policy = 'nobody'
+            OR (u.dm_policy = 'subscriptions' AND NOT EXISTS(
+                SELECT 1 FROM subscriptions s WHERE s.subscriber_id = u.id AND s.target_id = $2
+            ))
+        )
+        ORDER BY u.username
+        "#,
+        participant_ids,
+        creator_id
+    )
+    .fetch_all(&mut *transaction)
+    .await
+    .context("Failed to check who accepts messages")?;
+    if !refused.is_empty() {
+        let usernames: Vec<String> = refused.into_iter().map(|row| row.username).collect();
+        return Err(AlohaError::RequestParameterInvalid(format!(
+            "{} cannot be messaged by you.",
+            usernames.join(", ")
+        ))
+        .into());
+    }
+
+    let is_group = participant_ids.len() > 1;
+    if !is_group {
+        let row = sqlx::query!(
+            r#"
+            SELECT c.id
+            FROM conversations c
+            JOIN conversation_participants a ON a.conversation_id = c.id AND a.user_id = $1
+            JOIN conversation_participants b ON b.conversation_id = c.id AND b.user_id = $2
+            WHERE NOT c.is_group
+            LIMIT 1
+            "#,
+            creator_id,
+            participant_ids[0]
+        )
+        .fetch_optional(&mut *transaction)
+        .await
+        .context("Failed to look up existing conversation")?;
+        if let Some(row) = row {
+            return fetch_conversation(&mut transaction, creator_id, row.id)
+                .await?
+                .context("Existing conversation vanished");
+        }
+    }
+
+    let id = sqlx::query!(
+        r#"
+        INSERT INTO conversations (creator_id, is_group)
+        VALUES ($1, $2)
+        RETURNING id
+        "#,
+        creator_id,
+        is_group
+    )
+    .fetch_one(&mut *transaction)
+    .await
+    .context("Failed to insert conversation")?
+    .id;
+
+    // The creator has nothing to read yet, everybody else joins unread.
+    let members: Vec<Uuid> = std::iter::once(creator_id)
+        .chain(participant_ids.iter().copied())
+        .collect();
+    sqlx::query!(
+        r#"
+        INSERT INTO conversation_participants (conversation_id, user_id, last_read_at)
+        SELECT $1, p.user_id, CASE WHEN p.user_id = $2 THEN now() END
+        FROM UNNEST($3::uuid[]) AS p(user_id)
+        "#,
+        id,
+        creator_id,
+        &members
+    )
+    .execute(&mut *transaction)
+    .await
+    .context("Failed to insert conversation participants")?;
+
+    let conversation = fetch_conversation(&mut transaction, creator_id, id)
+        .await?
+        .context("Inserted conversation vanished")?;
+
+    transaction
+        .commit()
+        .await
+        .context("Failed to commit SQL transaction to insert a new conversation.")?;
+
+    Ok(conversation)
+}
+
+/// The conversations of `user_id`, most recently active first.
+pub async fn get_conversations(
+    mut transaction: Transaction<'_, Postgres>,
+    user_id: Uuid,
+    cursor_query: CursorQuery,
+) -> Result<CursorResponse<Vec<Conversation>>, anyhow::Error> {
+    let cursor = cursor_query
+        .cursor()
+        .map_err(AlohaError::RequestParameterInvalid)?;
+    let limit = cursor_query.size() as i64;
+
+    // Fetch one extra row to learn whether another page exists.
+    let mut data = sqlx::query_as!(
+        Conversation,
+        r#"
+        SELECT c.id, c.creator_id, c.is_group,
+               ARRAY(
+                   SELECT p.user_id FROM conversation_participants p
+                   WHERE p.conversation_id = c.id
+                   ORDER BY p.created_at, p.user_id
+               ) AS "participant_ids!",
+               (
+                   SELECT COUNT(*) FROM messages m
+                   WHERE m.conversation_id = c.id AND m.sender_id <> $1
+                   AND (me.last_read_at IS NULL OR m.created_at > me.last_read_at)
+                   AND NOT users_blocked(m.sender_id, $1)
+               ) AS "unread_count!",
+               c.created_at, c.updated_at
+        FROM conversations c
+        JOIN conversation_participants me ON me.conversation_id = c.id AND me.user_id = $1
+        WHERE ($2::timestamptz IS NULL OR (c.updated_at, c.id) < ($2, $3))
+        ORDER BY c.updated_at DESC, c.id DESC
+        LIMIT $4
+        "#,
+        user_id,
+        cursor.map(|c| c.created_at),
+        cursor.map(|c| c.id),
+        limit + 1
+    )
+    .fetch_all(&mut *transaction)
+    .await
+    .context("Failed to fetch conversations")?;
+
+    let next_cursor = if data.len() as i64 > limit {
+        data.truncate(limit as usize);
+        data.last()
+            .and_then(|c| c.updated_at.map(|updated_at| Cursor::new(updated_at, c.id)))
+            .map(|c| c.encode())
+    } else {
+        None
+    };
+
+    Ok(CursorResponse::new(data, next_cursor))
+}
+
+/// Returns `None` unless `user_id` takes part in the conversation.
+pub async fn get_conversation_by_id(
+    mut transaction: Transaction<'_, Postgres>,
+    user_id: Uuid,
+    id: Uuid,
+) -> Result<Option<Conversation>, anyhow::Error> {
+    fetch_conversation(&mut transaction, user_id, id).await
+}
+
+/// Sends a message to its conversation and marks the conversation read for
+/// the sender. Returns `None` unless the sender takes part in it; one-to-one
+/// conversations refuse messages once either user blocked the other.
+pub async fn insert_message(
+    mut transaction: Transaction<'_, Postgres>,
+    message: &DirectMessage,
+) -> Result<Option<DirectMessage>, anyhow::Error> {
+    if !check_participant(&mut transaction, message.conversation_id, message.sender_id).await? {
+        return Ok(None);
+    }
+    let blocked = sqlx::query!(
+        r#"
+        SELECT EXISTS(
+            SELECT 1
+            FROM conversations c
+            JOIN conversation_participants p ON p.conversation_id = c.id
+            WHERE c.id = $1 AND NOT c.is_group AND p.user_id <> $2
+            AND users_blocked(p.user_id, $2)
+        ) AS "exists!"
+        "#,
+        message.conversation_id,
+        message.sender_id
+    )
+    .fetch_one(&mut *transaction)
+    .await
+    .context("Failed to check blocks in conversation")?
+    .exists;
+    if blocked {
+        return Err(AlohaError::RequestParameterInvalid(
+            "You cannot message this user.".to_string(),
+        )
+        .into());
+    }
+
+    let row = sqlx::query!(
+        r#"
+        INSERT INTO messages (id, conversation_id, sender_id, content)
+        VALUES ($1, $2, $3, $4)
+        RETURNING id, conversation_id, sender_id, content, created_at
+        "#,
+        message.id,
+        message.conversation_id,
+        message.sender_id,
+        message.content
+    )
+    .fetch_one(&mut *transaction)
+    .await
+    .context("Failed to insert message")?;
+
+    sqlx::query!(
+        "UPDATE conversations SET updated_at = now() WHERE id = $1",
+        message.conversation_id
+    )
+    .execute(&mut *transaction)
+    .await
+    .context("Failed to touch conversation")?;
+    sqlx::query!(
+        r#"
+        UPDATE conversation_participants SET last_read_at = now()
+        WHERE conversation_id = $1 AND user_id = $2
+        "#,
+        message.conversation_id,
+        message.sender_id
+    )
+    .execute(&mut *transaction)
+    .await
+    .context("Failed to update read receipt of sender")?;
+
+    transaction
+        .commit()
+        .await
+        .context("Failed to commit SQL transaction to insert a new message.")?;
+
+    Ok(Some(DirectMessage {
+        id: row.id,
+        conversation_id: row.conversation_id,
+        sender_id: row.sender_id,
+        content: row.content,
+        read_by: Vec::new(),
+        created_at: row.created_at,
+    }))
+}
+
+/// The messages of a conversation, newest first, leaving out those of users
+/// blocking or blocked by `user_id`. Returns `None` unless `user_id` takes
+/// part in the conversation.
+pub async fn get_messages(
+    mut transaction: Transaction<'_, Postgres>,
+    user_id: Uuid,
+    conversation_id: Uuid,
+    cursor_query: CursorQuery,
+) -> Result<Option<CursorResponse<Vec<DirectMessage>>>, anyhow::Error> {
+    let cursor = cursor_query
+        .cursor()
+        .map_err(AlohaError::RequestParameterInvalid)?;
+    let limit = cursor_query.size() as i64;
+    if !check_participant(&mut transaction, conversation_id, user_id).await? {
+        return Ok(None);
+    }
+
+    // Fetch one extra row to learn whether another page exists.
+    let mut data = sqlx::query_as!(
+        DirectMessage,
+        r#"
+        SELECT m.id, m.conversation_id, m.sender_id, m.content,
+               ARRAY(
+                   SELECT p.user_id FROM conversation_participants p
+                   WHERE p.conversation_id = m.conversation_id AND p.user_id <> m.sender_id
+                   AND p.last_read_at >= m.created_at
+                   ORDER BY p.user_id
+               ) AS "read_by!",
+               m.created_at
+        FROM messages m
+        WHERE m.conversation_id = $1 AND NOT users_blocked(m.sender_id, $2)
+        AND ($3::timestamptz IS NULL OR (m.created_at, m.id) < ($3, $4))
+        ORDER BY m.created_at DESC, m.id DESC
+        LIMIT $5
+        "#,
+        conversation_id,
+        user_id,
+        cursor.map(|c| c.created_at),
+        cursor.map(|c| c.id),
+        limit + 1
+    )
+    .fetch_all(&mut *transaction)
+    .await
+    .context("Failed to fetch messages")?;
+
+    let next_cursor = if data.len() as i64 > limit {
+        data.truncate(limit as usize);
+        data.last()
+            .and_then(|m| m.created_at.map(|created_at| Cursor::new(created_at, m.id)))
+            .map(|c| c.encode())
+    } else {
+        None
+    };
+
+    Ok(Some(CursorResponse::new(data, next_cursor)))
+}
+
+/// Deletes a message `sender_id` sent. Returns `None` for messages of other
+/// users or of other conversations.
+pub async fn delete_message(
+    mut transaction: Transaction<'_, Postgres>,
+    sender_id: Uuid,
+    conversation_id: Uuid,
+    id: Uuid,
+) -> Result<Option<DirectMessage>, anyhow::Error> {
+    let row = sqlx::query!(
+        r#"
+        DELETE FROM messages
+        WHERE id = $1 AND conversation_id = $2 AND sender_id = $3
+        RETURNING id, conversation_id, sender_id, content, created_at
+        "#,
+        id,
+        conversation_id,
+        sender_id
+    )
+    .fetch_optional(&mut *transaction)
+    .await
+    .context("Failed to delete message")?;
+
+    transaction
+        .commit()
+        .await
+        .context("Failed to commit SQL transaction to delete a message.")?;
+
+    Ok(row.map(|row| DirectMessage {
+        id: row.id,
+        conversation_id: row.conversation_id,
+        sender_id: row.sender_id,
+        content: row.content,
+        read_by: Vec::new(),
+        created_at: row.created_at,
+    }))
+}
+
+/// Moves the read receipt of `user_id` to now, marking every message of the
+/// conversation sent so far as read. Returns `None` unless `user_id` takes
+/// part in the conversation.
+pub async fn mark_conversation_read(
+    mut transaction: Transaction<'_, Postgres>,
+    user_id: Uuid,
+    id: Uuid,
+) -> Result<Option<Conversation>, anyhow::Error> {
+    sqlx::query!(
+        r#"
+        UPDATE conversation_participants SET last_read_at = now()
+        WHERE conversation_id = $1 AND user_id = $2
+        "#,
+        id,
+        user_id
+    )
+    .execute(&mut *transaction)
+    .await
+    .context("Failed to update read receipt")?;
+
+    let conversation = fetch_conversation(&mut transaction, user_id, id).await?;
+
+    transaction
+        .commit()
+        .await
+        .context("Failed to commit SQL transaction to mark a conversation read.")?;
+
+    Ok(conversation)
+}
@@ -0,0 +1,395 @@
+use crate::dto::pagination::Pagination;
+use crate::dto::query::{DraftFilterQuery, DtoQuery};
+use crate::dto::response::DtoResponse;
+use crate::error::AlohaError;
+use crate::models::content_rule::ContentRuleAction;
+use crate::models::draft::{TweetDraft, TweetStatus};
+use crate::models::tweet::{Tweet, TweetVisibility};
+use anyhow::Context;
+use sqlx::{Acquire, Postgres, Transaction};
+use time::OffsetDateTime;
+use tracing::error;
+use uuid::Uuid;
+
+use super::content_rule::apply_content_rules;
+use super::hashtag::sync_tweet_hashtags;
+use super::link_preview::sync_tweet_links;
+use super::media::attach_tweet_media;
+use super::mention::sync_tweet_mentions;
+use super::moderation::record_held_tweet;
+use super::user::check_user_id_is_valid;
+
+/// Stores an unpublished tweet. It is only screened by the content rules and
+/// gets its hashtags and mentions once it is published.
+pub async fn insert_draft(
+    mut transaction: Transaction<'_, Postgres>,
+    draft: &TweetDraft,
+    media_ids: &[Uuid],
+) -> Result<TweetDraft, anyhow::Error> {
+    let is_user_valid = check_user_id_is_valid(&mut transaction, draft.user_id).await?;
+    if !is_user_valid {
+        return Err(AlohaError::UserIdInvalid.into());
+    }
+
+    let draft = sqlx::query_as!(
+        TweetDraft,
+        r#"
+        INSERT INTO tweet (id, content, user_id, status, publish_at, visibility)
+        VALUES ($1, $2, $3, $4, $5, $6)
+        RETURNING id, content, status AS "status: TweetStatus", publish_at,
+                  created_at, updated_at, user_id,
+                  visibility AS "visibility: TweetVisibility"
+        "#,
+        draft.id,
+        draft.content,
+        draft.user_id,
+        draft.status as TweetStatus,
+        draft.publish_at,
+        draft.visibility as TweetVisibility
+    )
+    .fetch_one(&mut *transaction)
+    .await
+    .context("Failed to insert draft")?;
+
+    if !media_ids.is_empty() {
+        attach_tweet_media(&mut transaction, draft.id, draft.user_id, media_ids).await?;
+    }
+
+    transaction
+        .commit()
+        .await
+        .context("Failed to commit SQL transaction to insert a new draft.")?;
+
+    Ok(draft)
+}
+
+/// Lists the drafts and scheduled tweets of `user_id`, the ones due soonest
+/// first followed by plain drafts, most recently edited first.
+pub async fn get_drafts_by_user_id(
+    mut transaction: Transaction<'_, Postgres>,
+    user_id: Uuid,
+    dto_query: DtoQuery<DraftFilterQuery>,
+) -> Result<DtoResponse<Vec<TweetDraft>>, anyhow::Error> {
+    let offset = dto_query.offset() as i64;
+    let limit = dto_query.size() as i64;
+    let status = dto_query.filter.as_ref().and_then(|f| f.status);
+
+    let total = sqlx::query!(
+        r#"
+        SELECT COUNT(*)
+        FROM tweet
+        WHERE user_id = $1 AND status IN ('draft', 'scheduled') AND deleted_at IS NULL
+        AND ($2::varchar IS NULL OR status = $2)
+        "#,
+        user_id,
+        status as Option<TweetStatus>
+    )
+    .fetch_one(&mut *transaction)
+    .await?
+    .count;
+
+    let data = sqlx::query_as!(
+        TweetDraft,
+        r#"
+        SELECT id, content, status AS "status: TweetStatus", publish_at,
+               created_at, updated_at, user_id,
+               visibility AS "visibility: TweetVisibility"
+        FROM tweet
+        WHERE user_id = $1 AND status IN ('draft', 'scheduled') AND deleted_at IS NULL
+        AND ($2::varchar IS NULL OR status = $2)
+        ORDER BY publish_at ASC NULLS LAST, updated_at DESC, id
+        LIMIT $3 OFFSET $4
+        "#,
+        user_id,
+        status as Option<TweetStatus>,
+        limit,
+        offset
+    )
+    .fetch_all(&mut *transaction)
+    .await
+    .context("Failed to fetch drafts")?;
+
+    let pagination = Pagination::new(Some(dto_query.page()), Some(dto_query.size()), total);
+    Ok(DtoResponse::new(data, Some(pagination)))
+}
+
+pub async fn get_draft_by_id(
+    mut transaction: Transaction<'_, Postgres>,
+    user_id: Uuid,
+    id: Uuid,
+) -> Result<Option<TweetDraft>, anyhow::Error> {
+    let draft = sqlx::query_as!(
+        TweetDraft,
+        r#"
+        SELECT id, content, status AS "status: TweetStatus", publish_at,
+               created_at, updated_at, user_id,
+               visibility AS "visibility: TweetVisibility"
+        FROM tweet
+        WHERE id = $1 AND user_id = $2 AND status IN ('draft', 'scheduled') AND deleted_at IS NULL
+        "#,
+        id,
+        user_id
+    )
+    .fetch_optional(&mut *transaction)
+    .await
+    .context("Failed to fetch draft by id")?;
+
+    Ok(draft)
+}
+
+/// Replaces the content, schedule and visibility of an unpublished tweet
+/// owned by `draft.user_id`. Returns `None` once it has been published.
+pub async fn update_draft(
+    mut transaction: Transaction<'_, Postgres>,
+    draft: &TweetDraft,
+) -> Result<Option<TweetDraft>, anyhow::Error> {
+    let updated = sqlx::query_as!(
+        TweetDraft,
+        r#"
+        UPDATE tweet
+        SET content = $1, status = $2, publish_at = $3, visibility = $6
+        WHERE id = $4 AND user_id = $5 AND status IN ('draft', 'scheduled') AND deleted_at IS NULL
+        RETURNING id, content, status AS "status: TweetStatus", publish_at,
+                  created_at, updated_at, user_id,
+                  visibility AS "visibility: TweetVisibility"
+        "#,
+        draft.content,
+        draft.status as TweetStatus,
+        draft.publish_at,
+        draft.id,
+        draft.user_id,
+        draft.visibility as TweetVisibility
+    )
+    .fetch_optional(&mut *transaction)
+    .await
+    .context("Failed to update draft")?;
+
+    transaction
+        .commit()
+        .await
+        .context("Failed to commit SQL transaction to update a draft.")?;
+
+    Ok(updated)
+}
+
+pub async fn delete_draft(
+    mut transaction: Transaction<'_, Postgres>,
+    user_id: Uuid,
+    id: Uuid,
+) -> Result<Option<TweetDraft>, anyhow::Error> {
+    let deleted = sqlx::query_as!(
+        TweetDraft,
+        r#"
+        UPDATE tweet
+        SET deleted_at = now()
+        WHERE id = $1 AND user_id = $2 AND status IN ('draft', 'scheduled') AND deleted_at IS NULL
+        RETURNING id, content, status AS "status: TweetStatus", publish_at,
+                  created_at, updated_at, user_id,
+                  visibility AS "visibility: TweetVisibility"
+        "#,
+        id,
+        user_id
+    )
+    .fetch_optional(&mut *transaction)
+    .await
+    .context("Failed to delete draft")?;
+
+    transaction
+        .commit()
+        .await
+        .context("Failed to commit SQL transaction to delete a draft.")?;
+
+    Ok(deleted)
+}
+
+/// Publishes one of `user_id`'s drafts or scheduled tweets right away, once
+/// the content rules allowed it. A held draft is returned with status `Held`;
+/// a rejected one stays unpublished and fails with `RequestParameterInvalid`
+/// once the rule hits are committed.
+pub async fn publish_draft(
+    mut transaction: Transaction<'_, Postgres>,
+    user_id: Uuid,
+    id: Uuid,
+) -> Result<Option<Tweet>, anyhow::Error> {
+    let locked = sqlx::query!(
+        r#"
+        SELECT id
+        FROM tweet
+        WHERE id = $1 AND user_id = $2 AND status IN ('draft', 'scheduled') AND deleted_at IS NULL
+        FOR UPDATE
+        "#,
+        id,
+        user_id
+    )
+    .fetch_optional(&mut *transaction)
+    .await
+    .context("Failed to lock draft")?;
+
+    let publication = match locked {
+        Some(row) => Some(publish_locked_draft(&mut transaction, row.id).await?),
+        None => None,
+    };
+
+    transaction
+        .commit()
+        .await
+        .context("Failed to commit SQL transaction to publish a draft.")?;
+
+    match publication {
+        Some(Publication::Published(tweet)) | Some(Publication::Held(tweet)) => Ok(Some(tweet)),
+        Some(Publication::Rejected) => Err(AlohaError::RequestParameterInvalid(
+            "This tweet breaks the content rules.".to_string(),
+        )
+        .into()),
+        None => Ok(None),
+    }
+}
+
+/// Publishes up to `limit` scheduled tweets whose `publish_at` is not after
+/// `now`. Rows are claimed with `FOR UPDATE SKIP LOCKED`, so concurrent
+/// schedulers on other server instances pick disjoint batches and every tweet
+/// is published exactly once. Tweets held by the content rules wait for a
+/// moderator, and rejected ones are turned back into drafts for their author
+/// to fix; neither is returned. A tweet that fails to publish is logged and
+/// stays scheduled without holding up the rest of the batch.
+pub async fn publish_due_tweets(
+    mut transaction: Transaction<'_, Postgres>,
+    now: OffsetDateTime,
+    limit: i64,
+) -> Result<Vec<Tweet>, anyhow::Error> {
+    let ids: Vec<Uuid> = sqlx::query!(
+        r#"
+        SELECT id
+        FROM tweet
+        WHERE status = 'scheduled' AND publish_at <= $1 AND deleted_at IS NULL
+        ORDER BY publish_at, id
+        LIMIT $2
+        FOR UPDATE SKIP LOCKED
+        "#,
+        now,
+        limit
+    )
+    .fetch_all(&mut *transaction)
+    .await
+    .context("Failed to claim due tweets")?
+    .into_iter()
+    .map(|row| row.id)
+    .collect();
+
+    let mut tweets = Vec::with_capacity(ids.len());
+    for id in ids {
+        // A savepoint per tweet keeps one failing tweet from rolling back the
+        // whole batch; it stays scheduled and is retried on the next run.
+        let mut savepoint = transaction
+            .begin()
+            .await
+            .context("Failed to create savepoint to publish a due tweet")?;
+        match publish_due_tweet(&mut savepoint, id).await {
+            Ok(tweet) => {
+                savepoint
+                    .commit()
+                    .await
+                    .context("Failed to release savepoint of a due tweet")?;
+                tweets.extend(tweet);
+            }
+            Err(e) => {
+                error!("Failed to publish scheduled tweet {}: {:?}", id, e);
+                savepoint
+                    .rollback()
+                    .await
+                    .context("Failed to roll back savepoint of a due tweet")?;
+            }
+        }
+    }
+
+    transaction
+        .commit()
+        .await
+        .context("Failed to commit SQL transaction to publish due tweets.")?;
+
+    Ok(tweets)
+}
+
+/// Publishes the claimed scheduled tweet `id`, returning it once it went
+/// out. A rejected tweet is turned back into a draft.
+async fn publish_due_tweet(
+    transaction: &mut Transaction<'_, Postgres>,
+    id: Uuid,
+) -> Result<Option<Tweet>, anyhow::Error> {
+    match publish_locked_draft(transaction, id).await? {
+        Publication::Published(tweet) => Ok(Some(tweet)),
+        Publication::Held(_) => Ok(None),
+        Publication::Rejected => {
+            sqlx::query!(
+                "UPDATE tweet SET status = 'draft', publish_at = NULL WHERE id = $1",
+                id
+            )
+            .execute(&mut **transaction)
+            .await
+            .context("Failed to unschedule rejected tweet")?;
+            Ok(None)
+        }
+    }
+}
+
+/// What became of a draft screened for publication.
+enum Publication {
+    Published(Tweet),
+    Held(Tweet),
+    /// Not published; what happens to the draft is up to the caller.
+    Rejected,
+}
+
+/// Screens an already locked draft by the content rules, then flips it to
+/// `published` (or `held`) with the masked content, dating it at the moment
+/// of publication. Published tweets get their hashtags, mentions and links
+/// extracted, held ones are recorded for the moderators.
+async fn publish_locked_draft(
+    transaction: &mut Transaction<'_, Postgres>,
+    id: Uuid,
+) -> Result<Publication, anyhow::Error> {
+    let content = sqlx::query!("SELECT content FROM tweet WHERE id = $1", id)
+        .fetch_one(&mut **transaction)
+        .await
+        .context("Failed to fetch draft content")?
+        .content;
+    let evaluation = apply_content_rules(transaction, &content).await?;
+    let status = match evaluation.action {
+        Some(ContentRuleAction::Reject) => return Ok(Publication::Rejected),
+        Some(ContentRuleAction::Hold) => TweetStatus::Held,
+        _ => TweetStatus::Published,
+    };
+
+    let row = sqlx::query!(
+        r#"
+        UPDATE tweet
+        SET content = $2, status = $3, created_at = now()
+        WHERE id = $1
+        RETURNING id, content, created_at, updated_at, user_id,
+                  visibility AS "visibility: TweetVisibility"
+        "#,
+        id,
+        evaluation.content,
+        status as TweetStatus
+    )
+    .fetch_one(&mut **transaction)
+    .await
+    .context("Failed to publish tweet")?;
+
+    let tweet = Tweet {
+        id: row.id,
+        content: row.content,
+        created_at: row.created_at,
+        updated_at: row.updated_at,
+        user_id: row.user_id,
+        visibility: row.visibility,
+    };
+    if status == TweetStatus::Held {
+        record_held_tweet(transaction, tweet.id, tweet.user_id, &evaluation).await?;
+        return Ok(Publication::Held(tweet));
+    }
+    sync_tweet_hashtags(transaction, tweet.id, &tweet.content).await?;
+    sync_tweet_mentions(transaction, tweet.id, tweet.user_id, &tweet.content).await?;
+    sync_tweet_links(transaction, tweet.id, &tweet.content).await?;
+    Ok(Publication::Published(tweet))
+}
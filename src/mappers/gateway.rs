@@ -0,0 +1,109 @@
+use crate::jobs::gateway::GATEWAY_CHANNEL;
+use crate::models::gateway::{GatewayChannel, TypingSignal};
+use crate::models::tweet::TweetVisibility;
+use anyhow::Context;
+use sqlx::{Postgres, Transaction};
+use uuid::Uuid;
+
+/// Whether `viewer_id` may follow the tweets of `user_id`: the user exists
+/// and neither of them blocks the other.
+pub async fn check_user_channel_allowed(
+    mut transaction: Transaction<'_, Postgres>,
+    viewer_id: Uuid,
+    user_id: Uuid,
+) -> Result<bool, anyhow::Error> {
+    let record = sqlx::query!(
+        r#"
+        SELECT EXISTS(
+            SELECT 1 FROM users
+            WHERE id = $2 AND deleted_at IS NULL AND NOT users_blocked($1, $2)
+        ) AS "exists!"
+        "#,
+        viewer_id,
+        user_id
+    )
+    .fetch_one(&mut *transaction)
+    .await
+    .context("Failed to check user channel")?;
+
+    Ok(record.exists)
+}
+
+/// The channels a published tweet goes to: its author's, and the ones of its
+/// hashtags when it is public. Empty when the tweet is gone.
+pub async fn get_tweet_channels(
+    mut transaction: Transaction<'_, Postgres>,
+    tweet_id: Uuid,
+) -> Result<Vec<GatewayChannel>, anyhow::Error> {
+    let row = sqlx::query!(
+        r#"
+        SELECT t.user_id, t.visibility AS "visibility: TweetVisibility",
+               ARRAY(
+                   SELECT h.tag
+                   FROM tweet_hashtags th
+                   JOIN hashtags h ON h.id = th.hashtag_id
+                   WHERE th.tweet_id = t.id
+                   ORDER BY h.tag
+               ) AS "tags!"
+        FROM tweet t
+        WHERE t.id = $1 AND t.deleted_at IS NULL AND t.status = 'published'
+        "#,
+        tweet_id
+    )
+    .fetch_optional(&mut *transaction)
+    .await
+    .context("Failed to fetch tweet channels")?;
+
+    let Some(row) = row else {
+        return Ok(Vec::new());
+    };
+    let mut channels = vec![GatewayChannel::User(row.user_id)];
+    if row.visibility == TweetVisibility::Public {
+        channels.extend(row.tags.into_iter().map(GatewayChannel::Hashtag));
+    }
+    Ok(channels)
+}
+
+/// The users among `user_ids` allowed to read `tweet_id`.
+pub async fn get_tweet_viewers(
+    mut transaction: Transaction<'_, Postgres>,
+    tweet_id: Uuid,
+    user_ids: &[Uuid],
+) -> Result<Vec<Uuid>, anyhow::Error> {
+    let rows = sqlx::query!(
+        r#"
+        SELECT viewer.id AS "id!"
+        FROM UNNEST($2::uuid[]) AS viewer(id)
+        JOIN tweet t ON t.id = $1
+        WHERE t.deleted_at IS NULL AND t.status = 'published'
+        AND tweet_visible_to(t.user_id, t.visibility, viewer.id)
+        "#,
+        tweet_id,
+        user_ids
+    )
+    .fetch_all(&mut *transaction)
+    .await
+    .context("Failed to resolve tweet viewers")?;
+
+    Ok(rows.into_iter().map(|row| row.id).collect())
+}
+
+/// Announces a typing indicator to every server instance once the
+/// transaction commits.
+pub async fn announce_typing(
+    mut transaction: Transaction<'_, Postgres>,
+    signal: &TypingSignal,
+) -> Result<(), anyhow::Error> {
+    let payload = serde_json::to_string(signal)?;
+    sqlx::query!("SELECT pg_notify($1, $2)", GATEWAY_CHANNEL, payload)
+        .execute(&mut *transaction)
+        .await
+        .context("Failed to announce typing")?;
+
+    transaction
+        .commit()
+        .await
+        .context("Failed to commit SQL transaction to announce typing.")?;
+
+    Ok(())
+}
@@ -0,0 +1,170 @@
+use crate::configuration::HashtagSettings;
+use crate::dto::query::DtoQuery;
+use crate::dto::response::DtoResponse;
+use crate::dto::{pagination::Pagination, query::TweetFilterQuery};
+use crate::models::hashtag::{extract_hashtags, normalize_hashtag, Hashtag, TrendingHashtag};
+use crate::models::tweet::{Tweet, TweetVisibility};
+use anyhow::Context;
+use sqlx::{Postgres, Transaction};
+use uuid::Uuid;
+
+/// Replaces the hashtags linked to `tweet_id` with the ones found in
+/// `content`. Runs inside the caller's transaction so the links are committed
+/// together with the tweet itself.
+pub async fn sync_tweet_hashtags(
+    transaction: &mut Transaction<'_, Postgres>,
+    tweet_id: Uuid,
+    content: &str,
+) -> Result<Vec<Hashtag>, anyhow::Error> {
+    sqlx::query!("DELETE FROM tweet_hashtags WHERE tweet_id = $1", tweet_id)
+        .execute(&mut **transaction)
+        .await
+        .context("Failed to clear tweet hashtags")?;
+
+    let tags = extract_hashtags(content);
+    if tags.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let hashtags = sqlx::query_as!(
+        Hashtag,
+        r#"
+        INSERT INTO hashtags (tag)
+        SELECT UNNEST($1::varchar[])
+        ON CONFLICT (tag) DO UPDATE SET tag = EXCLUDED.tag
+        RETURNING id, tag, created_at
+        "#,
+        &tags
+    )
+    .fetch_all(&mut **transaction)
+    .await
+    .context("Failed to upsert hashtags")?;
+
+    let hashtag_ids: Vec<Uuid> = hashtags.iter().map(|h| h.id).collect();
+    sqlx::query!(
+        r#"
+        INSERT INTO tweet_hashtags (tweet_id, hashtag_id)
+        SELECT $1, UNNEST($2::uuid[])
+        "#,
+        tweet_id,
+        &hashtag_ids
+    )
+    .execute(&mut **transaction)
+    .await
+    .context("Failed to link tweet hashtags")?;
+
+    Ok(hashtags)
+}
+
+/// Public tweets tagged with `tag`, newest first. Restricted tweets and
+/// tweets of users blocking or blocked by `viewer_id` are left out of
+/// hashtag feeds.
+pub async fn get_tweets_by_hashtag(
+    mut transaction: Transaction<'_, Postgres>,
+    tag: &str,
+    dto_query: DtoQuery<TweetFilterQuery>,
+    viewer_id: Option<Uuid>,
+) -> Result<DtoResponse<Vec<Tweet>>, anyhow::Error> {
+    let tag = normalize_hashtag(tag);
+    let offset = dto_query.offset() as i64;
+    let limit = dto_query.size() as i64;
+    let user_id = dto_query.filter.as_ref().and_then(|f| f.user_id);
+
+    let total = sqlx::query!(
+        r#"
+        SELECT COUNT(*)
+        FROM tweet t
+        JOIN tweet_hashtags th ON th.tweet_id = t.id
+        JOIN hashtags h ON h.id = th.hashtag_id
+        WHERE h.tag = $1 AND ($2::uuid IS NULL OR t.user_id = $2) AND t.deleted_at IS NULL
+        AND t.status = 'published' AND t.visibility = 'public' AND NOT users_blocked(t.user_id, $3)
+        AND NOT user_banned(t.user_id)
+        "#,
+        tag,
+        user_id,
+        viewer_id
+    )
+    .fetch_one(&mut *transaction)
+    .await?
+    .count;
+
+    let rows = sqlx::query!(
+        r#"
+        SELECT t.id, t.content, t.created_at, t.updated_at, t.user_id,
+               t.visibility AS "visibility: TweetVisibility"
+        FROM tweet t
+        JOIN tweet_hashtags th ON th.tweet_id = t.id
+        JOIN hashtags h ON h.id = th.hashtag_id
+        WHERE h.tag = $1 AND ($2::uuid IS NULL OR t.user_id = $2) AND t.deleted_at IS NULL
+        AND t.status = 'published' AND t.visibility = 'public' AND NOT users_blocked(t.user_id, $5)
+        AND NOT user_banned(t.user_id)
+        ORDER BY t.created_at DESC, t.id DESC
+        LIMIT $3 OFFSET $4
+        "#,
+        tag,
+        user_id,
+        limit,
+        offset,
+        viewer_id
+    )
+    .fetch_all(&mut *transaction)
+    .await
+    .context("Failed to fetch tweets by hashtag")?;
+
+    let data: Vec<Tweet> = rows
+        .into_iter()
+        .map(|row| Tweet {
+            id: row.id,
+            content: row.content,
+            created_at: row.created_at,
+            updated_at: row.updated_at,
+            user_id: row.user_id,
+            visibility: row.visibility,
+        })
+        .collect();
+
+    let pagination = Pagination::new(Some(dto_query.page()), Some(dto_query.size()), total);
+    Ok(DtoResponse::new(data, Some(pagination)))
+}
+
+/// Ranks hashtags used by published public tweets created within the trending window.
+/// Every tweet contributes `0.5 ^ (age / half_life)`, so recent bursts outrank
+/// steady but older usage.
+pub async fn get_trending_hashtags(
+    mut transaction: Transaction<'_, Postgres>,
+    settings: &HashtagSettings,
+) -> Result<Vec<TrendingHashtag>, anyhow::Error> {
+    let window_seconds = settings.trending_window_hours as f64 * 3600.0;
+    let half_life_seconds = settings.trending_half_life_hours * 3600.0;
+
+    let rows = sqlx::query!(
+        r#"
+        SELECT h.tag,
+               COUNT(*) AS "uses!",
+               SUM(POWER(0.5, EXTRACT(EPOCH FROM (now() - t.created_at))::float8 / $2)) AS "score!"
+        FROM tweet_hashtags th
+        JOIN hashtags h ON h.id = th.hashtag_id
+        JOIN tweet t ON t.id = th.tweet_id
+        WHERE t.created_at >= now() - make_interval(secs => $1) AND t.deleted_at IS NULL
+        AND t.status = 'published' AND t.visibility = 'public' AND NOT user_banned(t.user_id)
+        GROUP BY h.tag
+        ORDER BY 3 DESC, h.tag
+        LIMIT $3
+        "#,
+        window_seconds,
+        half_life_seconds,
+        settings.trending_limit as i64
+    )
+    .fetch_all(&mut *transaction)
+    .await
+    .context("Failed to fetch trending hashtags")?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| TrendingHashtag {
+            tag: row.tag,
+            uses: row.uses,
+            score: row.score,
+        })
+        .collect())
+}
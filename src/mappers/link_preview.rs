@@ -0,0 +1,217 @@
+use crate::models::link_preview::{extract_urls, LinkPreview, LinkPreviewStatus, PreviewMetadata};
+use anyhow::Context;
+use sqlx::{Postgres, Transaction};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+/// A preview the link preview job claimed, with the attempts made so far
+/// including the current one.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ClaimedLinkPreview {
+    pub url: String,
+    pub attempts: i32,
+}
+
+/// Replaces the links stored for `tweet_id` with the ones found in `content`.
+/// Links seen for the first time are queued for the link preview job, while
+/// known ones reuse the cached preview. Runs inside the caller's transaction
+/// so the links are committed together with the tweet itself.
+pub async fn sync_tweet_links(
+    transaction: &mut Transaction<'_, Postgres>,
+    tweet_id: Uuid,
+    content: &str,
+) -> Result<Vec<String>, anyhow::Error> {
+    sqlx::query!("DELETE FROM tweet_links WHERE tweet_id = $1", tweet_id)
+        .execute(&mut **transaction)
+        .await
+        .context("Failed to clear tweet links")?;
+
+    let urls = extract_urls(content);
+    if urls.is_empty() {
+        return Ok(urls);
+    }
+
+    sqlx::query!(
+        r#"
+        INSERT INTO link_previews (url)
+        SELECT UNNEST($1::varchar[])
+        ON CONFLICT (url) DO NOTHING
+        "#,
+        &urls
+    )
+    .execute(&mut **transaction)
+    .await
+    .context("Failed to queue link previews")?;
+
+    sqlx::query!(
+        r#"
+        INSERT INTO tweet_links (tweet_id, url, position)
+        SELECT $1, u.url, u.position - 1
+        FROM UNNEST($2::varchar[]) WITH ORDINALITY AS u(url, position)
+        "#,
+        tweet_id,
+        &urls
+    )
+    .execute(&mut **transaction)
+    .await
+    .context("Failed to link tweet urls")?;
+
+    Ok(urls)
+}
+
+/// Claims up to `limit` pending previews that are due and still linked by a
+/// tweet. Each claim counts as an attempt and pushes the next one back by
+/// `retry_seconds` times the attempts made, so a crashed fetch is retried
+/// later and concurrent jobs skip each other's rows.
+pub async fn claim_link_previews(
+    mut transaction: Transaction<'_, Postgres>,
+    limit: i64,
+    retry_seconds: i32,
+) -> Result<Vec<ClaimedLinkPreview>, anyhow::Error> {
+    let claimed = sqlx::query_as!(
+        ClaimedLinkPreview,
+        r#"
+        UPDATE link_previews
+        SET attempts = attempts + 1,
+            next_attempt_at = now() + make_interval(secs => $2::int * (attempts + 1))
+        WHERE url IN (
+            SELECT lp.url FROM link_previews lp
+            WHERE lp.status = 'pending' AND lp.next_attempt_at <= now()
+            AND EXISTS(SELECT 1 FROM tweet_links tl WHERE tl.url = lp.url)
+            ORDER BY lp.next_attempt_at
+            LIMIT $1
+            FOR UPDATE SKIP LOCKED
+        )
+        RETURNING url, attempts
+        "#,
+        limit,
+        retry_seconds
+    )
+    .fetch_all(&mut *transaction)
+    .await
+    .context("Failed to claim link previews")?;
+
+    transaction
+        .commit()
+        .await
+        .context("Failed to commit SQL transaction to claim link previews.")?;
+
+    Ok(claimed)
+}
+
+/// Stores the fetched metadata of `url` and makes its card available.
+pub async fn save_link_preview(
+    mut transaction: Transaction<'_, Postgres>,
+    url: &str,
+    metadata: &PreviewMetadata,
+) -> Result<Option<LinkPreview>, anyhow::Error> {
+    let preview = sqlx::query_as!(
+        LinkPreview,
+        r#"
+        UPDATE link_previews
+        SET status = 'ready', title = $2, description = $3, image_url = $4, site_name = $5,
+            fetched_at = now()
+        WHERE url = $1
+        RETURNING url, status AS "status: LinkPreviewStatus", title, description, image_url,
+                  site_name, fetched_at
+        "#,
+        url,
+        metadata.title,
+        metadata.description,
+        metadata.image_url,
+        metadata.site_name
+    )
+    .fetch_optional(&mut *transaction)
+    .await
+    .context("Failed to save link preview")?;
+
+    transaction
+        .commit()
+        .await
+        .context("Failed to commit SQL transaction to save a link preview.")?;
+
+    Ok(preview)
+}
+
+/// Gives up on `url`; its tweets are shown without a card.
+pub async fn fail_link_preview(
+    mut transaction: Transaction<'_, Postgres>,
+    url: &str,
+) -> Result<(), anyhow::Error> {
+    sqlx::query!(
+        "UPDATE link_previews SET status = 'failed', fetched_at = now() WHERE url = $1",
+        url
+    )
+    .execute(&mut *transaction)
+    .await
+    .context("Failed to mark link preview failed")?;
+
+    transaction
+        .commit()
+        .await
+        .context("Failed to commit SQL transaction to fail a link preview.")?;
+
+    Ok(())
+}
+
+pub async fn get_link_preview(
+    mut transaction: Transaction<'_, Postgres>,
+    url: &str,
+) -> Result<Option<LinkPreview>, anyhow::Error> {
+    let preview = sqlx::query_as!(
+        LinkPreview,
+        r#"
+        SELECT url, status AS "status: LinkPreviewStatus", title, description, image_url,
+               site_name, fetched_at
+        FROM link_previews
+        WHERE url = $1
+        "#,
+        url
+    )
+    .fetch_optional(&mut *transaction)
+    .await
+    .context("Failed to fetch link preview")?;
+
+    Ok(preview)
+}
+
+/// Loads the card of a batch of tweets with one query: the preview of the
+/// first link of each tweet whose preview is ready.
+pub async fn get_link_cards_by_tweet_ids(
+    transaction: &mut Transaction<'_, Postgres>,
+    tweet_ids: &[Uuid],
+) -> Result<HashMap<Uuid, LinkPreview>, anyhow::Error> {
+    let rows = sqlx::query!(
+        r#"
+        SELECT DISTINCT ON (tl.tweet_id)
+               tl.tweet_id, lp.url, lp.status AS "status: LinkPreviewStatus", lp.title,
+               lp.description, lp.image_url, lp.site_name, lp.fetched_at
+        FROM tweet_links tl
+        JOIN link_previews lp ON lp.url = tl.url
+        WHERE tl.tweet_id = ANY($1) AND lp.status = 'ready'
+        ORDER BY tl.tweet_id, tl.position
+        "#,
+        tweet_ids
+    )
+    .fetch_all(&mut **transaction)
+    .await
+    .context("Failed to fetch link cards")?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| {
+            (
+                row.tweet_id,
+                LinkPreview {
+                    url: row.url,
+                    status: row.status,
+                    title: row.title,
+                    description: row.description,
+                    image_url: row.image_url,
+                    site_name: row.site_name,
+                    fetched_at: row.fetched_at,
+                },
+            )
+        })
+        .collect())
+}
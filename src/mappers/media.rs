@@ -0,0 +1,255 @@
+use crate::models::media::{Media, MediaVariant};
+use anyhow::Context;
+use sqlx::{Postgres, Transaction};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+/// Stores the metadata of an upload. Uploading the same content twice returns
+/// the row created by the first upload.
+pub async fn insert_media(
+    mut transaction: Transaction<'_, Postgres>,
+    media: &Media,
+) -> Result<Media, anyhow::Error> {
+    let media = sqlx::query_as!(
+        Media,
+        r#"
+        INSERT INTO media (id, user_id, sha256, mime_type, size_bytes, storage_key)
+        VALUES ($1, $2, $3, $4, $5, $6)
+        ON CONFLICT (user_id, sha256) DO UPDATE SET sha256 = EXCLUDED.sha256
+        RETURNING id, user_id, sha256, mime_type, size_bytes, storage_key, created_at,
+                  processed_at
+        "#,
+        media.id,
+        media.user_id,
+        media.sha256,
+        media.mime_type,
+        media.size_bytes,
+        media.storage_key
+    )
+    .fetch_one(&mut *transaction)
+    .await
+    .context("Failed to insert media")?;
+
+    transaction
+        .commit()
+        .await
+        .context("Failed to commit SQL transaction to insert media.")?;
+
+    Ok(media)
+}
+
+pub async fn get_media_by_id(
+    mut transaction: Transaction<'_, Postgres>,
+    id: Uuid,
+) -> Result<Option<Media>, anyhow::Error> {
+    let media = sqlx::query_as!(
+        Media,
+        r#"
+        SELECT id, user_id, sha256, mime_type, size_bytes, storage_key, created_at,
+               processed_at
+        FROM media
+        WHERE id = $1
+        "#,
+        id
+    )
+    .fetch_optional(&mut *transaction)
+    .await
+    .context("Failed to fetch media")?;
+
+    Ok(media)
+}
+
+/// Records that the stored original of `id` no longer carries metadata.
+pub async fn mark_media_processed(
+    mut transaction: Transaction<'_, Postgres>,
+    id: Uuid,
+) -> Result<(), anyhow::Error> {
+    sqlx::query!("UPDATE media SET processed_at = now() WHERE id = $1", id)
+        .execute(&mut *transaction)
+        .await
+        .context("Failed to mark media processed")?;
+
+    transaction
+        .commit()
+        .await
+        .context("Failed to commit SQL transaction to mark media processed.")?;
+
+    Ok(())
+}
+
+/// Whether content with this hash has already been written to the store by
+/// any user.
+pub async fn media_content_exists(
+    mut transaction: Transaction<'_, Postgres>,
+    sha256: &str,
+) -> Result<bool, anyhow::Error> {
+    let exists = sqlx::query!(
+        r#"SELECT EXISTS(SELECT 1 FROM media WHERE sha256 = $1) AS "exists!""#,
+        sha256
+    )
+    .fetch_one(&mut *transaction)
+    .await
+    .context("Failed to look up media by hash")?
+    .exists;
+
+    Ok(exists)
+}
+
+/// Attaches `media_ids` to a tweet in the given order. Every id must belong
+/// to an upload of `user_id`.
+pub async fn attach_tweet_media(
+    transaction: &mut Transaction<'_, Postgres>,
+    tweet_id: Uuid,
+    user_id: Uuid,
+    media_ids: &[Uuid],
+) -> Result<(), anyhow::Error> {
+    let owned = sqlx::query!(
+        r#"SELECT COUNT(*) AS "count!" FROM media WHERE id = ANY($1) AND user_id = $2"#,
+        media_ids,
+        user_id
+    )
+    .fetch_one(&mut **transaction)
+    .await
+    .context("Failed to check media ownership")?
+    .count;
+    let mut unique_ids = media_ids.to_vec();
+    unique_ids.sort();
+    unique_ids.dedup();
+    if owned as usize != unique_ids.len() || unique_ids.len() != media_ids.len() {
+        anyhow::bail!("Media ids must be distinct uploads of the tweet author");
+    }
+
+    sqlx::query!(
+        r#"
+        INSERT INTO tweet_media (tweet_id, media_id, position)
+        SELECT $1, media_id, (position - 1)::int
+        FROM UNNEST($2::uuid[]) WITH ORDINALITY AS m(media_id, position)
+        "#,
+        tweet_id,
+        media_ids
+    )
+    .execute(&mut **transaction)
+    .await
+    .context("Failed to attach media to tweet")?;
+
+    Ok(())
+}
+
+/// Loads the attached media of several tweets at once, keyed by tweet id and
+/// in attachment order.
+pub async fn get_media_by_tweet_ids(
+    transaction: &mut Transaction<'_, Postgres>,
+    tweet_ids: &[Uuid],
+) -> Result<HashMap<Uuid, Vec<Media>>, anyhow::Error> {
+    let rows = sqlx::query!(
+        r#"
+        SELECT tm.tweet_id, m.id, m.user_id, m.sha256, m.mime_type, m.size_bytes,
+               m.storage_key, m.created_at, m.processed_at
+        FROM tweet_media tm
+        JOIN media m ON m.id = tm.media_id
+        WHERE tm.tweet_id = ANY($1)
+        ORDER BY tm.tweet_id, tm.position
+        "#,
+        tweet_ids
+    )
+    .fetch_all(&mut **transaction)
+    .await
+    .context("Failed to fetch tweet media")?;
+
+    let mut media: HashMap<Uuid, Vec<Media>> = HashMap::new();
+    for row in rows {
+        media.entry(row.tweet_id).or_default().push(Media {
+            id: row.id,
+            user_id: row.user_id,
+            sha256: row.sha256,
+            mime_type: row.mime_type,
+            size_bytes: row.size_bytes,
+            storage_key: row.storage_key,
+            created_at: row.created_at,
+            processed_at: row.processed_at,
+        });
+    }
+    Ok(media)
+}
+
+pub async fn insert_media_variant(
+    mut transaction: Transaction<'_, Postgres>,
+    variant: &MediaVariant,
+) -> Result<MediaVariant, anyhow::Error> {
+    let variant = sqlx::query_as!(
+        MediaVariant,
+        r#"
+        INSERT INTO media_variants (media_id, name, storage_key, mime_type, width, height, size_bytes)
+        VALUES ($1, $2, $3, $4, $5, $6, $7)
+        ON CONFLICT (media_id, name) DO UPDATE
+        SET storage_key = EXCLUDED.storage_key, mime_type = EXCLUDED.mime_type,
+            width = EXCLUDED.width, height = EXCLUDED.height, size_bytes = EXCLUDED.size_bytes
+        RETURNING media_id, name, storage_key, mime_type, width, height, size_bytes, created_at
+        "#,
+        variant.media_id,
+        variant.name,
+        variant.storage_key,
+        variant.mime_type,
+        variant.width,
+        variant.height,
+        variant.size_bytes
+    )
+    .fetch_one(&mut *transaction)
+    .await
+    .context("Failed to insert media variant")?;
+
+    transaction
+        .commit()
+        .await
+        .context("Failed to commit SQL transaction to insert a media variant.")?;
+
+    Ok(variant)
+}
+
+pub async fn get_media_variant(
+    mut transaction: Transaction<'_, Postgres>,
+    media_id: Uuid,
+    name: &str,
+) -> Result<Option<MediaVariant>, anyhow::Error> {
+    let variant = sqlx::query_as!(
+        MediaVariant,
+        r#"
+        SELECT media_id, name, storage_key, mime_type, width, height, size_bytes, created_at
+        FROM media_variants
+        WHERE media_id = $1 AND name = $2
+        "#,
+        media_id,
+        name
+    )
+    .fetch_optional(&mut *transaction)
+    .await
+    .context("Failed to fetch media variant")?;
+
+    Ok(variant)
+}
+
+/// Loads the variants of several media items at once, keyed by media id.
+pub async fn get_variants_by_media_ids(
+    transaction: &mut Transaction<'_, Postgres>,
+    media_ids: &[Uuid],
+) -> Result<HashMap<Uuid, Vec<MediaVariant>>, anyhow::Error> {
+    let variants = sqlx::query_as!(
+        MediaVariant,
+        r#"
+        SELECT media_id, name, storage_key, mime_type, width, height, size_bytes, created_at
+        FROM media_variants
+        WHERE media_id = ANY($1)
+        ORDER BY media_id, width, name
+        "#,
+        media_ids
+    )
+    .fetch_all(&mut **transaction)
+    .await
+    .context("Failed to fetch media variants")?;
+
+    let mut grouped: HashMap<Uuid, Vec<MediaVariant>> = HashMap::new();
+    for variant in variants {
+        grouped.entry(variant.media_id).or_default().push(variant);
+    }
+    Ok(grouped)
+}
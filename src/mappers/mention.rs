@@ -0,0 +1,194 @@
+use crate::dto::query::DtoQuery;
+use crate::dto::response::DtoResponse;
+use crate::dto::{pagination::Pagination, query::TweetFilterQuery};
+use crate::models::mention::{extract_mentions, MentionEntity, TweetMention};
+use crate::models::tweet::{Tweet, TweetVisibility};
+use anyhow::Context;
+use sqlx::{Postgres, Transaction};
+use std::collections::{HashMap, HashSet};
+use uuid::Uuid;
+
+use super::notification::notify_mentioned_users;
+
+/// Replaces the mentions stored for `tweet_id` with the `@username`s in
+/// `content` that belong to existing users. Unknown usernames, and users
+/// blocking or blocked by `author_id`, are left as plain text. Users who were
+/// not mentioned by the previous version of the tweet are notified.
+pub async fn sync_tweet_mentions(
+    transaction: &mut Transaction<'_, Postgres>,
+    tweet_id: Uuid,
+    author_id: Uuid,
+    content: &str,
+) -> Result<Vec<TweetMention>, anyhow::Error> {
+    let previous: HashSet<Uuid> = sqlx::query!(
+        "DELETE FROM tweet_mentions WHERE tweet_id = $1 RETURNING user_id",
+        tweet_id
+    )
+    .fetch_all(&mut **transaction)
+    .await
+    .context("Failed to clear tweet mentions")?
+    .into_iter()
+    .map(|row| row.user_id)
+    .collect();
+
+    let candidates = extract_mentions(content);
+    if candidates.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let usernames: Vec<String> = candidates.iter().map(|c| c.username.clone()).collect();
+    let users: HashMap<String, Uuid> = sqlx::query!(
+        r#"
+        SELECT id, username
+        FROM users
+        WHERE username = ANY($1) AND deleted_at IS NULL AND NOT users_blocked(id, $2)
+        "#,
+        &usernames,
+        author_id
+    )
+    .fetch_all(&mut **transaction)
+    .await
+    .context("Failed to resolve mentioned usernames")?
+    .into_iter()
+    .map(|row| (row.username, row.id))
+    .collect();
+
+    let mut user_ids = Vec::new();
+    let mut start_indexes = Vec::new();
+    let mut end_indexes = Vec::new();
+    for candidate in &candidates {
+        if let Some(user_id) = users.get(&candidate.username) {
+            user_ids.push(*user_id);
+            start_indexes.push(candidate.start as i32);
+            end_indexes.push(candidate.end as i32);
+        }
+    }
+
+    let mentions = sqlx::query_as!(
+        TweetMention,
+        r#"
+        INSERT INTO tweet_mentions (tweet_id, user_id, start_index, end_index)
+        SELECT $1, * FROM UNNEST($2::uuid[], $3::int[], $4::int[])
+        RETURNING tweet_id, user_id, start_index, end_index, created_at
+        "#,
+        tweet_id,
+        &user_ids,
+        &start_indexes,
+        &end_indexes
+    )
+    .fetch_all(&mut **transaction)
+    .await
+    .context("Failed to insert tweet mentions")?;
+
+    let mut newly_mentioned: Vec<Uuid> = user_ids
+        .into_iter()
+        .filter(|user_id| !previous.contains(user_id))
+        .collect();
+    newly_mentioned.sort();
+    newly_mentioned.dedup();
+    notify_mentioned_users(transaction, tweet_id, &newly_mentioned).await?;
+
+    Ok(mentions)
+}
+
+/// Loads the mention entities of several tweets at once, keyed by tweet id.
+pub async fn get_mentions_by_tweet_ids(
+    transaction: &mut Transaction<'_, Postgres>,
+    tweet_ids: &[Uuid],
+) -> Result<HashMap<Uuid, Vec<MentionEntity>>, anyhow::Error> {
+    let rows = sqlx::query!(
+        r#"
+        SELECT m.tweet_id, m.user_id, u.username, m.start_index, m.end_index
+        FROM tweet_mentions m
+        JOIN users u ON u.id = m.user_id
+        WHERE m.tweet_id = ANY($1) AND u.deleted_at IS NULL
+        ORDER BY m.tweet_id, m.start_index
+        "#,
+        tweet_ids
+    )
+    .fetch_all(&mut **transaction)
+    .await
+    .context("Failed to fetch tweet mentions")?;
+
+    let mut mentions: HashMap<Uuid, Vec<MentionEntity>> = HashMap::new();
+    for row in rows {
+        mentions
+            .entry(row.tweet_id)
+            .or_default()
+            .push(MentionEntity {
+                user_id: row.user_id,
+                username: row.username,
+                start: row.start_index,
+                end: row.end_index,
+            });
+    }
+    Ok(mentions)
+}
+
+/// Public tweets mentioning `user_id`, newest first, leaving out those of
+/// users blocking or blocked by `viewer_id`.
+pub async fn get_tweets_mentioning_user(
+    mut transaction: Transaction<'_, Postgres>,
+    user_id: Uuid,
+    dto_query: DtoQuery<TweetFilterQuery>,
+    viewer_id: Option<Uuid>,
+) -> Result<DtoResponse<Vec<Tweet>>, anyhow::Error> {
+    let offset = dto_query.offset() as i64;
+    let limit = dto_query.size() as i64;
+    let author_id = dto_query.filter.as_ref().and_then(|f| f.user_id);
+
+    let total = sqlx::query!(
+        r#"
+        SELECT COUNT(DISTINCT t.id)
+        FROM tweet t
+        JOIN tweet_mentions m ON m.tweet_id = t.id
+        WHERE m.user_id = $1 AND ($2::uuid IS NULL OR t.user_id = $2) AND t.deleted_at IS NULL
+        AND t.status = 'published' AND t.visibility = 'public' AND NOT users_blocked(t.user_id, $3)
+        AND NOT user_banned(t.user_id)
+        "#,
+        user_id,
+        author_id,
+        viewer_id
+    )
+    .fetch_one(&mut *transaction)
+    .await?
+    .count;
+
+    let rows = sqlx::query!(
+        r#"
+        SELECT t.id, t.content, t.created_at, t.updated_at, t.user_id,
+               t.visibility AS "visibility: TweetVisibility"
+        FROM tweet t
+        WHERE t.id IN (SELECT tweet_id FROM tweet_mentions WHERE user_id = $1)
+        AND ($2::uuid IS NULL OR t.user_id = $2)
+        AND t.deleted_at IS NULL AND t.status = 'published' AND t.visibility = 'public'
+        AND NOT users_blocked(t.user_id, $5)
+        AND NOT user_banned(t.user_id)
+        ORDER BY t.created_at DESC, t.id DESC
+        LIMIT $3 OFFSET $4
+        "#,
+        user_id,
+        author_id,
+        limit,
+        offset,
+        viewer_id
+    )
+    .fetch_all(&mut *transaction)
+    .await
+    .context("Failed to fetch tweets mentioning user")?;
+
+    let data: Vec<Tweet> = rows
+        .into_iter()
+        .map(|row| Tweet {
+            id: row.id,
+            content: row.content,
+            created_at: row.created_at,
+            updated_at: row.updated_at,
+            user_id: row.user_id,
+            visibility: row.visibility,
+        })
+        .collect();
+
+    let pagination = Pagination::new(Some(dto_query.page()), Some(dto_query.size()), total);
+    Ok(DtoResponse::new(data, Some(pagination)))
+}
@@ -0,0 +1,302 @@
+use crate::dto::pagination::Pagination;
+use crate::dto::query::{DtoQuery, ModerationActionFilterQuery, TweetFilterQuery};
+use crate::dto::response::DtoResponse;
+use crate::error::AlohaError;
+use crate::models::account::{AccountState, AccountStatus};
+use crate::models::content_rule::ContentRuleEvaluation;
+use crate::models::moderation::{ModerationAction, ModerationActionKind};
+use crate::models::report::ReportStatus;
+use crate::models::tweet::{Tweet, TweetVisibility};
+use anyhow::Context;
+use sqlx::{Postgres, Transaction};
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+use super::account::set_account_status;
+use super::hashtag::sync_tweet_hashtags;
+use super::link_preview::sync_tweet_links;
+use super::mention::sync_tweet_mentions;
+use super::report::resolve_report;
+use super::tweet::unpin_tweets;
+
+/// Appends an entry to the moderation log inside the caller's transaction.
+pub async fn insert_moderation_action(
+    transaction: &mut Transaction<'_, Postgres>,
+    action: &ModerationAction,
+) -> Result<ModerationAction, anyhow::Error> {
+    let row = sqlx::query_as!(
+        ModerationAction,
+        r#"
+        INSERT INTO moderation_actions (id, moderator_id, action, report_id, tweet_id, user_id, note)
+        VALUES ($1, $2, $3, $4, $5, $6, $7)
+        RETURNING id, moderator_id, action AS "action: ModerationActionKind", report_id,
+                  tweet_id, user_id, note, created_at
+        "#,
+        action.id,
+        action.moderator_id,
+        action.action as ModerationActionKind,
+        action.report_id,
+        action.tweet_id,
+        action.user_id,
+        action.note
+    )
+    .fetch_one(&mut **transaction)
+    .await
+    .context("Failed to insert moderation action")?;
+
+    Ok(row)
+}
+
+/// Marks the report a moderator action answers as actioned.
+async fn action_report(
+    transaction: &mut Transaction<'_, Postgres>,
+    action: &ModerationAction,
+) -> Result<(), anyhow::Error> {
+    let (Some(report_id), Some(moderator_id)) = (action.report_id, action.moderator_id) else {
+        return Ok(());
+    };
+    let report =
+        resolve_report(transaction, report_id, moderator_id, ReportStatus::Actioned).await?;
+    if report.is_none() {
+        return Err(AlohaError::RequestParameterInvalid("Unknown report.".to_string()).into());
+    }
+    Ok(())
+}
+
+/// Hides `action.tweet_id` by soft-deleting it, so it drops out of every
+/// listing until an admin restores it. Held tweets can be hidden as well.
+/// Returns `None` when the tweet is neither live nor held.
+pub async fn hide_tweet(
+    mut transaction: Transaction<'_, Postgres>,
+    action: &ModerationAction,
+) -> Result<Option<ModerationAction>, anyhow::Error> {
+    let row = sqlx::query!(
+        r#"
+        UPDATE tweet
+        SET deleted_at = now()
+        WHERE id = $1 AND deleted_at IS NULL AND status IN ('published', 'held')
+        RETURNING id
+        "#,
+        action.tweet_id
+    )
+    .fetch_optional(&mut *transaction)
+    .await
+    .context("Failed to hide tweet")?;
+
+    let Some(row) = row else {
+        return Ok(None);
+    };
+    unpin_tweets(&mut transaction, &[row.id]).await?;
+    action_report(&mut transaction, action).await?;
+    let action = insert_moderation_action(&mut transaction, action).await?;
+
+    transaction
+        .commit()
+        .await
+        .context("Failed to commit SQL transaction to hide a tweet.")?;
+
+    Ok(Some(action))
+}
+
+/// Logs that `tweet_id` was held by the content rules inside the caller's
+/// transaction, noting the rules it matched.
+pub async fn record_held_tweet(
+    transaction: &mut Transaction<'_, Postgres>,
+    tweet_id: Uuid,
+    user_id: Uuid,
+    evaluation: &ContentRuleEvaluation,
+) -> Result<ModerationAction, anyhow::Error> {
+    let rule_ids: Vec<String> = evaluation
+        .matched_rule_ids
+        .iter()
+        .map(|id| id.to_string())
+        .collect();
+    let action = ModerationAction {
+        tweet_id: Some(tweet_id),
+        user_id: Some(user_id),
+        note: Some(format!("Matched content rules: {}", rule_ids.join(", "))),
+        ..ModerationAction::automated(ModerationActionKind::HoldTweet)
+    };
+    insert_moderation_action(transaction, &action).await
+}
+
+/// Publishes the held tweet `action.tweet_id` and extracts its hashtags and
+/// mentions. Returns `None` when the tweet is not held.
+pub async fn release_tweet(
+    mut transaction: Transaction<'_, Postgres>,
+    action: &ModerationAction,
+) -> Result<Option<ModerationAction>, anyhow::Error> {
+    let row = sqlx::query!(
+        r#"
+        UPDATE tweet
+        SET status = 'published'
+        WHERE id = $1 AND deleted_at IS NULL AND status = 'held'
+        RETURNING id, content, user_id
+        "#,
+        action.tweet_id
+    )
+    .fetch_optional(&mut *transaction)
+    .await
+    .context("Failed to release tweet")?;
+
+    let Some(row) = row else {
+        return Ok(None);
+    };
+    sync_tweet_hashtags(&mut transaction, row.id, &row.content).await?;
+    sync_tweet_mentions(&mut transaction, row.id, row.user_id, &row.content).await?;
+    sync_tweet_links(&mut transaction, row.id, &row.content).await?;
+    action_report(&mut transaction, action).await?;
+    let action = insert_moderation_action(&mut transaction, action).await?;
+
+    transaction
+        .commit()
+        .await
+        .context("Failed to commit SQL transaction to release a tweet.")?;
+
+    Ok(Some(action))
+}
+
+/// Tweets held by the content rules, oldest first.
+pub async fn get_held_tweets(
+    mut transaction: Transaction<'_, Postgres>,
+    dto_query: DtoQuery<TweetFilterQuery>,
+) -> Result<DtoResponse<Vec<Tweet>>, anyhow::Error> {
+    let offset = dto_query.offset() as i64;
+    let limit = dto_query.size() as i64;
+    let author_id = dto_query.filter.as_ref().and_then(|f| f.user_id);
+
+    let total = sqlx::query!(
+        r#"
+        SELECT COUNT(*)
+        FROM tweet
+        WHERE status = 'held' AND deleted_at IS NULL
+        AND ($1::uuid IS NULL OR user_id = $1)
+        "#,
+        author_id
+    )
+    .fetch_one(&mut *transaction)
+    .await?
+    .count;
+
+    let rows = sqlx::query!(
+        r#"
+        SELECT id, content, created_at, updated_at, user_id,
+               visibility AS "visibility: TweetVisibility"
+        FROM tweet
+        WHERE status = 'held' AND deleted_at IS NULL
+        AND ($1::uuid IS NULL OR user_id = $1)
+        ORDER BY created_at, id
+        LIMIT $2 OFFSET $3
+        "#,
+        author_id,
+        limit,
+        offset
+    )
+    .fetch_all(&mut *transaction)
+    .await
+    .context("Failed to fetch held tweets")?;
+
+    let data = rows
+        .into_iter()
+        .map(|row| Tweet {
+            id: row.id,
+            content: row.content,
+            created_at: row.created_at,
+            updated_at: row.updated_at,
+            user_id: row.user_id,
+            visibility: row.visibility,
+        })
+        .collect();
+
+    let pagination = Pagination::new(Some(dto_query.page()), Some(dto_query.size()), total);
+    Ok(DtoResponse::new(data, Some(pagination)))
+}
+
+/// Keeps `action.user_id` from logging in until `until`, with `action.note`
+/// as the reason. Returns `None` for unknown users.
+pub async fn suspend_user(
+    transaction: Transaction<'_, Postgres>,
+    action: &ModerationAction,
+    until: OffsetDateTime,
+) -> Result<Option<ModerationAction>, anyhow::Error> {
+    let result =
+        apply_account_action(transaction, action, AccountStatus::Suspended, Some(until)).await?;
+    Ok(result.map(|(_, action)| action))
+}
+
+/// Moves the account of `action.user_id` to `status`, with `action.note` as
+/// the reason, and logs the change. Returns `None` for unknown users.
+pub async fn change_account_status(
+    transaction: Transaction<'_, Postgres>,
+    action: &ModerationAction,
+    status: AccountStatus,
+    suspended_until: Option<OffsetDateTime>,
+) -> Result<Option<AccountState>, anyhow::Error> {
+    let result = apply_account_action(transaction, action, status, suspended_until).await?;
+    Ok(result.map(|(state, _)| state))
+}
+
+async fn apply_account_action(
+    mut transaction: Transaction<'_, Postgres>,
+    action: &ModerationAction,
+    status: AccountStatus,
+    suspended_until: Option<OffsetDateTime>,
+) -> Result<Option<(AccountState, ModerationAction)>, anyhow::Error> {
+    let Some(user_id) = action.user_id else {
+        return Ok(None);
+    };
+    let state = set_account_status(
+        &mut transaction,
+        user_id,
+        status,
+        action.note.clone(),
+        suspended_until,
+    )
+    .await?;
+
+    let Some(state) = state else {
+        return Ok(None);
+    };
+    action_report(&mut transaction, action).await?;
+    let action = insert_moderation_action(&mut transaction, action).await?;
+
+    transaction
+        .commit()
+        .await
+        .context("Failed to commit SQL transaction to change an account status.")?;
+
+    Ok(Some((state, action)))
+}
+
+/// The moderation log, most recent first.
+pub async fn get_moderation_actions(
+    mut transaction: Transaction<'_, Postgres>,
+    dto_query: DtoQuery<ModerationActionFilterQuery>,
+) -> Result<DtoResponse<Vec<ModerationAction>>, anyhow::Error> {
+    let offset = dto_query.offset() as i64;
+    let limit = dto_query.size() as i64;
+
+    let total = sqlx::query!("SELECT COUNT(*) FROM moderation_actions")
+        .fetch_one(&mut *transaction)
+        .await?
+        .count;
+
+    let data = sqlx::query_as!(
+        ModerationAction,
+        r#"
+        SELECT id, moderator_id, action AS "action: ModerationActionKind", report_id,
+               tweet_id, user_id, note, created_at
+        FROM moderation_actions
+        ORDER BY created_at DESC, id
+        LIMIT $1 OFFSET $2
+        "#,
+        limit,
+        offset
+    )
+    .fetch_all(&mut *transaction)
+    .await
+    .context("Failed to fetch moderation actions")?;
+
+    let pagination = Pagination::new(Some(dto_query.page()), Some(dto_query.size()), total);
+    Ok(DtoResponse::new(data, Some(pagination)))
+}
@@ -0,0 +1,118 @@
+use crate::dto::pagination::Pagination;
+use crate::dto::query::{DtoQuery, MuteFilterQuery};
+use crate::dto::response::DtoResponse;
+use crate::error::AlohaError;
+use crate::models::mute::Mute;
+use crate::models::user::User;
+use anyhow::Context;
+use sqlx::{Postgres, Transaction};
+use uuid::Uuid;
+
+use super::user::check_user_id_is_valid;
+
+/// Mutes `mute.muted_id` for `mute.muter_id`. Muting twice is a no-op.
+pub async fn insert_mute(
+    mut transaction: Transaction<'_, Postgres>,
+    mute: &Mute,
+) -> Result<Mute, anyhow::Error> {
+    let is_user_valid = check_user_id_is_valid(&mut transaction, mute.muted_id).await?;
+    if !is_user_valid {
+        return Err(AlohaError::UserIdInvalid.into());
+    }
+
+    let row = sqlx::query_as!(
+        Mute,
+        r#"
+        INSERT INTO mutes (muter_id, muted_id)
+        VALUES ($1, $2)
+        ON CONFLICT (muter_id, muted_id)
+        DO UPDATE SET muter_id = EXCLUDED.muter_id
+        RETURNING muter_id, muted_id, created_at
+        "#,
+        mute.muter_id,
+        mute.muted_id
+    )
+    .fetch_one(&mut *transaction)
+    .await
+    .context("Failed to insert mute")?;
+
+    transaction
+        .commit()
+        .await
+        .context("Failed to commit SQL transaction to insert a new mute.")?;
+
+    Ok(row)
+}
+
+pub async fn delete_mute(
+    mut transaction: Transaction<'_, Postgres>,
+    muter_id: Uuid,
+    muted_id: Uuid,
+) -> Result<Option<Mute>, anyhow::Error> {
+    let row = sqlx::query_as!(
+        Mute,
+        r#"
+        DELETE FROM mutes
+        WHERE muter_id = $1 AND muted_id = $2
+        RETURNING muter_id, muted_id, created_at
+        "#,
+        muter_id,
+        muted_id
+    )
+    .fetch_optional(&mut *transaction)
+    .await
+    .context("Failed to delete mute")?;
+
+    transaction
+        .commit()
+        .await
+        .context("Failed to commit SQL transaction to delete a mute.")?;
+
+    Ok(row)
+}
+
+/// Users muted by `muter_id`, most recently muted first.
+pub async fn get_muted_users(
+    mut transaction: Transaction<'_, Postgres>,
+    muter_id: Uuid,
+    dto_query: DtoQuery<MuteFilterQuery>,
+) -> Result<DtoResponse<Vec<User>>, anyhow::Error> {
+    let offset = dto_query.offset() as i64;
+    let limit = dto_query.size() as i64;
+
+    let total = sqlx::query!(
+        r#"
+        SELECT COUNT(*)
+        FROM mutes m
+        JOIN users u ON u.id = m.muted_id
+        WHERE m.muter_id = $1 AND u.deleted_at IS NULL
+        "#,
+        muter_id
+    )
+    .fetch_one(&mut *transaction)
+    .await?
+    .count;
+
+    let users = sqlx::query_as!(
+        User,
+        r#"
+        SELECT u.id, u.username, u.password_hash, u.created_at, u.user_group_id,
+               u.display_name, u.bio, u.location, u.website, u.avatar_media_id,
+               u.banner_media_id
+        FROM mutes m
+        JOIN users u ON u.id = m.muted_id
+        WHERE m.muter_id = $1 AND u.deleted_at IS NULL
+        ORDER BY m.created_at DESC, u.id
+        LIMIT $2 OFFSET $3
+        "#,
+        muter_id,
+        limit,
+        offset
+    )
+    .fetch_all(&mut *transaction)
+    .await
+    .context("Failed to fetch muted users")?;
+
+    let pagination = Pagination::new(Some(dto_query.page()), Some(dto_query.size()), total);
+    Ok(DtoResponse::new(users, Some(pagination)))
+}
@@ -0,0 +1,237 @@
+use crate::dto::cursor::{Cursor, CursorQuery, CursorResponse};
+use crate::error::AlohaError;
+use crate::models::notification::{Notification, NotificationKind};
+use anyhow::Context;
+use sqlx::{Postgres, Transaction};
+use uuid::Uuid;
+
+/// Records a notification inside the caller's transaction. Kinds with a
+/// `group_key` are folded into the recipient's unread notification of that
+/// key when there is one: the actor moves to the front of `actor_ids` and
+/// `tweet_id` points at the latest tweet. Returns the id of the row the event
+/// landed in.
+pub async fn insert_notification(
+    transaction: &mut Transaction<'_, Postgres>,
+    notification: &Notification,
+) -> Result<Uuid, anyhow::Error> {
+    let row = sqlx::query!(
+        r#"
+        INSERT INTO notifications
+            (id, user_id, kind, group_key, actor_ids, tweet_id, permission_id, user_group_id)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+        ON CONFLICT (user_id, group_key) WHERE read_at IS NULL DO UPDATE
+        SET actor_ids = EXCLUDED.actor_ids
+                        || array_remove(notifications.actor_ids, EXCLUDED.actor_ids[1]),
+            event_count = notifications.event_count + 1,
+            tweet_id = EXCLUDED.tweet_id,
+            updated_at = now()
+        RETURNING id
+        "#,
+        notification.id,
+        notification.user_id,
+        notification.kind as NotificationKind,
+        notification.kind.group_key(),
+        &notification.actor_ids,
+        notification.tweet_id,
+        notification.permission_id,
+        notification.user_group_id
+    )
+    .fetch_one(&mut **transaction)
+    .await
+    .context("Failed to insert notification")?;
+
+    Ok(row.id)
+}
+
+/// Notifies the users in `user_ids` that the author of `tweet_id` mentioned
+/// them, skipping the author, users who cannot read the tweet and users who
+/// muted its author.
+pub async fn notify_mentioned_users(
+    transaction: &mut Transaction<'_, Postgres>,
+    tweet_id: Uuid,
+    user_ids: &[Uuid],
+) -> Result<(), anyhow::Error> {
+    let rows = sqlx::query!(
+        r#"
+        SELECT u.id AS recipient_id, t.user_id AS author_id
+        FROM users u
+        JOIN tweet t ON t.id = $1
+        WHERE u.id = ANY($2) AND u.id <> t.user_id
+        AND tweet_visible_to(t.user_id, t.visibility, u.id)
+        AND NOT user_muted_by(t.user_id, u.id)
+        "#,
+        tweet_id,
+        user_ids
+    )
+    .fetch_all(&mut **transaction)
+    .await
+    .context("Failed to resolve mention notification recipients")?;
+
+    for row in rows {
+        let notification = Notification {
+            actor_ids: vec![row.author_id],
+            tweet_id: Some(tweet_id),
+            ..Notification::new(row.recipient_id, NotificationKind::Mention)
+        };
+        insert_notification(transaction, &notification).await?;
+    }
+    Ok(())
+}
+
+/// The notifications of `user_id`, most recently updated first.
+pub async fn get_notifications(
+    mut transaction: Transaction<'_, Postgres>,
+    user_id: Uuid,
+    cursor_query: CursorQuery,
+) -> Result<CursorResponse<Vec<Notification>>, anyhow::Error> {
+    let cursor = cursor_query
+        .cursor()
+        .map_err(AlohaError::RequestParameterInvalid)?;
+    let limit = cursor_query.size() as i64;
+
+    // Fetch one extra row to learn whether another page exists.
+    let mut data = sqlx::query_as!(
+        Notification,
+        r#"
+        SELECT n.id, n.user_id, n.kind AS "kind: NotificationKind", n.actor_ids, n.event_count,
+               n.tweet_id, n.permission_id, n.user_group_id,
+               u.username AS "actor_username?", COALESCE(p.name, g.group_name) AS subject,
+               n.read_at, n.created_at, n.updated_at
+        FROM notifications n
+        LEFT JOIN users u ON u.id = n.actor_ids[1]
+        LEFT JOIN permissions p ON p.id = n.permission_id
+        LEFT JOIN user_groups g ON g.id = n.user_group_id
+        WHERE n.user_id = $1
+        AND ($2::timestamptz IS NULL OR (n.updated_at, n.id) < ($2, $3))
+        ORDER BY n.updated_at DESC, n.id DESC
+        LIMIT $4
+        "#,
+        user_id,
+        cursor.map(|c| c.created_at),
+        cursor.map(|c| c.id),
+        limit + 1
+    )
+    .fetch_all(&mut *transaction)
+    .await
+    .context("Failed to fetch notifications")?;
+
+    let next_cursor = if data.len() as i64 > limit {
+        data.truncate(limit as usize);
+        data.last()
+            .and_then(|n| n.updated_at.map(|updated_at| Cursor::new(updated_at, n.id)))
+            .map(|c| c.encode())
+    } else {
+        None
+    };
+
+    Ok(CursorResponse::new(data, next_cursor))
+}
+
+pub async fn get_notification_by_id(
+    mut transaction: Transaction<'_, Postgres>,
+    id: Uuid,
+) -> Result<Option<Notification>, anyhow::Error> {
+    let row = sqlx::query_as!(
+        Notification,
+        r#"
+        SELECT n.id, n.user_id, n.kind AS "kind: NotificationKind", n.actor_ids, n.event_count,
+               n.tweet_id, n.permission_id, n.user_group_id,
+               u.username AS "actor_username?", COALESCE(p.name, g.group_name) AS subject,
+               n.read_at, n.created_at, n.updated_at
+        FROM notifications n
+        LEFT JOIN users u ON u.id = n.actor_ids[1]
+        LEFT JOIN permissions p ON p.id = n.permission_id
+        LEFT JOIN user_groups g ON g.id = n.user_group_id
+        WHERE n.id = $1
+        "#,
+        id
+    )
+    .fetch_optional(&mut *transaction)
+    .await
+    .context("Failed to fetch notification by id")?;
+
+    Ok(row)
+}
+
+pub async fn get_unread_notification_count(
+    mut transaction: Transaction<'_, Postgres>,
+    user_id: Uuid,
+) -> Result<i64, anyhow::Error> {
+    let count = sqlx::query!(
+        r#"
+        SELECT COUNT(*) AS "count!"
+        FROM notifications
+        WHERE user_id = $1 AND read_at IS NULL
+        "#,
+        user_id
+    )
+    .fetch_one(&mut *transaction)
+    .await
+    .context("Failed to count unread notifications")?
+    .count;
+
+    Ok(count)
+}
+
+/// Marks a notification of `user_id` as read. Returns `None` when the
+/// notification does not exist or belongs to someone else.
+pub async fn mark_notification_read(
+    mut transaction: Transaction<'_, Postgres>,
+    user_id: Uuid,
+    id: Uuid,
+) -> Result<Option<Notification>, anyhow::Error> {
+    let row = sqlx::query_as!(
+        Notification,
+        r#"
+        WITH updated AS (
+            UPDATE notifications
+            SET read_at = COALESCE(read_at, now())
+            WHERE id = $1 AND user_id = $2
+            RETURNING *
+        )
+        SELECT n.id AS "id!", n.user_id AS "user_id!", n.kind AS "kind!: NotificationKind",
+               n.actor_ids AS "actor_ids!", n.event_count AS "event_count!",
+               n.tweet_id, n.permission_id, n.user_group_id,
+               u.username AS "actor_username?", COALESCE(p.name, g.group_name) AS subject,
+               n.read_at, n.created_at, n.updated_at
+        FROM updated n
+        LEFT JOIN users u ON u.id = n.actor_ids[1]
+        LEFT JOIN permissions p ON p.id = n.permission_id
+        LEFT JOIN user_groups g ON g.id = n.user_group_id
+        "#,
+        id,
+        user_id
+    )
+    .fetch_optional(&mut *transaction)
+    .await
+    .context("Failed to mark notification as read")?;
+
+    transaction
+        .commit()
+        .await
+        .context("Failed to commit SQL transaction to mark a notification as read.")?;
+
+    Ok(row)
+}
+
+/// Marks every unread notification of `user_id` as read and returns how many
+/// there were.
+pub async fn mark_all_notifications_read(
+    mut transaction: Transaction<'_, Postgres>,
+    user_id: Uuid,
+) -> Result<i64, anyhow::Error> {
+    let result = sqlx::query!(
+        "UPDATE notifications SET read_at = now() WHERE user_id = $1 AND read_at IS NULL",
+        user_id
+    )
+    .execute(&mut *transaction)
+    .await
+    .context("Failed to mark notifications as read")?;
+
+    transaction
+        .commit()
+        .await
+        .context("Failed to commit SQL transaction to mark notifications as read.")?;
+
+    Ok(result.rows_affected() as i64)
+}
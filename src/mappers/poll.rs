@@ -0,0 +1,163 @@
+use crate::error::AlohaError;
+use crate::models::poll::{Poll, PollOption, PollTally, PollVote};
+use anyhow::Context;
+use sqlx::{Postgres, Transaction};
+use std::collections::HashMap;
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+/// Stores a poll and its options inside the caller's transaction so they are
+/// committed together with the tweet carrying them.
+pub async fn insert_poll(
+    transaction: &mut Transaction<'_, Postgres>,
+    poll: &Poll,
+) -> Result<(), anyhow::Error> {
+    sqlx::query!(
+        "INSERT INTO polls (tweet_id, closes_at) VALUES ($1, $2)",
+        poll.tweet_id,
+        poll.closes_at
+    )
+    .execute(&mut **transaction)
+    .await
+    .context("Failed to insert poll")?;
+
+    let ids: Vec<Uuid> = poll.options.iter().map(|o| o.id).collect();
+    let positions: Vec<i16> = poll.options.iter().map(|o| o.position).collect();
+    let labels: Vec<String> = poll.options.iter().map(|o| o.label.clone()).collect();
+    sqlx::query!(
+        r#"
+        INSERT INTO poll_options (id, tweet_id, position, label)
+        SELECT id, $1, position, label
+        FROM UNNEST($2::uuid[], $3::smallint[], $4::varchar[]) AS o(id, position, label)
+        "#,
+        poll.tweet_id,
+        &ids,
+        &positions,
+        &labels
+    )
+    .execute(&mut **transaction)
+    .await
+    .context("Failed to insert poll options")?;
+
+    Ok(())
+}
+
+/// Loads the polls of several tweets with their vote counts and the option
+/// `viewer_id` voted for, keyed by tweet id.
+pub async fn get_poll_tallies_by_tweet_ids(
+    transaction: &mut Transaction<'_, Postgres>,
+    tweet_ids: &[Uuid],
+    viewer_id: Option<Uuid>,
+) -> Result<HashMap<Uuid, PollTally>, anyhow::Error> {
+    let rows = sqlx::query!(
+        r#"
+        SELECT p.tweet_id, p.closes_at, o.id, o.position, o.label,
+               (SELECT COUNT(*) FROM poll_votes v WHERE v.option_id = o.id) AS "votes!",
+               EXISTS(
+                   SELECT 1 FROM poll_votes v WHERE v.option_id = o.id AND v.user_id = $2
+               ) AS "voted!"
+        FROM polls p
+        JOIN poll_options o ON o.tweet_id = p.tweet_id
+        WHERE p.tweet_id = ANY($1)
+        ORDER BY p.tweet_id, o.position
+        "#,
+        tweet_ids,
+        viewer_id
+    )
+    .fetch_all(&mut **transaction)
+    .await
+    .context("Failed to fetch polls")?;
+
+    let mut tallies: HashMap<Uuid, PollTally> = HashMap::new();
+    for row in rows {
+        let tally = tallies.entry(row.tweet_id).or_insert_with(|| PollTally {
+            poll: Poll {
+                tweet_id: row.tweet_id,
+                closes_at: row.closes_at,
+                options: Vec::new(),
+            },
+            votes: Vec::new(),
+            voted_option_id: None,
+        });
+        tally.poll.options.push(PollOption {
+            id: row.id,
+            position: row.position,
+            label: row.label,
+        });
+        tally.votes.push(row.votes);
+        if row.voted {
+            tally.voted_option_id = Some(row.id);
+        }
+    }
+    Ok(tallies)
+}
+
+/// Records the vote of `user_id` on the poll of `tweet_id`. Returns `None`
+/// when the tweet carries no poll; closed polls, unknown options and second
+/// votes are rejected.
+pub async fn insert_poll_vote(
+    mut transaction: Transaction<'_, Postgres>,
+    tweet_id: Uuid,
+    user_id: Uuid,
+    option_id: Uuid,
+    now: OffsetDateTime,
+) -> Result<Option<PollVote>, anyhow::Error> {
+    let poll = sqlx::query!("SELECT closes_at FROM polls WHERE tweet_id = $1", tweet_id)
+        .fetch_optional(&mut *transaction)
+        .await
+        .context("Failed to fetch poll")?;
+
+    let Some(poll) = poll else {
+        return Ok(None);
+    };
+    if poll.closes_at <= now {
+        return Err(AlohaError::RequestParameterInvalid("This poll is closed.".to_string()).into());
+    }
+
+    let vote = sqlx::query_as!(
+        PollVote,
+        r#"
+        INSERT INTO poll_votes (tweet_id, user_id, option_id)
+        SELECT tweet_id, $2, id
+        FROM poll_options
+        WHERE tweet_id = $1 AND id = $3
+        ON CONFLICT (tweet_id, user_id) DO NOTHING
+        RETURNING tweet_id, user_id, option_id, created_at
+        "#,
+        tweet_id,
+        user_id,
+        option_id
+    )
+    .fetch_optional(&mut *transaction)
+    .await
+    .context("Failed to insert poll vote")?;
+
+    let Some(vote) = vote else {
+        let is_option_valid = sqlx::query!(
+            r#"
+            SELECT EXISTS(
+                SELECT 1 FROM poll_options WHERE tweet_id = $1 AND id = $2
+            ) AS "exists!"
+            "#,
+            tweet_id,
+            option_id
+        )
+        .fetch_one(&mut *transaction)
+        .await
+        .context("Failed to check poll option")?
+        .exists;
+        let message = if is_option_valid {
+            "You have already voted in this poll."
+        } else {
+            "Unknown poll option."
+        };
+        return Err(AlohaError::RequestParameterInvalid(message.to_string()).into());
+    };
+
+    transaction
+        .commit()
+        .await
+        .context("Failed to commit SQL transaction to insert a poll vote.")?;
+
+    Ok(Some(vote))
+}
@@ -0,0 +1,217 @@
+use crate::dto::pagination::Pagination;
+use crate::dto::query::{DtoQuery, ReportFilterQuery};
+use crate::dto::response::DtoResponse;
+use crate::error::AlohaError;
+use crate::models::moderation::{ModerationAction, ModerationActionKind};
+use crate::models::report::{Report, ReportStatus};
+use anyhow::Context;
+use sqlx::{Postgres, Transaction};
+use uuid::Uuid;
+
+use super::moderation::insert_moderation_action;
+use super::user::check_user_id_is_valid;
+
+/// Files a report. Returns `None` when the reported tweet does not exist or
+/// the reporter cannot read it, and `UserIdInvalid` for unknown users.
+pub async fn insert_report(
+    mut transaction: Transaction<'_, Postgres>,
+    report: &Report,
+) -> Result<Option<Report>, anyhow::Error> {
+    if let Some(tweet_id) = report.tweet_id {
+        let is_tweet_visible = sqlx::query!(
+            r#"
+            SELECT EXISTS(
+                SELECT 1 FROM tweet
+                WHERE id = $1 AND deleted_at IS NULL AND status = 'published'
+                AND tweet_visible_to(user_id, visibility, $2)
+            ) AS "exists!"
+            "#,
+            tweet_id,
+            report.reporter_id
+        )
+        .fetch_one(&mut *transaction)
+        .await
+        .context("Failed to check reported tweet")?
+        .exists;
+        if !is_tweet_visible {
+            return Ok(None);
+        }
+    }
+    if let Some(user_id) = report.user_id {
+        let is_user_valid = check_user_id_is_valid(&mut transaction, user_id).await?;
+        if !is_user_valid {
+            return Err(AlohaError::UserIdInvalid.into());
+        }
+    }
+
+    let row = sqlx::query_as!(
+        Report,
+        r#"
+        INSERT INTO reports (id, reporter_id, tweet_id, user_id, reason)
+        VALUES ($1, $2, $3, $4, $5)
+        RETURNING id, reporter_id, tweet_id, user_id, reason,
+                  status AS "status: ReportStatus", resolved_by, resolved_at, created_at
+        "#,
+        report.id,
+        report.reporter_id,
+        report.tweet_id,
+        report.user_id,
+        report.reason
+    )
+    .fetch_one(&mut *transaction)
+    .await
+    .context("Failed to insert report")?;
+
+    transaction
+        .commit()
+        .await
+        .context("Failed to commit SQL transaction to insert a new report.")?;
+
+    Ok(Some(row))
+}
+
+/// The moderation queue: reports with the requested status, open ones when
+/// no status is given, oldest first.
+pub async fn get_reports(
+    mut transaction: Transaction<'_, Postgres>,
+    dto_query: DtoQuery<ReportFilterQuery>,
+) -> Result<DtoResponse<Vec<Report>>, anyhow::Error> {
+    let offset = dto_query.offset() as i64;
+    let limit = dto_query.size() as i64;
+    let status = dto_query
+        .filter
+        .as_ref()
+        .and_then(|f| f.status)
+        .unwrap_or(ReportStatus::Open);
+
+    let total = sqlx::query!(
+        "SELECT COUNT(*) FROM reports WHERE status = $1",
+        status as ReportStatus
+    )
+    .fetch_one(&mut *transaction)
+    .await?
+    .count;
+
+    let data = sqlx::query_as!(
+        Report,
+        r#"
+        SELECT id, reporter_id, tweet_id, user_id, reason,
+               status AS "status: ReportStatus", resolved_by, resolved_at, created_at
+        FROM reports
+        WHERE status = $1
+        ORDER BY created_at, id
+        LIMIT $2 OFFSET $3
+        "#,
+        status as ReportStatus,
+        limit,
+        offset
+    )
+    .fetch_all(&mut *transaction)
+    .await
+    .context("Failed to fetch reports")?;
+
+    let pagination = Pagination::new(Some(dto_query.page()), Some(dto_query.size()), total);
+    Ok(DtoResponse::new(data, Some(pagination)))
+}
+
+pub async fn get_report_by_id(
+    mut transaction: Transaction<'_, Postgres>,
+    id: Uuid,
+) -> Result<Option<Report>, anyhow::Error> {
+    let row = sqlx::query_as!(
+        Report,
+        r#"
+        SELECT id, reporter_id, tweet_id, user_id, reason,
+               status AS "status: ReportStatus", resolved_by, resolved_at, created_at
+        FROM reports
+        WHERE id = $1
+        "#,
+        id
+    )
+    .fetch_optional(&mut *transaction)
+    .await
+    .context("Failed to fetch report by id")?;
+
+    Ok(row)
+}
+
+/// Moves a report to `status` on behalf of `moderator_id` inside the
+/// caller's transaction. Returns `None` for unknown reports and rejects
+/// transitions `ReportStatus::can_transition_to` does not allow.
+pub async fn resolve_report(
+    transaction: &mut Transaction<'_, Postgres>,
+    id: Uuid,
+    moderator_id: Uuid,
+    status: ReportStatus,
+) -> Result<Option<Report>, anyhow::Error> {
+    let current = sqlx::query!(
+        r#"SELECT status AS "status: ReportStatus" FROM reports WHERE id = $1 FOR UPDATE"#,
+        id
+    )
+    .fetch_optional(&mut **transaction)
+    .await
+    .context("Failed to lock report")?;
+
+    let Some(current) = current else {
+        return Ok(None);
+    };
+    if !current.status.can_transition_to(status) {
+        return Err(AlohaError::RequestParameterInvalid(
+            "Only open reports can be actioned or dismissed.".to_string(),
+        )
+        .into());
+    }
+
+    let row = sqlx::query_as!(
+        Report,
+        r#"
+        UPDATE reports
+        SET status = $2, resolved_by = $3, resolved_at = now()
+        WHERE id = $1
+        RETURNING id, reporter_id, tweet_id, user_id, reason,
+                  status AS "status: ReportStatus", resolved_by, resolved_at, created_at
+        "#,
+        id,
+        status as ReportStatus,
+        moderator_id
+    )
+    .fetch_one(&mut **transaction)
+    .await
+    .context("Failed to resolve report")?;
+
+    Ok(Some(row))
+}
+
+/// Actions or dismisses a report without touching its target, and records
+/// the decision in the moderation log.
+pub async fn update_report_status(
+    mut transaction: Transaction<'_, Postgres>,
+    id: Uuid,
+    moderator_id: Uuid,
+    status: ReportStatus,
+    note: Option<String>,
+) -> Result<Option<Report>, anyhow::Error> {
+    let Some(report) = resolve_report(&mut transaction, id, moderator_id, status).await? else {
+        return Ok(None);
+    };
+
+    let kind = match status {
+        ReportStatus::Dismissed => ModerationActionKind::DismissReport,
+        _ => ModerationActionKind::ActionReport,
+    };
+    let action = ModerationAction {
+        report_id: Some(report.id),
+        tweet_id: report.tweet_id,
+        user_id: report.user_id,
+        note,
+        ..ModerationAction::new(moderator_id, kind)
+    };
+    insert_moderation_action(&mut transaction, &action).await?;
+
+    transaction
+        .commit()
+        .await
+        .context("Failed to commit SQL transaction to update a report.")?;
+
+    Ok(Some(report))
+}
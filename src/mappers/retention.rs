@@ -0,0 +1,43 @@
+use anyhow::Context;
+use sqlx::{Postgres, Transaction};
+use time::OffsetDateTime;
+
+/// Number of rows hard-deleted by a single [`purge_deleted`] run.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct PurgeCounts {
+    pub users: u64,
+    pub tweets: u64,
+}
+
+/// Permanently removes users and tweets soft-deleted before `cutoff`. Purging
+/// a user cascades to everything they own, so only tweets of users that remain
+/// are counted separately.
+pub async fn purge_deleted(
+    mut transaction: Transaction<'_, Postgres>,
+    cutoff: OffsetDateTime,
+) -> Result<PurgeCounts, anyhow::Error> {
+    let users = sqlx::query!(
+        "DELETE FROM users WHERE deleted_at IS NOT NULL AND deleted_at < $1",
+        cutoff
+    )
+    .execute(&mut *transaction)
+    .await
+    .context("Failed to purge deleted users")?
+    .rows_affected();
+
+    let tweets = sqlx::query!(
+        "DELETE FROM tweet WHERE deleted_at IS NOT NULL AND deleted_at < $1",
+        cutoff
+    )
+    .execute(&mut *transaction)
+    .await
+    .context("Failed to purge deleted tweets")?
+    .rows_affected();
+
+    transaction
+        .commit()
+        .await
+        .context("Failed to commit SQL transaction to purge deleted rows.")?;
+
+    Ok(PurgeCounts { users, tweets })
+}
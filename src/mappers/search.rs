@@ -0,0 +1,189 @@
+use crate::dto::pagination::Pagination;
+use crate::dto::query::SearchQuery;
+use crate::dto::response::DtoResponse;
+use crate::models::search::{TweetSearchHit, TweetSearchQuery};
+use crate::models::tweet::{Tweet, TweetVisibility};
+use crate::models::user::User;
+use anyhow::Context;
+use sqlx::{Postgres, Transaction};
+use uuid::Uuid;
+
+/// Runs a parsed search against `tweet.search_vector`. Results are ordered by
+/// `ts_rank`, newest first among equal ranks; a search made of filters only
+/// is ordered by creation time. Only public tweets are searchable, and
+/// tweets of banned users and of users blocking or blocked by `viewer_id`
+/// are left out. Snippets are built from the HTML-escaped content.
+pub async fn search_tweets(
+    mut transaction: Transaction<'_, Postgres>,
+    search: &TweetSearchQuery,
+    search_query: &SearchQuery,
+    viewer_id: Option<Uuid>,
+) -> Result<DtoResponse<Vec<TweetSearchHit>>, anyhow::Error> {
+    let offset = search_query.offset() as i64;
+    let limit = search_query.size() as i64;
+    let tsquery = search.to_tsquery();
+    let created_after = search.created_after();
+    let created_before = search.created_before();
+
+    let total = sqlx::query!(
+        r#"
+        WITH q AS (SELECT to_tsquery('english', $1::text) AS query)
+        SELECT COUNT(*)
+        FROM tweet t
+        CROSS JOIN q
+        WHERE (q.query IS NULL OR t.search_vector @@ q.query)
+        AND t.deleted_at IS NULL AND t.status = 'published' AND t.visibility = 'public'
+        AND ($2::varchar IS NULL OR t.user_id = (
+            SELECT id FROM users WHERE username = $2 AND deleted_at IS NULL
+        ))
+        AND (cardinality($3::varchar[]) = 0 OR (
+            SELECT COUNT(*)
+            FROM tweet_hashtags th
+            JOIN hashtags h ON h.id = th.hashtag_id
+            WHERE th.tweet_id = t.id AND h.tag = ANY($3)
+        ) = cardinality($3))
+        AND ($4::timestamptz IS NULL OR t.created_at >= $4)
+        AND ($5::timestamptz IS NULL OR t.created_at < $5)
+        AND NOT users_blocked(t.user_id, $6)
+        AND NOT user_banned(t.user_id)
+        "#,
+        tsquery,
+        search.from,
+        &search.hashtags,
+        created_after,
+        created_before,
+        viewer_id
+    )
+    .fetch_one(&mut *transaction)
+    .await
+    .context("Failed to count search results")?
+    .count;
+
+    let rows = sqlx::query!(
+        r#"
+        WITH q AS (SELECT to_tsquery('english', $1::text) AS query)
+        SELECT t.id, t.content, t.created_at, t.updated_at, t.user_id,
+               t.visibility AS "visibility: TweetVisibility",
+               CASE WHEN q.query IS NULL THEN 0::real
+                    ELSE ts_rank(t.search_vector, q.query) END AS "rank!",
+               CASE WHEN q.query IS NULL THEN html_escape(t.content)
+                    ELSE ts_headline('english', html_escape(t.content), q.query,
+                        'StartSel=<mark>, StopSel=</mark>, MaxFragments=2')
+               END AS "snippet!"
+        FROM tweet t
+        CROSS JOIN q
+        WHERE (q.query IS NULL OR t.search_vector @@ q.query)
+        AND t.deleted_at IS NULL AND t.status = 'published' AND t.visibility = 'public'
+        AND ($2::varchar IS NULL OR t.user_id = (
+            SELECT id FROM users WHERE username = $2 AND deleted_at IS NULL
+        ))
+        AND (cardinality($3::varchar[]) = 0 OR (
+            SELECT COUNT(*)
+            FROM tweet_hashtags th
+            JOIN hashtags h ON h.id = th.hashtag_id
+            WHERE th.tweet_id = t.id AND h.tag = ANY($3)
+        ) = cardinality($3))
+        AND ($4::timestamptz IS NULL OR t.created_at >= $4)
+        AND ($5::timestamptz IS NULL OR t.created_at < $5)
+        AND NOT users_blocked(t.user_id, $8)
+        AND NOT user_banned(t.user_id)
+        ORDER BY "rank!" DESC, t.created_at DESC, t.id DESC
+        LIMIT $6 OFFSET $7
+        "#,
+        tsquery,
+        search.from,
+        &search.hashtags,
+        created_after,
+        created_before,
+        limit,
+        offset,
+        viewer_id
+    )
+    .fetch_all(&mut *transaction)
+    .await
+    .context("Failed to search tweets")?;
+
+    let data: Vec<TweetSearchHit> = rows
+        .into_iter()
+        .map(|row| TweetSearchHit {
+            tweet: Tweet {
+                id: row.id,
+                content: row.content,
+                created_at: row.created_at,
+                updated_at: row.updated_at,
+                user_id: row.user_id,
+                visibility: row.visibility,
+            },
+            rank: row.rank,
+            snippet: row.snippet,
+        })
+        .collect();
+
+    let pagination = Pagination::new(Some(search_query.page()), Some(search_query.size()), total);
+    Ok(DtoResponse::new(data, Some(pagination)))
+}
+
+/// Escapes `%`, `_` and `\` so `value` is matched literally by `LIKE`.
+fn escape_like(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        if matches!(c, '%' | '_' | '\\') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// Finds users whose username contains `q` or is similar to it by trigram
+/// similarity. Exact matches come first, then prefix matches, then the other
+/// substring matches, then the rest by decreasing similarity.
+pub async fn search_users(
+    mut transaction: Transaction<'_, Postgres>,
+    search_query: &SearchQuery,
+) -> Result<DtoResponse<Vec<User>>, anyhow::Error> {
+    let offset = search_query.offset() as i64;
+    let limit = search_query.size() as i64;
+    let q = search_query.q.trim();
+    let pattern = escape_like(q);
+
+    let total = sqlx::query!(
+        r#"
+        SELECT COUNT(*)
+        FROM users
+        WHERE (username ILIKE '%' || $1 || '%' OR username % $2) AND deleted_at IS NULL
+        "#,
+        pattern,
+        q
+    )
+    .fetch_one(&mut *transaction)
+    .await
+    .context("Failed to count user search results")?
+    .count;
+
+    let users = sqlx::query_as!(
+        User,
+        r#"
+        SELECT id, username, password_hash, created_at, user_group_id, display_name, bio,
+               location, website, avatar_media_id, banner_media_id
+        FROM users
+        WHERE (username ILIKE '%' || $1 || '%' OR username % $2) AND deleted_at IS NULL
+        ORDER BY lower(username) = lower($2) DESC,
+                 username ILIKE $1 || '%' DESC,
+                 username ILIKE '%' || $1 || '%' DESC,
+                 similarity(username, $2) DESC,
+                 username
+        LIMIT $3 OFFSET $4
+        "#,
+        pattern,
+        q,
+        limit,
+        offset
+    )
+    .fetch_all(&mut *transaction)
+    .await
+    .context("Failed to search users")?;
+
+    let pagination = Pagination::new(Some(search_query.page()), Some(search_query.size()), total);
+    Ok(DtoResponse::new(users, Some(pagination)))
+}
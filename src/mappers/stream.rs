@@ -0,0 +1,113 @@
+use crate::models::stream::{StreamEvent, StreamEventKind};
+use anyhow::Context;
+use sqlx::{Postgres, Transaction};
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+pub async fn get_stream_event_by_id(
+    mut transaction: Transaction<'_, Postgres>,
+    id: i64,
+) -> Result<Option<StreamEvent>, anyhow::Error> {
+    let row = sqlx::query_as!(
+        StreamEvent,
+        r#"
+        SELECT id, kind AS "kind: StreamEventKind", tweet_id, notification_id, created_at
+        FROM stream_events
+        WHERE id = $1
+        "#,
+        id
+    )
+    .fetch_optional(&mut *transaction)
+    .await
+    .context("Failed to fetch stream event by id")?;
+
+    Ok(row)
+}
+
+/// Up to `limit` events meant for `user_id` that came after `after_id`,
+/// oldest first: their own notifications and the tweets that reach their
+/// home timeline. Ids follow insertion rather than commit order, so events
+/// committed late with an id below `after_id` are not returned.
+pub async fn get_stream_events_since(
+    mut transaction: Transaction<'_, Postgres>,
+    user_id: Uuid,
+    after_id: i64,
+    limit: i64,
+) -> Result<Vec<StreamEvent>, anyhow::Error> {
+    let rows = sqlx::query_as!(
+        StreamEvent,
+        r#"
+        SELECT e.id, e.kind AS "kind: StreamEventKind", e.tweet_id, e.notification_id,
+               e.created_at
+        FROM stream_events e
+        LEFT JOIN tweet t ON t.id = e.tweet_id
+        LEFT JOIN notifications n ON n.id = e.notification_id
+        WHERE e.id > $2
+        AND (
+            n.user_id = $1
+            OR (
+                t.deleted_at IS NULL AND t.status = 'published'
+                AND tweet_on_home_timeline(t.user_id, $1)
+                AND tweet_visible_to(t.user_id, t.visibility, $1)
+                AND NOT user_muted_by(t.user_id, $1)
+            )
+        )
+        ORDER BY e.id
+        LIMIT $3
+        "#,
+        user_id,
+        after_id,
+        limit
+    )
+    .fetch_all(&mut *transaction)
+    .await
+    .context("Failed to fetch stream events")?;
+
+    Ok(rows)
+}
+
+/// The users among `user_ids` whose home timeline `tweet_id` reaches.
+pub async fn get_timeline_recipients(
+    mut transaction: Transaction<'_, Postgres>,
+    tweet_id: Uuid,
+    user_ids: &[Uuid],
+) -> Result<Vec<Uuid>, anyhow::Error> {
+    let rows = sqlx::query!(
+        r#"
+        SELECT viewer.id AS "id!"
+        FROM UNNEST($2::uuid[]) AS viewer(id)
+        JOIN tweet t ON t.id = $1
+        WHERE t.deleted_at IS NULL AND t.status = 'published'
+        AND tweet_on_home_timeline(t.user_id, viewer.id)
+        AND tweet_visible_to(t.user_id, t.visibility, viewer.id)
+        AND NOT user_muted_by(t.user_id, viewer.id)
+        "#,
+        tweet_id,
+        user_ids
+    )
+    .fetch_all(&mut *transaction)
+    .await
+    .context("Failed to resolve timeline recipients")?;
+
+    Ok(rows.into_iter().map(|row| row.id).collect())
+}
+
+/// Removes stream events created before `cutoff`; clients resuming from them
+/// only get what is left.
+pub async fn purge_stream_events(
+    mut transaction: Transaction<'_, Postgres>,
+    cutoff: OffsetDateTime,
+) -> Result<u64, anyhow::Error> {
+    let purged = sqlx::query!("DELETE FROM stream_events WHERE created_at < $1", cutoff)
+        .execute(&mut *transaction)
+        .await
+        .context("Failed to purge stream events")?
+        .rows_affected();
+
+    transaction
+        .commit()
+        .await
+        .context("Failed to commit SQL transaction to purge stream events.")?;
+
+    Ok(purged)
+}
@@ -0,0 +1,88 @@
+use crate::error::AlohaError;
+use crate::models::subscription::Subscription;
+use anyhow::Context;
+use sqlx::{Postgres, Transaction};
+use uuid::Uuid;
+
+use super::user::check_user_id_is_valid;
+
+pub async fn get_subscriptions_by_subscriber_id(
+    mut transaction: Transaction<'_, Postgres>,
+    subscriber_id: Uuid,
+) -> Result<Vec<Subscription>, anyhow::Error> {
+    let subscriptions = sqlx::query_as!(
+        Subscription,
+        r#"
+        SELECT subscriber_id, target_id, created_at
+        FROM subscriptions
+        WHERE subscriber_id = $1
+        ORDER BY created_at DESC
+        "#,
+        subscriber_id
+    )
+    .fetch_all(&mut *transaction)
+    .await
+    .context("Failed to fetch subscriptions by subscriber_id")?;
+
+    Ok(subscriptions)
+}
+
+pub async fn insert_subscription(
+    mut transaction: Transaction<'_, Postgres>,
+    subscription: &Subscription,
+) -> Result<Subscription, anyhow::Error> {
+    let is_target_valid = check_user_id_is_valid(&mut transaction, subscription.target_id).await?;
+    if !is_target_valid {
+        return Err(AlohaError::UserIdInvalid.into());
+    }
+
+    let row = sqlx::query_as!(
+        Subscription,
+        r#"
+        INSERT INTO subscriptions (subscriber_id, target_id)
+        VALUES ($1, $2)
+        ON CONFLICT (subscriber_id, target_id)
+        DO UPDATE SET subscriber_id = EXCLUDED.subscriber_id
+        RETURNING subscriber_id, target_id, created_at
+        "#,
+        subscription.subscriber_id,
+        subscription.target_id
+    )
+    .fetch_one(&mut *transaction)
+    .await
+    .context("Failed to insert subscription")?;
+
+    transaction
+        .commit()
+        .await
+        .context("Failed to commit SQL transaction to insert a new subscription.")?;
+
+    Ok(row)
+}
+
+pub async fn delete_subscription(
+    mut transaction: Transaction<'_, Postgres>,
+    subscriber_id: Uuid,
+    target_id: Uuid,
+) -> Result<Subscription, anyhow::Error> {
+    let row = sqlx::query_as!(
+        Subscription,
+        r#"
+        DELETE FROM subscriptions
+        WHERE subscriber_id = $1 AND target_id = $2
+        RETURNING subscriber_id, target_id, created_at
+        "#,
+        subscriber_id,
+        target_id
+    )
+    .fetch_one(&mut *transaction)
+    .await
+    .context("Failed to delete subscription")?;
+
+    transaction
+        .commit()
+        .await
+        .context("Failed to commit SQL transaction to delete a subscription.")?;
+
+    Ok(row)
+}
@@ -0,0 +1,77 @@
+use crate::dto::cursor::{Cursor, CursorQuery, CursorResponse};
+use crate::error::AlohaError;
+use crate::models::tweet::{Tweet, TweetVisibility};
+use anyhow::Context;
+use sqlx::{Postgres, Transaction};
+use uuid::Uuid;
+
+/// Tweets written by `user_id`, by members of the same `user_group`, and by
+/// accounts `user_id` subscribes to, newest first, skipping the ones
+/// `user_id` is not allowed to read and those of users `user_id` muted.
+pub async fn get_home_timeline(
+    mut transaction: Transaction<'_, Postgres>,
+    user_id: Uuid,
+    cursor_query: CursorQuery,
+) -> Result<CursorResponse<Vec<Tweet>>, anyhow::Error> {
+    let cursor = cursor_query
+        .cursor()
+        .map_err(AlohaError::RequestParameterInvalid)?;
+    let limit = cursor_query.size() as i64;
+
+    // Fetch one extra row to learn whether another page exists.
+    let rows = sqlx::query!(
+        r#"
+        SELECT t.id, t.content, t.created_at, t.updated_at, t.user_id,
+               t.visibility AS "visibility: TweetVisibility"
+        FROM tweet t
+        WHERE (
+            t.user_id = $1
+            OR t.user_id IN (
+                SELECT member.id
+                FROM users member
+                JOIN users viewer ON viewer.user_group_id = member.user_group_id
+                WHERE viewer.id = $1
+            )
+            OR t.user_id IN (
+                SELECT target_id FROM subscriptions WHERE subscriber_id = $1
+            )
+        )
+        AND t.deleted_at IS NULL AND t.status = 'published'
+        AND tweet_visible_to(t.user_id, t.visibility, $1)
+        AND NOT user_muted_by(t.user_id, $1)
+        AND ($2::timestamptz IS NULL OR (t.created_at, t.id) < ($2, $3))
+        ORDER BY t.created_at DESC, t.id DESC
+        LIMIT $4
+        "#,
+        user_id,
+        cursor.map(|c| c.created_at),
+        cursor.map(|c| c.id),
+        limit + 1
+    )
+    .fetch_all(&mut *transaction)
+    .await
+    .context("Failed to fetch home timeline")?;
+
+    let mut data: Vec<Tweet> = rows
+        .into_iter()
+        .map(|row| Tweet {
+            id: row.id,
+            content: row.content,
+            created_at: row.created_at,
+            updated_at: row.updated_at,
+            user_id: row.user_id,
+            visibility: row.visibility,
+        })
+        .collect();
+
+    let next_cursor = if data.len() as i64 > limit {
+        data.truncate(limit as usize);
+        data.last()
+            .and_then(|t| t.created_at.map(|created_at| Cursor::new(created_at, t.id)))
+            .map(|c| c.encode())
+    } else {
+        None
+    };
+
+    Ok(CursorResponse::new(data, next_cursor))
+}
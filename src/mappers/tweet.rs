@@ -2,37 +2,70 @@ use crate::dto::query::DtoQuery;
 use crate::dto::response::DtoResponse;
 use crate::dto::{pagination::Pagination, query::TweetFilterQuery};
 use crate::error::AlohaError;
-use crate::models::tweet::Tweet;
+use crate::models::content_rule::{ContentRuleAction, ContentRuleEvaluation};
+use crate::models::draft::TweetStatus;
+use crate::models::poll::Poll;
+use crate::models::tweet::{Tweet, TweetVisibility};
+use crate::models::user::AuthorResponse;
 use anyhow::Context;
 use sqlx::{Postgres, Transaction};
+use std::collections::{HashMap, HashSet};
 use uuid::Uuid;
 
+use super::content_rule::apply_content_rules;
+use super::hashtag::sync_tweet_hashtags;
+use super::link_preview::sync_tweet_links;
+use super::media::attach_tweet_media;
+use super::mention::sync_tweet_mentions;
+use super::moderation::record_held_tweet;
+use super::poll::insert_poll;
+use super::tweet_revision::record_tweet_revision;
 use super::user::check_user_id_is_valid;
 
+/// Lists the published tweets `viewer_id` may read; anonymous viewers
+/// (`None`) only see public tweets. Tweets of users the viewer muted are
+/// skipped unless filtering by that author, whose pinned tweet then comes
+/// first.
 pub async fn get_all_tweets(
     mut transaction: Transaction<'_, Postgres>,
     dto_query: DtoQuery<TweetFilterQuery>,
+    viewer_id: Option<Uuid>,
 ) -> Result<DtoResponse<Vec<Tweet>>, anyhow::Error> {
     let offset = dto_query.offset() as i64;
     let limit = dto_query.size() as i64;
-    let total = sqlx::query!("SELECT COUNT(*) FROM tweet")
-        .fetch_one(&mut *transaction)
-        .await?
-        .count;
-
     let user_id = dto_query.filter.as_ref().and_then(|f| f.user_id);
 
+    let total = sqlx::query!(
+        r#"
+        SELECT COUNT(*)
+        FROM tweet
+        WHERE ($1::uuid IS NULL OR user_id = $1) AND deleted_at IS NULL AND status = 'published'
+        AND tweet_visible_to(user_id, visibility, $2)
+        AND (user_id = $1 OR NOT user_muted_by(user_id, $2))
+        "#,
+        user_id,
+        viewer_id
+    )
+    .fetch_one(&mut *transaction)
+    .await?
+    .count;
+
     let rows = sqlx::query!(
         r#"
-        SELECT id, content, created_at, updated_at, user_id 
+        SELECT id, content, created_at, updated_at, user_id,
+               visibility AS "visibility: TweetVisibility"
         FROM tweet 
-        WHERE ($1::uuid IS NULL OR user_id = $1)
-        ORDER BY created_at DESC 
+        WHERE ($1::uuid IS NULL OR user_id = $1) AND deleted_at IS NULL AND status = 'published'
+        AND tweet_visible_to(user_id, visibility, $4)
+        AND (user_id = $1 OR NOT user_muted_by(user_id, $4))
+        ORDER BY id IS NOT DISTINCT FROM (SELECT pinned_tweet_id FROM users WHERE id = $1) DESC,
+                 created_at DESC
         LIMIT $2 OFFSET $3
         "#,
         user_id,
         limit,
-        offset
+        offset,
+        viewer_id
     )
     .fetch_all(&mut *transaction)
     .await
@@ -46,6 +79,7 @@ pub async fn get_all_tweets(
             created_at: row.created_at,
             updated_at: row.updated_at,
             user_id: row.user_id,
+            visibility: row.visibility,
         })
         .collect();
 
@@ -53,17 +87,23 @@ pub async fn get_all_tweets(
     Ok(DtoResponse::new(data, Some(pagination)))
 }
 
+/// Fetches a published tweet, or `None` when it does not exist or
+/// `viewer_id` is not allowed to read it.
 pub async fn get_tweet_by_id(
     mut transaction: Transaction<'_, Postgres>,
     id: Uuid,
+    viewer_id: Option<Uuid>,
 ) -> Result<Option<Tweet>, anyhow::Error> {
     let row = sqlx::query!(
         r#"
-        SELECT id, content, created_at, updated_at, user_id 
+        SELECT id, content, created_at, updated_at, user_id,
+               visibility AS "visibility: TweetVisibility"
         FROM tweet 
-        WHERE id = $1
+        WHERE id = $1 AND deleted_at IS NULL AND status = 'published'
+        AND tweet_visible_to(user_id, visibility, $2)
         "#,
-        id
+        id,
+        viewer_id
     )
     .fetch_optional(&mut *transaction)
     .await
@@ -75,33 +115,127 @@ pub async fn get_tweet_by_id(
         created_at: row.created_at,
         updated_at: row.updated_at,
         user_id: row.user_id,
+        visibility: row.visibility,
     }))
 }
 
 pub async fn insert_tweet(
+    transaction: Transaction<'_, Postgres>,
+    tweet: &Tweet,
+) -> Result<Tweet, anyhow::Error> {
+    insert_tweet_with_media(transaction, tweet, &[]).await
+}
+
+/// Inserts a tweet and attaches previously uploaded media in the same
+/// transaction.
+pub async fn insert_tweet_with_media(
+    transaction: Transaction<'_, Postgres>,
+    tweet: &Tweet,
+    media_ids: &[Uuid],
+) -> Result<Tweet, anyhow::Error> {
+    insert_tweet_with_attachments(transaction, tweet, media_ids, None).await
+}
+
+/// Checks `content` against the content rules. Rejected content fails with
+/// `RequestParameterInvalid` once the rule hits are committed; otherwise
+/// returns the status the tweet gets and the masked content.
+async fn screen_tweet_content<'c>(
+    mut transaction: Transaction<'c, Postgres>,
+    content: &str,
+) -> Result<
+    (
+        Transaction<'c, Postgres>,
+        TweetStatus,
+        ContentRuleEvaluation,
+    ),
+    anyhow::Error,
+> {
+    let evaluation = apply_content_rules(&mut transaction, content).await?;
+    match evaluation.action {
+        Some(ContentRuleAction::Reject) => {
+            transaction
+                .commit()
+                .await
+                .context("Failed to commit SQL transaction to record content rule hits.")?;
+            Err(AlohaError::RequestParameterInvalid(
+                "This tweet breaks the content rules.".to_string(),
+            )
+            .into())
+        }
+        Some(ContentRuleAction::Hold) => Ok((transaction, TweetStatus::Held, evaluation)),
+        _ => Ok((transaction, TweetStatus::Published, evaluation)),
+    }
+}
+
+/// Returns the lifecycle status of a tweet that is not deleted.
+pub async fn get_tweet_status(
+    mut transaction: Transaction<'_, Postgres>,
+    id: Uuid,
+) -> Result<Option<TweetStatus>, anyhow::Error> {
+    let row = sqlx::query!(
+        r#"
+        SELECT status AS "status: TweetStatus"
+        FROM tweet
+        WHERE id = $1 AND deleted_at IS NULL
+        "#,
+        id
+    )
+    .fetch_optional(&mut *transaction)
+    .await
+    .context("Failed to fetch tweet status")?;
+
+    Ok(row.map(|row| row.status))
+}
+
+/// Inserts a tweet together with its media and optional poll in the same
+/// transaction. The content is screened by the content rules first: tweets
+/// matching a `hold` rule are stored as `Held` and only get their hashtags,
+/// mentions and links once a moderator releases them.
+pub async fn insert_tweet_with_attachments(
     mut transaction: Transaction<'_, Postgres>,
     tweet: &Tweet,
+    media_ids: &[Uuid],
+    poll: Option<&Poll>,
 ) -> Result<Tweet, anyhow::Error> {
     let user_id = tweet.user_id;
     let is_user_valid = check_user_id_is_valid(&mut transaction, user_id).await?;
     if !is_user_valid {
         return Err(AlohaError::UserIdInvalid.into());
     }
+    let (mut transaction, status, evaluation) =
+        screen_tweet_content(transaction, &tweet.content).await?;
 
     let row = sqlx::query!(
         r#"
-        INSERT INTO tweet (id, content, user_id)
-        VALUES ($1, $2, $3)
-        RETURNING id, content, created_at, updated_at, user_id
+        INSERT INTO tweet (id, content, user_id, visibility, status)
+        VALUES ($1, $2, $3, $4, $5)
+        RETURNING id, content, created_at, updated_at, user_id,
+                  visibility AS "visibility: TweetVisibility"
         "#,
         tweet.id,
-        tweet.content,
-        tweet.user_id
+        evaluation.content,
+        tweet.user_id,
+        tweet.visibility as TweetVisibility,
+        status as TweetStatus
     )
     .fetch_one(&mut *transaction)
     .await
     .context("Failed to insert tweet")?;
 
+    if status == TweetStatus::Held {
+        record_held_tweet(&mut transaction, row.id, row.user_id, &evaluation).await?;
+    } else {
+        sync_tweet_hashtags(&mut transaction, row.id, &row.content).await?;
+        sync_tweet_mentions(&mut transaction, row.id, row.user_id, &row.content).await?;
+        sync_tweet_links(&mut transaction, row.id, &row.content).await?;
+    }
+    if !media_ids.is_empty() {
+        attach_tweet_media(&mut transaction, row.id, row.user_id, media_ids).await?;
+    }
+    if let Some(poll) = poll {
+        insert_poll(&mut transaction, poll).await?;
+    }
+
     transaction
         .commit()
         .await
@@ -113,6 +247,7 @@ pub async fn insert_tweet(
         created_at: row.created_at,
         updated_at: row.updated_at,
         user_id: row.user_id,
+        visibility: row.visibility,
     })
 }
 
@@ -122,9 +257,11 @@ pub async fn delete_tweet_by_id(
 ) -> Result<Tweet, anyhow::Error> {
     let row = sqlx::query!(
         r#"
-        DELETE FROM tweet
-        WHERE id = $1
-        RETURNING id, content, created_at, updated_at, user_id
+        UPDATE tweet
+        SET deleted_at = now()
+        WHERE id = $1 AND deleted_at IS NULL AND status = 'published'
+        RETURNING id, content, created_at, updated_at, user_id,
+                  visibility AS "visibility: TweetVisibility"
         "#,
         id
     )
@@ -132,6 +269,8 @@ pub async fn delete_tweet_by_id(
     .await
     .context("Failed to delete tweet")?;
 
+    unpin_tweets(&mut transaction, &[row.id]).await?;
+
     transaction
         .commit()
         .await
@@ -143,27 +282,68 @@ pub async fn delete_tweet_by_id(
         created_at: row.created_at,
         updated_at: row.updated_at,
         user_id: row.user_id,
+        visibility: row.visibility,
     })
 }
 
+/// Drops the hashtags, mentions and links of a tweet taken out of
+/// circulation, so it no longer shows up in hashtag feeds, trends or mention
+/// lists. They are extracted again when a moderator releases it.
+async fn clear_tweet_entities(
+    transaction: &mut Transaction<'_, Postgres>,
+    tweet_id: Uuid,
+) -> Result<(), anyhow::Error> {
+    sqlx::query!("DELETE FROM tweet_hashtags WHERE tweet_id = $1", tweet_id)
+        .execute(&mut **transaction)
+        .await
+        .context("Failed to clear tweet hashtags")?;
+    sqlx::query!("DELETE FROM tweet_mentions WHERE tweet_id = $1", tweet_id)
+        .execute(&mut **transaction)
+        .await
+        .context("Failed to clear tweet mentions")?;
+    sqlx::query!("DELETE FROM tweet_links WHERE tweet_id = $1", tweet_id)
+        .execute(&mut **transaction)
+        .await
+        .context("Failed to clear tweet links")?;
+    Ok(())
+}
+
+/// Replaces the content of a published tweet after screening it by the
+/// content rules. An edit matching a `hold` rule takes the tweet back out of
+/// circulation until a moderator releases it.
 pub async fn update_tweet(
-    mut transaction: Transaction<'_, Postgres>,
+    transaction: Transaction<'_, Postgres>,
     tweet: &Tweet,
 ) -> Result<Tweet, anyhow::Error> {
+    let (mut transaction, status, evaluation) =
+        screen_tweet_content(transaction, &tweet.content).await?;
+    record_tweet_revision(&mut transaction, tweet.id, &evaluation.content).await?;
+
     let row = sqlx::query!(
         r#"
         UPDATE tweet
-        SET content = $1
-        WHERE id = $2
-        RETURNING id, content, created_at, updated_at, user_id
+        SET content = $1, status = $3
+        WHERE id = $2 AND deleted_at IS NULL AND status = 'published'
+        RETURNING id, content, created_at, updated_at, user_id,
+                  visibility AS "visibility: TweetVisibility"
         "#,
-        tweet.content,
-        tweet.id
+        evaluation.content,
+        tweet.id,
+        status as TweetStatus
     )
     .fetch_one(&mut *transaction)
     .await
     .context("Failed to update tweet")?;
 
+    if status == TweetStatus::Held {
+        record_held_tweet(&mut transaction, row.id, row.user_id, &evaluation).await?;
+        clear_tweet_entities(&mut transaction, row.id).await?;
+    } else {
+        sync_tweet_hashtags(&mut transaction, row.id, &row.content).await?;
+        sync_tweet_mentions(&mut transaction, row.id, row.user_id, &row.content).await?;
+        sync_tweet_links(&mut transaction, row.id, &row.content).await?;
+    }
+
     transaction
         .commit()
         .await
@@ -175,6 +355,7 @@ pub async fn update_tweet(
         created_at: row.created_at,
         updated_at: row.updated_at,
         user_id: row.user_id,
+        visibility: row.visibility,
     })
 }
 
@@ -184,9 +365,11 @@ pub async fn delete_tweets_by_ids(
 ) -> Result<Vec<Tweet>, anyhow::Error> {
     let rows = sqlx::query!(
         r#"
-        DELETE FROM tweet
-        WHERE id = ANY($1)
-        RETURNING id, content, created_at, updated_at, user_id
+        UPDATE tweet
+        SET deleted_at = now()
+        WHERE id = ANY($1) AND deleted_at IS NULL AND status = 'published'
+        RETURNING id, content, created_at, updated_at, user_id,
+                  visibility AS "visibility: TweetVisibility"
         "#,
         &ids as &[Uuid]
     )
@@ -194,6 +377,9 @@ pub async fn delete_tweets_by_ids(
     .await
     .context("Failed to delete tweets")?;
 
+    let deleted_ids: Vec<Uuid> = rows.iter().map(|row| row.id).collect();
+    unpin_tweets(&mut transaction, &deleted_ids).await?;
+
     transaction
         .commit()
         .await
@@ -207,8 +393,178 @@ pub async fn delete_tweets_by_ids(
             created_at: row.created_at,
             updated_at: row.updated_at,
             user_id: row.user_id,
+            visibility: row.visibility,
         })
         .collect();
 
     Ok(tweets)
 }
+
+/// Clears `deleted_at` on a soft-deleted tweet. Tweets whose author is itself
+/// soft-deleted stay hidden until the author is restored.
+pub async fn restore_tweet_by_id(
+    mut transaction: Transaction<'_, Postgres>,
+    id: Uuid,
+) -> Result<Option<Tweet>, anyhow::Error> {
+    let row = sqlx::query!(
+        r#"
+        UPDATE tweet t
+        SET deleted_at = NULL
+        FROM users u
+        WHERE t.id = $1 AND t.deleted_at IS NOT NULL
+        AND u.id = t.user_id AND u.deleted_at IS NULL
+        RETURNING t.id, t.content, t.created_at, t.updated_at, t.user_id,
+                  t.visibility AS "visibility: TweetVisibility"
+        "#,
+        id
+    )
+    .fetch_optional(&mut *transaction)
+    .await
+    .context("Failed to restore tweet")?;
+
+    transaction
+        .commit()
+        .await
+        .context("Failed to commit SQL transaction to restore a tweet.")?;
+
+    Ok(row.map(|row| Tweet {
+        id: row.id,
+        content: row.content,
+        created_at: row.created_at,
+        updated_at: row.updated_at,
+        user_id: row.user_id,
+        visibility: row.visibility,
+    }))
+}
+
+/// Clears the pins pointing at `ids`, so deleted tweets stop being shown first
+/// on their author's profile.
+pub async fn unpin_tweets(
+    transaction: &mut Transaction<'_, Postgres>,
+    ids: &[Uuid],
+) -> Result<(), anyhow::Error> {
+    sqlx::query!(
+        "UPDATE users SET pinned_tweet_id = NULL WHERE pinned_tweet_id = ANY($1)",
+        ids
+    )
+    .execute(&mut **transaction)
+    .await
+    .context("Failed to unpin tweets")?;
+    Ok(())
+}
+
+/// Pins one of `user_id`'s published tweets to their profile, replacing any
+/// previous pin. Returns `None` when the tweet is not theirs or not live.
+pub async fn pin_tweet(
+    mut transaction: Transaction<'_, Postgres>,
+    user_id: Uuid,
+    tweet_id: Uuid,
+) -> Result<Option<Tweet>, anyhow::Error> {
+    let row = sqlx::query!(
+        r#"
+        SELECT id, content, created_at, updated_at, user_id,
+               visibility AS "visibility: TweetVisibility"
+        FROM tweet
+        WHERE id = $1 AND user_id = $2 AND deleted_at IS NULL AND status = 'published'
+        FOR UPDATE
+        "#,
+        tweet_id,
+        user_id
+    )
+    .fetch_optional(&mut *transaction)
+    .await
+    .context("Failed to fetch tweet to pin")?;
+
+    let Some(row) = row else {
+        return Ok(None);
+    };
+
+    sqlx::query!(
+        "UPDATE users SET pinned_tweet_id = $1 WHERE id = $2",
+        row.id,
+        user_id
+    )
+    .execute(&mut *transaction)
+    .await
+    .context("Failed to pin tweet")?;
+
+    transaction
+        .commit()
+        .await
+        .context("Failed to commit SQL transaction to pin a tweet.")?;
+
+    Ok(Some(Tweet {
+        id: row.id,
+        content: row.content,
+        created_at: row.created_at,
+        updated_at: row.updated_at,
+        user_id: row.user_id,
+        visibility: row.visibility,
+    }))
+}
+
+/// Removes the pin of `user_id` when it points at `tweet_id`. Returns whether
+/// the tweet was pinned.
+pub async fn unpin_tweet(
+    mut transaction: Transaction<'_, Postgres>,
+    user_id: Uuid,
+    tweet_id: Uuid,
+) -> Result<bool, anyhow::Error> {
+    let result = sqlx::query!(
+        "UPDATE users SET pinned_tweet_id = NULL WHERE id = $1 AND pinned_tweet_id = $2",
+        user_id,
+        tweet_id
+    )
+    .execute(&mut *transaction)
+    .await
+    .context("Failed to unpin tweet")?;
+
+    transaction
+        .commit()
+        .await
+        .context("Failed to commit SQL transaction to unpin a tweet.")?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// Returns which of `tweet_ids` are pinned by their author.
+pub async fn get_pinned_tweet_ids(
+    transaction: &mut Transaction<'_, Postgres>,
+    tweet_ids: &[Uuid],
+) -> Result<HashSet<Uuid>, anyhow::Error> {
+    let rows = sqlx::query!(
+        r#"
+        SELECT pinned_tweet_id AS "pinned_tweet_id!"
+        FROM users
+        WHERE pinned_tweet_id = ANY($1)
+        "#,
+        tweet_ids
+    )
+    .fetch_all(&mut **transaction)
+    .await
+    .context("Failed to fetch pinned tweets")?;
+
+    Ok(rows.into_iter().map(|row| row.pinned_tweet_id).collect())
+}
+
+/// Loads the author embeds of a batch of tweets with one query, keyed by
+/// user id.
+pub async fn get_tweet_authors(
+    transaction: &mut Transaction<'_, Postgres>,
+    user_ids: &[Uuid],
+) -> Result<HashMap<Uuid, AuthorResponse>, anyhow::Error> {
+    let users = sqlx::query_as!(
+        AuthorResponse,
+        r#"
+        SELECT id, username, display_name, avatar_media_id
+        FROM users
+        WHERE id = ANY($1)
+        "#,
+        user_ids
+    )
+    .fetch_all(&mut **transaction)
+    .await
+    .context("Failed to fetch tweet authors")?;
+
+    Ok(users.into_iter().map(|user| (user.id, user)).collect())
+}
@@ -0,0 +1,88 @@
+use crate::models::tweet_revision::TweetRevision;
+use anyhow::Context;
+use sqlx::{Postgres, Transaction};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+/// Copies the current content of `tweet_id` into `tweet_revisions` when it is
+/// about to be replaced by different `new_content`. Must run before the
+/// update, inside the same transaction.
+pub async fn record_tweet_revision(
+    transaction: &mut Transaction<'_, Postgres>,
+    tweet_id: Uuid,
+    new_content: &str,
+) -> Result<Option<TweetRevision>, anyhow::Error> {
+    // Serialises concurrent edits so revision numbers stay consecutive.
+    sqlx::query!("SELECT id FROM tweet WHERE id = $1 FOR UPDATE", tweet_id)
+        .fetch_optional(&mut **transaction)
+        .await
+        .context("Failed to lock tweet")?;
+
+    let revision = sqlx::query_as!(
+        TweetRevision,
+        r#"
+        INSERT INTO tweet_revisions (tweet_id, revision, content, created_at)
+        SELECT t.id,
+               COALESCE((SELECT MAX(r.revision) FROM tweet_revisions r WHERE r.tweet_id = t.id), 0) + 1,
+               t.content,
+               t.updated_at
+        FROM tweet t
+        WHERE t.id = $1 AND t.content <> $2
+        RETURNING id, tweet_id, revision, content, created_at, replaced_at
+        "#,
+        tweet_id,
+        new_content
+    )
+    .fetch_optional(&mut **transaction)
+    .await
+    .context("Failed to record tweet revision")?;
+
+    Ok(revision)
+}
+
+/// Prior versions of a tweet, oldest first.
+pub async fn get_tweet_revisions(
+    mut transaction: Transaction<'_, Postgres>,
+    tweet_id: Uuid,
+) -> Result<Vec<TweetRevision>, anyhow::Error> {
+    let revisions = sqlx::query_as!(
+        TweetRevision,
+        r#"
+        SELECT id, tweet_id, revision, content, created_at, replaced_at
+        FROM tweet_revisions
+        WHERE tweet_id = $1
+        ORDER BY revision
+        "#,
+        tweet_id
+    )
+    .fetch_all(&mut *transaction)
+    .await
+    .context("Failed to fetch tweet revisions")?;
+
+    Ok(revisions)
+}
+
+/// Number of recorded revisions per tweet; tweets that were never edited are
+/// absent from the map.
+pub async fn get_revision_counts_by_tweet_ids(
+    transaction: &mut Transaction<'_, Postgres>,
+    tweet_ids: &[Uuid],
+) -> Result<HashMap<Uuid, i64>, anyhow::Error> {
+    let rows = sqlx::query!(
+        r#"
+        SELECT tweet_id, COUNT(*) AS "count!"
+        FROM tweet_revisions
+        WHERE tweet_id = ANY($1)
+        GROUP BY tweet_id
+        "#,
+        tweet_ids
+    )
+    .fetch_all(&mut **transaction)
+    .await
+    .context("Failed to count tweet revisions")?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| (row.tweet_id, row.count))
+        .collect())
+}
@@ -1,18 +1,23 @@
 use crate::dto::query::DtoQuery;
 use crate::dto::response::DtoResponse;
 use crate::dto::{pagination::Pagination, query::UserFilterQuery};
-use crate::models::user::User;
+use crate::error::AlohaError;
+use crate::models::conversation::DmPolicy;
+use crate::models::notification::{Notification, NotificationKind};
+use crate::models::user::{User, UserProfile};
 use anyhow::Context;
 use sqlx::{Postgres, Transaction};
 use uuid::Uuid;
 
+use super::notification::insert_notification;
+
 pub async fn get_all_users(
     mut transaction: Transaction<'_, Postgres>,
     dto_query: DtoQuery<UserFilterQuery>,
 ) -> Result<DtoResponse<Vec<User>>, anyhow::Error> {
     let offset = dto_query.offset() as i64;
     let limit = dto_query.size() as i64;
-    let total = sqlx::query!("SELECT COUNT(*) FROM users")
+    let total = sqlx::query!("SELECT COUNT(*) FROM users WHERE deleted_at IS NULL")
         .fetch_one(&mut *transaction)
         .await?
         .count;
@@ -24,9 +29,10 @@ pub async fn get_all_users(
 
     let rows = sqlx::query!(
         r#"
-        SELECT id, username, password_hash, created_at, user_group_id 
+        SELECT id, username, password_hash, created_at, user_group_id,
+               display_name, bio, location, website, avatar_media_id, banner_media_id 
         FROM users 
-        WHERE user_group_id IS NOT DISTINCT FROM $1
+        WHERE user_group_id IS NOT DISTINCT FROM $1 AND deleted_at IS NULL
         ORDER BY id 
         LIMIT $2 OFFSET $3
         "#,
@@ -46,6 +52,12 @@ pub async fn get_all_users(
             password_hash: row.password_hash,
             created_at: row.created_at,
             user_group_id: row.user_group_id,
+            display_name: row.display_name,
+            bio: row.bio,
+            location: row.location,
+            website: row.website,
+            avatar_media_id: row.avatar_media_id,
+            banner_media_id: row.banner_media_id,
         })
         .collect();
 
@@ -64,9 +76,10 @@ pub async fn get_user_by_id(
     let row = sqlx::query_as!(
         User,
         r#"
-        SELECT id, username, password_hash, created_at, user_group_id 
+        SELECT id, username, password_hash, created_at, user_group_id,
+               display_name, bio, location, website, avatar_media_id, banner_media_id 
         FROM users
-        WHERE id = $1
+        WHERE id = $1 AND deleted_at IS NULL
         "#,
         id
     )
@@ -82,9 +95,10 @@ pub async fn get_user_by_username(
 ) -> Result<User, anyhow::Error> {
     let row = sqlx::query!(
         r#"
-        SELECT id, username, password_hash, created_at, user_group_id 
+        SELECT id, username, password_hash, created_at, user_group_id,
+               display_name, bio, location, website, avatar_media_id, banner_media_id 
         FROM users
-        WHERE username = $1
+        WHERE username = $1 AND deleted_at IS NULL
         "#,
         username
     )
@@ -98,6 +112,12 @@ pub async fn get_user_by_username(
         password_hash: row.password_hash,
         created_at: row.created_at,
         user_group_id: row.user_group_id,
+        display_name: row.display_name,
+        bio: row.bio,
+        location: row.location,
+        website: row.website,
+        avatar_media_id: row.avatar_media_id,
+        banner_media_id: row.banner_media_id,
     })
 }
 
@@ -109,7 +129,8 @@ pub async fn insert_user(
         r#"
         INSERT INTO users (id, username, password_hash, user_group_id) 
         VALUES ($1, $2, $3, $4) 
-        RETURNING id, username, password_hash, created_at, user_group_id
+        RETURNING id, username, password_hash, created_at, user_group_id,
+                  display_name, bio, location, website, avatar_media_id, banner_media_id
         "#,
         user.id,
         user.username,
@@ -131,6 +152,12 @@ pub async fn insert_user(
         password_hash: row.password_hash,
         created_at: row.created_at,
         user_group_id: row.user_group_id,
+        display_name: row.display_name,
+        bio: row.bio,
+        location: row.location,
+        website: row.website,
+        avatar_media_id: row.avatar_media_id,
+        banner_media_id: row.banner_media_id,
     })
 }
 
@@ -140,9 +167,21 @@ pub async fn delete_user_by_id(
 ) -> Result<User, anyhow::Error> {
     let row = sqlx::query!(
         r#"
-        DELETE FROM users 
-        WHERE id = $1 
-        RETURNING id, username, password_hash, created_at, user_group_id
+        WITH deleted AS (
+            UPDATE users
+            SET deleted_at = now()
+            WHERE id = $1 AND deleted_at IS NULL
+            RETURNING id, username, password_hash, created_at, user_group_id,
+                      display_name, bio, location, website, avatar_media_id, banner_media_id, deleted_at
+        ), deleted_tweets AS (
+            UPDATE tweet
+            SET deleted_at = deleted.deleted_at
+            FROM deleted
+            WHERE tweet.user_id = deleted.id AND tweet.deleted_at IS NULL
+        )
+        SELECT id, username, password_hash, created_at, user_group_id,
+               display_name, bio, location, website, avatar_media_id, banner_media_id
+        FROM deleted
         "#,
         id
     )
@@ -161,19 +200,37 @@ pub async fn delete_user_by_id(
         password_hash: row.password_hash,
         created_at: row.created_at,
         user_group_id: row.user_group_id,
+        display_name: row.display_name,
+        bio: row.bio,
+        location: row.location,
+        website: row.website,
+        avatar_media_id: row.avatar_media_id,
+        banner_media_id: row.banner_media_id,
     })
 }
 
+/// Replaces a user's account fields. Moving the user to another
+/// `user_group` notifies them.
 pub async fn update_user(
     mut transaction: Transaction<'_, Postgres>,
     user: &User,
 ) -> Result<User, anyhow::Error> {
+    let previous_group_id = sqlx::query!(
+        "SELECT user_group_id FROM users WHERE id = $1 AND deleted_at IS NULL FOR UPDATE",
+        user.id
+    )
+    .fetch_optional(&mut *transaction)
+    .await
+    .context("Failed to lock user")?
+    .and_then(|row| row.user_group_id);
+
     let row = sqlx::query!(
         r#"
         UPDATE users 
         SET username = $1, password_hash = $2, user_group_id = $3 
-        WHERE id = $4 
-        RETURNING id, username, password_hash, created_at, user_group_id
+        WHERE id = $4 AND deleted_at IS NULL
+        RETURNING id, username, password_hash, created_at, user_group_id,
+                  display_name, bio, location, website, avatar_media_id, banner_media_id
         "#,
         user.username,
         user.password_hash,
@@ -184,6 +241,14 @@ pub async fn update_user(
     .await
     .context("Failed to update user")?;
 
+    if row.user_group_id != previous_group_id {
+        let notification = Notification {
+            user_group_id: row.user_group_id,
+            ..Notification::new(row.id, NotificationKind::UserGroupChanged)
+        };
+        insert_notification(&mut transaction, &notification).await?;
+    }
+
     transaction
         .commit()
         .await
@@ -195,18 +260,148 @@ pub async fn update_user(
         password_hash: row.password_hash,
         created_at: row.created_at,
         user_group_id: row.user_group_id,
+        display_name: row.display_name,
+        bio: row.bio,
+        location: row.location,
+        website: row.website,
+        avatar_media_id: row.avatar_media_id,
+        banner_media_id: row.banner_media_id,
     })
 }
 
+/// Replaces the profile of `user_id`. The avatar and banner must be images
+/// the user uploaded. Returns `None` for unknown users.
+pub async fn update_user_profile(
+    mut transaction: Transaction<'_, Postgres>,
+    user_id: Uuid,
+    profile: &UserProfile,
+) -> Result<Option<User>, anyhow::Error> {
+    let media_ids: Vec<Uuid> = [profile.avatar_media_id, profile.banner_media_id]
+        .into_iter()
+        .flatten()
+        .collect();
+    if !media_ids.is_empty() {
+        let images = sqlx::query!(
+            r#"
+            SELECT COUNT(*) AS "count!"
+            FROM media
+            WHERE id = ANY($1) AND user_id = $2 AND mime_type LIKE 'image/%'
+            "#,
+            &media_ids,
+            user_id
+        )
+        .fetch_one(&mut *transaction)
+        .await
+        .context("Failed to check profile media")?
+        .count;
+        let mut unique_ids = media_ids.clone();
+        unique_ids.dedup();
+        if images as usize != unique_ids.len() {
+            return Err(AlohaError::RequestParameterInvalid(
+                "Avatars and banners must be images you uploaded.".to_string(),
+            )
+            .into());
+        }
+    }
+
+    let row = sqlx::query_as!(
+        User,
+        r#"
+        UPDATE users
+        SET display_name = $2, bio = $3, location = $4, website = $5,
+            avatar_media_id = $6, banner_media_id = $7
+        WHERE id = $1 AND deleted_at IS NULL
+        RETURNING id, username, password_hash, created_at, user_group_id,
+                  display_name, bio, location, website, avatar_media_id, banner_media_id
+        "#,
+        user_id,
+        profile.display_name,
+        profile.bio,
+        profile.location,
+        profile.website,
+        profile.avatar_media_id,
+        profile.banner_media_id
+    )
+    .fetch_optional(&mut *transaction)
+    .await
+    .context("Failed to update user profile")?;
+
+    transaction
+        .commit()
+        .await
+        .context("Failed to commit SQL transaction to update a user profile.")?;
+
+    Ok(row)
+}
+
+pub async fn get_dm_policy(
+    mut transaction: Transaction<'_, Postgres>,
+    user_id: Uuid,
+) -> Result<Option<DmPolicy>, anyhow::Error> {
+    let row = sqlx::query!(
+        r#"
+        SELECT dm_policy AS "dm_policy: DmPolicy"
+        FROM users
+        WHERE id = $1 AND deleted_at IS NULL
+        "#,
+        user_id
+    )
+    .fetch_optional(&mut *transaction)
+    .await
+    .context("Failed to fetch DM policy")?;
+
+    Ok(row.map(|row| row.dm_policy))
+}
+
+/// Sets who may start conversations with `user_id`. Returns `None` for
+/// unknown users.
+pub async fn update_dm_policy(
+    mut transaction: Transaction<'_, Postgres>,
+    user_id: Uuid,
+    dm_policy: DmPolicy,
+) -> Result<Option<DmPolicy>, anyhow::Error> {
+    let row = sqlx::query!(
+        r#"
+        UPDATE users SET dm_policy = $2
+        WHERE id = $1 AND deleted_at IS NULL
+        RETURNING dm_policy AS "dm_policy: DmPolicy"
+        "#,
+        user_id,
+        dm_policy as DmPolicy
+    )
+    .fetch_optional(&mut *transaction)
+    .await
+    .context("Failed to update DM policy")?;
+
+    transaction
+        .commit()
+        .await
+        .context("Failed to commit SQL transaction to update a DM policy.")?;
+
+    Ok(row.map(|row| row.dm_policy))
+}
+
 pub async fn delete_users_by_ids(
     mut transaction: Transaction<'_, Postgres>,
     ids: Vec<Uuid>,
 ) -> Result<Vec<User>, anyhow::Error> {
     let rows = sqlx::query!(
         r#"
-        DELETE FROM users 
-        WHERE id = ANY($1) 
-        RETURNING id, username, password_hash, created_at, user_group_id
+        WITH deleted AS (
+            UPDATE users
+            SET deleted_at = now()
+            WHERE id = ANY($1) AND deleted_at IS NULL
+            RETURNING id, username, password_hash, created_at, user_group_id,
+                      display_name, bio, location, website, avatar_media_id, banner_media_id, deleted_at
+        ), deleted_tweets AS (
+            UPDATE tweet
+            SET deleted_at = deleted.deleted_at
+            FROM deleted
+            WHERE tweet.user_id = deleted.id AND tweet.deleted_at IS NULL
+        )
+        SELECT id, username, password_hash, created_at, user_group_id,
+               display_name, bio, location, website, avatar_media_id, banner_media_id
+        FROM deleted
         "#,
         &ids
     )
@@ -227,6 +422,12 @@ pub async fn delete_users_by_ids(
             password_hash: row.password_hash,
             created_at: row.created_at,
             user_group_id: row.user_group_id,
+            display_name: row.display_name,
+            bio: row.bio,
+            location: row.location,
+            website: row.website,
+            avatar_media_id: row.avatar_media_id,
+            banner_media_id: row.banner_media_id,
         })
         .collect();
 
@@ -239,7 +440,7 @@ pub async fn check_user_id_is_valid(
 ) -> Result<bool, anyhow::Error> {
     let record = sqlx::query!(
         r#"
-        SELECT EXISTS(SELECT 1 FROM users WHERE id = $1) AS "exists!"
+        SELECT EXISTS(SELECT 1 FROM users WHERE id = $1 AND deleted_at IS NULL) AS "exists!"
         "#,
         user_id
     )
@@ -266,3 +467,57 @@ pub async fn check_user_password_correct(
 
     Ok(record.exists)
 }
+
+/// Clears `deleted_at` on a soft-deleted user together with the tweets that
+/// were deleted alongside them. Tweets the user had already deleted beforehand
+/// stay deleted.
+pub async fn restore_user_by_id(
+    mut transaction: Transaction<'_, Postgres>,
+    id: Uuid,
+) -> Result<Option<User>, anyhow::Error> {
+    let row = sqlx::query!(
+        r#"
+        WITH target AS (
+            SELECT id, deleted_at
+            FROM users
+            WHERE id = $1 AND deleted_at IS NOT NULL
+            FOR UPDATE
+        ), restored_tweets AS (
+            UPDATE tweet
+            SET deleted_at = NULL
+            FROM target
+            WHERE tweet.user_id = target.id AND tweet.deleted_at = target.deleted_at
+        )
+        UPDATE users
+        SET deleted_at = NULL
+        FROM target
+        WHERE users.id = target.id
+        RETURNING users.id, users.username, users.password_hash, users.created_at, users.user_group_id,
+                  users.display_name, users.bio, users.location, users.website,
+                  users.avatar_media_id, users.banner_media_id
+        "#,
+        id
+    )
+    .fetch_optional(&mut *transaction)
+    .await
+    .context("Failed to restore user")?;
+
+    transaction
+        .commit()
+        .await
+        .context("Failed to commit SQL transaction to restore a user.")?;
+
+    Ok(row.map(|row| User {
+        id: row.id,
+        username: row.username,
+        password_hash: row.password_hash,
+        created_at: row.created_at,
+        user_group_id: row.user_group_id,
+        display_name: row.display_name,
+        bio: row.bio,
+        location: row.location,
+        website: row.website,
+        avatar_media_id: row.avatar_media_id,
+        banner_media_id: row.banner_media_id,
+    }))
+}
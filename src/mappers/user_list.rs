@@ -0,0 +1,368 @@
+use crate::dto::pagination::Pagination;
+use crate::dto::query::{DtoQuery, TweetFilterQuery, UserListFilterQuery};
+use crate::dto::response::DtoResponse;
+use crate::error::AlohaError;
+use crate::models::tweet::{Tweet, TweetVisibility};
+use crate::models::user::User;
+use crate::models::user_list::{UserList, UserListMember};
+use anyhow::Context;
+use sqlx::{Postgres, Transaction};
+use uuid::Uuid;
+
+use super::user::check_user_id_is_valid;
+
+/// Returns whether `list_id` exists and belongs to `owner_id`.
+async fn check_list_owner(
+    transaction: &mut Transaction<'_, Postgres>,
+    owner_id: Uuid,
+    list_id: Uuid,
+) -> Result<bool, anyhow::Error> {
+    let row = sqlx::query!(
+        "SELECT EXISTS(SELECT 1 FROM user_lists WHERE id = $1 AND owner_id = $2) AS \"exists!\"",
+        list_id,
+        owner_id
+    )
+    .fetch_one(&mut **transaction)
+    .await
+    .context("Failed to check list owner")?;
+
+    Ok(row.exists)
+}
+
+pub async fn insert_user_list(
+    mut transaction: Transaction<'_, Postgres>,
+    list: &UserList,
+) -> Result<UserList, anyhow::Error> {
+    let row = sqlx::query_as!(
+        UserList,
+        r#"
+        INSERT INTO user_lists (id, owner_id, name, description)
+        VALUES ($1, $2, $3, $4)
+        RETURNING id, owner_id, name, description, created_at, updated_at
+        "#,
+        list.id,
+        list.owner_id,
+        list.name,
+        list.description
+    )
+    .fetch_one(&mut *transaction)
+    .await
+    .context("Failed to insert list")?;
+
+    transaction
+        .commit()
+        .await
+        .context("Failed to commit SQL transaction to insert a new list.")?;
+
+    Ok(row)
+}
+
+pub async fn get_user_lists_by_owner_id(
+    mut transaction: Transaction<'_, Postgres>,
+    owner_id: Uuid,
+    dto_query: DtoQuery<UserListFilterQuery>,
+) -> Result<DtoResponse<Vec<UserList>>, anyhow::Error> {
+    let offset = dto_query.offset() as i64;
+    let limit = dto_query.size() as i64;
+    let total = sqlx::query!(
+        "SELECT COUNT(*) FROM user_lists WHERE owner_id = $1",
+        owner_id
+    )
+    .fetch_one(&mut *transaction)
+    .await?
+    .count;
+
+    let data = sqlx::query_as!(
+        UserList,
+        r#"
+        SELECT id, owner_id, name, description, created_at, updated_at
+        FROM user_lists
+        WHERE owner_id = $1
+        ORDER BY created_at DESC, id
+        LIMIT $2 OFFSET $3
+        "#,
+        owner_id,
+        limit,
+        offset
+    )
+    .fetch_all(&mut *transaction)
+    .await
+    .context("Failed to fetch lists")?;
+
+    let pagination = Pagination::new(Some(dto_query.page()), Some(dto_query.size()), total);
+    Ok(DtoResponse::new(data, Some(pagination)))
+}
+
+pub async fn get_user_list_by_id(
+    mut transaction: Transaction<'_, Postgres>,
+    owner_id: Uuid,
+    id: Uuid,
+) -> Result<Option<UserList>, anyhow::Error> {
+    let row = sqlx::query_as!(
+        UserList,
+        r#"
+        SELECT id, owner_id, name, description, created_at, updated_at
+        FROM user_lists
+        WHERE id = $1 AND owner_id = $2
+        "#,
+        id,
+        owner_id
+    )
+    .fetch_optional(&mut *transaction)
+    .await
+    .context("Failed to fetch list by id")?;
+
+    Ok(row)
+}
+
+/// Renames a list owned by `list.owner_id`. Returns `None` for lists of other
+/// users.
+pub async fn update_user_list(
+    mut transaction: Transaction<'_, Postgres>,
+    list: &UserList,
+) -> Result<Option<UserList>, anyhow::Error> {
+    let row = sqlx::query_as!(
+        UserList,
+        r#"
+        UPDATE user_lists
+        SET name = $1, description = $2, updated_at = now()
+        WHERE id = $3 AND owner_id = $4
+        RETURNING id, owner_id, name, description, created_at, updated_at
+        "#,
+        list.name,
+        list.description,
+        list.id,
+        list.owner_id
+    )
+    .fetch_optional(&mut *transaction)
+    .await
+    .context("Failed to update list")?;
+
+    transaction
+        .commit()
+        .await
+        .context("Failed to commit SQL transaction to update a list.")?;
+
+    Ok(row)
+}
+
+pub async fn delete_user_list(
+    mut transaction: Transaction<'_, Postgres>,
+    owner_id: Uuid,
+    id: Uuid,
+) -> Result<Option<UserList>, anyhow::Error> {
+    let row = sqlx::query_as!(
+        UserList,
+        r#"
+        DELETE FROM user_lists
+        WHERE id = $1 AND owner_id = $2
+        RETURNING id, owner_id, name, description, created_at, updated_at
+        "#,
+        id,
+        owner_id
+    )
+    .fetch_optional(&mut *transaction)
+    .await
+    .context("Failed to delete list")?;
+
+    transaction
+        .commit()
+        .await
+        .context("Failed to commit SQL transaction to delete a list.")?;
+
+    Ok(row)
+}
+
+/// Adds `user_id` to a list owned by `owner_id`. Adding a member twice is a
+/// no-op. Returns `None` when the list is not one of the owner's.
+pub async fn insert_user_list_member(
+    mut transaction: Transaction<'_, Postgres>,
+    owner_id: Uuid,
+    list_id: Uuid,
+    user_id: Uuid,
+) -> Result<Option<UserListMember>, anyhow::Error> {
+    if !check_list_owner(&mut transaction, owner_id, list_id).await? {
+        return Ok(None);
+    }
+    let is_user_valid = check_user_id_is_valid(&mut transaction, user_id).await?;
+    if !is_user_valid {
+        return Err(AlohaError::UserIdInvalid.into());
+    }
+
+    let row = sqlx::query_as!(
+        UserListMember,
+        r#"
+        INSERT INTO user_list_members (list_id, user_id)
+        VALUES ($1, $2)
+        ON CONFLICT (list_id, user_id)
+        DO UPDATE SET list_id = EXCLUDED.list_id
+        RETURNING list_id, user_id, created_at
+        "#,
+        list_id,
+        user_id
+    )
+    .fetch_one(&mut *transaction)
+    .await
+    .context("Failed to insert list member")?;
+
+    transaction
+        .commit()
+        .await
+        .context("Failed to commit SQL transaction to insert a list member.")?;
+
+    Ok(Some(row))
+}
+
+pub async fn delete_user_list_member(
+    mut transaction: Transaction<'_, Postgres>,
+    owner_id: Uuid,
+    list_id: Uuid,
+    user_id: Uuid,
+) -> Result<Option<UserListMember>, anyhow::Error> {
+    let row = sqlx::query_as!(
+        UserListMember,
+        r#"
+        DELETE FROM user_list_members m
+        USING user_lists l
+        WHERE l.id = m.list_id AND m.list_id = $1 AND m.user_id = $2 AND l.owner_id = $3
+        RETURNING m.list_id, m.user_id, m.created_at
+        "#,
+        list_id,
+        user_id,
+        owner_id
+    )
+    .fetch_optional(&mut *transaction)
+    .await
+    .context("Failed to delete list member")?;
+
+    transaction
+        .commit()
+        .await
+        .context("Failed to commit SQL transaction to delete a list member.")?;
+
+    Ok(row)
+}
+
+/// Members of a list owned by `owner_id`, most recently added first, or `None`
+/// when the list is not one of the owner's.
+pub async fn get_user_list_members(
+    mut transaction: Transaction<'_, Postgres>,
+    owner_id: Uuid,
+    list_id: Uuid,
+    dto_query: DtoQuery<UserListFilterQuery>,
+) -> Result<Option<DtoResponse<Vec<User>>>, anyhow::Error> {
+    if !check_list_owner(&mut transaction, owner_id, list_id).await? {
+        return Ok(None);
+    }
+    let offset = dto_query.offset() as i64;
+    let limit = dto_query.size() as i64;
+
+    let total = sqlx::query!(
+        r#"
+        SELECT COUNT(*)
+        FROM user_list_members m
+        JOIN users u ON u.id = m.user_id
+        WHERE m.list_id = $1 AND u.deleted_at IS NULL
+        "#,
+        list_id
+    )
+    .fetch_one(&mut *transaction)
+    .await?
+    .count;
+
+    let users = sqlx::query_as!(
+        User,
+        r#"
+        SELECT u.id, u.username, u.password_hash, u.created_at, u.user_group_id,
+               u.display_name, u.bio, u.location, u.website, u.avatar_media_id,
+               u.banner_media_id
+        FROM user_list_members m
+        JOIN users u ON u.id = m.user_id
+        WHERE m.list_id = $1 AND u.deleted_at IS NULL
+        ORDER BY m.created_at DESC, u.id
+        LIMIT $2 OFFSET $3
+        "#,
+        list_id,
+        limit,
+        offset
+    )
+    .fetch_all(&mut *transaction)
+    .await
+    .context("Failed to fetch list members")?;
+
+    let pagination = Pagination::new(Some(dto_query.page()), Some(dto_query.size()), total);
+    Ok(Some(DtoResponse::new(users, Some(pagination))))
+}
+
+/// Tweets written by the members of a list owned by `owner_id` that the owner
+/// may read and has not muted, newest first, or `None` when the list is not
+/// one of the owner's.
+pub async fn get_user_list_timeline(
+    mut transaction: Transaction<'_, Postgres>,
+    owner_id: Uuid,
+    list_id: Uuid,
+    dto_query: DtoQuery<TweetFilterQuery>,
+) -> Result<Option<DtoResponse<Vec<Tweet>>>, anyhow::Error> {
+    if !check_list_owner(&mut transaction, owner_id, list_id).await? {
+        return Ok(None);
+    }
+    let offset = dto_query.offset() as i64;
+    let limit = dto_query.size() as i64;
+    let author_id = dto_query.filter.as_ref().and_then(|f| f.user_id);
+
+    let total = sqlx::query!(
+        r#"
+        SELECT COUNT(*)
+        FROM tweet t
+        WHERE t.user_id IN (SELECT user_id FROM user_list_members WHERE list_id = $1)
+        AND ($2::uuid IS NULL OR t.user_id = $2)
+        AND t.deleted_at IS NULL AND t.status = 'published'
+        AND tweet_visible_to(t.user_id, t.visibility, $3)
+        AND NOT user_muted_by(t.user_id, $3)
+        "#,
+        list_id,
+        author_id,
+        owner_id
+    )
+    .fetch_one(&mut *transaction)
+    .await?
+    .count;
+
+    let rows = sqlx::query!(
+        r#"
+        SELECT t.id, t.content, t.created_at, t.updated_at, t.user_id,
+               t.visibility AS "visibility: TweetVisibility"
+        FROM tweet t
+        WHERE t.user_id IN (SELECT user_id FROM user_list_members WHERE list_id = $1)
+        AND ($2::uuid IS NULL OR t.user_id = $2)
+        AND t.deleted_at IS NULL AND t.status = 'published'
+        AND tweet_visible_to(t.user_id, t.visibility, $3)
+        AND NOT user_muted_by(t.user_id, $3)
+        ORDER BY t.created_at DESC, t.id DESC
+        LIMIT $4 OFFSET $5
+        "#,
+        list_id,
+        author_id,
+        owner_id,
+        limit,
+        offset
+    )
+    .fetch_all(&mut *transaction)
+    .await
+    .context("Failed to fetch list timeline")?;
+
+    let data: Vec<Tweet> = rows
+        .into_iter()
+        .map(|row| Tweet {
+            id: row.id,
+            content: row.content,
+            created_at: row.created_at,
+            updated_at: row.updated_at,
+            user_id: row.user_id,
+            visibility: row.visibility,
+        })
+        .collect();
+
+    let pagination = Pagination::new(Some(dto_query.page()), Some(dto_query.size()), total);
+    Ok(Some(DtoResponse::new(data, Some(pagination))))
+}
@@ -1,12 +1,15 @@
 use crate::dto::query::DtoQuery;
 use crate::dto::response::DtoResponse;
 use crate::dto::{pagination::Pagination, query::UserPermissionFilterQuery};
+use crate::models::notification::{Notification, NotificationKind};
 use crate::models::user_permission::UserPermission;
 use anyhow::{Context, Result};
 use sqlx::{Postgres, Transaction};
 use tracing::error;
 use uuid::Uuid;
 
+use super::notification::insert_notification;
+
 pub async fn get_all_user_permissions(
     mut transaction: Transaction<'_, Postgres>,
     dto_query: DtoQuery<UserPermissionFilterQuery>,
@@ -68,6 +71,7 @@ pub async fn get_user_permissions_by_permission_id(
     Ok(permissions)
 }
 
+/// Grants a permission to a user and notifies them.
 pub async fn insert_user_permission(
     mut transaction: Transaction<'_, Postgres>,
     user_permission: &UserPermission,
@@ -83,6 +87,11 @@ pub async fn insert_user_permission(
     .context("Failed to insert user_permission")
     {
         Ok(row) => {
+            let notification = Notification {
+                permission_id: Some(row.permission_id),
+                ..Notification::new(row.user_id, NotificationKind::PermissionGranted)
+            };
+            insert_notification(&mut transaction, &notification).await?;
             transaction
                 .commit()
                 .await
@@ -93,6 +102,7 @@ pub async fn insert_user_permission(
     }
 }
 
+/// Revokes a permission from a user and notifies them.
 pub async fn delete_user_permission(
     mut transaction: Transaction<'_, Postgres>,
     user_id: Uuid,
@@ -109,6 +119,11 @@ pub async fn delete_user_permission(
     .context("Failed to delete user_permission")
     {
         Ok(row) => {
+            let notification = Notification {
+                permission_id: Some(row.permission_id),
+                ..Notification::new(row.user_id, NotificationKind::PermissionRevoked)
+            };
+            insert_notification(&mut transaction, &notification).await?;
             transaction
                 .commit()
                 .await
@@ -163,3 +178,37 @@ pub async fn delete_user_permissions_by_permission_id(
 
     Ok(permissions)
 }
+
+/// Whether `user_id` holds the permission called `name`, granted either
+/// directly or through the user's group.
+pub async fn user_has_permission(
+    transaction: &mut Transaction<'_, Postgres>,
+    user_id: Uuid,
+    name: &str,
+) -> Result<bool, anyhow::Error> {
+    let record = sqlx::query!(
+        r#"
+        SELECT EXISTS(
+            SELECT 1
+            FROM permissions p
+            WHERE p.name = $2
+            AND (
+                p.id IN (SELECT permission_id FROM user_permissions WHERE user_id = $1)
+                OR p.id IN (
+                    SELECT gp.permission_id
+                    FROM group_permissions gp
+                    JOIN users u ON u.user_group_id = gp.group_id
+                    WHERE u.id = $1
+                )
+            )
+        ) AS "exists!"
+        "#,
+        user_id,
+        name
+    )
+    .fetch_one(&mut **transaction)
+    .await
+    .context("Failed to check user permission")?;
+
+    Ok(record.exists)
+}
@@ -0,0 +1,159 @@
+use crate::dto::response::get_time_formatter;
+use serde::{Deserialize, Serialize};
+use sqlx::types::time::OffsetDateTime;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// Longest accepted reason for an account status change, in characters.
+pub const MAX_ACCOUNT_STATUS_REASON_LENGTH: usize = 500;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, ToSchema, sqlx::Type)]
+#[serde(rename_all = "lowercase")]
+#[sqlx(type_name = "varchar", rename_all = "lowercase")]
+pub enum AccountStatus {
+    Active,
+    /// Deactivated by the user themselves.
+    Deactivated,
+    /// Kept out until `suspended_until`, after which the account is active
+    /// again.
+    Suspended,
+    /// Kept out for good; the user's tweets are hidden.
+    Banned,
+}
+
+impl AccountStatus {
+    /// Active accounts can be deactivated, suspended or banned. Deactivated
+    /// and suspended accounts can be reactivated or banned, and suspensions
+    /// can be extended. Banned accounts can only be reactivated.
+    pub fn can_transition_to(self, next: AccountStatus) -> bool {
+        use AccountStatus::*;
+        matches!(
+            (self, next),
+            (Active, Deactivated | Suspended | Banned)
+                | (Deactivated, Active | Banned)
+                | (Suspended, Active | Suspended | Banned)
+                | (Banned, Active)
+        )
+    }
+}
+
+/// The status of the account of `user_id` and why it was last changed.
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq)]
+pub struct AccountState {
+    pub user_id: Uuid,
+    pub status: AccountStatus,
+    pub reason: Option<String>,
+    pub suspended_until: Option<OffsetDateTime>,
+    pub changed_at: Option<OffsetDateTime>,
+}
+
+impl AccountState {
+    /// The status in force at `now`: suspensions that have run out count as
+    /// active.
+    pub fn effective_status(&self, now: OffsetDateTime) -> AccountStatus {
+        match (self.status, self.suspended_until) {
+            (AccountStatus::Suspended, Some(until)) if until <= now => AccountStatus::Active,
+            (status, _) => status,
+        }
+    }
+
+    /// Why the account may not log in at `now`, or `None` when it may.
+    pub fn login_denial(&self, now: OffsetDateTime) -> Option<String> {
+        let message = match self.effective_status(now) {
+            AccountStatus::Active => return None,
+            AccountStatus::Deactivated => "This account is deactivated.".to_string(),
+            AccountStatus::Suspended => match self.suspended_until {
+                Some(until) => format!(
+                    "This account is suspended until {}.",
+                    until.format(&get_time_formatter()).unwrap()
+                ),
+                None => "This account is suspended.".to_string(),
+            },
+            AccountStatus::Banned => "This account is banned.".to_string(),
+        };
+        Some(match &self.reason {
+            Some(reason) => format!("{} Reason: {}", message, reason),
+            None => message,
+        })
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq, ToSchema)]
+pub struct AccountStateResponse {
+    pub user_id: Uuid,
+    pub status: AccountStatus,
+    pub reason: Option<String>,
+    pub suspended_until: Option<String>,
+    pub changed_at: Option<String>,
+}
+
+impl AccountStateResponse {
+    /// Reports expired suspensions as active.
+    pub fn new(state: AccountState, now: OffsetDateTime) -> Self {
+        let status = state.effective_status(now);
+        Self {
+            user_id: state.user_id,
+            status,
+            reason: state.reason,
+            suspended_until: state
+                .suspended_until
+                .filter(|_| status == AccountStatus::Suspended)
+                .map(|t| t.format(&get_time_formatter()).unwrap()),
+            changed_at: state
+                .changed_at
+                .map(|t| t.format(&get_time_formatter()).unwrap()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::models::account::{AccountState, AccountStatus};
+    use time::{Duration, OffsetDateTime};
+    use uuid::Uuid;
+
+    #[test]
+    fn test_account_status_transitions() {
+        use AccountStatus::*;
+        assert!(Active.can_transition_to(Deactivated));
+        assert!(Active.can_transition_to(Banned));
+        assert!(!Active.can_transition_to(Active));
+        assert!(Deactivated.can_transition_to(Active));
+        assert!(!Deactivated.can_transition_to(Suspended));
+        assert!(Suspended.can_transition_to(Suspended));
+        assert!(Banned.can_transition_to(Active));
+        assert!(!Banned.can_transition_to(Suspended));
+        assert!(!Banned.can_transition_to(Deactivated));
+    }
+
+    #[test]
+    fn test_account_state_login_denial() {
+        let now = OffsetDateTime::now_utc();
+        let suspended = AccountState {
+            user_id: Uuid::new_v4(),
+            status: AccountStatus::Suspended,
+            reason: Some("Spam".to_string()),
+            suspended_until: Some(now + Duration::days(1)),
+            changed_at: Some(now),
+        };
+        assert_eq!(suspended.effective_status(now), AccountStatus::Suspended);
+        let denial = suspended.login_denial(now).unwrap();
+        assert!(denial.starts_with("This account is suspended until"));
+        assert!(denial.ends_with("Reason: Spam"));
+
+        let later = now + Duration::days(2);
+        assert_eq!(suspended.effective_status(later), AccountStatus::Active);
+        assert_eq!(suspended.login_denial(later), None);
+
+        let banned = AccountState {
+            status: AccountStatus::Banned,
+            reason: None,
+            suspended_until: None,
+            ..suspended
+        };
+        assert_eq!(
+            banned.login_denial(later).as_deref(),
+            Some("This account is banned.")
+        );
+    }
+}
@@ -0,0 +1,44 @@
+use crate::dto::response::get_time_formatter;
+use serde::{Deserialize, Serialize};
+use sqlx::types::time::OffsetDateTime;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// `blocker_id` has blocked `blocked_id`. Neither user can read the other's
+/// tweets or mention the other.
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq, ToSchema)]
+pub struct Block {
+    pub blocker_id: Uuid,
+    pub blocked_id: Uuid,
+    #[schema(value_type = String)]
+    pub created_at: Option<OffsetDateTime>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq, ToSchema)]
+pub struct BlockResponse {
+    pub blocker_id: Uuid,
+    pub blocked_id: Uuid,
+    pub created_at: Option<String>,
+}
+
+impl From<Block> for BlockResponse {
+    fn from(value: Block) -> Self {
+        Self {
+            blocker_id: value.blocker_id,
+            blocked_id: value.blocked_id,
+            created_at: value
+                .created_at
+                .map(|t| t.format(&get_time_formatter()).unwrap()),
+        }
+    }
+}
+
+impl Block {
+    pub fn new(blocker_id: Uuid, blocked_id: Uuid) -> Self {
+        Self {
+            blocker_id,
+            blocked_id,
+            created_at: Some(OffsetDateTime::now_utc()),
+        }
+    }
+}
@@ -0,0 +1,43 @@
+use crate::dto::response::get_time_formatter;
+use serde::{Deserialize, Serialize};
+use sqlx::types::time::OffsetDateTime;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// A tweet saved by `user_id`. Bookmarks are only ever shown to their owner.
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq, ToSchema)]
+pub struct Bookmark {
+    pub user_id: Uuid,
+    pub tweet_id: Uuid,
+    #[schema(value_type = String)]
+    pub created_at: Option<OffsetDateTime>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq, ToSchema)]
+pub struct BookmarkResponse {
+    pub user_id: Uuid,
+    pub tweet_id: Uuid,
+    pub created_at: Option<String>,
+}
+
+impl From<Bookmark> for BookmarkResponse {
+    fn from(value: Bookmark) -> Self {
+        Self {
+            user_id: value.user_id,
+            tweet_id: value.tweet_id,
+            created_at: value
+                .created_at
+                .map(|t| t.format(&get_time_formatter()).unwrap()),
+        }
+    }
+}
+
+impl Bookmark {
+    pub fn new(user_id: Uuid, tweet_id: Uuid) -> Self {
+        Self {
+            user_id,
+            tweet_id,
+            created_at: Some(OffsetDateTime::now_utc()),
+        }
+    }
+}
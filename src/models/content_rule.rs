@@ -0,0 +1,354 @@
+use crate::dto::response::get_time_formatter;
+use once_cell::sync::Lazy;
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+use sqlx::types::time::OffsetDateTime;
+use std::ops::Range;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// Longest accepted rule pattern, matching `content_rules.pattern`.
+pub const MAX_CONTENT_RULE_PATTERN_LENGTH: usize = 255;
+/// Upper bound on the compiled size of regex rules, in bytes.
+const MAX_COMPILED_PATTERN_SIZE: usize = 1 << 20;
+
+/// Links and bare host names, capturing the host.
+static LINK_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)\b(?:https?://)?((?:[a-z0-9-]+\.)+[a-z]{2,})(?:[:/?#]\S*)?").unwrap()
+});
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, ToSchema, sqlx::Type)]
+#[serde(rename_all = "lowercase")]
+#[sqlx(type_name = "varchar", rename_all = "lowercase")]
+pub enum ContentRuleKind {
+    /// A word or phrase, matched case-insensitively on word boundaries.
+    Word,
+    /// A regular expression, matched case-insensitively.
+    Regex,
+    /// Links to a domain or any of its subdomains.
+    Domain,
+}
+
+/// What happens to a tweet matching a rule. Variants are ordered by
+/// severity; when several rules match, the most severe action wins.
+#[derive(
+    Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, ToSchema, sqlx::Type,
+)]
+#[serde(rename_all = "lowercase")]
+#[sqlx(type_name = "varchar", rename_all = "lowercase")]
+pub enum ContentRuleAction {
+    /// Replace the matched text with asterisks.
+    Mask,
+    /// Keep the tweet unpublished until a moderator releases it.
+    Hold,
+    /// Refuse the tweet.
+    Reject,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq)]
+pub struct ContentRule {
+    pub id: Uuid,
+    pub kind: ContentRuleKind,
+    pub pattern: String,
+    pub action: ContentRuleAction,
+    pub enabled: bool,
+    pub hit_count: i64,
+    pub last_hit_at: Option<OffsetDateTime>,
+    pub created_at: Option<OffsetDateTime>,
+    pub updated_at: Option<OffsetDateTime>,
+}
+
+/// A rule compiled for matching.
+enum ContentMatcher {
+    Pattern(Regex),
+    Domain(String),
+}
+
+impl ContentMatcher {
+    fn find_ranges(&self, content: &str) -> Vec<Range<usize>> {
+        match self {
+            ContentMatcher::Pattern(regex) => regex
+                .find_iter(content)
+                .filter(|m| !m.is_empty())
+                .map(|m| m.range())
+                .collect(),
+            ContentMatcher::Domain(domain) => LINK_REGEX
+                .captures_iter(content)
+                .filter(|c| {
+                    let host = c[1].to_lowercase();
+                    host == *domain || host.ends_with(&format!(".{}", domain))
+                })
+                .map(|c| c.get(0).unwrap().range())
+                .collect(),
+        }
+    }
+}
+
+impl ContentRule {
+    /// Builds an enabled rule. Word and domain patterns are trimmed and
+    /// domains lowercased; see `validate` for the accepted patterns.
+    pub fn new(kind: ContentRuleKind, pattern: &str, action: ContentRuleAction) -> Self {
+        let pattern = match kind {
+            ContentRuleKind::Word => pattern.trim().to_string(),
+            ContentRuleKind::Regex => pattern.to_string(),
+            ContentRuleKind::Domain => pattern.trim().trim_end_matches('.').to_lowercase(),
+        };
+        Self {
+            id: Uuid::new_v4(),
+            kind,
+            pattern,
+            action,
+            enabled: true,
+            hit_count: 0,
+            last_hit_at: None,
+            created_at: Some(OffsetDateTime::now_utc()),
+            updated_at: Some(OffsetDateTime::now_utc()),
+        }
+    }
+
+    /// Checks that the pattern is non-empty, fits the column, and compiles.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.pattern.is_empty() || self.pattern.chars().count() > MAX_CONTENT_RULE_PATTERN_LENGTH
+        {
+            return Err(format!(
+                "Rule patterns must be between 1 and {} characters long.",
+                MAX_CONTENT_RULE_PATTERN_LENGTH
+            ));
+        }
+        self.matcher().map(|_| ())
+    }
+
+    fn matcher(&self) -> Result<ContentMatcher, String> {
+        match self.kind {
+            ContentRuleKind::Word => {
+                let is_word_char = |c: char| c.is_alphanumeric() || c == '_';
+                let start = if self.pattern.starts_with(is_word_char) {
+                    r"\b"
+                } else {
+                    ""
+                };
+                let end = if self.pattern.ends_with(is_word_char) {
+                    r"\b"
+                } else {
+                    ""
+                };
+                let pattern = format!("{}{}{}", start, regex::escape(&self.pattern), end);
+                compile(&pattern).map(ContentMatcher::Pattern)
+            }
+            ContentRuleKind::Regex => compile(&self.pattern).map(ContentMatcher::Pattern),
+            ContentRuleKind::Domain => {
+                let is_valid = self.pattern.contains('.')
+                    && self.pattern.split('.').all(|label| {
+                        !label.is_empty()
+                            && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+                    });
+                if !is_valid {
+                    return Err(format!("'{}' is not a valid domain.", self.pattern));
+                }
+                Ok(ContentMatcher::Domain(self.pattern.clone()))
+            }
+        }
+    }
+}
+
+fn compile(pattern: &str) -> Result<Regex, String> {
+    RegexBuilder::new(pattern)
+        .case_insensitive(true)
+        .size_limit(MAX_COMPILED_PATTERN_SIZE)
+        .build()
+        .map_err(|e| format!("Invalid pattern: {}", e))
+}
+
+/// The outcome of checking a text against a set of rules.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ContentRuleEvaluation {
+    /// The text with the matches of `mask` rules replaced by asterisks.
+    pub content: String,
+    /// The most severe action among the matched rules.
+    pub action: Option<ContentRuleAction>,
+    pub matched_rule_ids: Vec<Uuid>,
+}
+
+/// Checks `content` against the enabled `rules`. Rules whose pattern no
+/// longer compiles are skipped.
+pub fn evaluate_content_rules(rules: &[ContentRule], content: &str) -> ContentRuleEvaluation {
+    let mut action = None;
+    let mut matched_rule_ids = Vec::new();
+    let mut masked: Vec<Range<usize>> = Vec::new();
+    for rule in rules.iter().filter(|r| r.enabled) {
+        let Ok(matcher) = rule.matcher() else {
+            continue;
+        };
+        let ranges = matcher.find_ranges(content);
+        if ranges.is_empty() {
+            continue;
+        }
+        matched_rule_ids.push(rule.id);
+        action = action.max(Some(rule.action));
+        if rule.action == ContentRuleAction::Mask {
+            masked.extend(ranges);
+        }
+    }
+
+    ContentRuleEvaluation {
+        content: mask_ranges(content, masked),
+        action,
+        matched_rule_ids,
+    }
+}
+
+/// Replaces every character inside `ranges`, which may overlap, with `*`.
+fn mask_ranges(content: &str, ranges: Vec<Range<usize>>) -> String {
+    if ranges.is_empty() {
+        return content.to_string();
+    }
+    content
+        .char_indices()
+        .map(|(i, c)| {
+            if ranges.iter().any(|r| r.contains(&i)) {
+                '*'
+            } else {
+                c
+            }
+        })
+        .collect()
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq, ToSchema)]
+pub struct ContentRuleResponse {
+    pub id: Uuid,
+    pub kind: ContentRuleKind,
+    pub pattern: String,
+    pub action: ContentRuleAction,
+    pub enabled: bool,
+    /// Number of tweets the rule matched.
+    pub hit_count: i64,
+    pub last_hit_at: Option<String>,
+    pub created_at: Option<String>,
+    pub updated_at: Option<String>,
+}
+
+impl From<ContentRule> for ContentRuleResponse {
+    fn from(rule: ContentRule) -> Self {
+        Self {
+            id: rule.id,
+            kind: rule.kind,
+            pattern: rule.pattern,
+            action: rule.action,
+            enabled: rule.enabled,
+            hit_count: rule.hit_count,
+            last_hit_at: rule
+                .last_hit_at
+                .map(|t| t.format(&get_time_formatter()).unwrap()),
+            created_at: rule
+                .created_at
+                .map(|t| t.format(&get_time_formatter()).unwrap()),
+            updated_at: rule
+                .updated_at
+                .map(|t| t.format(&get_time_formatter()).unwrap()),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq, ToSchema)]
+pub struct ContentRuleEvaluationResponse {
+    pub content: String,
+    pub action: Option<ContentRuleAction>,
+    pub matched_rule_ids: Vec<Uuid>,
+}
+
+impl From<ContentRuleEvaluation> for ContentRuleEvaluationResponse {
+    fn from(evaluation: ContentRuleEvaluation) -> Self {
+        Self {
+            content: evaluation.content,
+            action: evaluation.action,
+            matched_rule_ids: evaluation.matched_rule_ids,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::models::content_rule::{
+        evaluate_content_rules, ContentRule, ContentRuleAction, ContentRuleKind,
+    };
+
+    #[test]
+    fn test_content_rule_validation() {
+        let valid = [
+            ContentRule::new(ContentRuleKind::Word, " darn ", ContentRuleAction::Mask),
+            ContentRule::new(
+                ContentRuleKind::Regex,
+                r"fr[e3]{2} money",
+                ContentRuleAction::Hold,
+            ),
+            ContentRule::new(
+                ContentRuleKind::Domain,
+                "Spam.Example.",
+                ContentRuleAction::Reject,
+            ),
+        ];
+        assert!(valid.iter().all(|r| r.validate().is_ok()));
+        assert_eq!(valid[0].pattern, "darn");
+        assert_eq!(valid[2].pattern, "spam.example");
+
+        let invalid = [
+            ContentRule::new(ContentRuleKind::Word, "   ", ContentRuleAction::Mask),
+            ContentRule::new(ContentRuleKind::Regex, "(unclosed", ContentRuleAction::Hold),
+            ContentRule::new(
+                ContentRuleKind::Domain,
+                "localhost",
+                ContentRuleAction::Reject,
+            ),
+            ContentRule::new(ContentRuleKind::Domain, "a..b", ContentRuleAction::Reject),
+            ContentRule::new(
+                ContentRuleKind::Word,
+                &"a".repeat(256),
+                ContentRuleAction::Mask,
+            ),
+        ];
+        assert!(invalid.iter().all(|r| r.validate().is_err()));
+    }
+
+    #[test]
+    fn test_evaluate_content_rules() {
+        let word = ContentRule::new(ContentRuleKind::Word, "darn", ContentRuleAction::Mask);
+        let regex = ContentRule::new(
+            ContentRuleKind::Regex,
+            r"fr[e3]{2} money",
+            ContentRuleAction::Hold,
+        );
+        let domain = ContentRule::new(
+            ContentRuleKind::Domain,
+            "spam.example",
+            ContentRuleAction::Reject,
+        );
+        let disabled = ContentRule {
+            enabled: false,
+            ..ContentRule::new(ContentRuleKind::Word, "hello", ContentRuleAction::Reject)
+        };
+        let rules = vec![word.clone(), regex.clone(), domain.clone(), disabled];
+
+        let clean = evaluate_content_rules(&rules, "Hello darnation, notspam.example.org");
+        assert_eq!(clean.action, None);
+        assert!(clean.matched_rule_ids.is_empty());
+        assert_eq!(clean.content, "Hello darnation, notspam.example.org");
+
+        let masked = evaluate_content_rules(&rules, "Darn it, DARN it");
+        assert_eq!(masked.action, Some(ContentRuleAction::Mask));
+        assert_eq!(masked.content, "**** it, **** it");
+
+        let held = evaluate_content_rules(&rules, "Darn, FR33 money!");
+        assert_eq!(held.action, Some(ContentRuleAction::Hold));
+        assert_eq!(held.matched_rule_ids, vec![word.id, regex.id]);
+        assert_eq!(held.content, "****, FR33 money!");
+
+        let rejected = evaluate_content_rules(&rules, "See https://www.Spam.example/x?y=1 now");
+        assert_eq!(rejected.action, Some(ContentRuleAction::Reject));
+        assert_eq!(rejected.matched_rule_ids, vec![domain.id]);
+        assert_eq!(
+            evaluate_content_rules(&rules, "spam.example").action,
+            Some(ContentRuleAction::Reject)
+        );
+    }
+}
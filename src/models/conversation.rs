@@ -0,0 +1,187 @@
+use crate::dto::response::get_time_formatter;
+use serde::{Deserialize, Serialize};
+use sqlx::types::time::OffsetDateTime;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// Most users in a conversation, its creator included.
+pub const MAX_CONVERSATION_PARTICIPANTS: usize = 10;
+/// Longest accepted message, in characters.
+pub const MAX_MESSAGE_LENGTH: usize = 1000;
+
+/// Who may start a conversation with a user.
+#[derive(
+    Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq, ToSchema, sqlx::Type,
+)]
+#[serde(rename_all = "lowercase")]
+#[sqlx(type_name = "varchar", rename_all = "lowercase")]
+pub enum DmPolicy {
+    #[default]
+    Everyone,
+    /// Only the accounts the user subscribes to.
+    Subscriptions,
+    Nobody,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, Eq, PartialEq, ToSchema)]
+pub struct MessagingSettings {
+    pub dm_policy: DmPolicy,
+}
+
+/// A conversation as seen by one of its participants: `unread_count` counts
+/// the messages of the others sent after that participant's read receipt.
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq)]
+pub struct Conversation {
+    pub id: Uuid,
+    pub creator_id: Option<Uuid>,
+    pub is_group: bool,
+    pub participant_ids: Vec<Uuid>,
+    pub unread_count: i64,
+    pub created_at: Option<OffsetDateTime>,
+    pub updated_at: Option<OffsetDateTime>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq, ToSchema)]
+pub struct ConversationResponse {
+    pub id: Uuid,
+    pub creator_id: Option<Uuid>,
+    pub is_group: bool,
+    pub participant_ids: Vec<Uuid>,
+    pub unread_count: i64,
+    pub created_at: Option<String>,
+    /// Time of the latest message.
+    pub updated_at: Option<String>,
+}
+
+impl From<Conversation> for ConversationResponse {
+    fn from(conversation: Conversation) -> Self {
+        Self {
+            id: conversation.id,
+            creator_id: conversation.creator_id,
+            is_group: conversation.is_group,
+            participant_ids: conversation.participant_ids,
+            unread_count: conversation.unread_count,
+            created_at: conversation
+                .created_at
+                .map(|t| t.format(&get_time_formatter()).unwrap()),
+            updated_at: conversation
+                .updated_at
+                .map(|t| t.format(&get_time_formatter()).unwrap()),
+        }
+    }
+}
+
+/// A message of a conversation. `read_by` lists the other participants whose
+/// read receipt covers it.
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq)]
+pub struct DirectMessage {
+    pub id: Uuid,
+    pub conversation_id: Uuid,
+    pub sender_id: Uuid,
+    pub content: String,
+    pub read_by: Vec<Uuid>,
+    pub created_at: Option<OffsetDateTime>,
+}
+
+impl DirectMessage {
+    pub fn new(conversation_id: Uuid, sender_id: Uuid, content: String) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            conversation_id,
+            sender_id,
+            content,
+            read_by: Vec::new(),
+            created_at: Some(OffsetDateTime::now_utc()),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq, ToSchema)]
+pub struct DirectMessageResponse {
+    pub id: Uuid,
+    pub conversation_id: Uuid,
+    pub sender_id: Uuid,
+    pub content: String,
+    pub read_by: Vec<Uuid>,
+    pub created_at: Option<String>,
+}
+
+impl From<DirectMessage> for DirectMessageResponse {
+    fn from(message: DirectMessage) -> Self {
+        Self {
+            id: message.id,
+            conversation_id: message.conversation_id,
+            sender_id: message.sender_id,
+            content: message.content,
+            read_by: message.read_by,
+            created_at: message
+                .created_at
+                .map(|t| t.format(&get_time_formatter()).unwrap()),
+        }
+    }
+}
+
+/// Drops `creator_id` and duplicates from the requested participants and
+/// checks that the conversation stays small.
+pub fn normalize_participant_ids(
+    creator_id: Uuid,
+    participant_ids: &[Uuid],
+) -> Result<Vec<Uuid>, String> {
+    let mut ids: Vec<Uuid> = Vec::new();
+    for id in participant_ids {
+        if *id != creator_id && !ids.contains(id) {
+            ids.push(*id);
+        }
+    }
+    if ids.is_empty() || ids.len() >= MAX_CONVERSATION_PARTICIPANTS {
+        return Err(format!(
+            "Conversations need between 1 and {} other participants.",
+            MAX_CONVERSATION_PARTICIPANTS - 1
+        ));
+    }
+    Ok(ids)
+}
+
+/// Trims a message and rejects empty or overly long ones.
+pub fn normalize_message_content(content: &str) -> Result<String, String> {
+    let content = content.trim();
+    if content.is_empty() || content.chars().count() > MAX_MESSAGE_LENGTH {
+        return Err(format!(
+            "Messages must be between 1 and {} characters long.",
+            MAX_MESSAGE_LENGTH
+        ));
+    }
+    Ok(content.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::models::conversation::{
+        normalize_message_content, normalize_participant_ids, MAX_CONVERSATION_PARTICIPANTS,
+        MAX_MESSAGE_LENGTH,
+    };
+    use uuid::Uuid;
+
+    #[test]
+    fn test_normalize_participant_ids() {
+        let creator = Uuid::new_v4();
+        let other = Uuid::new_v4();
+        assert_eq!(
+            normalize_participant_ids(creator, &[creator, other, other]),
+            Ok(vec![other])
+        );
+        assert!(normalize_participant_ids(creator, &[creator]).is_err());
+        let crowd: Vec<Uuid> = (0..MAX_CONVERSATION_PARTICIPANTS)
+            .map(|_| Uuid::new_v4())
+            .collect();
+        assert!(normalize_participant_ids(creator, &crowd).is_err());
+        assert!(normalize_participant_ids(creator, &crowd[1..]).is_ok());
+    }
+
+    #[test]
+    fn test_normalize_message_content() {
+        assert_eq!(normalize_message_content("  hi "), Ok("hi".to_string()));
+        assert!(normalize_message_content(" \n ").is_err());
+        assert!(normalize_message_content(&"a".repeat(MAX_MESSAGE_LENGTH + 1)).is_err());
+    }
+}
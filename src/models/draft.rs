@@ -0,0 +1,100 @@
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::dto::response::get_time_formatter;
+use crate::models::media::MediaResponse;
+use crate::models::tweet::TweetVisibility;
+
+/// Lifecycle of a row in the `tweet` table. Only `Published` tweets are
+/// visible outside their author's drafts, and `Held` tweets are only listed
+/// to moderators.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, ToSchema, sqlx::Type)]
+#[serde(rename_all = "lowercase")]
+#[sqlx(type_name = "varchar", rename_all = "lowercase")]
+pub enum TweetStatus {
+    Draft,
+    Scheduled,
+    Published,
+    /// Matched a `hold` content rule and waits for a moderator.
+    Held,
+}
+
+impl TweetStatus {
+    /// `Scheduled` when a publication time is set, `Draft` otherwise.
+    pub fn for_publish_at(publish_at: Option<OffsetDateTime>) -> Self {
+        match publish_at {
+            Some(_) => TweetStatus::Scheduled,
+            None => TweetStatus::Draft,
+        }
+    }
+}
+
+/// An unpublished tweet, either kept as a draft or waiting for `publish_at`.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TweetDraft {
+    pub id: Uuid,
+    pub content: String,
+    pub status: TweetStatus,
+    pub publish_at: Option<OffsetDateTime>,
+    pub created_at: Option<OffsetDateTime>,
+    pub updated_at: Option<OffsetDateTime>,
+    pub user_id: Uuid,
+    /// Who may read the tweet once it is published.
+    pub visibility: TweetVisibility,
+}
+
+impl TweetDraft {
+    pub fn new(content: String, publish_at: Option<OffsetDateTime>, user_id: Uuid) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            content,
+            status: TweetStatus::for_publish_at(publish_at),
+            publish_at,
+            created_at: Some(OffsetDateTime::now_utc()),
+            updated_at: Some(OffsetDateTime::now_utc()),
+            user_id,
+            visibility: TweetVisibility::default(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, ToSchema)]
+pub struct TweetDraftResponse {
+    pub id: Uuid,
+    pub content: String,
+    pub status: TweetStatus,
+    #[schema(value_type = String)]
+    pub publish_at: Option<String>,
+    #[schema(value_type = String)]
+    pub created_at: Option<String>,
+    #[schema(value_type = String)]
+    pub updated_at: Option<String>,
+    pub user_id: Uuid,
+    pub visibility: TweetVisibility,
+    #[serde(default)]
+    pub media: Vec<MediaResponse>,
+}
+
+impl From<TweetDraft> for TweetDraftResponse {
+    fn from(draft: TweetDraft) -> Self {
+        Self {
+            id: draft.id,
+            content: draft.content,
+            status: draft.status,
+            publish_at: draft
+                .publish_at
+                .map(|t| t.format(&get_time_formatter()).unwrap()),
+            created_at: draft
+                .created_at
+                .map(|t| t.format(&get_time_formatter()).unwrap()),
+            updated_at: draft
+                .updated_at
+                .map(|t| t.format(&get_time_formatter()).unwrap()),
+            user_id: draft.user_id,
+            visibility: draft.visibility,
+            media: Vec::new(),
+        }
+    }
+}
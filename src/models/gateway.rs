@@ -0,0 +1,171 @@
+use crate::models::hashtag::{normalize_hashtag, MAX_HASHTAG_LENGTH};
+use crate::models::tweet::TweetResponse;
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// What a WebSocket connection can subscribe to, written `user:<id>`,
+/// `hashtag:<tag>` or `conversation:<tweet id>` on the wire.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum GatewayChannel {
+    /// Tweets published by a user.
+    User(Uuid),
+    /// Public tweets tagged with a hashtag.
+    Hashtag(String),
+    /// Users typing a reply to a tweet.
+    Conversation(Uuid),
+}
+
+impl GatewayChannel {
+    /// Whether clients may send typing indicators to the channel.
+    pub fn accepts_typing(&self) -> bool {
+        matches!(self, GatewayChannel::Conversation(_))
+    }
+}
+
+impl Display for GatewayChannel {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GatewayChannel::User(id) => write!(f, "user:{}", id),
+            GatewayChannel::Hashtag(tag) => write!(f, "hashtag:{}", tag),
+            GatewayChannel::Conversation(id) => write!(f, "conversation:{}", id),
+        }
+    }
+}
+
+impl FromStr for GatewayChannel {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("Channel `{}` is invalid.", value);
+        let (kind, key) = value.split_once(':').ok_or_else(invalid)?;
+        match kind {
+            "user" => Uuid::parse_str(key)
+                .map(GatewayChannel::User)
+                .map_err(|_| invalid()),
+            "conversation" => Uuid::parse_str(key)
+                .map(GatewayChannel::Conversation)
+                .map_err(|_| invalid()),
+            "hashtag" => {
+                let tag = normalize_hashtag(key);
+                let valid = tag.chars().any(char::is_alphabetic)
+                    && tag.chars().count() <= MAX_HASHTAG_LENGTH
+                    && tag.chars().all(|c| c.is_alphanumeric() || c == '_');
+                if valid {
+                    Ok(GatewayChannel::Hashtag(tag))
+                } else {
+                    Err(invalid())
+                }
+            }
+            _ => Err(invalid()),
+        }
+    }
+}
+
+/// A frame sent by the client.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, ToSchema)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ClientEvent {
+    Subscribe {
+        channel: String,
+    },
+    Unsubscribe {
+        channel: String,
+    },
+    /// Tells the other subscribers of a conversation the sender is typing.
+    Typing {
+        channel: String,
+    },
+}
+
+/// A frame sent by the server.
+#[derive(Serialize, Deserialize, Debug, Clone, ToSchema)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ServerEvent {
+    Subscribed {
+        channel: String,
+    },
+    Unsubscribed {
+        channel: String,
+    },
+    Tweet {
+        channel: String,
+        tweet: Box<TweetResponse>,
+    },
+    Typing {
+        channel: String,
+        user_id: Uuid,
+        username: String,
+    },
+    /// A rejected client frame; the connection stays open.
+    Error {
+        message: String,
+    },
+}
+
+impl ServerEvent {
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("Server events always serialize")
+    }
+}
+
+/// A typing indicator on its way to every server instance through the
+/// gateway notification channel.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct TypingSignal {
+    pub channel: String,
+    pub user_id: Uuid,
+    pub username: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::models::gateway::{ClientEvent, GatewayChannel, ServerEvent};
+    use uuid::Uuid;
+
+    #[test]
+    fn test_parse_gateway_channel() {
+        let id = Uuid::new_v4();
+        assert_eq!(
+            format!("user:{}", id).parse::<GatewayChannel>(),
+            Ok(GatewayChannel::User(id))
+        );
+        assert_eq!(
+            format!("conversation:{}", id).parse::<GatewayChannel>(),
+            Ok(GatewayChannel::Conversation(id))
+        );
+        assert_eq!(
+            "hashtag:#Rust".parse::<GatewayChannel>(),
+            Ok(GatewayChannel::Hashtag("rust".to_string()))
+        );
+        assert!("hashtag:123".parse::<GatewayChannel>().is_err());
+        assert!("hashtag:a b".parse::<GatewayChannel>().is_err());
+        assert!("user:abc".parse::<GatewayChannel>().is_err());
+        assert!("timeline".parse::<GatewayChannel>().is_err());
+        assert_eq!(
+            GatewayChannel::Conversation(id).to_string(),
+            format!("conversation:{}", id)
+        );
+    }
+
+    #[test]
+    fn test_gateway_event_json() {
+        let event: ClientEvent =
+            serde_json::from_str(r#"{"type":"subscribe","channel":"hashtag:rust"}"#).unwrap();
+        assert_eq!(
+            event,
+            ClientEvent::Subscribe {
+                channel: "hashtag:rust".to_string()
+            }
+        );
+        assert_eq!(
+            ServerEvent::Error {
+                message: "nope".to_string()
+            }
+            .to_json(),
+            r#"{"type":"error","message":"nope"}"#
+        );
+    }
+}
@@ -0,0 +1,88 @@
+use serde::{Deserialize, Serialize};
+use sqlx::types::time::OffsetDateTime;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// Longest tag that is still recognised as a hashtag.
+pub const MAX_HASHTAG_LENGTH: usize = 100;
+
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq, ToSchema)]
+pub struct Hashtag {
+    pub id: Uuid,
+    pub tag: String,
+    #[schema(value_type = String)]
+    pub created_at: Option<OffsetDateTime>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, ToSchema)]
+pub struct TrendingHashtag {
+    pub tag: String,
+    /// Number of tweets using the tag inside the trending window.
+    pub uses: i64,
+    /// Sum of the decayed weights of those tweets.
+    pub score: f64,
+}
+
+fn is_hashtag_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+/// Extracts the distinct, lower-cased hashtags of a tweet in order of first
+/// appearance. A `#` only starts a tag at the beginning of the text or after a
+/// non-word character, and purely numeric tags such as `#1` are ignored.
+pub fn extract_hashtags(content: &str) -> Vec<String> {
+    let chars: Vec<char> = content.chars().collect();
+    let mut tags: Vec<String> = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        if chars[i] != '#' || (i > 0 && is_hashtag_char(chars[i - 1])) {
+            i += 1;
+            continue;
+        }
+        let start = i + 1;
+        let mut end = start;
+        while end < chars.len() && is_hashtag_char(chars[end]) {
+            end += 1;
+        }
+        let tag = chars[start..end].iter().collect::<String>().to_lowercase();
+        if tag.chars().any(char::is_alphabetic)
+            && tag.chars().count() <= MAX_HASHTAG_LENGTH
+            && !tags.contains(&tag)
+        {
+            tags.push(tag);
+        }
+        i = end.max(start);
+    }
+    tags
+}
+
+/// Normalises a tag taken from a URL, accepting it with or without the `#`.
+pub fn normalize_hashtag(tag: &str) -> String {
+    tag.trim_start_matches('#').to_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::models::hashtag::{extract_hashtags, normalize_hashtag};
+
+    #[test]
+    fn test_extract_hashtags() {
+        assert_eq!(
+            extract_hashtags("#Rust is great, #rust #async_await!"),
+            vec!["rust", "async_await"]
+        );
+        assert_eq!(extract_hashtags("中文 #话题 test"), vec!["话题"]);
+    }
+
+    #[test]
+    fn test_extract_hashtags_ignores_non_tags() {
+        assert!(extract_hashtags("issue#12 #1 # alone ##").is_empty());
+        assert!(extract_hashtags("").is_empty());
+    }
+
+    #[test]
+    fn test_normalize_hashtag() {
+        assert_eq!(normalize_hashtag("#Rust"), "rust");
+        assert_eq!(normalize_hashtag("rust"), "rust");
+    }
+}
@@ -0,0 +1,361 @@
+use image::codecs::jpeg::JpegEncoder;
+use image::ImageFormat;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::io::Cursor;
+use time::OffsetDateTime;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::dto::response::get_time_formatter;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Media {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    /// Hash of the bytes as uploaded, used to find repeated uploads.
+    pub sha256: String,
+    pub mime_type: String,
+    /// Size of the bytes as uploaded.
+    pub size_bytes: i64,
+    /// Key of the stored object. Named after `sha256`, but processing
+    /// replaces the object with a copy stripped of its metadata, which later
+    /// uploads of the same original are then served as well.
+    pub storage_key: String,
+    pub created_at: Option<OffsetDateTime>,
+    /// When background processing stripped the stored original of its
+    /// metadata; `None` until then.
+    pub processed_at: Option<OffsetDateTime>,
+}
+
+impl Media {
+    /// Describes freshly uploaded `bytes`. The content is stored under its
+    /// hash, so identical uploads share one stored object, even once that
+    /// object has been stripped of its metadata.
+    pub fn new(user_id: Uuid, mime_type: &str, bytes: &[u8]) -> Self {
+        let sha256 = sha256_hex(bytes);
+        Self {
+            id: Uuid::new_v4(),
+            user_id,
+            storage_key: sha256.clone(),
+            sha256,
+            mime_type: mime_type.to_string(),
+            size_bytes: bytes.len() as i64,
+            created_at: Some(OffsetDateTime::now_utc()),
+            processed_at: None,
+        }
+    }
+}
+
+/// Metadata of an uploaded file; the bytes are served by
+/// `GET /api/media/{id}/content`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, ToSchema)]
+pub struct MediaResponse {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub sha256: String,
+    pub mime_type: String,
+    pub size_bytes: i64,
+    #[schema(value_type = String)]
+    pub created_at: Option<String>,
+    /// Resized copies, served by `GET /api/media/{id}/variants/{name}`. Empty
+    /// until background processing of the upload has finished.
+    #[serde(default)]
+    pub variants: Vec<MediaVariantResponse>,
+}
+
+impl From<Media> for MediaResponse {
+    fn from(media: Media) -> Self {
+        Self {
+            id: media.id,
+            user_id: media.user_id,
+            sha256: media.sha256,
+            mime_type: media.mime_type,
+            size_bytes: media.size_bytes,
+            created_at: Some(
+                media
+                    .created_at
+                    .unwrap()
+                    .format(&get_time_formatter())
+                    .unwrap(),
+            ),
+            variants: Vec::new(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct MediaVariant {
+    pub media_id: Uuid,
+    pub name: String,
+    pub storage_key: String,
+    pub mime_type: String,
+    pub width: i32,
+    pub height: i32,
+    pub size_bytes: i64,
+    pub created_at: Option<OffsetDateTime>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, ToSchema)]
+pub struct MediaVariantResponse {
+    pub name: String,
+    pub mime_type: String,
+    pub width: i32,
+    pub height: i32,
+    pub size_bytes: i64,
+}
+
+impl From<MediaVariant> for MediaVariantResponse {
+    fn from(variant: MediaVariant) -> Self {
+        Self {
+            name: variant.name,
+            mime_type: variant.mime_type,
+            width: variant.width,
+            height: variant.height,
+            size_bytes: variant.size_bytes,
+        }
+    }
+}
+
+/// An encoded resized copy of an image.
+#[derive(Debug, Clone)]
+pub struct RenderedVariant {
+    pub bytes: Vec<u8>,
+    pub mime_type: &'static str,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// Scales an image down to fit `max_width` x `max_height`, never up. JPEG
+/// stays JPEG, every other format becomes PNG. Re-encoding drops all
+/// metadata of the source.
+pub fn render_variant(
+    bytes: &[u8],
+    mime_type: &str,
+    max_width: u32,
+    max_height: u32,
+) -> Result<RenderedVariant, anyhow::Error> {
+    let image = image::load_from_memory(bytes)?;
+    let image = if image.width() > max_width || image.height() > max_height {
+        image.thumbnail(max_width, max_height)
+    } else {
+        image
+    };
+
+    let mut encoded = Vec::new();
+    let mime_type = if mime_type == "image/jpeg" {
+        let encoder = JpegEncoder::new_with_quality(&mut encoded, 85);
+        image.to_rgb8().write_with_encoder(encoder)?;
+        "image/jpeg"
+    } else {
+        image.write_to(&mut Cursor::new(&mut encoded), ImageFormat::Png)?;
+        "image/png"
+    };
+
+    Ok(RenderedVariant {
+        bytes: encoded,
+        mime_type,
+        width: image.width(),
+        height: image.height(),
+    })
+}
+
+/// Removes EXIF (and XMP) metadata from JPEG, PNG and WebP files without
+/// re-encoding the image. Other formats, and files that cannot be parsed, are
+/// returned unchanged.
+pub fn strip_metadata(bytes: &[u8], mime_type: &str) -> Vec<u8> {
+    let stripped = match mime_type {
+        "image/jpeg" => strip_jpeg_metadata(bytes),
+        "image/png" => strip_png_metadata(bytes),
+        "image/webp" => strip_webp_metadata(bytes),
+        _ => None,
+    };
+    stripped.unwrap_or_else(|| bytes.to_vec())
+}
+
+fn strip_jpeg_metadata(bytes: &[u8]) -> Option<Vec<u8>> {
+    let mut stripped = bytes.get(..2)?.to_vec();
+    let mut i = 2;
+    while i + 4 <= bytes.len() {
+        if bytes[i] != 0xFF {
+            return None;
+        }
+        let marker = bytes[i + 1];
+        // Everything from the start of scan on is image data.
+        if marker == 0xDA {
+            stripped.extend_from_slice(&bytes[i..]);
+            return Some(stripped);
+        }
+        let length = u16::from_be_bytes([bytes[i + 2], bytes[i + 3]]) as usize;
+        let end = i + 2 + length;
+        if length < 2 || end > bytes.len() {
+            return None;
+        }
+        // APP1 carries EXIF and XMP.
+        if marker != 0xE1 {
+            stripped.extend_from_slice(&bytes[i..end]);
+        }
+        i = end;
+    }
+    None
+}
+
+fn strip_png_metadata(bytes: &[u8]) -> Option<Vec<u8>> {
+    let mut stripped = bytes.get(..8)?.to_vec();
+    let mut i = 8;
+    while i + 12 <= bytes.len() {
+        let length = u32::from_be_bytes(bytes[i..i + 4].try_into().ok()?) as usize;
+        let end = i.checked_add(12)?.checked_add(length)?;
+        if end > bytes.len() {
+            return None;
+        }
+        if &bytes[i + 4..i + 8] != b"eXIf" {
+            stripped.extend_from_slice(&bytes[i..end]);
+        }
+        i = end;
+    }
+    (i == bytes.len()).then_some(stripped)
+}
+
+fn strip_webp_metadata(bytes: &[u8]) -> Option<Vec<u8>> {
+    if bytes.len() < 12 || &bytes[..4] != b"RIFF" || &bytes[8..12] != b"WEBP" {
+        return None;
+    }
+    let mut stripped = bytes[..12].to_vec();
+    let mut i = 12;
+    while i + 8 <= bytes.len() {
+        let fourcc = &bytes[i..i + 4];
+        let length = u32::from_le_bytes(bytes[i + 4..i + 8].try_into().ok()?) as usize;
+        // Chunks are padded to an even size.
+        let end = i
+            .checked_add(8)?
+            .checked_add(length)?
+            .checked_add(length % 2)?;
+        if end > bytes.len() {
+            return None;
+        }
+        if fourcc != b"EXIF" && fourcc != b"XMP " {
+            let start = stripped.len();
+            stripped.extend_from_slice(&bytes[i..end]);
+            // Clear the EXIF and XMP flags of the extended format header.
+            if fourcc == b"VP8X" && length > 0 {
+                stripped[start + 8] &= !0b1100;
+            }
+        }
+        i = end;
+    }
+    if i != bytes.len() {
+        return None;
+    }
+    let riff_size = u32::try_from(stripped.len() - 8).ok()?;
+    stripped[4..8].copy_from_slice(&riff_size.to_le_bytes());
+    Some(stripped)
+}
+
+pub fn sha256_hex(bytes: &[u8]) -> String {
+    hex::encode(Sha256::digest(bytes))
+}
+
+/// Detects the type of an upload from its leading bytes rather than trusting
+/// the client supplied content type. Returns `None` for unsupported formats.
+pub fn sniff_mime_type(bytes: &[u8]) -> Option<&'static str> {
+    if bytes.starts_with(&[0xFF, 0xD8, 0xFF]) {
+        Some("image/jpeg")
+    } else if bytes.starts_with(b"\x89PNG\r\n\x1a\n") {
+        Some("image/png")
+    } else if bytes.starts_with(b"GIF87a") || bytes.starts_with(b"GIF89a") {
+        Some("image/gif")
+    } else if bytes.len() >= 12 && &bytes[..4] == b"RIFF" && &bytes[8..12] == b"WEBP" {
+        Some("image/webp")
+    } else if bytes.len() >= 12 && &bytes[4..8] == b"ftyp" {
+        Some("video/mp4")
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::models::media::{render_variant, sha256_hex, sniff_mime_type, strip_metadata};
+    use image::{ImageFormat, RgbImage};
+    use std::io::Cursor;
+
+    #[test]
+    fn test_sniff_mime_type() {
+        assert_eq!(sniff_mime_type(b"\x89PNG\r\n\x1a\n...."), Some("image/png"));
+        assert_eq!(
+            sniff_mime_type(&[0xFF, 0xD8, 0xFF, 0xE0]),
+            Some("image/jpeg")
+        );
+        assert_eq!(sniff_mime_type(b"GIF89a..."), Some("image/gif"));
+        assert_eq!(sniff_mime_type(b"RIFF\0\0\0\0WEBPVP8 "), Some("image/webp"));
+        assert_eq!(sniff_mime_type(b"\0\0\0\x18ftypmp42"), Some("video/mp4"));
+        assert_eq!(sniff_mime_type(b"<html></html>"), None);
+        assert_eq!(sniff_mime_type(b""), None);
+    }
+
+    #[test]
+    fn test_sha256_hex() {
+        assert_eq!(
+            sha256_hex(b"abc"),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+    }
+
+    #[test]
+    fn test_render_variant_scales_down_only() {
+        let mut png = Vec::new();
+        RgbImage::new(400, 200)
+            .write_to(&mut Cursor::new(&mut png), ImageFormat::Png)
+            .unwrap();
+
+        let variant = render_variant(&png, "image/png", 100, 100).unwrap();
+        assert_eq!((variant.width, variant.height), (100, 50));
+        assert_eq!(variant.mime_type, "image/png");
+
+        let variant = render_variant(&png, "image/png", 1000, 1000).unwrap();
+        assert_eq!((variant.width, variant.height), (400, 200));
+    }
+
+    #[test]
+    fn test_strip_metadata_removes_jpeg_app1() {
+        let jpeg = [
+            &[0xFF, 0xD8][..],
+            &[0xFF, 0xE0, 0x00, 0x04, 0x4A, 0x46],
+            &[0xFF, 0xE1, 0x00, 0x06, b'E', b'x', b'i', b'f'],
+            &[0xFF, 0xDA, 0x00, 0x02, 0x01, 0x02, 0xFF, 0xD9],
+        ]
+        .concat();
+        let expected = [
+            &[0xFF, 0xD8][..],
+            &[0xFF, 0xE0, 0x00, 0x04, 0x4A, 0x46],
+            &[0xFF, 0xDA, 0x00, 0x02, 0x01, 0x02, 0xFF, 0xD9],
+        ]
+        .concat();
+        assert_eq!(strip_metadata(&jpeg, "image/jpeg"), expected);
+        assert_eq!(strip_metadata(b"GIF89a", "image/gif"), b"GIF89a");
+    }
+
+    #[test]
+    fn test_strip_metadata_removes_webp_exif_chunk() {
+        let webp = [
+            &b"RIFF\x2C\0\0\0WEBP"[..],
+            b"VP8X\x0A\0\0\0\x08\0\0\0\0\0\0\0\0\0",
+            b"VP8L\x01\0\0\0\x2F\0",
+            b"EXIF\x03\0\0\0GPS\0",
+        ]
+        .concat();
+        let expected = [
+            &b"RIFF\x20\0\0\0WEBP"[..],
+            b"VP8X\x0A\0\0\0\0\0\0\0\0\0\0\0\0\0",
+            b"VP8L\x01\0\0\0\x2F\0",
+        ]
+        .concat();
+        assert_eq!(strip_metadata(&webp, "image/webp"), expected);
+        // Truncated files are left alone
+        assert_eq!(
+            strip_metadata(&webp[..webp.len() - 2], "image/webp"),
+            &webp[..webp.len() - 2]
+        );
+    }
+}
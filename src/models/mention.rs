@@ -0,0 +1,106 @@
+use serde::{Deserialize, Serialize};
+use sqlx::types::time::OffsetDateTime;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq, ToSchema)]
+pub struct TweetMention {
+    pub tweet_id: Uuid,
+    pub user_id: Uuid,
+    pub start_index: i32,
+    pub end_index: i32,
+    #[schema(value_type = String)]
+    pub created_at: Option<OffsetDateTime>,
+}
+
+/// A resolved `@username` inside `TweetResponse::content`.
+///
+/// `start` and `end` are character offsets, `end` exclusive, covering the
+/// leading `@` as well as the username.
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq, ToSchema)]
+pub struct MentionEntity {
+    pub user_id: Uuid,
+    pub username: String,
+    pub start: i32,
+    pub end: i32,
+}
+
+/// An `@username` found in a tweet before it is resolved against `users`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MentionCandidate {
+    pub username: String,
+    pub start: usize,
+    pub end: usize,
+}
+
+fn is_username_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_' || c == '-'
+}
+
+/// Finds every `@username` in `content`. An `@` preceded by a word character,
+/// as in an e-mail address, does not start a mention.
+pub fn extract_mentions(content: &str) -> Vec<MentionCandidate> {
+    let chars: Vec<char> = content.chars().collect();
+    let mut mentions = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        if chars[i] != '@' || (i > 0 && is_username_char(chars[i - 1])) {
+            i += 1;
+            continue;
+        }
+        let start = i + 1;
+        let mut end = start;
+        while end < chars.len() && is_username_char(chars[end]) {
+            end += 1;
+        }
+        // A trailing dash is punctuation, as in "thanks @bob--".
+        while end > start && chars[end - 1] == '-' {
+            end -= 1;
+        }
+        if end > start {
+            mentions.push(MentionCandidate {
+                username: chars[start..end].iter().collect(),
+                start: i,
+                end,
+            });
+        }
+        i = end.max(start);
+    }
+    mentions
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::models::mention::{extract_mentions, MentionCandidate};
+
+    #[test]
+    fn test_extract_mentions() {
+        assert_eq!(
+            extract_mentions("hi @alice and @bob_1--"),
+            vec![
+                MentionCandidate {
+                    username: String::from("alice"),
+                    start: 3,
+                    end: 9,
+                },
+                MentionCandidate {
+                    username: String::from("bob_1"),
+                    start: 14,
+                    end: 20,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_extract_mentions_ignores_emails_and_bare_at() {
+        assert!(extract_mentions("mail me@example.com @ later").is_empty());
+    }
+
+    #[test]
+    fn test_extract_mentions_uses_character_offsets() {
+        let mentions = extract_mentions("你好 @小明");
+        assert_eq!(mentions[0].username, "小明");
+        assert_eq!((mentions[0].start, mentions[0].end), (3, 6));
+    }
+}
@@ -1,6 +1,27 @@
+pub mod account;
+pub mod block;
+pub mod bookmark;
+pub mod content_rule;
+pub mod conversation;
+pub mod draft;
+pub mod gateway;
 pub mod group_permission;
+pub mod hashtag;
+pub mod link_preview;
+pub mod media;
+pub mod mention;
+pub mod moderation;
+pub mod mute;
+pub mod notification;
 pub mod permission;
+pub mod poll;
+pub mod report;
+pub mod search;
+pub mod stream;
+pub mod subscription;
 pub mod tweet;
+pub mod tweet_revision;
 pub mod user;
 pub mod user_group;
+pub mod user_list;
 pub mod user_permission;
@@ -0,0 +1,103 @@
+use crate::dto::response::get_time_formatter;
+use crate::models::account::AccountStatus;
+use serde::{Deserialize, Serialize};
+use sqlx::types::time::OffsetDateTime;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, ToSchema, sqlx::Type)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(type_name = "varchar", rename_all = "snake_case")]
+pub enum ModerationActionKind {
+    HideTweet,
+    SuspendUser,
+    ActionReport,
+    DismissReport,
+    /// A tweet matched a `hold` content rule.
+    HoldTweet,
+    /// A moderator published a held tweet.
+    ReleaseTweet,
+    BanUser,
+    /// A user deactivated their own account.
+    DeactivateUser,
+    /// An account was made active again.
+    ReactivateUser,
+}
+
+impl ModerationActionKind {
+    /// The action moving an account to `status`.
+    pub fn for_account_status(status: AccountStatus) -> Self {
+        match status {
+            AccountStatus::Active => ModerationActionKind::ReactivateUser,
+            AccountStatus::Deactivated => ModerationActionKind::DeactivateUser,
+            AccountStatus::Suspended => ModerationActionKind::SuspendUser,
+            AccountStatus::Banned => ModerationActionKind::BanUser,
+        }
+    }
+}
+
+/// An entry of the moderation log: what `moderator_id` did, to which tweet
+/// or user, and in answer to which report.
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq)]
+pub struct ModerationAction {
+    pub id: Uuid,
+    pub moderator_id: Option<Uuid>,
+    pub action: ModerationActionKind,
+    pub report_id: Option<Uuid>,
+    pub tweet_id: Option<Uuid>,
+    pub user_id: Option<Uuid>,
+    pub note: Option<String>,
+    pub created_at: Option<OffsetDateTime>,
+}
+
+impl ModerationAction {
+    pub fn new(moderator_id: Uuid, action: ModerationActionKind) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            moderator_id: Some(moderator_id),
+            action,
+            report_id: None,
+            tweet_id: None,
+            user_id: None,
+            note: None,
+            created_at: Some(OffsetDateTime::now_utc()),
+        }
+    }
+
+    /// An action taken by the content rules rather than by a moderator.
+    pub fn automated(action: ModerationActionKind) -> Self {
+        Self {
+            moderator_id: None,
+            ..Self::new(Uuid::nil(), action)
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq, ToSchema)]
+pub struct ModerationActionResponse {
+    pub id: Uuid,
+    pub moderator_id: Option<Uuid>,
+    pub action: ModerationActionKind,
+    pub report_id: Option<Uuid>,
+    pub tweet_id: Option<Uuid>,
+    pub user_id: Option<Uuid>,
+    pub note: Option<String>,
+    pub created_at: Option<String>,
+}
+
+impl From<ModerationAction> for ModerationActionResponse {
+    fn from(action: ModerationAction) -> Self {
+        Self {
+            id: action.id,
+            moderator_id: action.moderator_id,
+            action: action.action,
+            report_id: action.report_id,
+            tweet_id: action.tweet_id,
+            user_id: action.user_id,
+            note: action.note,
+            created_at: action
+                .created_at
+                .map(|t| t.format(&get_time_formatter()).unwrap()),
+        }
+    }
+}
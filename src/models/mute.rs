@@ -0,0 +1,44 @@
+use crate::dto::response::get_time_formatter;
+use serde::{Deserialize, Serialize};
+use sqlx::types::time::OffsetDateTime;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// `muter_id` has muted `muted_id`. Unlike a block, this only hides the muted
+/// user's tweets from the muter's own timelines.
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq, ToSchema)]
+pub struct Mute {
+    pub muter_id: Uuid,
+    pub muted_id: Uuid,
+    #[schema(value_type = String)]
+    pub created_at: Option<OffsetDateTime>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq, ToSchema)]
+pub struct MuteResponse {
+    pub muter_id: Uuid,
+    pub muted_id: Uuid,
+    pub created_at: Option<String>,
+}
+
+impl From<Mute> for MuteResponse {
+    fn from(value: Mute) -> Self {
+        Self {
+            muter_id: value.muter_id,
+            muted_id: value.muted_id,
+            created_at: value
+                .created_at
+                .map(|t| t.format(&get_time_formatter()).unwrap()),
+        }
+    }
+}
+
+impl Mute {
+    pub fn new(muter_id: Uuid, muted_id: Uuid) -> Self {
+        Self {
+            muter_id,
+            muted_id,
+            created_at: Some(OffsetDateTime::now_utc()),
+        }
+    }
+}
@@ -0,0 +1,168 @@
+use crate::dto::response::get_time_formatter;
+use serde::{Deserialize, Serialize};
+use sqlx::types::time::OffsetDateTime;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, ToSchema, sqlx::Type)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(type_name = "varchar", rename_all = "snake_case")]
+pub enum NotificationKind {
+    /// The user was @mentioned in a tweet.
+    Mention,
+    PermissionGranted,
+    PermissionRevoked,
+    /// The user was moved to another `user_group`, or out of any.
+    UserGroupChanged,
+}
+
+impl NotificationKind {
+    /// Key under which unread notifications of this kind are folded into one,
+    /// as in "alice and 4 others mentioned you". `None` for kinds shown one by
+    /// one.
+    pub fn group_key(self) -> Option<&'static str> {
+        match self {
+            NotificationKind::Mention => Some("mention"),
+            _ => None,
+        }
+    }
+}
+
+/// Something that happened to `user_id`. A grouped notification stands for
+/// `event_count` events caused by `actor_ids`, most recent first, and
+/// `tweet_id` is the tweet of the latest of them.
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq)]
+pub struct Notification {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub kind: NotificationKind,
+    pub actor_ids: Vec<Uuid>,
+    pub event_count: i32,
+    pub tweet_id: Option<Uuid>,
+    pub permission_id: Option<Uuid>,
+    pub user_group_id: Option<Uuid>,
+    /// Username of the latest actor, filled when reading.
+    pub actor_username: Option<String>,
+    /// Name of the permission or user group, filled when reading.
+    pub subject: Option<String>,
+    pub read_at: Option<OffsetDateTime>,
+    pub created_at: Option<OffsetDateTime>,
+    pub updated_at: Option<OffsetDateTime>,
+}
+
+impl Notification {
+    pub fn new(user_id: Uuid, kind: NotificationKind) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            user_id,
+            kind,
+            actor_ids: Vec::new(),
+            event_count: 1,
+            tweet_id: None,
+            permission_id: None,
+            user_group_id: None,
+            actor_username: None,
+            subject: None,
+            read_at: None,
+            created_at: Some(OffsetDateTime::now_utc()),
+            updated_at: Some(OffsetDateTime::now_utc()),
+        }
+    }
+
+    /// The sentence shown to the recipient.
+    pub fn message(&self) -> String {
+        let subject = self.subject.as_deref().unwrap_or("unknown");
+        match self.kind {
+            NotificationKind::Mention => {
+                let actor = self.actor_username.as_deref().unwrap_or("Someone");
+                match self.actor_ids.len() {
+                    0 | 1 => format!("{} mentioned you", actor),
+                    2 => format!("{} and 1 other mentioned you", actor),
+                    n => format!("{} and {} others mentioned you", actor, n - 1),
+                }
+            }
+            NotificationKind::PermissionGranted => {
+                format!("You were granted the {} permission", subject)
+            }
+            NotificationKind::PermissionRevoked => {
+                format!("Your {} permission was revoked", subject)
+            }
+            NotificationKind::UserGroupChanged => match &self.subject {
+                Some(group_name) => format!("You were moved to the {} group", group_name),
+                None => "You were removed from your user group".to_string(),
+            },
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq, ToSchema)]
+pub struct NotificationResponse {
+    pub id: Uuid,
+    pub kind: NotificationKind,
+    pub message: String,
+    /// Users behind the notification, most recent first.
+    pub actor_ids: Vec<Uuid>,
+    pub event_count: i32,
+    pub tweet_id: Option<Uuid>,
+    pub permission_id: Option<Uuid>,
+    pub user_group_id: Option<Uuid>,
+    pub read: bool,
+    pub created_at: Option<String>,
+    pub updated_at: Option<String>,
+}
+
+impl From<Notification> for NotificationResponse {
+    fn from(notification: Notification) -> Self {
+        Self {
+            id: notification.id,
+            kind: notification.kind,
+            message: notification.message(),
+            actor_ids: notification.actor_ids,
+            event_count: notification.event_count,
+            tweet_id: notification.tweet_id,
+            permission_id: notification.permission_id,
+            user_group_id: notification.user_group_id,
+            read: notification.read_at.is_some(),
+            created_at: notification
+                .created_at
+                .map(|t| t.format(&get_time_formatter()).unwrap()),
+            updated_at: notification
+                .updated_at
+                .map(|t| t.format(&get_time_formatter()).unwrap()),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq, ToSchema)]
+pub struct NotificationCountResponse {
+    pub count: i64,
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::models::notification::{Notification, NotificationKind};
+    use uuid::Uuid;
+
+    #[test]
+    fn test_mention_message_counts_other_actors() {
+        let mut notification = Notification {
+            actor_ids: vec![Uuid::new_v4()],
+            actor_username: Some("alice".to_string()),
+            ..Notification::new(Uuid::new_v4(), NotificationKind::Mention)
+        };
+        assert_eq!(notification.message(), "alice mentioned you");
+        notification.actor_ids.push(Uuid::new_v4());
+        assert_eq!(notification.message(), "alice and 1 other mentioned you");
+        notification
+            .actor_ids
+            .extend([Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4()]);
+        assert_eq!(notification.message(), "alice and 4 others mentioned you");
+    }
+
+    #[test]
+    fn test_only_mentions_are_grouped() {
+        assert!(NotificationKind::Mention.group_key().is_some());
+        assert!(NotificationKind::PermissionGranted.group_key().is_none());
+        assert!(NotificationKind::UserGroupChanged.group_key().is_none());
+    }
+}
@@ -0,0 +1,198 @@
+use serde::{Deserialize, Serialize};
+use sqlx::types::time::OffsetDateTime;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::dto::response::get_time_formatter;
+
+pub const MIN_POLL_OPTIONS: usize = 2;
+pub const MAX_POLL_OPTIONS: usize = 4;
+/// Longest option label, matching `poll_options.label`.
+pub const MAX_POLL_OPTION_LENGTH: usize = 64;
+
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq)]
+pub struct PollOption {
+    pub id: Uuid,
+    pub position: i16,
+    pub label: String,
+}
+
+/// A poll carried by `tweet_id`. Votes are accepted until `closes_at`.
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq)]
+pub struct Poll {
+    pub tweet_id: Uuid,
+    pub closes_at: OffsetDateTime,
+    pub options: Vec<PollOption>,
+}
+
+impl Poll {
+    /// Builds a poll from the option labels in display order. Labels are
+    /// trimmed; there must be between `MIN_POLL_OPTIONS` and
+    /// `MAX_POLL_OPTIONS` distinct, non-empty ones.
+    pub fn new(
+        tweet_id: Uuid,
+        labels: &[String],
+        closes_at: OffsetDateTime,
+    ) -> Result<Self, String> {
+        if !(MIN_POLL_OPTIONS..=MAX_POLL_OPTIONS).contains(&labels.len()) {
+            return Err(format!(
+                "A poll needs between {} and {} options.",
+                MIN_POLL_OPTIONS, MAX_POLL_OPTIONS
+            ));
+        }
+        let mut options: Vec<PollOption> = Vec::with_capacity(labels.len());
+        for (position, label) in labels.iter().enumerate() {
+            let label = label.trim();
+            if label.is_empty() || label.chars().count() > MAX_POLL_OPTION_LENGTH {
+                return Err(format!(
+                    "Poll options must be between 1 and {} characters long.",
+                    MAX_POLL_OPTION_LENGTH
+                ));
+            }
+            if options.iter().any(|o| o.label == label) {
+                return Err("Poll options must be distinct.".to_string());
+            }
+            options.push(PollOption {
+                id: Uuid::new_v4(),
+                position: position as i16,
+                label: label.to_string(),
+            });
+        }
+        Ok(Self {
+            tweet_id,
+            closes_at,
+            options,
+        })
+    }
+
+    pub fn is_closed(&self, now: OffsetDateTime) -> bool {
+        self.closes_at <= now
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq)]
+pub struct PollVote {
+    pub tweet_id: Uuid,
+    pub user_id: Uuid,
+    pub option_id: Uuid,
+    pub created_at: Option<OffsetDateTime>,
+}
+
+/// A poll with its vote counts as seen by one viewer.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PollTally {
+    pub poll: Poll,
+    /// Votes per option, in the order of `poll.options`.
+    pub votes: Vec<i64>,
+    /// The option the viewer voted for, if any.
+    pub voted_option_id: Option<Uuid>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq, ToSchema)]
+pub struct PollOptionResponse {
+    pub id: Uuid,
+    pub label: String,
+    /// Hidden until the viewer has voted or the poll has closed.
+    pub votes: Option<i64>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq, ToSchema)]
+pub struct PollResponse {
+    #[schema(value_type = String)]
+    pub closes_at: Option<String>,
+    pub closed: bool,
+    pub options: Vec<PollOptionResponse>,
+    /// Hidden until the viewer has voted or the poll has closed.
+    pub total_votes: Option<i64>,
+    pub voted_option_id: Option<Uuid>,
+}
+
+impl PollResponse {
+    pub fn new(tally: PollTally, now: OffsetDateTime) -> Self {
+        let closed = tally.poll.is_closed(now);
+        let show_results = closed || tally.voted_option_id.is_some();
+        Self {
+            closes_at: Some(tally.poll.closes_at.format(&get_time_formatter()).unwrap()),
+            closed,
+            total_votes: show_results.then(|| tally.votes.iter().sum()),
+            options: tally
+                .poll
+                .options
+                .into_iter()
+                .zip(tally.votes)
+                .map(|(option, votes)| PollOptionResponse {
+                    id: option.id,
+                    label: option.label,
+                    votes: show_results.then_some(votes),
+                })
+                .collect(),
+            voted_option_id: tally.voted_option_id,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::models::poll::{Poll, PollResponse, PollTally};
+    use time::{Duration, OffsetDateTime};
+    use uuid::Uuid;
+
+    fn labels(labels: &[&str]) -> Vec<String> {
+        labels.iter().map(|l| l.to_string()).collect()
+    }
+
+    #[test]
+    fn test_poll_new_validates_options() {
+        let now = OffsetDateTime::now_utc();
+        let poll = Poll::new(Uuid::new_v4(), &labels(&[" Yes ", "No"]), now).unwrap();
+        assert_eq!(
+            poll.options
+                .iter()
+                .map(|o| o.label.as_str())
+                .collect::<Vec<_>>(),
+            vec!["Yes", "No"]
+        );
+        assert_eq!(poll.options[1].position, 1);
+
+        assert!(Poll::new(Uuid::new_v4(), &labels(&["Only"]), now).is_err());
+        assert!(Poll::new(Uuid::new_v4(), &labels(&["a", "b", "c", "d", "e"]), now).is_err());
+        assert!(Poll::new(Uuid::new_v4(), &labels(&["a", "  "]), now).is_err());
+        assert!(Poll::new(Uuid::new_v4(), &labels(&["a", "a"]), now).is_err());
+        assert!(Poll::new(Uuid::new_v4(), &labels(&["a", &"b".repeat(65)]), now).is_err());
+    }
+
+    #[test]
+    fn test_poll_response_hides_results_until_voted_or_closed() {
+        let now = OffsetDateTime::now_utc();
+        let poll = Poll::new(
+            Uuid::new_v4(),
+            &labels(&["Yes", "No"]),
+            now + Duration::hours(1),
+        )
+        .unwrap();
+        let tally = PollTally {
+            poll: poll.clone(),
+            votes: vec![2, 1],
+            voted_option_id: None,
+        };
+
+        let hidden = PollResponse::new(tally.clone(), now);
+        assert!(!hidden.closed);
+        assert_eq!(hidden.total_votes, None);
+        assert!(hidden.options.iter().all(|o| o.votes.is_none()));
+
+        let voted = PollResponse::new(
+            PollTally {
+                voted_option_id: Some(poll.options[0].id),
+                ..tally.clone()
+            },
+            now,
+        );
+        assert_eq!(voted.total_votes, Some(3));
+        assert_eq!(voted.options[1].votes, Some(1));
+
+        let closed = PollResponse::new(tally, now + Duration::hours(2));
+        assert!(closed.closed);
+        assert_eq!(closed.options[0].votes, Some(2));
+    }
+}
@@ -0,0 +1,108 @@
+use crate::dto::response::get_time_formatter;
+use serde::{Deserialize, Serialize};
+use sqlx::types::time::OffsetDateTime;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// Longest accepted report reason, in characters.
+pub const MAX_REPORT_REASON_LENGTH: usize = 500;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, ToSchema, sqlx::Type)]
+#[serde(rename_all = "lowercase")]
+#[sqlx(type_name = "varchar", rename_all = "lowercase")]
+pub enum ReportStatus {
+    Open,
+    Actioned,
+    Dismissed,
+}
+
+impl ReportStatus {
+    /// Reports are resolved once: an open report may be actioned or
+    /// dismissed, resolved reports stay as they are.
+    pub fn can_transition_to(self, next: ReportStatus) -> bool {
+        self == ReportStatus::Open && next != ReportStatus::Open
+    }
+}
+
+/// A complaint filed by `reporter_id` about either a tweet or a user, waiting
+/// in the moderation queue until a moderator resolves it.
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq)]
+pub struct Report {
+    pub id: Uuid,
+    pub reporter_id: Uuid,
+    pub tweet_id: Option<Uuid>,
+    pub user_id: Option<Uuid>,
+    pub reason: String,
+    pub status: ReportStatus,
+    pub resolved_by: Option<Uuid>,
+    pub resolved_at: Option<OffsetDateTime>,
+    pub created_at: Option<OffsetDateTime>,
+}
+
+impl Report {
+    pub fn new(
+        reporter_id: Uuid,
+        tweet_id: Option<Uuid>,
+        user_id: Option<Uuid>,
+        reason: String,
+    ) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            reporter_id,
+            tweet_id,
+            user_id,
+            reason,
+            status: ReportStatus::Open,
+            resolved_by: None,
+            resolved_at: None,
+            created_at: Some(OffsetDateTime::now_utc()),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq, ToSchema)]
+pub struct ReportResponse {
+    pub id: Uuid,
+    pub reporter_id: Uuid,
+    pub tweet_id: Option<Uuid>,
+    pub user_id: Option<Uuid>,
+    pub reason: String,
+    pub status: ReportStatus,
+    pub resolved_by: Option<Uuid>,
+    pub resolved_at: Option<String>,
+    pub created_at: Option<String>,
+}
+
+impl From<Report> for ReportResponse {
+    fn from(report: Report) -> Self {
+        Self {
+            id: report.id,
+            reporter_id: report.reporter_id,
+            tweet_id: report.tweet_id,
+            user_id: report.user_id,
+            reason: report.reason,
+            status: report.status,
+            resolved_by: report.resolved_by,
+            resolved_at: report
+                .resolved_at
+                .map(|t| t.format(&get_time_formatter()).unwrap()),
+            created_at: report
+                .created_at
+                .map(|t| t.format(&get_time_formatter()).unwrap()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::models::report::ReportStatus;
+
+    #[test]
+    fn test_report_status_transitions() {
+        assert!(ReportStatus::Open.can_transition_to(ReportStatus::Actioned));
+        assert!(ReportStatus::Open.can_transition_to(ReportStatus::Dismissed));
+        assert!(!ReportStatus::Open.can_transition_to(ReportStatus::Open));
+        assert!(!ReportStatus::Actioned.can_transition_to(ReportStatus::Dismissed));
+        assert!(!ReportStatus::Dismissed.can_transition_to(ReportStatus::Open));
+    }
+}
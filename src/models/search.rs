@@ -0,0 +1,231 @@
+use crate::models::hashtag::normalize_hashtag;
+use crate::models::tweet::{Tweet, TweetResponse};
+use serde::{Deserialize, Serialize};
+use time::{Date, Month, OffsetDateTime};
+use utoipa::ToSchema;
+
+/// A free-text part of a search expression.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum SearchTerm {
+    /// `word`
+    Word(String),
+    /// `word*`, matching every lexeme that starts with `word`.
+    Prefix(String),
+    /// `"several words"`, matching the words in this order.
+    Phrase(Vec<String>),
+}
+
+/// Parsed form of the `q` parameter of `/api/search/tweets`.
+///
+/// Besides free text the expression accepts `from:username`, `#tag`,
+/// `since:YYYY-MM-DD` and `until:YYYY-MM-DD`; both dates are inclusive.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct TweetSearchQuery {
+    pub terms: Vec<SearchTerm>,
+    pub from: Option<String>,
+    pub hashtags: Vec<String>,
+    pub since: Option<Date>,
+    pub until: Option<Date>,
+}
+
+impl TweetSearchQuery {
+    pub fn parse(input: &str) -> Result<Self, String> {
+        let mut query = TweetSearchQuery::default();
+        let mut chars = input.chars().peekable();
+
+        while let Some(&c) = chars.peek() {
+            if c.is_whitespace() {
+                chars.next();
+                continue;
+            }
+            if c == '"' {
+                chars.next();
+                let phrase: String = chars.by_ref().take_while(|&c| c != '"').collect();
+                let words = split_words(&phrase);
+                if !words.is_empty() {
+                    query.terms.push(SearchTerm::Phrase(words));
+                }
+                continue;
+            }
+            let mut token = String::new();
+            while let Some(&c) = chars.peek() {
+                if c.is_whitespace() {
+                    break;
+                }
+                token.push(c);
+                chars.next();
+            }
+            query.push_token(&token)?;
+        }
+
+        if query.terms.is_empty()
+            && query.from.is_none()
+            && query.hashtags.is_empty()
+            && query.since.is_none()
+            && query.until.is_none()
+        {
+            return Err(String::from("Search query is empty."));
+        }
+        Ok(query)
+    }
+
+    fn push_token(&mut self, token: &str) -> Result<(), String> {
+        if let Some(username) = token.strip_prefix("from:") {
+            let username = username.trim_start_matches('@');
+            if username.is_empty() {
+                return Err(String::from("`from:` requires a username."));
+            }
+            self.from = Some(username.to_string());
+        } else if let Some(value) = token.strip_prefix("since:") {
+            self.since = Some(parse_date(value)?);
+        } else if let Some(value) = token.strip_prefix("until:") {
+            self.until = Some(parse_date(value)?);
+        } else if token.starts_with('#') {
+            let tag = normalize_hashtag(token);
+            if !tag.is_empty() {
+                self.hashtags.push(tag);
+            }
+        } else if let Some(prefix) = token.strip_suffix('*') {
+            let mut words = split_words(prefix);
+            if let Some(last) = words.pop() {
+                self.terms.extend(words.into_iter().map(SearchTerm::Word));
+                self.terms.push(SearchTerm::Prefix(last));
+            }
+        } else {
+            self.terms
+                .extend(split_words(token).into_iter().map(SearchTerm::Word));
+        }
+        Ok(())
+    }
+
+    /// Renders the free-text terms as a `to_tsquery` expression, or `None`
+    /// when the search only uses filters. Every lexeme is reduced to
+    /// alphanumeric characters, so the result needs no further escaping.
+    pub fn to_tsquery(&self) -> Option<String> {
+        if self.terms.is_empty() {
+            return None;
+        }
+        let parts: Vec<String> = self
+            .terms
+            .iter()
+            .map(|term| match term {
+                SearchTerm::Word(word) => format!("'{}'", word),
+                SearchTerm::Prefix(word) => format!("'{}':*", word),
+                SearchTerm::Phrase(words) => format!(
+                    "({})",
+                    words
+                        .iter()
+                        .map(|w| format!("'{}'", w))
+                        .collect::<Vec<_>>()
+                        .join(" <-> ")
+                ),
+            })
+            .collect();
+        Some(parts.join(" & "))
+    }
+
+    /// Start of the `since` day, in UTC.
+    pub fn created_after(&self) -> Option<OffsetDateTime> {
+        self.since.map(|d| d.midnight().assume_utc())
+    }
+
+    /// Start of the day following `until`, in UTC.
+    pub fn created_before(&self) -> Option<OffsetDateTime> {
+        self.until
+            .and_then(|d| d.next_day())
+            .map(|d| d.midnight().assume_utc())
+    }
+}
+
+fn split_words(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(|w| w.to_lowercase())
+        .collect()
+}
+
+fn parse_date(value: &str) -> Result<Date, String> {
+    let invalid = || format!("Date `{}` is invalid, expected YYYY-MM-DD.", value);
+    let mut parts = value.splitn(3, '-');
+    let year = parts.next().and_then(|p| p.parse::<i32>().ok());
+    let month = parts.next().and_then(|p| p.parse::<u8>().ok());
+    let day = parts.next().and_then(|p| p.parse::<u8>().ok());
+    match (year, month, day) {
+        (Some(year), Some(month), Some(day)) => {
+            let month = Month::try_from(month).map_err(|_| invalid())?;
+            Date::from_calendar_date(year, month, day).map_err(|_| invalid())
+        }
+        _ => Err(invalid()),
+    }
+}
+
+/// A tweet matched by a search, before it is converted into a response.
+#[derive(Clone, Debug)]
+pub struct TweetSearchHit {
+    pub tweet: Tweet,
+    pub rank: f32,
+    pub snippet: String,
+}
+
+/// A search result. `snippet` holds the best matching fragments of the
+/// HTML-escaped content with the matched words wrapped in `<mark>` tags, so
+/// it can be rendered as HTML as is.
+#[derive(Serialize, Deserialize, Debug, Clone, ToSchema)]
+pub struct TweetSearchResponse {
+    pub tweet: TweetResponse,
+    pub rank: f32,
+    pub snippet: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::models::search::{SearchTerm, TweetSearchQuery};
+    use time::{Date, Month};
+
+    #[test]
+    fn test_parse_search_query() {
+        let query = TweetSearchQuery::parse(
+            r#"rust "async runtime" tok* from:@alice #SQLx since:2025-01-31 until:2025-02-01"#,
+        )
+        .unwrap();
+        assert_eq!(
+            query.terms,
+            vec![
+                SearchTerm::Word(String::from("rust")),
+                SearchTerm::Phrase(vec![String::from("async"), String::from("runtime")]),
+                SearchTerm::Prefix(String::from("tok")),
+            ]
+        );
+        assert_eq!(query.from.as_deref(), Some("alice"));
+        assert_eq!(query.hashtags, vec![String::from("sqlx")]);
+        assert_eq!(
+            query.since,
+            Some(Date::from_calendar_date(2025, Month::January, 31).unwrap())
+        );
+        assert_eq!(
+            query.to_tsquery().as_deref(),
+            Some("'rust' & ('async' <-> 'runtime') & 'tok':*")
+        );
+        assert!(query.created_before().unwrap() > query.created_after().unwrap());
+    }
+
+    #[test]
+    fn test_parse_search_query_strips_tsquery_syntax() {
+        let query = TweetSearchQuery::parse("it's a&b | !c").unwrap();
+        assert_eq!(
+            query.to_tsquery().as_deref(),
+            Some("'it' & 's' & 'a' & 'b' & 'c'")
+        );
+    }
+
+    #[test]
+    fn test_parse_search_query_rejects_invalid_input() {
+        assert!(TweetSearchQuery::parse("   ").is_err());
+        assert!(TweetSearchQuery::parse("since:2025-13-01").is_err());
+        assert!(TweetSearchQuery::parse("from:").is_err());
+        assert!(TweetSearchQuery::parse("#tag")
+            .unwrap()
+            .to_tsquery()
+            .is_none());
+    }
+}
@@ -0,0 +1,96 @@
+use serde::{Deserialize, Serialize};
+use sqlx::types::time::OffsetDateTime;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// Comment line sent on idle connections so proxies do not close them.
+pub const STREAM_HEARTBEAT: &str = ": heartbeat\n\n";
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, ToSchema, sqlx::Type)]
+#[serde(rename_all = "lowercase")]
+#[sqlx(type_name = "varchar", rename_all = "lowercase")]
+pub enum StreamEventKind {
+    /// A tweet was published to the recipient's home timeline.
+    Tweet,
+    /// A notification of the recipient was created or grew.
+    Notification,
+}
+
+impl StreamEventKind {
+    /// The SSE `event` field.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            StreamEventKind::Tweet => "tweet",
+            StreamEventKind::Notification => "notification",
+        }
+    }
+}
+
+/// An entry of the stream log. `id` is the SSE event id clients send back as
+/// `Last-Event-ID`.
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq)]
+pub struct StreamEvent {
+    pub id: i64,
+    pub kind: StreamEventKind,
+    pub tweet_id: Option<Uuid>,
+    pub notification_id: Option<Uuid>,
+    pub created_at: Option<OffsetDateTime>,
+}
+
+/// A stream event rendered for the users in `recipients`: `data` is the
+/// JSON of a `TweetResponse` or a `NotificationResponse`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct StreamMessage {
+    pub id: i64,
+    pub kind: StreamEventKind,
+    pub recipients: Vec<Uuid>,
+    pub data: String,
+}
+
+impl StreamMessage {
+    /// Formats the message as a Server-Sent Events frame.
+    pub fn to_sse(&self) -> String {
+        format!(
+            "id: {}\nevent: {}\ndata: {}\n\n",
+            self.id,
+            self.kind.as_str(),
+            self.data
+        )
+    }
+}
+
+/// Parses a `Last-Event-ID` header.
+pub fn parse_last_event_id(value: &str) -> Result<i64, String> {
+    value
+        .trim()
+        .parse::<i64>()
+        .ok()
+        .filter(|id| *id >= 0)
+        .ok_or_else(|| format!("Last-Event-ID `{}` is invalid.", value))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::models::stream::{parse_last_event_id, StreamEventKind, StreamMessage};
+
+    #[test]
+    fn test_stream_message_to_sse() {
+        let message = StreamMessage {
+            id: 42,
+            kind: StreamEventKind::Notification,
+            recipients: Vec::new(),
+            data: r#"{"id":1}"#.to_string(),
+        };
+        assert_eq!(
+            message.to_sse(),
+            "id: 42\nevent: notification\ndata: {\"id\":1}\n\n"
+        );
+    }
+
+    #[test]
+    fn test_parse_last_event_id() {
+        assert_eq!(parse_last_event_id(" 17 "), Ok(17));
+        assert!(parse_last_event_id("-1").is_err());
+        assert!(parse_last_event_id("abc").is_err());
+    }
+}
@@ -0,0 +1,46 @@
+use crate::dto::response::get_time_formatter;
+use serde::{Deserialize, Serialize};
+use sqlx::types::time::OffsetDateTime;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq, ToSchema)]
+pub struct Subscription {
+    pub subscriber_id: Uuid,
+    pub target_id: Uuid,
+    #[schema(value_type = String)]
+    pub created_at: Option<OffsetDateTime>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq, ToSchema)]
+pub struct SubscriptionResponse {
+    pub subscriber_id: Uuid,
+    pub target_id: Uuid,
+    pub created_at: Option<String>,
+}
+
+impl From<Subscription> for SubscriptionResponse {
+    fn from(value: Subscription) -> Self {
+        Self {
+            subscriber_id: value.subscriber_id,
+            target_id: value.target_id,
+            created_at: Some(
+                value
+                    .created_at
+                    .unwrap()
+                    .format(&get_time_formatter())
+                    .unwrap(),
+            ),
+        }
+    }
+}
+
+impl Subscription {
+    pub fn new(subscriber_id: Uuid, target_id: Uuid) -> Self {
+        Self {
+            subscriber_id,
+            target_id,
+            created_at: Some(OffsetDateTime::now_utc()),
+        }
+    }
+}
@@ -1,9 +1,30 @@
 use serde::{Deserialize, Serialize};
-use time::OffsetDateTime;
+use time::{Duration, OffsetDateTime};
 use utoipa::ToSchema;
 use uuid::Uuid;
 
 use crate::dto::response::get_time_formatter;
+use crate::models::link_preview::LinkPreviewResponse;
+use crate::models::media::MediaResponse;
+use crate::models::mention::MentionEntity;
+use crate::models::poll::PollResponse;
+use crate::models::user::AuthorResponse;
+
+/// Who may read a tweet besides its author.
+#[derive(
+    Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq, ToSchema, sqlx::Type,
+)]
+#[serde(rename_all = "lowercase")]
+#[sqlx(type_name = "varchar", rename_all = "lowercase")]
+pub enum TweetVisibility {
+    /// Everyone, including anonymous visitors.
+    #[default]
+    Public,
+    /// Users in the author's user group.
+    Group,
+    /// Nobody but the author.
+    Private,
+}
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Tweet {
@@ -12,6 +33,7 @@ pub struct Tweet {
     pub created_at: Option<OffsetDateTime>,
     pub updated_at: Option<OffsetDateTime>,
     pub user_id: Uuid,
+    pub visibility: TweetVisibility,
 }
 
 impl Tweet {
@@ -22,9 +44,16 @@ impl Tweet {
             created_at: Some(OffsetDateTime::now_utc()),
             updated_at: Some(OffsetDateTime::now_utc()),
             user_id,
+            visibility: TweetVisibility::Public,
         }
     }
 
+    /// Whether the author may still edit the tweet at `now`.
+    pub fn is_editable(&self, edit_window: Duration, now: OffsetDateTime) -> bool {
+        self.created_at
+            .is_some_and(|created_at| now - created_at <= edit_window)
+    }
+
     pub fn default_test(user_id: Uuid) -> Self {
         Self {
             id: Uuid::new_v4(),
@@ -32,6 +61,7 @@ impl Tweet {
             created_at: Some(OffsetDateTime::now_utc()),
             updated_at: Some(OffsetDateTime::now_utc()),
             user_id,
+            visibility: TweetVisibility::Public,
         }
     }
 
@@ -46,6 +76,7 @@ impl Tweet {
                 created_at: Some(OffsetDateTime::now_utc()),
                 updated_at: Some(OffsetDateTime::now_utc()),
                 user_id,
+                visibility: TweetVisibility::Public,
             });
         }
 
@@ -62,6 +93,27 @@ pub struct TweetResponse {
     #[schema(value_type = String)]
     pub updated_at: Option<String>,
     pub user_id: Uuid,
+    /// Compact profile of the author, so clients need no request per author.
+    #[serde(default)]
+    pub author: Option<AuthorResponse>,
+    #[serde(default)]
+    pub visibility: TweetVisibility,
+    #[serde(default)]
+    pub mentions: Vec<MentionEntity>,
+    #[serde(default)]
+    pub media: Vec<MediaResponse>,
+    /// Whether the content was changed after posting; earlier versions are
+    /// listed by `GET /api/tweets/{id}/revisions`.
+    #[serde(default)]
+    pub edited: bool,
+    /// Whether the author pinned this tweet to their profile.
+    #[serde(default)]
+    pub pinned: bool,
+    #[serde(default)]
+    pub poll: Option<PollResponse>,
+    /// Preview of the first link in the content, once it has been fetched.
+    #[serde(default)]
+    pub card: Option<LinkPreviewResponse>,
 }
 
 impl From<Tweet> for TweetResponse {
@@ -84,6 +136,14 @@ impl From<Tweet> for TweetResponse {
                     .unwrap(),
             ),
             user_id: tweet.user_id,
+            author: None,
+            visibility: tweet.visibility,
+            mentions: Vec::new(),
+            media: Vec::new(),
+            edited: false,
+            pinned: false,
+            poll: None,
+            card: None,
         }
     }
 }
@@ -0,0 +1,47 @@
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::dto::response::get_time_formatter;
+
+/// A superseded version of a tweet. `created_at` is when this content was
+/// written and `replaced_at` when an edit replaced it.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TweetRevision {
+    pub id: Uuid,
+    pub tweet_id: Uuid,
+    pub revision: i32,
+    pub content: String,
+    pub created_at: Option<OffsetDateTime>,
+    pub replaced_at: Option<OffsetDateTime>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, ToSchema)]
+pub struct TweetRevisionResponse {
+    pub id: Uuid,
+    pub tweet_id: Uuid,
+    pub revision: i32,
+    pub content: String,
+    #[schema(value_type = String)]
+    pub created_at: Option<String>,
+    #[schema(value_type = String)]
+    pub replaced_at: Option<String>,
+}
+
+impl From<TweetRevision> for TweetRevisionResponse {
+    fn from(revision: TweetRevision) -> Self {
+        Self {
+            id: revision.id,
+            tweet_id: revision.tweet_id,
+            revision: revision.revision,
+            content: revision.content,
+            created_at: revision
+                .created_at
+                .map(|t| t.format(&get_time_formatter()).unwrap()),
+            replaced_at: revision
+                .replaced_at
+                .map(|t| t.format(&get_time_formatter()).unwrap()),
+        }
+    }
+}
@@ -5,6 +5,12 @@ use uuid::Uuid;
 
 use crate::dto::response::get_time_formatter;
 
+/// Longest accepted profile fields, in characters.
+pub const MAX_DISPLAY_NAME_LENGTH: usize = 50;
+pub const MAX_BIO_LENGTH: usize = 160;
+pub const MAX_LOCATION_LENGTH: usize = 30;
+pub const MAX_WEBSITE_LENGTH: usize = 100;
+
 #[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq, ToSchema)]
 pub struct User {
     pub id: Uuid,
@@ -14,6 +20,12 @@ pub struct User {
     #[serde(skip_serializing, skip_deserializing)]
     pub created_at: Option<OffsetDateTime>,
     pub user_group_id: Option<Uuid>,
+    pub display_name: Option<String>,
+    pub bio: Option<String>,
+    pub location: Option<String>,
+    pub website: Option<String>,
+    pub avatar_media_id: Option<Uuid>,
+    pub banner_media_id: Option<Uuid>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq, ToSchema)]
@@ -22,6 +34,13 @@ pub struct UserResponse {
     pub username: String,
     pub created_at: Option<String>,
     pub user_group_id: Option<Uuid>,
+    pub display_name: Option<String>,
+    pub bio: Option<String>,
+    pub location: Option<String>,
+    pub website: Option<String>,
+    /// Served by `GET /api/media/{id}/content`.
+    pub avatar_media_id: Option<Uuid>,
+    pub banner_media_id: Option<Uuid>,
 }
 
 impl From<User> for UserResponse {
@@ -36,10 +55,94 @@ impl From<User> for UserResponse {
                     .unwrap(),
             ),
             user_group_id: user.user_group_id,
+            display_name: user.display_name,
+            bio: user.bio,
+            location: user.location,
+            website: user.website,
+            avatar_media_id: user.avatar_media_id,
+            banner_media_id: user.banner_media_id,
         }
     }
 }
 
+/// The profile fields shown next to a user's tweets: the name and avatar
+/// every tweet is rendered with. The bio, location, website and banner only
+/// appear on the profile page, so they are left out to keep each tweet of a
+/// page small; clients load them from `GET /api/users/{id}` when needed.
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq, ToSchema)]
+pub struct AuthorResponse {
+    pub id: Uuid,
+    pub username: String,
+    pub display_name: Option<String>,
+    pub avatar_media_id: Option<Uuid>,
+}
+
+/// The profile a user edits about themselves. Blank text fields clear the
+/// stored value.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, Eq, PartialEq, ToSchema)]
+pub struct UserProfile {
+    #[serde(default)]
+    pub display_name: Option<String>,
+    #[serde(default)]
+    pub bio: Option<String>,
+    #[serde(default)]
+    pub location: Option<String>,
+    /// An absolute `http` or `https` URL.
+    #[serde(default)]
+    pub website: Option<String>,
+    /// An image uploaded by the user.
+    #[serde(default)]
+    pub avatar_media_id: Option<Uuid>,
+    /// An image uploaded by the user.
+    #[serde(default)]
+    pub banner_media_id: Option<Uuid>,
+}
+
+impl UserProfile {
+    /// Trims the text fields and turns blank ones into `None`.
+    pub fn normalized(self) -> Self {
+        fn clean(value: Option<String>) -> Option<String> {
+            value
+                .map(|v| v.trim().to_string())
+                .filter(|v| !v.is_empty())
+        }
+        Self {
+            display_name: clean(self.display_name),
+            bio: clean(self.bio),
+            location: clean(self.location),
+            website: clean(self.website),
+            ..self
+        }
+    }
+
+    /// Checks the lengths of the text fields and that `website` is a web URL.
+    pub fn validate(&self) -> Result<(), String> {
+        let limits = [
+            ("Display names", &self.display_name, MAX_DISPLAY_NAME_LENGTH),
+            ("Bios", &self.bio, MAX_BIO_LENGTH),
+            ("Locations", &self.location, MAX_LOCATION_LENGTH),
+            ("Websites", &self.website, MAX_WEBSITE_LENGTH),
+        ];
+        for (field, value, max) in limits {
+            if value.as_ref().is_some_and(|v| v.chars().count() > max) {
+                return Err(format!(
+                    "{} must be at most {} characters long.",
+                    field, max
+                ));
+            }
+        }
+        if let Some(website) = &self.website {
+            let valid = reqwest::Url::parse(website).is_ok_and(|url| {
+                matches!(url.scheme(), "http" | "https") && url.host_str().is_some()
+            });
+            if !valid {
+                return Err("Websites must be http or https URLs.".to_string());
+            }
+        }
+        Ok(())
+    }
+}
+
 impl User {
     pub fn default_test() -> Self {
         Self {
@@ -48,6 +151,12 @@ impl User {
             password_hash: String::from("test_password_hash"),
             created_at: Some(OffsetDateTime::now_utc()),
             user_group_id: None,
+            display_name: None,
+            bio: None,
+            location: None,
+            website: None,
+            avatar_media_id: None,
+            banner_media_id: None,
         }
     }
 
@@ -61,6 +170,12 @@ impl User {
                 password_hash: String::from("test_password_hash"),
                 created_at: Some(OffsetDateTime::now_utc()),
                 user_group_id: None,
+                display_name: None,
+                bio: None,
+                location: None,
+                website: None,
+                avatar_media_id: None,
+                banner_media_id: None,
             };
             result.push(new);
         });
@@ -75,6 +190,45 @@ impl User {
             password_hash,
             created_at: Some(OffsetDateTime::now_utc()),
             user_group_id,
+            display_name: None,
+            bio: None,
+            location: None,
+            website: None,
+            avatar_media_id: None,
+            banner_media_id: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::models::user::{UserProfile, MAX_BIO_LENGTH};
+
+    #[test]
+    fn test_user_profile_validation() {
+        let profile = UserProfile {
+            display_name: Some("  Ada  ".to_string()),
+            bio: Some("   ".to_string()),
+            website: Some("https://example.com/ada".to_string()),
+            ..UserProfile::default()
+        }
+        .normalized();
+        assert_eq!(profile.display_name.as_deref(), Some("Ada"));
+        assert_eq!(profile.bio, None);
+        assert!(profile.validate().is_ok());
+
+        let long_bio = UserProfile {
+            bio: Some("a".repeat(MAX_BIO_LENGTH + 1)),
+            ..UserProfile::default()
+        };
+        assert!(long_bio.validate().is_err());
+
+        for website in ["example.com", "ftp://example.com", "javascript:alert(1)"] {
+            let profile = UserProfile {
+                website: Some(website.to_string()),
+                ..UserProfile::default()
+            };
+            assert!(profile.validate().is_err(), "{}", website);
         }
     }
 }
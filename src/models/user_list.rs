@@ -0,0 +1,84 @@
+use crate::dto::response::get_time_formatter;
+use serde::{Deserialize, Serialize};
+use sqlx::types::time::OffsetDateTime;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// A named set of users curated by `owner_id`, read as a timeline of their
+/// members' tweets. Lists are private to their owner.
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq)]
+pub struct UserList {
+    pub id: Uuid,
+    pub owner_id: Uuid,
+    pub name: String,
+    pub description: Option<String>,
+    pub created_at: Option<OffsetDateTime>,
+    pub updated_at: Option<OffsetDateTime>,
+}
+
+impl UserList {
+    pub fn new(owner_id: Uuid, name: String, description: Option<String>) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            owner_id,
+            name,
+            description,
+            created_at: Some(OffsetDateTime::now_utc()),
+            updated_at: Some(OffsetDateTime::now_utc()),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq, ToSchema)]
+pub struct UserListResponse {
+    pub id: Uuid,
+    pub owner_id: Uuid,
+    pub name: String,
+    pub description: Option<String>,
+    pub created_at: Option<String>,
+    pub updated_at: Option<String>,
+}
+
+impl From<UserList> for UserListResponse {
+    fn from(list: UserList) -> Self {
+        Self {
+            id: list.id,
+            owner_id: list.owner_id,
+            name: list.name,
+            description: list.description,
+            created_at: list
+                .created_at
+                .map(|t| t.format(&get_time_formatter()).unwrap()),
+            updated_at: list
+                .updated_at
+                .map(|t| t.format(&get_time_formatter()).unwrap()),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq, ToSchema)]
+pub struct UserListMember {
+    pub list_id: Uuid,
+    pub user_id: Uuid,
+    #[schema(value_type = String)]
+    pub created_at: Option<OffsetDateTime>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq, ToSchema)]
+pub struct UserListMemberResponse {
+    pub list_id: Uuid,
+    pub user_id: Uuid,
+    pub created_at: Option<String>,
+}
+
+impl From<UserListMember> for UserListMemberResponse {
+    fn from(member: UserListMember) -> Self {
+        Self {
+            list_id: member.list_id,
+            user_id: member.user_id,
+            created_at: member
+                .created_at
+                .map(|t| t.format(&get_time_formatter()).unwrap()),
+        }
+    }
+}
@@ -1,14 +1,36 @@
-use actix_session::Session;
+use actix_session::{Session, SessionExt};
+use actix_web::body::MessageBody;
+use actix_web::dev::{ServiceRequest, ServiceResponse};
+use actix_web::middleware::Next;
+use actix_web::web::{Data, Json};
 use actix_web::{web, HttpResponse};
 use serde::{Deserialize, Serialize};
-use sqlx::Pool;
+use sqlx::{PgPool, Pool};
+use time::OffsetDateTime;
+use utoipa::ToSchema;
+use uuid::Uuid;
 
 use crate::{
     configuration::get_configuration,
     error::AlohaError,
+    mappers::account::get_account_state,
+    mappers::moderation::change_account_status,
     mappers::user::{check_user_password_correct, get_user_by_username},
+    mappers::user_permission::user_has_permission,
+    models::account::{AccountStateResponse, AccountStatus, MAX_ACCOUNT_STATUS_REASON_LENGTH},
+    models::moderation::{ModerationAction, ModerationActionKind},
 };
 
+/// Name of the permission that grants access to administrative endpoints.
+pub const ADMIN_PERMISSION: &str = "admin";
+/// Lets moderators read the report queue and the moderation log, and resolve
+/// reports.
+pub const MODERATE_REPORTS_PERMISSION: &str = "moderate_reports";
+/// Lets moderators hide tweets.
+pub const HIDE_TWEETS_PERMISSION: &str = "hide_tweets";
+/// Lets moderators suspend users.
+pub const SUSPEND_USERS_PERMISSION: &str = "suspend_users";
+
 /// - `user_name`：用户的用户名，用于身份验证。
 /// - `password`：用户的密码，用于身份验证。
 #[derive(Serialize, Deserialize, Default)]
@@ -43,6 +65,15 @@ pub async fn login(
         Ok(user) => {
             match check_user_password_correct(&mut transaction, user.id, password_hash).await {
                 Ok(true) => {
+                    let now = OffsetDateTime::now_utc();
+                    match get_account_state(&mut transaction, user.id).await {
+                        Ok(state) => {
+                            if let Some(denial) = state.and_then(|s| s.login_denial(now)) {
+                                return Ok(HttpResponse::Forbidden().body(denial));
+                            }
+                        }
+                        Err(e) => return Err(AlohaError::DatabaseError(e.to_string())),
+                    }
                     tracing::log::debug!("Insert session data");
                     // Store the user ID in the session
                     session
@@ -86,17 +117,137 @@ pub async fn logout(session: Session) -> Result<HttpResponse, AlohaError> {
     }
 }
 
+#[derive(Deserialize, Clone, ToSchema)]
+pub struct DeactivateAccountFormData {
+    #[serde(default)]
+    reason: Option<String>,
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/auth/deactivate",
+    request_body = DeactivateAccountFormData,
+    responses(
+        (status = 200, description = "Account deactivated and logged out", body = AccountStateResponse),
+        (status = 400, description = "Reason too long or database error", body = AlohaError),
+        (status = 401, description = "User is not logged in", body = AlohaError)
+    )
+)]
+pub async fn deactivate_account(
+    session: Session,
+    body: Json<DeactivateAccountFormData>,
+    pool: Data<PgPool>,
+) -> Result<HttpResponse, AlohaError> {
+    let user_id = get_session_user_id(&session)?;
+    let reason = body
+        .reason
+        .as_deref()
+        .map(str::trim)
+        .filter(|r| !r.is_empty());
+    if reason.is_some_and(|r| r.chars().count() > MAX_ACCOUNT_STATUS_REASON_LENGTH) {
+        return Err(AlohaError::RequestParameterInvalid(format!(
+            "Reasons must be at most {} characters long.",
+            MAX_ACCOUNT_STATUS_REASON_LENGTH
+        )));
+    }
+    let action = ModerationAction {
+        user_id: Some(user_id),
+        note: reason.map(str::to_string),
+        ..ModerationAction::new(user_id, ModerationActionKind::DeactivateUser)
+    };
+    let transaction = pool.begin().await.unwrap();
+    match change_account_status(transaction, &action, AccountStatus::Deactivated, None).await {
+        Ok(Some(state)) => {
+            session.purge();
+            Ok(
+                HttpResponse::Ok()
+                    .json(AccountStateResponse::new(state, OffsetDateTime::now_utc())),
+            )
+        }
+        Ok(None) => Err(AlohaError::UserUnauthentication),
+        Err(e) => Err(AlohaError::DatabaseError(e.to_string())),
+    }
+}
+
+/// Purges the session of users whose account is no longer active, so
+/// deactivating, suspending or banning an account also ends the sessions it
+/// opened before. Costs one query per request carrying a logged-in session.
+pub async fn end_inactive_sessions(
+    req: ServiceRequest,
+    next: Next<impl MessageBody>,
+) -> Result<ServiceResponse<impl MessageBody>, actix_web::Error> {
+    let session = req.get_session();
+    if let (Ok(Some(user_id)), Some(pool)) = (
+        session.get::<Uuid>("user_id"),
+        req.app_data::<Data<PgPool>>(),
+    ) {
+        let mut transaction = pool
+            .begin()
+            .await
+            .map_err(actix_web::error::ErrorInternalServerError)?;
+        let state = get_account_state(&mut transaction, user_id)
+            .await
+            .map_err(actix_web::error::ErrorInternalServerError)?;
+        let now = OffsetDateTime::now_utc();
+        if state.is_some_and(|s| s.effective_status(now) != AccountStatus::Active) {
+            session.purge();
+        }
+    }
+    next.call(req).await
+}
+
 pub async fn check_login(session: &Session) -> Result<bool, AlohaError> {
     match session.get::<String>("username") {
         Ok(_user_name) => Ok(true),
         _ => Ok(false),
     }
 }
+
+/// Returns the id of the logged-in user, or `UserUnauthentication` when the
+/// session carries none.
+pub fn get_session_user_id(session: &Session) -> Result<Uuid, AlohaError> {
+    match session.get::<Uuid>("user_id") {
+        Ok(Some(user_id)) => Ok(user_id),
+        Ok(None) => Err(AlohaError::UserUnauthentication),
+        Err(e) => Err(AlohaError::DatabaseError(e.to_string())),
+    }
+}
+
+/// Returns the id of the logged-in user, or `None` for anonymous viewers.
+pub fn get_session_viewer_id(session: &Session) -> Result<Option<Uuid>, AlohaError> {
+    session
+        .get::<Uuid>("user_id")
+        .map_err(|e| AlohaError::DatabaseError(e.to_string()))
+}
+
+/// Returns the id of the logged-in user when they hold the admin permission,
+/// either directly or through their group.
+pub async fn require_admin(session: &Session, pool: &PgPool) -> Result<Uuid, AlohaError> {
+    require_permission(session, pool, ADMIN_PERMISSION).await
+}
+
+/// Returns the id of the logged-in user when they hold the permission called
+/// `name`, either directly or through their group.
+pub async fn require_permission(
+    session: &Session,
+    pool: &PgPool,
+    name: &str,
+) -> Result<Uuid, AlohaError> {
+    let user_id = get_session_user_id(session)?;
+    let mut transaction = pool.begin().await.unwrap();
+    match user_has_permission(&mut transaction, user_id, name).await {
+        Ok(true) => Ok(user_id),
+        Ok(false) => Err(AlohaError::PermissionDenied),
+        Err(e) => Err(AlohaError::DatabaseError(e.to_string())),
+    }
+}
+
 pub fn auth_routes(cfg: &mut web::ServiceConfig) {
     let config = get_configuration().unwrap();
     cfg.service(
         web::scope(format!("/{}", config.routes.auth).as_str())
             .route("/login", web::post().to(login))
-            .route("/logout", web::post().to(logout)),
+            .route("/logout", web::post().to(logout))
+            .route("/deactivate", web::post().to(deactivate_account)),
     );
 }
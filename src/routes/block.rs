@@ -0,0 +1,110 @@
+use crate::configuration::get_configuration;
+use crate::dto::query::{BlockFilterQuery, DtoQuery};
+use crate::dto::response::DtoResponse;
+use crate::error::AlohaError;
+use crate::mappers::block::{delete_block, get_blocked_users, insert_block};
+use crate::models::block::{Block, BlockResponse};
+use crate::models::user::UserResponse;
+use crate::routes::auth::get_session_user_id;
+use actix_session::Session;
+use actix_web::web::{self, Data};
+use actix_web::HttpResponse;
+use serde_qs::actix::QsQuery;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+#[utoipa::path(
+    put,
+    path = "/api/users/{id}/block",
+    params(
+        ("id" = Uuid, Path, description = "ID of the user to block")
+    ),
+    responses(
+        (status = 200, description = "User blocked successfully", body = BlockResponse),
+        (status = 400, description = "Unknown user, the logged-in user or database error", body = AlohaError),
+        (status = 401, description = "User is not logged in", body = AlohaError)
+    )
+)]
+pub async fn insert_block_route(
+    session: Session,
+    id: web::Path<(Uuid,)>,
+    pool: Data<PgPool>,
+) -> Result<HttpResponse, AlohaError> {
+    let user_id = get_session_user_id(&session)?;
+    if id.0 == user_id {
+        return Err(AlohaError::RequestParameterInvalid(
+            "You cannot block yourself.".to_string(),
+        ));
+    }
+    let transaction = pool.begin().await.unwrap();
+    match insert_block(transaction, &Block::new(user_id, id.0)).await {
+        Ok(result) => Ok(HttpResponse::Ok().json(BlockResponse::from(result))),
+        Err(e) => Err(AlohaError::DatabaseError(e.to_string())),
+    }
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/users/{id}/block",
+    params(
+        ("id" = Uuid, Path, description = "ID of the user to unblock")
+    ),
+    responses(
+        (status = 200, description = "User unblocked successfully", body = BlockResponse),
+        (status = 400, description = "Database error", body = AlohaError),
+        (status = 401, description = "User is not logged in", body = AlohaError),
+        (status = 404, description = "User is not blocked")
+    )
+)]
+pub async fn delete_block_route(
+    session: Session,
+    id: web::Path<(Uuid,)>,
+    pool: Data<PgPool>,
+) -> Result<HttpResponse, AlohaError> {
+    let user_id = get_session_user_id(&session)?;
+    let transaction = pool.begin().await.unwrap();
+    match delete_block(transaction, user_id, id.0).await {
+        Ok(Some(result)) => Ok(HttpResponse::Ok().json(BlockResponse::from(result))),
+        Ok(None) => Ok(HttpResponse::NotFound().finish()),
+        Err(e) => Err(AlohaError::DatabaseError(e.to_string())),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/blocks",
+    params(
+        ("page" = Option<i32>, Query, description = "Page number"),
+        ("size" = Option<i32>, Query, description = "Page size")
+    ),
+    responses(
+        (status = 200, description = "Users blocked by the logged-in user", body = DtoResponse<Vec<UserResponse>>),
+        (status = 400, description = "Database error", body = AlohaError),
+        (status = 401, description = "User is not logged in", body = AlohaError)
+    )
+)]
+pub async fn get_blocks_route(
+    session: Session,
+    query: QsQuery<DtoQuery<BlockFilterQuery>>,
+    pool: Data<PgPool>,
+) -> Result<HttpResponse, AlohaError> {
+    let user_id = get_session_user_id(&session)?;
+    let transaction = pool.begin().await.unwrap();
+    match get_blocked_users(transaction, user_id, query.into_inner()).await {
+        Ok(result) => {
+            let data: Vec<UserResponse> = result.data.into_iter().map(UserResponse::from).collect();
+            Ok(HttpResponse::Ok().json(DtoResponse::new(data, result.pagination)))
+        }
+        Err(e) => Err(AlohaError::DatabaseError(e.to_string())),
+    }
+}
+
+/// Blocked users are listed under their own scope; they are blocked and
+/// unblocked through `/api/users/{id}/block`, registered by `user_routes`.
+pub fn block_routes(cfg: &mut web::ServiceConfig) {
+    let config = get_configuration().unwrap();
+    cfg.service(
+        web::scope(format!("/{}", config.routes.blocks).as_str())
+            .route("", web::get().to(get_blocks_route)),
+    );
+}
@@ -0,0 +1,111 @@
+use crate::configuration::get_configuration;
+use crate::dto::query::{DtoQuery, TweetFilterQuery};
+use crate::dto::response::DtoResponse;
+use crate::error::AlohaError;
+use crate::mappers::bookmark::{delete_bookmark, get_bookmarked_tweets, insert_bookmark};
+use crate::models::bookmark::{Bookmark, BookmarkResponse};
+use crate::models::tweet::TweetResponse;
+use crate::routes::auth::get_session_user_id;
+use crate::routes::tweet::build_tweet_responses;
+use actix_session::Session;
+use actix_web::web::{self, Data};
+use actix_web::HttpResponse;
+use serde_qs::actix::QsQuery;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+#[utoipa::path(
+    put,
+    path = "/api/tweets/{id}/bookmark",
+    params(
+        ("id" = Uuid, Path, description = "Tweet ID")
+    ),
+    responses(
+        (status = 200, description = "Tweet bookmarked successfully", body = BookmarkResponse),
+        (status = 400, description = "Database error", body = AlohaError),
+        (status = 401, description = "User is not logged in", body = AlohaError),
+        (status = 404, description = "Tweet not found or not visible to the user")
+    )
+)]
+pub async fn insert_bookmark_route(
+    session: Session,
+    id: web::Path<(Uuid,)>,
+    pool: Data<PgPool>,
+) -> Result<HttpResponse, AlohaError> {
+    let user_id = get_session_user_id(&session)?;
+    let transaction = pool.begin().await.unwrap();
+    match insert_bookmark(transaction, &Bookmark::new(user_id, id.0)).await {
+        Ok(Some(result)) => Ok(HttpResponse::Ok().json(BookmarkResponse::from(result))),
+        Ok(None) => Ok(HttpResponse::NotFound().finish()),
+        Err(e) => Err(AlohaError::DatabaseError(e.to_string())),
+    }
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/tweets/{id}/bookmark",
+    params(
+        ("id" = Uuid, Path, description = "Tweet ID")
+    ),
+    responses(
+        (status = 200, description = "Bookmark removed successfully", body = BookmarkResponse),
+        (status = 400, description = "Database error", body = AlohaError),
+        (status = 401, description = "User is not logged in", body = AlohaError),
+        (status = 404, description = "Tweet is not bookmarked")
+    )
+)]
+pub async fn delete_bookmark_route(
+    session: Session,
+    id: web::Path<(Uuid,)>,
+    pool: Data<PgPool>,
+) -> Result<HttpResponse, AlohaError> {
+    let user_id = get_session_user_id(&session)?;
+    let transaction = pool.begin().await.unwrap();
+    match delete_bookmark(transaction, user_id, id.0).await {
+        Ok(Some(result)) => Ok(HttpResponse::Ok().json(BookmarkResponse::from(result))),
+        Ok(None) => Ok(HttpResponse::NotFound().finish()),
+        Err(e) => Err(AlohaError::DatabaseError(e.to_string())),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/bookmarks",
+    params(
+        ("page" = Option<i32>, Query, description = "Page number"),
+        ("size" = Option<i32>, Query, description = "Page size"),
+        ("user_id" = Option<Uuid>, Query, description = "Filter by tweet author ID")
+    ),
+    responses(
+        (status = 200, description = "Bookmarked tweets of the logged-in user", body = DtoResponse<Vec<TweetResponse>>),
+        (status = 400, description = "Database error", body = AlohaError),
+        (status = 401, description = "User is not logged in", body = AlohaError)
+    )
+)]
+pub async fn get_bookmarks_route(
+    session: Session,
+    query: QsQuery<DtoQuery<TweetFilterQuery>>,
+    pool: Data<PgPool>,
+) -> Result<HttpResponse, AlohaError> {
+    let user_id = get_session_user_id(&session)?;
+    let transaction = pool.begin().await.unwrap();
+    match get_bookmarked_tweets(transaction, user_id, query.into_inner()).await {
+        Ok(result) => match build_tweet_responses(&pool, result.data, Some(user_id)).await {
+            Ok(response) => {
+                Ok(HttpResponse::Ok().json(DtoResponse::new(response, result.pagination)))
+            }
+            Err(e) => Err(AlohaError::DatabaseError(e.to_string())),
+        },
+        Err(e) => Err(AlohaError::DatabaseError(e.to_string())),
+    }
+}
+
+/// Bookmarks are listed under their own scope; they are added and removed
+/// through `/api/tweets/{id}/bookmark`, registered by `tweet_routes`.
+pub fn bookmark_routes(cfg: &mut web::ServiceConfig) {
+    let config = get_configuration().unwrap();
+    cfg.service(
+        web::scope(format!("/{}", config.routes.bookmarks).as_str())
+            .route("", web::get().to(get_bookmarks_route)),
+    );
+}
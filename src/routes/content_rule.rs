@@ -0,0 +1,239 @@
+use crate::configuration::get_configuration;
+use crate::dto::query::{ContentRuleFilterQuery, DtoQuery};
+use crate::dto::response::DtoResponse;
+use crate::error::AlohaError;
+use crate::mappers::content_rule::{
+    delete_content_rule, get_content_rule_by_id, get_content_rules, insert_content_rule,
+    test_content_rules, update_content_rule,
+};
+use crate::models::content_rule::{
+    ContentRule, ContentRuleAction, ContentRuleEvaluationResponse, ContentRuleKind,
+    ContentRuleResponse,
+};
+use crate::routes::auth::require_admin;
+use actix_session::Session;
+use actix_web::web::{self, Data, Json};
+use actix_web::HttpResponse;
+use serde::Deserialize;
+use serde_qs::actix::QsQuery;
+use sqlx::PgPool;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+#[derive(Deserialize, Clone, ToSchema)]
+pub struct ContentRuleFormData {
+    kind: ContentRuleKind,
+    /// A word or phrase, a regular expression, or a domain, depending on
+    /// `kind`.
+    pattern: String,
+    action: ContentRuleAction,
+    /// Defaults to `true`.
+    #[serde(default)]
+    enabled: Option<bool>,
+}
+
+impl ContentRuleFormData {
+    fn to_content_rule(&self) -> Result<ContentRule, AlohaError> {
+        let rule = ContentRule {
+            enabled: self.enabled.unwrap_or(true),
+            ..ContentRule::new(self.kind, &self.pattern, self.action)
+        };
+        rule.validate()
+            .map_err(AlohaError::RequestParameterInvalid)?;
+        Ok(rule)
+    }
+}
+
+#[derive(Deserialize, Clone, ToSchema)]
+pub struct TestContentRulesFormData {
+    content: String,
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/content_rules",
+    request_body = ContentRuleFormData,
+    responses(
+        (status = 200, description = "Content rule created successfully", body = ContentRuleResponse),
+        (status = 400, description = "Invalid or duplicate pattern, or database error", body = AlohaError),
+        (status = 401, description = "User is not logged in", body = AlohaError),
+        (status = 403, description = "User is not an admin", body = AlohaError)
+    )
+)]
+pub async fn insert_content_rule_route(
+    session: Session,
+    body: Json<ContentRuleFormData>,
+    pool: Data<PgPool>,
+) -> Result<HttpResponse, AlohaError> {
+    require_admin(&session, &pool).await?;
+    let rule = body.to_content_rule()?;
+    let transaction = pool.begin().await.unwrap();
+    match insert_content_rule(transaction, &rule).await {
+        Ok(result) => Ok(HttpResponse::Ok().json(ContentRuleResponse::from(result))),
+        Err(e) => Err(AlohaError::DatabaseError(e.to_string())),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/content_rules",
+    params(
+        ("page" = Option<i32>, Query, description = "Page number"),
+        ("size" = Option<i32>, Query, description = "Page size"),
+        ("kind" = Option<ContentRuleKind>, Query, description = "Filter by rule kind"),
+        ("action" = Option<ContentRuleAction>, Query, description = "Filter by rule action"),
+        ("enabled" = Option<bool>, Query, description = "Filter by enabled flag")
+    ),
+    responses(
+        (status = 200, description = "Content rules with their hit statistics", body = DtoResponse<Vec<ContentRuleResponse>>),
+        (status = 400, description = "Database error", body = AlohaError),
+        (status = 401, description = "User is not logged in", body = AlohaError),
+        (status = 403, description = "User is not an admin", body = AlohaError)
+    )
+)]
+pub async fn get_content_rules_route(
+    session: Session,
+    query: QsQuery<DtoQuery<ContentRuleFilterQuery>>,
+    pool: Data<PgPool>,
+) -> Result<HttpResponse, AlohaError> {
+    require_admin(&session, &pool).await?;
+    let transaction = pool.begin().await.unwrap();
+    match get_content_rules(transaction, query.into_inner()).await {
+        Ok(result) => {
+            let data: Vec<ContentRuleResponse> = result
+                .data
+                .into_iter()
+                .map(ContentRuleResponse::from)
+                .collect();
+            Ok(HttpResponse::Ok().json(DtoResponse::new(data, result.pagination)))
+        }
+        Err(e) => Err(AlohaError::DatabaseError(e.to_string())),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/content_rules/{id}",
+    params(
+        ("id" = Uuid, Path, description = "Content rule ID")
+    ),
+    responses(
+        (status = 200, description = "Content rule with its hit statistics", body = ContentRuleResponse),
+        (status = 400, description = "Database error", body = AlohaError),
+        (status = 401, description = "User is not logged in", body = AlohaError),
+        (status = 403, description = "User is not an admin", body = AlohaError),
+        (status = 404, description = "Content rule not found")
+    )
+)]
+pub async fn get_content_rule_route(
+    session: Session,
+    id: web::Path<(Uuid,)>,
+    pool: Data<PgPool>,
+) -> Result<HttpResponse, AlohaError> {
+    require_admin(&session, &pool).await?;
+    let transaction = pool.begin().await.unwrap();
+    match get_content_rule_by_id(transaction, id.0).await {
+        Ok(Some(result)) => Ok(HttpResponse::Ok().json(ContentRuleResponse::from(result))),
+        Ok(None) => Ok(HttpResponse::NotFound().finish()),
+        Err(e) => Err(AlohaError::DatabaseError(e.to_string())),
+    }
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/content_rules/{id}",
+    params(
+        ("id" = Uuid, Path, description = "Content rule ID")
+    ),
+    request_body = ContentRuleFormData,
+    responses(
+        (status = 200, description = "Content rule updated successfully", body = ContentRuleResponse),
+        (status = 400, description = "Invalid or duplicate pattern, or database error", body = AlohaError),
+        (status = 401, description = "User is not logged in", body = AlohaError),
+        (status = 403, description = "User is not an admin", body = AlohaError),
+        (status = 404, description = "Content rule not found")
+    )
+)]
+pub async fn update_content_rule_route(
+    session: Session,
+    id: web::Path<(Uuid,)>,
+    body: Json<ContentRuleFormData>,
+    pool: Data<PgPool>,
+) -> Result<HttpResponse, AlohaError> {
+    require_admin(&session, &pool).await?;
+    let rule = ContentRule {
+        id: id.0,
+        ..body.to_content_rule()?
+    };
+    let transaction = pool.begin().await.unwrap();
+    match update_content_rule(transaction, &rule).await {
+        Ok(Some(result)) => Ok(HttpResponse::Ok().json(ContentRuleResponse::from(result))),
+        Ok(None) => Ok(HttpResponse::NotFound().finish()),
+        Err(e) => Err(AlohaError::DatabaseError(e.to_string())),
+    }
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/content_rules/{id}",
+    params(
+        ("id" = Uuid, Path, description = "Content rule ID")
+    ),
+    responses(
+        (status = 200, description = "Content rule deleted successfully", body = ContentRuleResponse),
+        (status = 400, description = "Database error", body = AlohaError),
+        (status = 401, description = "User is not logged in", body = AlohaError),
+        (status = 403, description = "User is not an admin", body = AlohaError),
+        (status = 404, description = "Content rule not found")
+    )
+)]
+pub async fn delete_content_rule_route(
+    session: Session,
+    id: web::Path<(Uuid,)>,
+    pool: Data<PgPool>,
+) -> Result<HttpResponse, AlohaError> {
+    require_admin(&session, &pool).await?;
+    let transaction = pool.begin().await.unwrap();
+    match delete_content_rule(transaction, id.0).await {
+        Ok(Some(result)) => Ok(HttpResponse::Ok().json(ContentRuleResponse::from(result))),
+        Ok(None) => Ok(HttpResponse::NotFound().finish()),
+        Err(e) => Err(AlohaError::DatabaseError(e.to_string())),
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/content_rules/test",
+    request_body = TestContentRulesFormData,
+    responses(
+        (status = 200, description = "What the enabled rules would do to the text; no hits are recorded", body = ContentRuleEvaluationResponse),
+        (status = 400, description = "Database error", body = AlohaError),
+        (status = 401, description = "User is not logged in", body = AlohaError),
+        (status = 403, description = "User is not an admin", body = AlohaError)
+    )
+)]
+pub async fn test_content_rules_route(
+    session: Session,
+    body: Json<TestContentRulesFormData>,
+    pool: Data<PgPool>,
+) -> Result<HttpResponse, AlohaError> {
+    require_admin(&session, &pool).await?;
+    let transaction = pool.begin().await.unwrap();
+    match test_content_rules(transaction, &body.content).await {
+        Ok(result) => Ok(HttpResponse::Ok().json(ContentRuleEvaluationResponse::from(result))),
+        Err(e) => Err(AlohaError::DatabaseError(e.to_string())),
+    }
+}
+
+pub fn content_rule_routes(cfg: &mut web::ServiceConfig) {
+    let config = get_configuration().unwrap();
+    cfg.service(
+        web::scope(format!("/{}", config.routes.content_rules).as_str())
+            .route("", web::post().to(insert_content_rule_route))
+            .route("", web::get().to(get_content_rules_route))
+            .route("/test", web::post().to(test_content_rules_route))
+            .route("/{id}", web::get().to(get_content_rule_route))
+            .route("/{id}", web::put().to(update_content_rule_route))
+            .route("/{id}", web::delete().to(delete_content_rule_route)),
+    );
+}
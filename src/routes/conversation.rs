@@ -0,0 +1,265 @@
+use crate::configuration::{get_configuration, PaginationSettings};
+use crate::dto::cursor::{CursorQuery, CursorResponse};
+use crate::error::AlohaError;
+use crate::mappers::conversation::{
+    delete_message, get_conversation_by_id, get_conversations, get_messages, insert_conversation,
+    insert_message, mark_conversation_read,
+};
+use crate::models::conversation::{
+    normalize_message_content, normalize_participant_ids, ConversationResponse, DirectMessage,
+    DirectMessageResponse,
+};
+use crate::routes::auth::get_session_user_id;
+use actix_session::Session;
+use actix_web::web::{self, Data, Json, Query};
+use actix_web::HttpResponse;
+use serde::Deserialize;
+use sqlx::PgPool;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+#[derive(Deserialize, Clone, ToSchema)]
+pub struct ConversationFormData {
+    /// The other participants; one for a one-to-one conversation.
+    participant_ids: Vec<Uuid>,
+}
+
+#[derive(Deserialize, Clone, ToSchema)]
+pub struct MessageFormData {
+    content: String,
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/conversations",
+    request_body = ConversationFormData,
+    responses(
+        (status = 200, description = "Conversation started, or the existing one-to-one conversation with that user", body = ConversationResponse),
+        (status = 400, description = "Invalid participants, a participant does not accept messages from the user, or database error", body = AlohaError),
+        (status = 401, description = "User is not logged in", body = AlohaError)
+    )
+)]
+pub async fn insert_conversation_route(
+    session: Session,
+    body: Json<ConversationFormData>,
+    pool: Data<PgPool>,
+) -> Result<HttpResponse, AlohaError> {
+    let user_id = get_session_user_id(&session)?;
+    let participant_ids = normalize_participant_ids(user_id, &body.participant_ids)
+        .map_err(AlohaError::RequestParameterInvalid)?;
+    let transaction = pool.begin().await.unwrap();
+    match insert_conversation(transaction, user_id, &participant_ids).await {
+        Ok(result) => Ok(HttpResponse::Ok().json(ConversationResponse::from(result))),
+        Err(e) => Err(AlohaError::DatabaseError(e.to_string())),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/conversations",
+    params(
+        ("cursor" = Option<String>, Query, description = "Opaque cursor returned as `next_cursor` by the previous page"),
+        ("size" = Option<i32>, Query, description = "Page size")
+    ),
+    responses(
+        (status = 200, description = "Conversations of the user, most recently active first", body = CursorResponse<Vec<ConversationResponse>>),
+        (status = 400, description = "Invalid cursor or database error", body = AlohaError),
+        (status = 401, description = "User is not logged in", body = AlohaError)
+    )
+)]
+pub async fn get_conversations_route(
+    session: Session,
+    query: Query<CursorQuery>,
+    pool: Data<PgPool>,
+    pagination: Data<PaginationSettings>,
+) -> Result<HttpResponse, AlohaError> {
+    let user_id = get_session_user_id(&session)?;
+    let query = query
+        .into_inner()
+        .capped(pagination.max_cursor_size as usize);
+    let transaction = pool.begin().await.unwrap();
+    match get_conversations(transaction, user_id, query).await {
+        Ok(result) => {
+            let data: Vec<ConversationResponse> = result
+                .data
+                .into_iter()
+                .map(ConversationResponse::from)
+                .collect();
+            Ok(HttpResponse::Ok().json(CursorResponse::new(data, result.next_cursor)))
+        }
+        Err(e) => Err(AlohaError::DatabaseError(e.to_string())),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/conversations/{id}",
+    params(
+        ("id" = Uuid, Path, description = "Conversation ID")
+    ),
+    responses(
+        (status = 200, description = "Conversation found", body = ConversationResponse),
+        (status = 400, description = "Database error", body = AlohaError),
+        (status = 401, description = "User is not logged in", body = AlohaError),
+        (status = 404, description = "Conversation not found or the user does not take part in it")
+    )
+)]
+pub async fn get_conversation_route(
+    session: Session,
+    id: web::Path<(Uuid,)>,
+    pool: Data<PgPool>,
+) -> Result<HttpResponse, AlohaError> {
+    let user_id = get_session_user_id(&session)?;
+    let transaction = pool.begin().await.unwrap();
+    match get_conversation_by_id(transaction, user_id, id.into_inner().0).await {
+        Ok(Some(result)) => Ok(HttpResponse::Ok().json(ConversationResponse::from(result))),
+        Ok(None) => Ok(HttpResponse::NotFound().finish()),
+        Err(e) => Err(AlohaError::DatabaseError(e.to_string())),
+    }
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/conversations/{id}/read",
+    params(
+        ("id" = Uuid, Path, description = "Conversation ID")
+    ),
+    responses(
+        (status = 200, description = "Every message so far marked read", body = ConversationResponse),
+        (status = 400, description = "Database error", body = AlohaError),
+        (status = 401, description = "User is not logged in", body = AlohaError),
+        (status = 404, description = "Conversation not found or the user does not take part in it")
+    )
+)]
+pub async fn mark_conversation_read_route(
+    session: Session,
+    id: web::Path<(Uuid,)>,
+    pool: Data<PgPool>,
+) -> Result<HttpResponse, AlohaError> {
+    let user_id = get_session_user_id(&session)?;
+    let transaction = pool.begin().await.unwrap();
+    match mark_conversation_read(transaction, user_id, id.into_inner().0).await {
+        Ok(Some(result)) => Ok(HttpResponse::Ok().json(ConversationResponse::from(result))),
+        Ok(None) => Ok(HttpResponse::NotFound().finish()),
+        Err(e) => Err(AlohaError::DatabaseError(e.to_string())),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/conversations/{id}/messages",
+    params(
+        ("id" = Uuid, Path, description = "Conversation ID"),
+        ("cursor" = Option<String>, Query, description = "Opaque cursor returned as `next_cursor` by the previous page"),
+        ("size" = Option<i32>, Query, description = "Page size")
+    ),
+    responses(
+        (status = 200, description = "Messages of the conversation, newest first", body = CursorResponse<Vec<DirectMessageResponse>>),
+        (status = 400, description = "Invalid cursor or database error", body = AlohaError),
+        (status = 401, description = "User is not logged in", body = AlohaError),
+        (status = 404, description = "Conversation not found or the user does not take part in it")
+    )
+)]
+pub async fn get_messages_route(
+    session: Session,
+    id: web::Path<(Uuid,)>,
+    query: Query<CursorQuery>,
+    pool: Data<PgPool>,
+    pagination: Data<PaginationSettings>,
+) -> Result<HttpResponse, AlohaError> {
+    let user_id = get_session_user_id(&session)?;
+    let query = query
+        .into_inner()
+        .capped(pagination.max_cursor_size as usize);
+    let transaction = pool.begin().await.unwrap();
+    match get_messages(transaction, user_id, id.into_inner().0, query).await {
+        Ok(Some(result)) => {
+            let data: Vec<DirectMessageResponse> = result
+                .data
+                .into_iter()
+                .map(DirectMessageResponse::from)
+                .collect();
+            Ok(HttpResponse::Ok().json(CursorResponse::new(data, result.next_cursor)))
+        }
+        Ok(None) => Ok(HttpResponse::NotFound().finish()),
+        Err(e) => Err(AlohaError::DatabaseError(e.to_string())),
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/conversations/{id}/messages",
+    params(
+        ("id" = Uuid, Path, description = "Conversation ID")
+    ),
+    request_body = MessageFormData,
+    responses(
+        (status = 200, description = "Message sent", body = DirectMessageResponse),
+        (status = 400, description = "Invalid content, blocked recipient or database error", body = AlohaError),
+        (status = 401, description = "User is not logged in", body = AlohaError),
+        (status = 404, description = "Conversation not found or the user does not take part in it")
+    )
+)]
+pub async fn insert_message_route(
+    session: Session,
+    id: web::Path<(Uuid,)>,
+    body: Json<MessageFormData>,
+    pool: Data<PgPool>,
+) -> Result<HttpResponse, AlohaError> {
+    let user_id = get_session_user_id(&session)?;
+    let content =
+        normalize_message_content(&body.content).map_err(AlohaError::RequestParameterInvalid)?;
+    let message = DirectMessage::new(id.into_inner().0, user_id, content);
+    let transaction = pool.begin().await.unwrap();
+    match insert_message(transaction, &message).await {
+        Ok(Some(result)) => Ok(HttpResponse::Ok().json(DirectMessageResponse::from(result))),
+        Ok(None) => Ok(HttpResponse::NotFound().finish()),
+        Err(e) => Err(AlohaError::DatabaseError(e.to_string())),
+    }
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/conversations/{id}/messages/{message_id}",
+    params(
+        ("id" = Uuid, Path, description = "Conversation ID"),
+        ("message_id" = Uuid, Path, description = "Message ID")
+    ),
+    responses(
+        (status = 200, description = "Message deleted", body = DirectMessageResponse),
+        (status = 400, description = "Database error", body = AlohaError),
+        (status = 401, description = "User is not logged in", body = AlohaError),
+        (status = 404, description = "Message not found or sent by another user")
+    )
+)]
+pub async fn delete_message_route(
+    session: Session,
+    path: web::Path<(Uuid, Uuid)>,
+    pool: Data<PgPool>,
+) -> Result<HttpResponse, AlohaError> {
+    let user_id = get_session_user_id(&session)?;
+    let (conversation_id, message_id) = path.into_inner();
+    let transaction = pool.begin().await.unwrap();
+    match delete_message(transaction, user_id, conversation_id, message_id).await {
+        Ok(Some(result)) => Ok(HttpResponse::Ok().json(DirectMessageResponse::from(result))),
+        Ok(None) => Ok(HttpResponse::NotFound().finish()),
+        Err(e) => Err(AlohaError::DatabaseError(e.to_string())),
+    }
+}
+
+pub fn conversation_routes(cfg: &mut web::ServiceConfig) {
+    let config = get_configuration().unwrap();
+    cfg.service(
+        web::scope(format!("/{}", config.routes.conversations).as_str())
+            .route("", web::post().to(insert_conversation_route))
+            .route("", web::get().to(get_conversations_route))
+            .route("/{id}", web::get().to(get_conversation_route))
+            .route("/{id}/read", web::put().to(mark_conversation_read_route))
+            .route("/{id}/messages", web::get().to(get_messages_route))
+            .route("/{id}/messages", web::post().to(insert_message_route))
+            .route(
+                "/{id}/messages/{message_id}",
+                web::delete().to(delete_message_route),
+            ),
+    );
+}
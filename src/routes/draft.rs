@@ -0,0 +1,314 @@
+use crate::configuration::{get_configuration, MediaSettings};
+use crate::dto::query::{DraftFilterQuery, DtoQuery};
+use crate::dto::response::DtoResponse;
+use crate::error::AlohaError;
+use crate::mappers::draft::{
+    delete_draft, get_draft_by_id, get_drafts_by_user_id, insert_draft, publish_draft, update_draft,
+};
+use crate::mappers::media::get_media_by_tweet_ids;
+use crate::models::draft::{TweetDraft, TweetDraftResponse, TweetStatus};
+use crate::models::media::MediaResponse;
+use crate::models::tweet::{TweetResponse, TweetVisibility};
+use crate::routes::auth::get_session_user_id;
+use crate::routes::media::build_media_responses;
+use crate::routes::tweet::written_tweet_response;
+use actix_session::Session;
+use actix_web::web::{self, Data, Json};
+use actix_web::HttpResponse;
+use serde::Deserialize;
+use serde_qs::actix::QsQuery;
+use sqlx::PgPool;
+use std::collections::HashMap;
+use time::OffsetDateTime;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// Converts drafts into responses with their attached media.
+async fn build_draft_responses(
+    pool: &PgPool,
+    drafts: Vec<TweetDraft>,
+) -> Result<Vec<TweetDraftResponse>, anyhow::Error> {
+    let draft_ids: Vec<Uuid> = drafts.iter().map(|d| d.id).collect();
+    let mut transaction = pool.begin().await?;
+    let mut media = get_media_by_tweet_ids(&mut transaction, &draft_ids).await?;
+    let media_responses: HashMap<Uuid, MediaResponse> = build_media_responses(
+        &mut transaction,
+        media.values().flatten().cloned().collect(),
+    )
+    .await?
+    .into_iter()
+    .map(|m| (m.id, m))
+    .collect();
+    transaction.commit().await?;
+
+    Ok(drafts
+        .into_iter()
+        .map(|draft| {
+            let draft_id = draft.id;
+            let mut response = TweetDraftResponse::from(draft);
+            response.media = media
+                .remove(&draft_id)
+                .unwrap_or_default()
+                .iter()
+                .filter_map(|m| media_responses.get(&m.id).cloned())
+                .collect();
+            response
+        })
+        .collect())
+}
+
+async fn build_draft_response(
+    pool: &PgPool,
+    draft: TweetDraft,
+) -> Result<TweetDraftResponse, anyhow::Error> {
+    let mut responses = build_draft_responses(pool, vec![draft]).await?;
+    Ok(responses.remove(0))
+}
+
+/// Rejects publication times that are not in the future.
+fn check_publish_at(publish_at: Option<OffsetDateTime>) -> Result<(), AlohaError> {
+    match publish_at {
+        Some(publish_at) if publish_at <= OffsetDateTime::now_utc() => Err(
+            AlohaError::RequestParameterInvalid("publish_at must be in the future.".to_string()),
+        ),
+        _ => Ok(()),
+    }
+}
+
+#[derive(Deserialize, Clone, ToSchema)]
+pub struct CreateDraftFormData {
+    content: String,
+    /// RFC 3339 timestamp at which the tweet is published. Without it the
+    /// tweet is kept as a draft.
+    #[serde(default, with = "time::serde::rfc3339::option")]
+    #[schema(value_type = Option<String>)]
+    publish_at: Option<OffsetDateTime>,
+    /// Ids returned by `POST /api/media`, in display order.
+    #[serde(default)]
+    media_ids: Vec<Uuid>,
+    /// Who may read the tweet once published; public when omitted.
+    #[serde(default)]
+    visibility: TweetVisibility,
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/drafts",
+    request_body = CreateDraftFormData,
+    responses(
+        (status = 200, description = "Draft or scheduled tweet created successfully", body = TweetDraftResponse),
+        (status = 400, description = "Invalid publication time or database error", body = AlohaError),
+        (status = 401, description = "User is not logged in", body = AlohaError)
+    )
+)]
+pub async fn insert_draft_route(
+    session: Session,
+    body: Json<CreateDraftFormData>,
+    media_settings: Data<MediaSettings>,
+    pool: Data<PgPool>,
+) -> Result<HttpResponse, AlohaError> {
+    let user_id = get_session_user_id(&session)?;
+    check_publish_at(body.publish_at)?;
+    if body.media_ids.len() > media_settings.max_attachments as usize {
+        return Err(AlohaError::RequestParameterInvalid(format!(
+            "A tweet can have at most {} media attachments.",
+            media_settings.max_attachments
+        )));
+    }
+
+    let transaction = pool.begin().await.unwrap();
+    let draft = TweetDraft {
+        visibility: body.visibility,
+        ..TweetDraft::new(body.content.clone(), body.publish_at, user_id)
+    };
+    match insert_draft(transaction, &draft, &body.media_ids).await {
+        Ok(result) => match build_draft_response(&pool, result).await {
+            Ok(response) => Ok(HttpResponse::Ok().json(response)),
+            Err(e) => Err(AlohaError::DatabaseError(e.to_string())),
+        },
+        Err(e) => Err(AlohaError::DatabaseError(e.to_string())),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/drafts",
+    params(
+        ("page" = Option<i32>, Query, description = "Page number"),
+        ("size" = Option<i32>, Query, description = "Page size"),
+        ("status" = Option<TweetStatus>, Query, description = "Only `draft` or only `scheduled` tweets")
+    ),
+    responses(
+        (status = 200, description = "Drafts and scheduled tweets of the logged-in user", body = DtoResponse<Vec<TweetDraftResponse>>),
+        (status = 400, description = "Database error", body = AlohaError),
+        (status = 401, description = "User is not logged in", body = AlohaError)
+    )
+)]
+pub async fn get_drafts_route(
+    session: Session,
+    query: QsQuery<DtoQuery<DraftFilterQuery>>,
+    pool: Data<PgPool>,
+) -> Result<HttpResponse, AlohaError> {
+    let user_id = get_session_user_id(&session)?;
+    let transaction = pool.begin().await.unwrap();
+    match get_drafts_by_user_id(transaction, user_id, query.into_inner()).await {
+        Ok(result) => match build_draft_responses(&pool, result.data).await {
+            Ok(response) => {
+                Ok(HttpResponse::Ok().json(DtoResponse::new(response, result.pagination)))
+            }
+            Err(e) => Err(AlohaError::DatabaseError(e.to_string())),
+        },
+        Err(e) => Err(AlohaError::DatabaseError(e.to_string())),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/drafts/{id}",
+    params(
+        ("id" = Uuid, Path, description = "Draft ID")
+    ),
+    responses(
+        (status = 200, description = "Draft retrieved successfully", body = TweetDraftResponse),
+        (status = 401, description = "User is not logged in", body = AlohaError),
+        (status = 404, description = "Draft not found"),
+        (status = 400, description = "Database error", body = AlohaError)
+    )
+)]
+pub async fn get_draft_route(
+    session: Session,
+    id: web::Path<(Uuid,)>,
+    pool: Data<PgPool>,
+) -> Result<HttpResponse, AlohaError> {
+    let user_id = get_session_user_id(&session)?;
+    let transaction = pool.begin().await.unwrap();
+    match get_draft_by_id(transaction, user_id, id.0).await {
+        Ok(Some(result)) => match build_draft_response(&pool, result).await {
+            Ok(response) => Ok(HttpResponse::Ok().json(response)),
+            Err(e) => Err(AlohaError::DatabaseError(e.to_string())),
+        },
+        Ok(None) => Ok(HttpResponse::NotFound().finish()),
+        Err(e) => Err(AlohaError::DatabaseError(e.to_string())),
+    }
+}
+
+#[derive(Deserialize, Clone, ToSchema)]
+pub struct PutDraftFormData {
+    content: String,
+    /// New publication time; omitting it turns a scheduled tweet back into a
+    /// draft.
+    #[serde(default, with = "time::serde::rfc3339::option")]
+    #[schema(value_type = Option<String>)]
+    publish_at: Option<OffsetDateTime>,
+    /// Who may read the tweet once published; public when omitted.
+    #[serde(default)]
+    visibility: TweetVisibility,
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/drafts/{id}",
+    params(
+        ("id" = Uuid, Path, description = "Draft ID")
+    ),
+    request_body = PutDraftFormData,
+    responses(
+        (status = 200, description = "Draft updated successfully", body = TweetDraftResponse),
+        (status = 400, description = "Invalid publication time or database error", body = AlohaError),
+        (status = 401, description = "User is not logged in", body = AlohaError),
+        (status = 404, description = "Draft not found or already published")
+    )
+)]
+pub async fn update_draft_route(
+    session: Session,
+    id: web::Path<(Uuid,)>,
+    body: Json<PutDraftFormData>,
+    pool: Data<PgPool>,
+) -> Result<HttpResponse, AlohaError> {
+    let user_id = get_session_user_id(&session)?;
+    check_publish_at(body.publish_at)?;
+
+    let transaction = pool.begin().await.unwrap();
+    let draft = TweetDraft {
+        id: id.0,
+        visibility: body.visibility,
+        ..TweetDraft::new(body.content.clone(), body.publish_at, user_id)
+    };
+    match update_draft(transaction, &draft).await {
+        Ok(Some(result)) => match build_draft_response(&pool, result).await {
+            Ok(response) => Ok(HttpResponse::Ok().json(response)),
+            Err(e) => Err(AlohaError::DatabaseError(e.to_string())),
+        },
+        Ok(None) => Ok(HttpResponse::NotFound().finish()),
+        Err(e) => Err(AlohaError::DatabaseError(e.to_string())),
+    }
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/drafts/{id}",
+    params(
+        ("id" = Uuid, Path, description = "Draft ID")
+    ),
+    responses(
+        (status = 200, description = "Draft deleted successfully", body = TweetDraftResponse),
+        (status = 401, description = "User is not logged in", body = AlohaError),
+        (status = 404, description = "Draft not found or already published"),
+        (status = 400, description = "Database error", body = AlohaError)
+    )
+)]
+pub async fn delete_draft_route(
+    session: Session,
+    id: web::Path<(Uuid,)>,
+    pool: Data<PgPool>,
+) -> Result<HttpResponse, AlohaError> {
+    let user_id = get_session_user_id(&session)?;
+    let transaction = pool.begin().await.unwrap();
+    match delete_draft(transaction, user_id, id.0).await {
+        Ok(Some(result)) => Ok(HttpResponse::Ok().json(TweetDraftResponse::from(result))),
+        Ok(None) => Ok(HttpResponse::NotFound().finish()),
+        Err(e) => Err(AlohaError::DatabaseError(e.to_string())),
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/drafts/{id}/publish",
+    params(
+        ("id" = Uuid, Path, description = "Draft ID")
+    ),
+    responses(
+        (status = 200, description = "Draft published immediately", body = TweetResponse),
+        (status = 202, description = "Draft held for review by the content rules", body = TweetResponse),
+        (status = 400, description = "Content rejected by the content rules", body = AlohaError),
+        (status = 401, description = "User is not logged in", body = AlohaError),
+        (status = 404, description = "Draft not found or already published"),
+        (status = 400, description = "Database error", body = AlohaError)
+    )
+)]
+pub async fn publish_draft_route(
+    session: Session,
+    id: web::Path<(Uuid,)>,
+    pool: Data<PgPool>,
+) -> Result<HttpResponse, AlohaError> {
+    let user_id = get_session_user_id(&session)?;
+    let transaction = pool.begin().await.unwrap();
+    match publish_draft(transaction, user_id, id.0).await {
+        Ok(Some(result)) => written_tweet_response(&pool, result, Some(user_id)).await,
+        Ok(None) => Ok(HttpResponse::NotFound().finish()),
+        Err(e) => Err(AlohaError::DatabaseError(e.to_string())),
+    }
+}
+
+pub fn draft_routes(cfg: &mut web::ServiceConfig) {
+    let config = get_configuration().unwrap();
+    cfg.service(
+        web::scope(format!("/{}", config.routes.drafts).as_str())
+            .route("", web::post().to(insert_draft_route))
+            .route("", web::get().to(get_drafts_route))
+            .route("/{id}", web::get().to(get_draft_route))
+            .route("/{id}", web::put().to(update_draft_route))
+            .route("/{id}", web::delete().to(delete_draft_route))
+            .route("/{id}/publish", web::post().to(publish_draft_route)),
+    );
+}
@@ -0,0 +1,254 @@
+use crate::configuration::{get_configuration, GatewaySettings};
+use crate::error::AlohaError;
+use crate::jobs::gateway::{Gateway, GatewayHandle};
+use crate::mappers::gateway::{announce_typing, check_user_channel_allowed};
+use crate::mappers::tweet::get_tweet_by_id;
+use crate::mappers::user::get_user_by_id;
+use crate::models::gateway::{ClientEvent, GatewayChannel, ServerEvent, TypingSignal};
+use crate::routes::auth::get_session_user_id;
+use actix_session::Session;
+use actix_web::rt::time::{timeout, Instant};
+use actix_web::web::{self, Data};
+use actix_web::{HttpRequest, HttpResponse};
+use actix_ws::{AggregatedMessage, CloseCode, CloseReason};
+use sqlx::PgPool;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc;
+
+/// Largest frame a client may send.
+const MAX_CLIENT_FRAME_BYTES: usize = 16 * 1024;
+/// Typing indicators a client repeats more often than this are ignored.
+const TYPING_INTERVAL: Duration = Duration::from_secs(3);
+
+/// State of one connection while reading what its client sends.
+struct GatewayClient {
+    pool: Data<PgPool>,
+    gateway: Arc<Gateway>,
+    handle: GatewayHandle,
+    username: String,
+    max_channels: usize,
+    last_typing: HashMap<GatewayChannel, Instant>,
+}
+
+impl GatewayClient {
+    async fn channel_allowed(&self, channel: &GatewayChannel) -> Result<bool, anyhow::Error> {
+        let user_id = self.handle.user_id;
+        match channel {
+            GatewayChannel::User(id) => {
+                let transaction = self.pool.begin().await?;
+                check_user_channel_allowed(transaction, user_id, *id).await
+            }
+            GatewayChannel::Hashtag(_) => Ok(true),
+            GatewayChannel::Conversation(tweet_id) => {
+                let transaction = self.pool.begin().await?;
+                Ok(get_tweet_by_id(transaction, *tweet_id, Some(user_id))
+                    .await?
+                    .is_some())
+            }
+        }
+    }
+
+    /// Handles a client frame and returns the reply, if any.
+    async fn handle_text(&mut self, text: &str) -> Option<ServerEvent> {
+        let event = match serde_json::from_str::<ClientEvent>(text) {
+            Ok(event) => event,
+            Err(e) => {
+                return Some(ServerEvent::Error {
+                    message: format!("Invalid event: {}", e),
+                })
+            }
+        };
+        let result = match event {
+            ClientEvent::Subscribe { channel } => self.subscribe(&channel).await.map(Some),
+            ClientEvent::Unsubscribe { channel } => self.unsubscribe(&channel).map(Some),
+            ClientEvent::Typing { channel } => self.typing(&channel).await.map(|_| None),
+        };
+        result.unwrap_or_else(|message| Some(ServerEvent::Error { message }))
+    }
+
+    async fn subscribe(&mut self, name: &str) -> Result<ServerEvent, String> {
+        let channel: GatewayChannel = name.parse()?;
+        match self.channel_allowed(&channel).await {
+            Ok(true) => {}
+            Ok(false) => return Err(format!("Channel `{}` is not available.", channel)),
+            Err(e) => return Err(e.to_string()),
+        }
+        let channel_name = channel.to_string();
+        self.gateway
+            .subscribe(self.handle.id, channel, self.max_channels)?;
+        Ok(ServerEvent::Subscribed {
+            channel: channel_name,
+        })
+    }
+
+    fn unsubscribe(&mut self, name: &str) -> Result<ServerEvent, String> {
+        let channel: GatewayChannel = name.parse()?;
+        self.gateway.unsubscribe(self.handle.id, &channel);
+        self.last_typing.remove(&channel);
+        Ok(ServerEvent::Unsubscribed {
+            channel: channel.to_string(),
+        })
+    }
+
+    async fn typing(&mut self, name: &str) -> Result<(), String> {
+        let channel: GatewayChannel = name.parse()?;
+        if !channel.accepts_typing() || !self.gateway.is_subscribed(self.handle.id, &channel) {
+            return Err(format!(
+                "Typing requires a subscription to conversation channel `{}`.",
+                channel
+            ));
+        }
+        let now = Instant::now();
+        if self
+            .last_typing
+            .get(&channel)
+            .is_some_and(|last| now.duration_since(*last) < TYPING_INTERVAL)
+        {
+            return Ok(());
+        }
+        let signal = TypingSignal {
+            channel: channel.to_string(),
+            user_id: self.handle.user_id,
+            username: self.username.clone(),
+        };
+        self.last_typing.insert(channel, now);
+        let transaction = self.pool.begin().await.map_err(|e| e.to_string())?;
+        announce_typing(transaction, &signal)
+            .await
+            .map_err(|e| e.to_string())
+    }
+}
+
+/// Reads client frames until the client leaves, then unregisters the
+/// connection.
+async fn read_client_frames(
+    mut client: GatewayClient,
+    mut session: actix_ws::Session,
+    messages: actix_ws::MessageStream,
+) {
+    let mut messages = messages
+        .max_frame_size(MAX_CLIENT_FRAME_BYTES)
+        .aggregate_continuations()
+        .max_continuation_size(MAX_CLIENT_FRAME_BYTES);
+    while let Some(message) = messages.recv().await {
+        match message {
+            Ok(AggregatedMessage::Text(text)) => {
+                if let Some(reply) = client.handle_text(&text).await {
+                    client.gateway.send(client.handle.id, &reply);
+                }
+            }
+            Ok(AggregatedMessage::Binary(_)) => {
+                let reply = ServerEvent::Error {
+                    message: "Binary frames are not supported.".to_string(),
+                };
+                client.gateway.send(client.handle.id, &reply);
+            }
+            Ok(AggregatedMessage::Ping(bytes)) => {
+                if session.pong(&bytes).await.is_err() {
+                    break;
+                }
+            }
+            Ok(AggregatedMessage::Pong(_)) => {}
+            Ok(AggregatedMessage::Close(reason)) => {
+                let _ = session.close(reason).await;
+                return;
+            }
+            Err(e) => {
+                tracing::log::warn!(
+                    "Closing gateway connection of user {}: {}",
+                    client.handle.user_id,
+                    e
+                );
+                let _ = session.close(Some(CloseCode::Protocol.into())).await;
+                return;
+            }
+        }
+    }
+}
+
+/// Writes queued frames to the socket, pinging it when idle. Closes it when
+/// the gateway dropped the connection for falling behind.
+async fn write_server_frames(
+    mut session: actix_ws::Session,
+    mut frames: mpsc::Receiver<Arc<str>>,
+    ping: Duration,
+) {
+    let mut next_ping = Instant::now() + ping;
+    loop {
+        let until_ping = next_ping.saturating_duration_since(Instant::now());
+        match timeout(until_ping, frames.recv()).await {
+            Err(_) => {
+                if session.ping(b"").await.is_err() {
+                    return;
+                }
+                next_ping = Instant::now() + ping;
+            }
+            Ok(Some(frame)) => {
+                if session.text(String::from(&*frame)).await.is_err() {
+                    return;
+                }
+            }
+            Ok(None) => {
+                let reason = CloseReason::from((CloseCode::Again, "Client is too slow"));
+                let _ = session.close(Some(reason)).await;
+                return;
+            }
+        }
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/gateway",
+    responses(
+        (status = 101, description = "WebSocket upgrade. Clients send ClientEvent and receive ServerEvent JSON text frames; channels are `user:<id>`, `hashtag:<tag>` and `conversation:<tweet id>`"),
+        (status = 400, description = "Not a WebSocket handshake or database error", body = AlohaError),
+        (status = 401, description = "User is not logged in", body = AlohaError)
+    )
+)]
+pub async fn gateway_route(
+    request: HttpRequest,
+    body: web::Payload,
+    session: Session,
+    pool: Data<PgPool>,
+    gateway: Data<Gateway>,
+    settings: Data<GatewaySettings>,
+) -> Result<HttpResponse, AlohaError> {
+    let user_id = get_session_user_id(&session)?;
+    let transaction = pool.begin().await.unwrap();
+    let user = match get_user_by_id(transaction, user_id).await {
+        Ok(Some(user)) => user,
+        Ok(None) => return Err(AlohaError::UserUnauthentication),
+        Err(e) => return Err(AlohaError::DatabaseError(e.to_string())),
+    };
+    let (response, ws_session, messages) = actix_ws::handle(&request, body)
+        .map_err(|e| AlohaError::RequestParameterInvalid(e.to_string()))?;
+
+    let gateway = gateway.into_inner();
+    let mut handle = gateway.connect(user_id);
+    let frames = handle
+        .take_receiver()
+        .expect("A new connection has its receiver");
+    let ping = Duration::from_secs(settings.ping_seconds.max(1) as u64);
+    actix_web::rt::spawn(write_server_frames(ws_session.clone(), frames, ping));
+    let client = GatewayClient {
+        pool,
+        gateway,
+        handle,
+        username: user.username,
+        max_channels: settings.max_channels as usize,
+        last_typing: HashMap::new(),
+    };
+    actix_web::rt::spawn(read_client_frames(client, ws_session, messages));
+    Ok(response)
+}
+
+pub fn gateway_routes(cfg: &mut web::ServiceConfig) {
+    let config = get_configuration().unwrap();
+    cfg.service(
+        web::scope(format!("/{}", config.routes.gateway).as_str())
+            .route("", web::get().to(gateway_route)),
+    );
+}
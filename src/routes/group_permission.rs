@@ -9,6 +9,8 @@ use crate::mappers::group_permission::{
     insert_group_permission,
 };
 use crate::models::group_permission::{GroupPermission, GroupPermissionResponse};
+use crate::routes::auth::require_admin;
+use actix_session::Session;
 use actix_web::web::{self, Data, Json, Path, Query};
 use actix_web::HttpResponse;
 use serde::Deserialize;
@@ -28,13 +30,17 @@ pub struct CreateGroupPermissionFormData {
     request_body = CreateGroupPermissionFormData,
     responses(
         (status = 200, description = "Group permission created successfully", body = GroupPermission),
-        (status = 400, description = "Database error", body = AlohaError)
+        (status = 400, description = "Database error", body = AlohaError),
+        (status = 401, description = "User is not logged in", body = AlohaError),
+        (status = 403, description = "User is not an admin", body = AlohaError)
     )
 )]
 pub async fn insert_group_permission_route(
+    session: Session,
     body: Json<CreateGroupPermissionFormData>,
     pool: Data<PgPool>,
 ) -> Result<HttpResponse, AlohaError> {
+    require_admin(&session, &pool).await?;
     let transaction = pool.begin().await.unwrap();
     let group_permission = GroupPermission::from(body.0);
     match insert_group_permission(transaction, &group_permission).await {
@@ -139,13 +145,17 @@ pub struct DeleteGroupPermissionFormData {
     request_body =DeleteGroupPermissionFormData,
     responses(
         (status = 200, description = "Group permission deleted successfully", body = GroupPermission),
-        (status = 400, description = "Database error", body = AlohaError)
+        (status = 400, description = "Database error", body = AlohaError),
+        (status = 401, description = "User is not logged in", body = AlohaError),
+        (status = 403, description = "User is not an admin", body = AlohaError)
     )
 )]
 pub async fn delete_group_permission_route(
+    session: Session,
     body: Json<DeleteGroupPermissionFormData>,
     pool: Data<PgPool>,
 ) -> Result<HttpResponse, AlohaError> {
+    require_admin(&session, &pool).await?;
     let transaction = pool.begin().await.unwrap();
     match delete_group_permission(transaction, body.group_id, body.permission_id).await {
         Ok(result) => Ok(HttpResponse::Ok().json(GroupPermissionResponse::from(result))),
@@ -161,13 +171,17 @@ pub async fn delete_group_permission_route(
     ),
     responses(
         (status = 200, description = "Group permissions deleted successfully", body = Vec<GroupPermission>),
-        (status = 400, description = "Database error", body = AlohaError)
+        (status = 400, description = "Database error", body = AlohaError),
+        (status = 401, description = "User is not logged in", body = AlohaError),
+        (status = 403, description = "User is not an admin", body = AlohaError)
     )
 )]
 pub async fn delete_group_permissions_by_group_id_route(
+    session: Session,
     group_id: Path<Uuid>,
     pool: Data<PgPool>,
 ) -> Result<HttpResponse, AlohaError> {
+    require_admin(&session, &pool).await?;
     let transaction = pool.begin().await.unwrap();
     match delete_group_permissions_by_group_id(transaction, *group_id).await {
         Ok(group_permissions) => {
@@ -189,13 +203,17 @@ pub async fn delete_group_permissions_by_group_id_route(
     ),
     responses(
         (status = 200, description = "Group permissions deleted successfully", body = Vec<GroupPermission>),
-        (status = 400, description = "Database error", body = AlohaError)
+        (status = 400, description = "Database error", body = AlohaError),
+        (status = 401, description = "User is not logged in", body = AlohaError),
+        (status = 403, description = "User is not an admin", body = AlohaError)
     )
 )]
 pub async fn delete_group_permissions_by_permission_id_route(
+    session: Session,
     permission_id: Path<Uuid>,
     pool: Data<PgPool>,
 ) -> Result<HttpResponse, AlohaError> {
+    require_admin(&session, &pool).await?;
     let transaction = pool.begin().await.unwrap();
     match delete_group_permissions_by_permission_id(transaction, *permission_id).await {
         Ok(group_permissions) => {
@@ -0,0 +1,75 @@
+use crate::configuration::{get_configuration, HashtagSettings};
+use crate::dto::query::{DtoQuery, TweetFilterQuery};
+use crate::dto::response::DtoResponse;
+use crate::error::AlohaError;
+use crate::mappers::hashtag::{get_trending_hashtags, get_tweets_by_hashtag};
+use crate::models::hashtag::TrendingHashtag;
+use crate::models::tweet::TweetResponse;
+use crate::routes::auth::get_session_viewer_id;
+use crate::routes::tweet::build_tweet_responses;
+use actix_session::Session;
+use actix_web::web::{self, Data, Path};
+use actix_web::HttpResponse;
+use serde_qs::actix::QsQuery;
+use sqlx::PgPool;
+
+#[utoipa::path(
+    get,
+    path = "/api/hashtags/{tag}/tweets",
+    params(
+        ("tag" = String, Path, description = "Hashtag, with or without the leading `#`"),
+        ("page" = Option<i32>, Query, description = "Page number"),
+        ("size" = Option<i32>, Query, description = "Page size"),
+        ("user_id" = Option<Uuid>, Query, description = "Filter by user ID")
+    ),
+    responses(
+        (status = 200, description = "Tweets retrieved successfully", body = DtoResponse<Vec<TweetResponse>>),
+        (status = 400, description = "Database error", body = AlohaError)
+    )
+)]
+pub async fn get_hashtag_tweets_route(
+    session: Session,
+    tag: Path<String>,
+    query: QsQuery<DtoQuery<TweetFilterQuery>>,
+    pool: Data<PgPool>,
+) -> Result<HttpResponse, AlohaError> {
+    let viewer_id = get_session_viewer_id(&session)?;
+    let transaction = pool.begin().await.unwrap();
+    match get_tweets_by_hashtag(transaction, &tag, query.into_inner(), viewer_id).await {
+        Ok(result) => match build_tweet_responses(&pool, result.data, viewer_id).await {
+            Ok(response) => {
+                Ok(HttpResponse::Ok().json(DtoResponse::new(response, result.pagination)))
+            }
+            Err(e) => Err(AlohaError::DatabaseError(e.to_string())),
+        },
+        Err(e) => Err(AlohaError::DatabaseError(e.to_string())),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/hashtags/trending",
+    responses(
+        (status = 200, description = "Trending hashtags retrieved successfully", body = DtoResponse<Vec<TrendingHashtag>>),
+        (status = 400, description = "Database error", body = AlohaError)
+    )
+)]
+pub async fn get_trending_hashtags_route(
+    settings: Data<HashtagSettings>,
+    pool: Data<PgPool>,
+) -> Result<HttpResponse, AlohaError> {
+    let transaction = pool.begin().await.unwrap();
+    match get_trending_hashtags(transaction, &settings).await {
+        Ok(result) => Ok(HttpResponse::Ok().json(DtoResponse::new(result, None))),
+        Err(e) => Err(AlohaError::DatabaseError(e.to_string())),
+    }
+}
+
+pub fn hashtag_routes(cfg: &mut web::ServiceConfig) {
+    let config = get_configuration().unwrap();
+    cfg.service(
+        web::scope(format!("/{}", config.routes.hashtags).as_str())
+            .route("/trending", web::get().to(get_trending_hashtags_route))
+            .route("/{tag}/tweets", web::get().to(get_hashtag_tweets_route)),
+    );
+}
@@ -0,0 +1,321 @@
+use crate::configuration::{get_configuration, MediaSettings};
+use crate::error::AlohaError;
+use crate::mappers::media::{
+    get_media_by_id, get_media_variant, get_variants_by_media_ids, insert_media,
+    insert_media_variant, mark_media_processed, media_content_exists,
+};
+use crate::models::media::{
+    render_variant, sniff_mime_type, strip_metadata, Media, MediaResponse, MediaVariant,
+    MediaVariantResponse,
+};
+use crate::routes::auth::get_session_user_id;
+use crate::storage::MediaStore;
+use actix_multipart::Multipart;
+use actix_session::Session;
+use actix_web::web::{self, Data};
+use actix_web::HttpResponse;
+use futures_util::StreamExt;
+use sqlx::{PgPool, Postgres, Transaction};
+use uuid::Uuid;
+
+/// Reads the `file` field of the upload, failing as soon as it grows past
+/// `max_bytes` instead of buffering the whole body first.
+async fn read_file_field(mut payload: Multipart, max_bytes: u64) -> Result<Vec<u8>, AlohaError> {
+    while let Some(field) = payload.next().await {
+        let mut field = field.map_err(|e| AlohaError::RequestParameterInvalid(e.to_string()))?;
+        if field.name() != Some("file") {
+            continue;
+        }
+        let mut bytes = Vec::new();
+        while let Some(chunk) = field.next().await {
+            let chunk = chunk.map_err(|e| AlohaError::RequestParameterInvalid(e.to_string()))?;
+            if (bytes.len() + chunk.len()) as u64 > max_bytes {
+                return Err(AlohaError::RequestParameterInvalid(format!(
+                    "Media must not be larger than {} bytes.",
+                    max_bytes
+                )));
+            }
+            bytes.extend_from_slice(&chunk);
+        }
+        return Ok(bytes);
+    }
+    Err(AlohaError::RequestParameterInvalid(String::from(
+        "Multipart field `file` is missing.",
+    )))
+}
+
+/// Converts media into responses carrying their variants.
+pub async fn build_media_responses(
+    transaction: &mut Transaction<'_, Postgres>,
+    media: Vec<Media>,
+) -> Result<Vec<MediaResponse>, anyhow::Error> {
+    let media_ids: Vec<Uuid> = media.iter().map(|m| m.id).collect();
+    let mut variants = get_variants_by_media_ids(transaction, &media_ids).await?;
+
+    Ok(media
+        .into_iter()
+        .map(|media| {
+            let media_id = media.id;
+            let mut response = MediaResponse::from(media);
+            response.variants = variants
+                .remove(&media_id)
+                .unwrap_or_default()
+                .into_iter()
+                .map(MediaVariantResponse::from)
+                .collect();
+            response
+        })
+        .collect())
+}
+
+/// Post-processes an uploaded image: strips EXIF metadata from the stored
+/// original, marks the media processed and generates every configured
+/// variant. Runs in the background after `POST /api/media` has answered.
+///
+/// The stripped copy replaces the original under the same key, so
+/// `media.sha256` keeps describing the upload rather than the stored bytes,
+/// and later uploads of the same original share the stripped copy.
+pub async fn process_uploaded_media(
+    pool: &PgPool,
+    store: &dyn MediaStore,
+    settings: &MediaSettings,
+    media: &Media,
+) -> Result<Vec<MediaVariant>, anyhow::Error> {
+    if !media.mime_type.starts_with("image/") {
+        let transaction = pool.begin().await?;
+        mark_media_processed(transaction, media.id).await?;
+        return Ok(Vec::new());
+    }
+    let original = store
+        .get(&media.storage_key)
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("Media content {} is missing", media.storage_key))?;
+
+    let mime_type = media.mime_type.clone();
+    let (original, stripped) = web::block(move || {
+        let stripped = strip_metadata(&original, &mime_type);
+        (original, stripped)
+    })
+    .await?;
+    if stripped != original {
+        store
+            .put(&media.storage_key, &media.mime_type, stripped.clone())
+            .await?;
+    }
+    let transaction = pool.begin().await?;
+    mark_media_processed(transaction, media.id).await?;
+
+    let mut variants = Vec::new();
+    for variant_settings in &settings.variants {
+        let source = stripped.clone();
+        let mime_type = media.mime_type.clone();
+        let (max_width, max_height) = (variant_settings.max_width, variant_settings.max_height);
+        let rendered =
+            web::block(move || render_variant(&source, &mime_type, max_width, max_height))
+                .await??;
+
+        let storage_key = format!("{}-{}", media.storage_key, variant_settings.name);
+        let size_bytes = rendered.bytes.len() as i64;
+        store
+            .put(&storage_key, rendered.mime_type, rendered.bytes)
+            .await?;
+
+        let variant = MediaVariant {
+            media_id: media.id,
+            name: variant_settings.name.clone(),
+            storage_key,
+            mime_type: rendered.mime_type.to_string(),
+            width: rendered.width as i32,
+            height: rendered.height as i32,
+            size_bytes,
+            created_at: None,
+        };
+        let transaction = pool.begin().await?;
+        variants.push(insert_media_variant(transaction, &variant).await?);
+    }
+    Ok(variants)
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/media",
+    request_body(content = String, content_type = "multipart/form-data", description = "Multipart form with a `file` field"),
+    responses(
+        (status = 200, description = "Media uploaded successfully", body = MediaResponse),
+        (status = 400, description = "Unsupported, oversized or missing file", body = AlohaError),
+        (status = 401, description = "User is not logged in", body = AlohaError)
+    )
+)]
+pub async fn upload_media_route(
+    session: Session,
+    payload: Multipart,
+    settings: Data<MediaSettings>,
+    store: Data<dyn MediaStore>,
+    pool: Data<PgPool>,
+) -> Result<HttpResponse, AlohaError> {
+    let user_id = get_session_user_id(&session)?;
+    let bytes = read_file_field(payload, settings.max_upload_bytes).await?;
+    let mime_type = sniff_mime_type(&bytes).ok_or_else(|| {
+        AlohaError::RequestParameterInvalid(String::from(
+            "Only JPEG, PNG, GIF, WebP and MP4 files are supported.",
+        ))
+    })?;
+    let media = Media::new(user_id, mime_type, &bytes);
+
+    let transaction = pool.begin().await.unwrap();
+    let stored = match media_content_exists(transaction, &media.sha256).await {
+        Ok(stored) => stored,
+        Err(e) => return Err(AlohaError::DatabaseError(e.to_string())),
+    };
+    if !stored {
+        if let Err(e) = store.put(&media.storage_key, mime_type, bytes).await {
+            return Err(AlohaError::MediaStorageError(e.to_string()));
+        }
+    }
+
+    let transaction = pool.begin().await.unwrap();
+    let result = match insert_media(transaction, &media).await {
+        Ok(result) => result,
+        Err(e) => return Err(AlohaError::DatabaseError(e.to_string())),
+    };
+
+    // A repeated upload returns the existing row, which is already processed.
+    if result.id == media.id {
+        let (pool, store, settings) = (pool.clone(), store.clone(), settings.clone());
+        let media = result.clone();
+        actix_web::rt::spawn(async move {
+            if let Err(e) = process_uploaded_media(&pool, store.get_ref(), &settings, &media).await
+            {
+                tracing::log::error!("Failed to process media {}: {:?}", media.id, e);
+            }
+        });
+    }
+
+    let mut transaction = pool.begin().await.unwrap();
+    match build_media_responses(&mut transaction, vec![result]).await {
+        Ok(mut response) => Ok(HttpResponse::Ok().json(response.remove(0))),
+        Err(e) => Err(AlohaError::DatabaseError(e.to_string())),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/media/{id}",
+    params(
+        ("id" = Uuid, Path, description = "Media ID")
+    ),
+    responses(
+        (status = 200, description = "Media retrieved successfully", body = MediaResponse),
+        (status = 400, description = "Media not found", body = AlohaError)
+    )
+)]
+pub async fn get_media_route(
+    id: web::Path<(Uuid,)>,
+    pool: Data<PgPool>,
+) -> Result<HttpResponse, AlohaError> {
+    let transaction = pool.begin().await.unwrap();
+    match get_media_by_id(transaction, id.0).await {
+        Ok(Some(result)) => {
+            let mut transaction = pool.begin().await.unwrap();
+            match build_media_responses(&mut transaction, vec![result]).await {
+                Ok(mut response) => Ok(HttpResponse::Ok().json(response.remove(0))),
+                Err(e) => Err(AlohaError::DatabaseError(e.to_string())),
+            }
+        }
+        Ok(None) => Err(AlohaError::DatabaseError("Media not found".to_string())),
+        Err(e) => Err(AlohaError::DatabaseError(e.to_string())),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/media/{id}/content",
+    params(
+        ("id" = Uuid, Path, description = "Media ID")
+    ),
+    responses(
+        (status = 200, description = "Raw media bytes with their sniffed content type, only cacheable once processed"),
+        (status = 400, description = "Media not found", body = AlohaError)
+    )
+)]
+pub async fn get_media_content_route(
+    id: web::Path<(Uuid,)>,
+    store: Data<dyn MediaStore>,
+    pool: Data<PgPool>,
+) -> Result<HttpResponse, AlohaError> {
+    let transaction = pool.begin().await.unwrap();
+    let media = match get_media_by_id(transaction, id.0).await {
+        Ok(Some(media)) => media,
+        Ok(None) => return Err(AlohaError::DatabaseError("Media not found".to_string())),
+        Err(e) => return Err(AlohaError::DatabaseError(e.to_string())),
+    };
+    // The original may still carry EXIF metadata until it has been processed.
+    let cache_control = match media.processed_at {
+        Some(_) => "public, max-age=31536000, immutable",
+        None => "no-store",
+    };
+    match store.get(&media.storage_key).await {
+        Ok(Some(bytes)) => Ok(HttpResponse::Ok()
+            .content_type(media.mime_type)
+            .insert_header(("Cache-Control", cache_control))
+            .body(bytes)),
+        Ok(None) => Err(AlohaError::MediaStorageError(
+            "Media content is missing".to_string(),
+        )),
+        Err(e) => Err(AlohaError::MediaStorageError(e.to_string())),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/media/{id}/variants/{name}",
+    params(
+        ("id" = Uuid, Path, description = "Media ID"),
+        ("name" = String, Path, description = "Variant name, e.g. `thumbnail`")
+    ),
+    responses(
+        (status = 200, description = "Raw variant bytes"),
+        (status = 400, description = "Variant not found", body = AlohaError)
+    )
+)]
+pub async fn get_media_variant_content_route(
+    path: web::Path<(Uuid, String)>,
+    store: Data<dyn MediaStore>,
+    pool: Data<PgPool>,
+) -> Result<HttpResponse, AlohaError> {
+    let (media_id, name) = path.into_inner();
+    let transaction = pool.begin().await.unwrap();
+    let variant = match get_media_variant(transaction, media_id, &name).await {
+        Ok(Some(variant)) => variant,
+        Ok(None) => {
+            return Err(AlohaError::DatabaseError(
+                "Media variant not found".to_string(),
+            ))
+        }
+        Err(e) => return Err(AlohaError::DatabaseError(e.to_string())),
+    };
+    match store.get(&variant.storage_key).await {
+        Ok(Some(bytes)) => Ok(HttpResponse::Ok()
+            .content_type(variant.mime_type)
+            .insert_header(("Cache-Control", "public, max-age=31536000, immutable"))
+            .body(bytes)),
+        Ok(None) => Err(AlohaError::MediaStorageError(
+            "Media variant content is missing".to_string(),
+        )),
+        Err(e) => Err(AlohaError::MediaStorageError(e.to_string())),
+    }
+}
+
+pub fn media_routes(cfg: &mut web::ServiceConfig) {
+    let config = get_configuration().unwrap();
+    cfg.service(
+        web::scope(format!("/{}", config.routes.media).as_str())
+            .route("", web::post().to(upload_media_route))
+            .route("/{id}", web::get().to(get_media_route))
+            .route("/{id}/content", web::get().to(get_media_content_route))
+            .route(
+                "/{id}/variants/{name}",
+                web::get().to(get_media_variant_content_route),
+            ),
+    );
+}
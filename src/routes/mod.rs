@@ -1,21 +1,57 @@
 use actix_web::web;
 use auth::auth_routes;
+use block::block_routes;
+use bookmark::bookmark_routes;
+use content_rule::content_rule_routes;
+use conversation::conversation_routes;
+use draft::draft_routes;
+use gateway::gateway_routes;
 use group_permission::group_permissions_routes;
+use hashtag::hashtag_routes;
 use health_check::health_check;
+use media::media_routes;
+use moderation::moderation_routes;
+use mute::mute_routes;
+use notification::notification_routes;
 use permission::permission_routes;
+use profile::profile_routes;
+use report::report_routes;
+use search::search_routes;
 use serde::Deserialize;
+use stream::stream_routes;
+use subscription::subscription_routes;
+use timeline::timeline_routes;
 use tweet::tweet_routes;
 use user::user_routes;
 use user_group::user_group_routes;
+use user_list::user_list_routes;
 use user_permission::user_permissions_routes;
 
 pub mod auth;
+pub mod block;
+pub mod bookmark;
+pub mod content_rule;
+pub mod conversation;
+pub mod draft;
+pub mod gateway;
 pub mod group_permission;
+pub mod hashtag;
 pub mod health_check;
+pub mod media;
+pub mod moderation;
+pub mod mute;
+pub mod notification;
 pub mod permission;
+pub mod profile;
+pub mod report;
+pub mod search;
+pub mod stream;
+pub mod subscription;
+pub mod timeline;
 pub mod tweet;
 pub mod user;
 pub mod user_group;
+pub mod user_list;
 pub mod user_permission;
 
 #[derive(Clone, Debug, Deserialize)]
@@ -27,6 +63,24 @@ pub struct Routes {
     pub group_permissions: String,
     pub user_permissions: String,
     pub auth: String,
+    pub subscriptions: String,
+    pub timeline: String,
+    pub hashtags: String,
+    pub search: String,
+    pub media: String,
+    pub drafts: String,
+    pub bookmarks: String,
+    pub lists: String,
+    pub blocks: String,
+    pub mutes: String,
+    pub reports: String,
+    pub moderation: String,
+    pub content_rules: String,
+    pub profile: String,
+    pub notifications: String,
+    pub stream: String,
+    pub gateway: String,
+    pub conversations: String,
 }
 pub fn api_routes(cfg: &mut web::ServiceConfig) {
     cfg.service(
@@ -38,6 +92,24 @@ pub fn api_routes(cfg: &mut web::ServiceConfig) {
             .configure(user_permissions_routes)
             .configure(tweet_routes)
             .configure(auth_routes)
+            .configure(subscription_routes)
+            .configure(timeline_routes)
+            .configure(hashtag_routes)
+            .configure(search_routes)
+            .configure(media_routes)
+            .configure(draft_routes)
+            .configure(bookmark_routes)
+            .configure(user_list_routes)
+            .configure(block_routes)
+            .configure(mute_routes)
+            .configure(report_routes)
+            .configure(moderation_routes)
+            .configure(content_rule_routes)
+            .configure(profile_routes)
+            .configure(notification_routes)
+            .configure(stream_routes)
+            .configure(gateway_routes)
+            .configure(conversation_routes)
             .route("/health", web::get().to(health_check)),
     );
 }
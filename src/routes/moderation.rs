@@ -0,0 +1,251 @@
+use crate::configuration::get_configuration;
+use crate::dto::query::{DtoQuery, ModerationActionFilterQuery, TweetFilterQuery};
+use crate::dto::response::DtoResponse;
+use crate::error::AlohaError;
+use crate::mappers::moderation::{
+    get_held_tweets, get_moderation_actions, hide_tweet, release_tweet, suspend_user,
+};
+use crate::models::moderation::{ModerationAction, ModerationActionKind, ModerationActionResponse};
+use crate::models::tweet::TweetResponse;
+use crate::routes::auth::{
+    require_permission, HIDE_TWEETS_PERMISSION, MODERATE_REPORTS_PERMISSION,
+    SUSPEND_USERS_PERMISSION,
+};
+use crate::routes::tweet::build_tweet_responses;
+use actix_session::Session;
+use actix_web::web::{self, Data, Json};
+use actix_web::HttpResponse;
+use serde::Deserialize;
+use serde_qs::actix::QsQuery;
+use sqlx::PgPool;
+use time::OffsetDateTime;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+#[derive(Deserialize, Clone, ToSchema)]
+pub struct HideTweetFormData {
+    /// Open report answered by this action; it is marked as actioned.
+    #[serde(default)]
+    report_id: Option<Uuid>,
+    #[serde(default)]
+    note: Option<String>,
+}
+
+#[derive(Deserialize, Clone, ToSchema)]
+pub struct ReleaseTweetFormData {
+    /// Open report answered by this action; it is marked as actioned.
+    #[serde(default)]
+    report_id: Option<Uuid>,
+    #[serde(default)]
+    note: Option<String>,
+}
+
+#[derive(Deserialize, Clone, ToSchema)]
+pub struct SuspendUserFormData {
+    /// RFC 3339 timestamp at which the suspension ends.
+    #[serde(with = "time::serde::rfc3339")]
+    #[schema(value_type = String)]
+    until: OffsetDateTime,
+    /// Open report answered by this action; it is marked as actioned.
+    #[serde(default)]
+    report_id: Option<Uuid>,
+    #[serde(default)]
+    note: Option<String>,
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/moderation/tweets/{id}/hide",
+    params(
+        ("id" = Uuid, Path, description = "Tweet ID")
+    ),
+    request_body = HideTweetFormData,
+    responses(
+        (status = 200, description = "Tweet hidden; the logged action", body = ModerationActionResponse),
+        (status = 400, description = "Unknown or resolved report, or database error", body = AlohaError),
+        (status = 401, description = "User is not logged in", body = AlohaError),
+        (status = 403, description = "User may not hide tweets", body = AlohaError),
+        (status = 404, description = "Tweet not found or already hidden")
+    )
+)]
+pub async fn hide_tweet_route(
+    session: Session,
+    id: web::Path<(Uuid,)>,
+    body: Json<HideTweetFormData>,
+    pool: Data<PgPool>,
+) -> Result<HttpResponse, AlohaError> {
+    let moderator_id = require_permission(&session, &pool, HIDE_TWEETS_PERMISSION).await?;
+    let action = ModerationAction {
+        tweet_id: Some(id.0),
+        report_id: body.report_id,
+        note: body.note.clone(),
+        ..ModerationAction::new(moderator_id, ModerationActionKind::HideTweet)
+    };
+    let transaction = pool.begin().await.unwrap();
+    match hide_tweet(transaction, &action).await {
+        Ok(Some(result)) => Ok(HttpResponse::Ok().json(ModerationActionResponse::from(result))),
+        Ok(None) => Ok(HttpResponse::NotFound().finish()),
+        Err(e) => Err(AlohaError::DatabaseError(e.to_string())),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/moderation/held-tweets",
+    params(
+        ("page" = Option<i32>, Query, description = "Page number"),
+        ("size" = Option<i32>, Query, description = "Page size"),
+        ("user_id" = Option<Uuid>, Query, description = "Filter by tweet author ID")
+    ),
+    responses(
+        (status = 200, description = "Tweets held by the content rules, oldest first", body = DtoResponse<Vec<TweetResponse>>),
+        (status = 400, description = "Database error", body = AlohaError),
+        (status = 401, description = "User is not logged in", body = AlohaError),
+        (status = 403, description = "User is not a moderator", body = AlohaError)
+    )
+)]
+pub async fn get_held_tweets_route(
+    session: Session,
+    query: QsQuery<DtoQuery<TweetFilterQuery>>,
+    pool: Data<PgPool>,
+) -> Result<HttpResponse, AlohaError> {
+    let moderator_id = require_permission(&session, &pool, MODERATE_REPORTS_PERMISSION).await?;
+    let transaction = pool.begin().await.unwrap();
+    match get_held_tweets(transaction, query.into_inner()).await {
+        Ok(result) => match build_tweet_responses(&pool, result.data, Some(moderator_id)).await {
+            Ok(response) => {
+                Ok(HttpResponse::Ok().json(DtoResponse::new(response, result.pagination)))
+            }
+            Err(e) => Err(AlohaError::DatabaseError(e.to_string())),
+        },
+        Err(e) => Err(AlohaError::DatabaseError(e.to_string())),
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/moderation/tweets/{id}/release",
+    params(
+        ("id" = Uuid, Path, description = "Tweet ID")
+    ),
+    request_body = ReleaseTweetFormData,
+    responses(
+        (status = 200, description = "Held tweet published; the logged action", body = ModerationActionResponse),
+        (status = 400, description = "Unknown or resolved report, or database error", body = AlohaError),
+        (status = 401, description = "User is not logged in", body = AlohaError),
+        (status = 403, description = "User may not moderate tweets", body = AlohaError),
+        (status = 404, description = "Tweet not found or not held")
+    )
+)]
+pub async fn release_tweet_route(
+    session: Session,
+    id: web::Path<(Uuid,)>,
+    body: Json<ReleaseTweetFormData>,
+    pool: Data<PgPool>,
+) -> Result<HttpResponse, AlohaError> {
+    let moderator_id = require_permission(&session, &pool, HIDE_TWEETS_PERMISSION).await?;
+    let action = ModerationAction {
+        tweet_id: Some(id.0),
+        report_id: body.report_id,
+        note: body.note.clone(),
+        ..ModerationAction::new(moderator_id, ModerationActionKind::ReleaseTweet)
+    };
+    let transaction = pool.begin().await.unwrap();
+    match release_tweet(transaction, &action).await {
+        Ok(Some(result)) => Ok(HttpResponse::Ok().json(ModerationActionResponse::from(result))),
+        Ok(None) => Ok(HttpResponse::NotFound().finish()),
+        Err(e) => Err(AlohaError::DatabaseError(e.to_string())),
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/moderation/users/{id}/suspend",
+    params(
+        ("id" = Uuid, Path, description = "User ID")
+    ),
+    request_body = SuspendUserFormData,
+    responses(
+        (status = 200, description = "User suspended; the logged action", body = ModerationActionResponse),
+        (status = 400, description = "Invalid end date, unknown or resolved report, or database error", body = AlohaError),
+        (status = 401, description = "User is not logged in", body = AlohaError),
+        (status = 403, description = "User may not suspend users", body = AlohaError),
+        (status = 404, description = "User not found")
+    )
+)]
+pub async fn suspend_user_route(
+    session: Session,
+    id: web::Path<(Uuid,)>,
+    body: Json<SuspendUserFormData>,
+    pool: Data<PgPool>,
+) -> Result<HttpResponse, AlohaError> {
+    let moderator_id = require_permission(&session, &pool, SUSPEND_USERS_PERMISSION).await?;
+    if id.0 == moderator_id {
+        return Err(AlohaError::RequestParameterInvalid(
+            "You cannot suspend yourself.".to_string(),
+        ));
+    }
+    if body.until <= OffsetDateTime::now_utc() {
+        return Err(AlohaError::RequestParameterInvalid(
+            "until must be in the future.".to_string(),
+        ));
+    }
+    let action = ModerationAction {
+        user_id: Some(id.0),
+        report_id: body.report_id,
+        note: body.note.clone(),
+        ..ModerationAction::new(moderator_id, ModerationActionKind::SuspendUser)
+    };
+    let transaction = pool.begin().await.unwrap();
+    match suspend_user(transaction, &action, body.until).await {
+        Ok(Some(result)) => Ok(HttpResponse::Ok().json(ModerationActionResponse::from(result))),
+        Ok(None) => Ok(HttpResponse::NotFound().finish()),
+        Err(e) => Err(AlohaError::DatabaseError(e.to_string())),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/moderation/actions",
+    params(
+        ("page" = Option<i32>, Query, description = "Page number"),
+        ("size" = Option<i32>, Query, description = "Page size")
+    ),
+    responses(
+        (status = 200, description = "Moderation log, most recent first", body = DtoResponse<Vec<ModerationActionResponse>>),
+        (status = 400, description = "Database error", body = AlohaError),
+        (status = 401, description = "User is not logged in", body = AlohaError),
+        (status = 403, description = "User is not a moderator", body = AlohaError)
+    )
+)]
+pub async fn get_moderation_actions_route(
+    session: Session,
+    query: QsQuery<DtoQuery<ModerationActionFilterQuery>>,
+    pool: Data<PgPool>,
+) -> Result<HttpResponse, AlohaError> {
+    require_permission(&session, &pool, MODERATE_REPORTS_PERMISSION).await?;
+    let transaction = pool.begin().await.unwrap();
+    match get_moderation_actions(transaction, query.into_inner()).await {
+        Ok(result) => {
+            let data: Vec<ModerationActionResponse> = result
+                .data
+                .into_iter()
+                .map(ModerationActionResponse::from)
+                .collect();
+            Ok(HttpResponse::Ok().json(DtoResponse::new(data, result.pagination)))
+        }
+        Err(e) => Err(AlohaError::DatabaseError(e.to_string())),
+    }
+}
+
+pub fn moderation_routes(cfg: &mut web::ServiceConfig) {
+    let config = get_configuration().unwrap();
+    cfg.service(
+        web::scope(format!("/{}", config.routes.moderation).as_str())
+            .route("/tweets/{id}/hide", web::post().to(hide_tweet_route))
+            .route("/tweets/{id}/release", web::post().to(release_tweet_route))
+            .route("/held-tweets", web::get().to(get_held_tweets_route))
+            .route("/users/{id}/suspend", web::post().to(suspend_user_route))
+            .route("/actions", web::get().to(get_moderation_actions_route)),
+    );
+}
@@ -0,0 +1,110 @@
+use crate::configuration::get_configuration;
+use crate::dto::query::{DtoQuery, MuteFilterQuery};
+use crate::dto::response::DtoResponse;
+use crate::error::AlohaError;
+use crate::mappers::mute::{delete_mute, get_muted_users, insert_mute};
+use crate::models::mute::{Mute, MuteResponse};
+use crate::models::user::UserResponse;
+use crate::routes::auth::get_session_user_id;
+use actix_session::Session;
+use actix_web::web::{self, Data};
+use actix_web::HttpResponse;
+use serde_qs::actix::QsQuery;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+#[utoipa::path(
+    put,
+    path = "/api/users/{id}/mute",
+    params(
+        ("id" = Uuid, Path, description = "ID of the user to mute")
+    ),
+    responses(
+        (status = 200, description = "User muted successfully", body = MuteResponse),
+        (status = 400, description = "Unknown user, the logged-in user or database error", body = AlohaError),
+        (status = 401, description = "User is not logged in", body = AlohaError)
+    )
+)]
+pub async fn insert_mute_route(
+    session: Session,
+    id: web::Path<(Uuid,)>,
+    pool: Data<PgPool>,
+) -> Result<HttpResponse, AlohaError> {
+    let user_id = get_session_user_id(&session)?;
+    if id.0 == user_id {
+        return Err(AlohaError::RequestParameterInvalid(
+            "You cannot mute yourself.".to_string(),
+        ));
+    }
+    let transaction = pool.begin().await.unwrap();
+    match insert_mute(transaction, &Mute::new(user_id, id.0)).await {
+        Ok(result) => Ok(HttpResponse::Ok().json(MuteResponse::from(result))),
+        Err(e) => Err(AlohaError::DatabaseError(e.to_string())),
+    }
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/users/{id}/mute",
+    params(
+        ("id" = Uuid, Path, description = "ID of the user to unmute")
+    ),
+    responses(
+        (status = 200, description = "User unmuted successfully", body = MuteResponse),
+        (status = 400, description = "Database error", body = AlohaError),
+        (status = 401, description = "User is not logged in", body = AlohaError),
+        (status = 404, description = "User is not muted")
+    )
+)]
+pub async fn delete_mute_route(
+    session: Session,
+    id: web::Path<(Uuid,)>,
+    pool: Data<PgPool>,
+) -> Result<HttpResponse, AlohaError> {
+    let user_id = get_session_user_id(&session)?;
+    let transaction = pool.begin().await.unwrap();
+    match delete_mute(transaction, user_id, id.0).await {
+        Ok(Some(result)) => Ok(HttpResponse::Ok().json(MuteResponse::from(result))),
+        Ok(None) => Ok(HttpResponse::NotFound().finish()),
+        Err(e) => Err(AlohaError::DatabaseError(e.to_string())),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/mutes",
+    params(
+        ("page" = Option<i32>, Query, description = "Page number"),
+        ("size" = Option<i32>, Query, description = "Page size")
+    ),
+    responses(
+        (status = 200, description = "Users muted by the logged-in user", body = DtoResponse<Vec<UserResponse>>),
+        (status = 400, description = "Database error", body = AlohaError),
+        (status = 401, description = "User is not logged in", body = AlohaError)
+    )
+)]
+pub async fn get_mutes_route(
+    session: Session,
+    query: QsQuery<DtoQuery<MuteFilterQuery>>,
+    pool: Data<PgPool>,
+) -> Result<HttpResponse, AlohaError> {
+    let user_id = get_session_user_id(&session)?;
+    let transaction = pool.begin().await.unwrap();
+    match get_muted_users(transaction, user_id, query.into_inner()).await {
+        Ok(result) => {
+            let data: Vec<UserResponse> = result.data.into_iter().map(UserResponse::from).collect();
+            Ok(HttpResponse::Ok().json(DtoResponse::new(data, result.pagination)))
+        }
+        Err(e) => Err(AlohaError::DatabaseError(e.to_string())),
+    }
+}
+
+/// Muted users are listed under their own scope; they are muted and
+/// unmuted through `/api/users/{id}/mute`, registered by `user_routes`.
+pub fn mute_routes(cfg: &mut web::ServiceConfig) {
+    let config = get_configuration().unwrap();
+    cfg.service(
+        web::scope(format!("/{}", config.routes.mutes).as_str())
+            .route("", web::get().to(get_mutes_route)),
+    );
+}
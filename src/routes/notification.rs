@@ -0,0 +1,134 @@
+use crate::configuration::{get_configuration, PaginationSettings};
+use crate::dto::cursor::{CursorQuery, CursorResponse};
+use crate::error::AlohaError;
+use crate::mappers::notification::{
+    get_notifications, get_unread_notification_count, mark_all_notifications_read,
+    mark_notification_read,
+};
+use crate::models::notification::{NotificationCountResponse, NotificationResponse};
+use crate::routes::auth::get_session_user_id;
+use actix_session::Session;
+use actix_web::web::{self, Data, Query};
+use actix_web::HttpResponse;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+#[utoipa::path(
+    get,
+    path = "/api/notifications",
+    params(
+        ("cursor" = Option<String>, Query, description = "Opaque cursor returned as `next_cursor` by the previous page"),
+        ("size" = Option<i32>, Query, description = "Page size")
+    ),
+    responses(
+        (status = 200, description = "Notifications, most recently updated first", body = CursorResponse<Vec<NotificationResponse>>),
+        (status = 400, description = "Invalid cursor or database error", body = AlohaError),
+        (status = 401, description = "User is not logged in", body = AlohaError)
+    )
+)]
+pub async fn get_notifications_route(
+    session: Session,
+    query: Query<CursorQuery>,
+    pool: Data<PgPool>,
+    pagination: Data<PaginationSettings>,
+) -> Result<HttpResponse, AlohaError> {
+    let user_id = get_session_user_id(&session)?;
+    let query = query
+        .into_inner()
+        .capped(pagination.max_cursor_size as usize);
+    let transaction = pool.begin().await.unwrap();
+    match get_notifications(transaction, user_id, query).await {
+        Ok(result) => {
+            let data: Vec<NotificationResponse> = result
+                .data
+                .into_iter()
+                .map(NotificationResponse::from)
+                .collect();
+            Ok(HttpResponse::Ok().json(CursorResponse::new(data, result.next_cursor)))
+        }
+        Err(e) => Err(AlohaError::DatabaseError(e.to_string())),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/notifications/unread_count",
+    responses(
+        (status = 200, description = "Number of unread notifications", body = NotificationCountResponse),
+        (status = 400, description = "Database error", body = AlohaError),
+        (status = 401, description = "User is not logged in", body = AlohaError)
+    )
+)]
+pub async fn get_unread_notification_count_route(
+    session: Session,
+    pool: Data<PgPool>,
+) -> Result<HttpResponse, AlohaError> {
+    let user_id = get_session_user_id(&session)?;
+    let transaction = pool.begin().await.unwrap();
+    match get_unread_notification_count(transaction, user_id).await {
+        Ok(count) => Ok(HttpResponse::Ok().json(NotificationCountResponse { count })),
+        Err(e) => Err(AlohaError::DatabaseError(e.to_string())),
+    }
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/notifications/{id}/read",
+    params(
+        ("id" = Uuid, Path, description = "Notification ID")
+    ),
+    responses(
+        (status = 200, description = "Notification marked as read", body = NotificationResponse),
+        (status = 400, description = "Database error", body = AlohaError),
+        (status = 401, description = "User is not logged in", body = AlohaError),
+        (status = 404, description = "Notification not found")
+    )
+)]
+pub async fn mark_notification_read_route(
+    session: Session,
+    id: web::Path<(Uuid,)>,
+    pool: Data<PgPool>,
+) -> Result<HttpResponse, AlohaError> {
+    let user_id = get_session_user_id(&session)?;
+    let transaction = pool.begin().await.unwrap();
+    match mark_notification_read(transaction, user_id, id.0).await {
+        Ok(Some(result)) => Ok(HttpResponse::Ok().json(NotificationResponse::from(result))),
+        Ok(None) => Ok(HttpResponse::NotFound().finish()),
+        Err(e) => Err(AlohaError::DatabaseError(e.to_string())),
+    }
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/notifications/read",
+    responses(
+        (status = 200, description = "Number of notifications marked as read", body = NotificationCountResponse),
+        (status = 400, description = "Database error", body = AlohaError),
+        (status = 401, description = "User is not logged in", body = AlohaError)
+    )
+)]
+pub async fn mark_all_notifications_read_route(
+    session: Session,
+    pool: Data<PgPool>,
+) -> Result<HttpResponse, AlohaError> {
+    let user_id = get_session_user_id(&session)?;
+    let transaction = pool.begin().await.unwrap();
+    match mark_all_notifications_read(transaction, user_id).await {
+        Ok(count) => Ok(HttpResponse::Ok().json(NotificationCountResponse { count })),
+        Err(e) => Err(AlohaError::DatabaseError(e.to_string())),
+    }
+}
+
+pub fn notification_routes(cfg: &mut web::ServiceConfig) {
+    let config = get_configuration().unwrap();
+    cfg.service(
+        web::scope(format!("/{}", config.routes.notifications).as_str())
+            .route("", web::get().to(get_notifications_route))
+            .route(
+                "/unread_count",
+                web::get().to(get_unread_notification_count_route),
+            )
+            .route("/read", web::put().to(mark_all_notifications_read_route))
+            .route("/{id}/read", web::put().to(mark_notification_read_route)),
+    );
+}
@@ -7,6 +7,8 @@ use crate::mappers::permission::{
     update_permission,
 };
 use crate::models::permission::{Permission, PermissionResponse};
+use crate::routes::auth::require_admin;
+use actix_session::Session;
 use actix_web::web::{Data, Json};
 use actix_web::{web, HttpResponse};
 use serde::{Deserialize, Serialize};
@@ -26,13 +28,17 @@ pub struct CreatePermissionFormData {
     request_body = CreatePermissionFormData,
     responses(
         (status = 200, description = "Permission created successfully", body = Permission),
-        (status = 500, description = "Database error", body = AlohaError)
+        (status = 500, description = "Database error", body = AlohaError),
+        (status = 401, description = "User is not logged in", body = AlohaError),
+        (status = 403, description = "User is not an admin", body = AlohaError)
     )
 )]
 pub async fn insert_permission_route(
+    session: Session,
     body: Json<CreatePermissionFormData>,
     pool: Data<PgPool>,
 ) -> Result<HttpResponse, AlohaError> {
+    require_admin(&session, &pool).await?;
     let transaction = pool.begin().await.unwrap();
     let permission = Permission::from(body.0);
     match insert_permission(transaction, &permission).await {
@@ -108,13 +114,17 @@ pub struct PutPermissionFormData {
     request_body = PutPermissionFormData,
     responses(
         (status = 200, description = "Permission updated successfully", body = Permission),
-        (status = 500, description = "Database error", body = AlohaError)
+        (status = 500, description = "Database error", body = AlohaError),
+        (status = 401, description = "User is not logged in", body = AlohaError),
+        (status = 403, description = "User is not an admin", body = AlohaError)
     )
 )]
 pub async fn update_permission_by_id_route(
+    session: Session,
     body: Json<PutPermissionFormData>,
     pool: Data<PgPool>,
 ) -> Result<HttpResponse, AlohaError> {
+    require_admin(&session, &pool).await?;
     let transaction = pool.begin().await.unwrap();
 
     let find_permission = get_permission_by_id(transaction, body.0.id).await.unwrap();
@@ -148,13 +158,17 @@ pub async fn update_permission_by_id_route(
     ),
     responses(
         (status = 200, description = "Permission deleted successfully", body = Permission),
-        (status = 500, description = "Database error", body = AlohaError)
+        (status = 500, description = "Database error", body = AlohaError),
+        (status = 401, description = "User is not logged in", body = AlohaError),
+        (status = 403, description = "User is not an admin", body = AlohaError)
     )
 )]
 pub async fn delete_permission_by_id_route(
+    session: Session,
     id: web::Path<(Uuid,)>,
     pool: Data<PgPool>,
 ) -> Result<HttpResponse, AlohaError> {
+    require_admin(&session, &pool).await?;
     let transaction = pool.begin().await.unwrap();
     match delete_permission_by_id(transaction, id.0).await {
         Ok(result) => Ok(HttpResponse::Ok().json(PermissionResponse::from(result))),
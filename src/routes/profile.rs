@@ -0,0 +1,117 @@
+use crate::configuration::get_configuration;
+use crate::error::AlohaError;
+use crate::mappers::user::{get_dm_policy, get_user_by_id, update_dm_policy, update_user_profile};
+use crate::models::conversation::MessagingSettings;
+use crate::models::user::{UserProfile, UserResponse};
+use crate::routes::auth::get_session_user_id;
+use actix_session::Session;
+use actix_web::web::{self, Data, Json};
+use actix_web::HttpResponse;
+use sqlx::PgPool;
+
+#[utoipa::path(
+    get,
+    path = "/api/profile",
+    responses(
+        (status = 200, description = "The logged-in user with their profile", body = UserResponse),
+        (status = 400, description = "Database error", body = AlohaError),
+        (status = 401, description = "User is not logged in", body = AlohaError)
+    )
+)]
+pub async fn get_profile_route(
+    session: Session,
+    pool: Data<PgPool>,
+) -> Result<HttpResponse, AlohaError> {
+    let user_id = get_session_user_id(&session)?;
+    let transaction = pool.begin().await.unwrap();
+    match get_user_by_id(transaction, user_id).await {
+        Ok(Some(result)) => Ok(HttpResponse::Ok().json(UserResponse::from(result))),
+        Ok(None) => Err(AlohaError::UserUnauthentication),
+        Err(e) => Err(AlohaError::DatabaseError(e.to_string())),
+    }
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/profile",
+    request_body = UserProfile,
+    responses(
+        (status = 200, description = "Profile replaced successfully", body = UserResponse),
+        (status = 400, description = "Field too long, invalid website, avatar or banner, or database error", body = AlohaError),
+        (status = 401, description = "User is not logged in", body = AlohaError)
+    )
+)]
+pub async fn update_profile_route(
+    session: Session,
+    body: Json<UserProfile>,
+    pool: Data<PgPool>,
+) -> Result<HttpResponse, AlohaError> {
+    let user_id = get_session_user_id(&session)?;
+    let profile = body.into_inner().normalized();
+    profile
+        .validate()
+        .map_err(AlohaError::RequestParameterInvalid)?;
+    let transaction = pool.begin().await.unwrap();
+    match update_user_profile(transaction, user_id, &profile).await {
+        Ok(Some(result)) => Ok(HttpResponse::Ok().json(UserResponse::from(result))),
+        Ok(None) => Err(AlohaError::UserUnauthentication),
+        Err(e) => Err(AlohaError::DatabaseError(e.to_string())),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/profile/messaging",
+    responses(
+        (status = 200, description = "Who may start a conversation with the logged-in user", body = MessagingSettings),
+        (status = 400, description = "Database error", body = AlohaError),
+        (status = 401, description = "User is not logged in", body = AlohaError)
+    )
+)]
+pub async fn get_messaging_settings_route(
+    session: Session,
+    pool: Data<PgPool>,
+) -> Result<HttpResponse, AlohaError> {
+    let user_id = get_session_user_id(&session)?;
+    let transaction = pool.begin().await.unwrap();
+    match get_dm_policy(transaction, user_id).await {
+        Ok(Some(dm_policy)) => Ok(HttpResponse::Ok().json(MessagingSettings { dm_policy })),
+        Ok(None) => Err(AlohaError::UserUnauthentication),
+        Err(e) => Err(AlohaError::DatabaseError(e.to_string())),
+    }
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/profile/messaging",
+    request_body = MessagingSettings,
+    responses(
+        (status = 200, description = "Messaging settings replaced successfully", body = MessagingSettings),
+        (status = 400, description = "Database error", body = AlohaError),
+        (status = 401, description = "User is not logged in", body = AlohaError)
+    )
+)]
+pub async fn update_messaging_settings_route(
+    session: Session,
+    body: Json<MessagingSettings>,
+    pool: Data<PgPool>,
+) -> Result<HttpResponse, AlohaError> {
+    let user_id = get_session_user_id(&session)?;
+    let transaction = pool.begin().await.unwrap();
+    match update_dm_policy(transaction, user_id, body.dm_policy).await {
+        Ok(Some(dm_policy)) => Ok(HttpResponse::Ok().json(MessagingSettings { dm_policy })),
+        Ok(None) => Err(AlohaError::UserUnauthentication),
+        Err(e) => Err(AlohaError::DatabaseError(e.to_string())),
+    }
+}
+
+pub fn profile_routes(cfg: &mut web::ServiceConfig) {
+    let config = get_configuration().unwrap();
+    cfg.service(
+        web::scope(format!("/{}", config.routes.profile).as_str())
+            .route("", web::get().to(get_profile_route))
+            .route("", web::put().to(update_profile_route))
+            .route("/messaging", web::get().to(get_messaging_settings_route))
+            .route("/messaging", web::put().to(update_messaging_settings_route)),
+    );
+}
@@ -0,0 +1,197 @@
+use crate::configuration::get_configuration;
+use crate::dto::query::{DtoQuery, ReportFilterQuery};
+use crate::dto::response::DtoResponse;
+use crate::error::AlohaError;
+use crate::mappers::report::{get_report_by_id, get_reports, insert_report, update_report_status};
+use crate::models::report::{Report, ReportResponse, ReportStatus, MAX_REPORT_REASON_LENGTH};
+use crate::routes::auth::{get_session_user_id, require_permission, MODERATE_REPORTS_PERMISSION};
+use actix_session::Session;
+use actix_web::web::{self, Data, Json};
+use actix_web::HttpResponse;
+use serde::Deserialize;
+use serde_qs::actix::QsQuery;
+use sqlx::PgPool;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+#[derive(Deserialize, Clone, ToSchema)]
+pub struct CreateReportFormData {
+    /// The reported tweet; exactly one of `tweet_id` and `user_id` is set.
+    #[serde(default)]
+    tweet_id: Option<Uuid>,
+    /// The reported user; exactly one of `tweet_id` and `user_id` is set.
+    #[serde(default)]
+    user_id: Option<Uuid>,
+    reason: String,
+}
+
+impl CreateReportFormData {
+    fn to_report(&self, reporter_id: Uuid) -> Result<Report, AlohaError> {
+        if self.tweet_id.is_some() == self.user_id.is_some() {
+            return Err(AlohaError::RequestParameterInvalid(
+                "A report targets either a tweet or a user.".to_string(),
+            ));
+        }
+        if self.user_id == Some(reporter_id) {
+            return Err(AlohaError::RequestParameterInvalid(
+                "You cannot report yourself.".to_string(),
+            ));
+        }
+        let reason = self.reason.trim();
+        if reason.is_empty() || reason.chars().count() > MAX_REPORT_REASON_LENGTH {
+            return Err(AlohaError::RequestParameterInvalid(format!(
+                "Report reasons must be between 1 and {} characters long.",
+                MAX_REPORT_REASON_LENGTH
+            )));
+        }
+        Ok(Report::new(
+            reporter_id,
+            self.tweet_id,
+            self.user_id,
+            reason.to_string(),
+        ))
+    }
+}
+
+#[derive(Deserialize, Clone, ToSchema)]
+pub struct UpdateReportFormData {
+    /// `actioned` or `dismissed`.
+    status: ReportStatus,
+    #[serde(default)]
+    note: Option<String>,
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/reports",
+    request_body = CreateReportFormData,
+    responses(
+        (status = 200, description = "Report filed successfully", body = ReportResponse),
+        (status = 400, description = "Invalid report, unknown user or database error", body = AlohaError),
+        (status = 401, description = "User is not logged in", body = AlohaError),
+        (status = 404, description = "Tweet not found or not visible to the user")
+    )
+)]
+pub async fn insert_report_route(
+    session: Session,
+    body: Json<CreateReportFormData>,
+    pool: Data<PgPool>,
+) -> Result<HttpResponse, AlohaError> {
+    let user_id = get_session_user_id(&session)?;
+    let report = body.to_report(user_id)?;
+    let transaction = pool.begin().await.unwrap();
+    match insert_report(transaction, &report).await {
+        Ok(Some(result)) => Ok(HttpResponse::Ok().json(ReportResponse::from(result))),
+        Ok(None) => Ok(HttpResponse::NotFound().finish()),
+        Err(e) => Err(AlohaError::DatabaseError(e.to_string())),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/reports",
+    params(
+        ("page" = Option<i32>, Query, description = "Page number"),
+        ("size" = Option<i32>, Query, description = "Page size"),
+        ("status" = Option<ReportStatus>, Query, description = "Report status, open when omitted")
+    ),
+    responses(
+        (status = 200, description = "Moderation queue, oldest first", body = DtoResponse<Vec<ReportResponse>>),
+        (status = 400, description = "Database error", body = AlohaError),
+        (status = 401, description = "User is not logged in", body = AlohaError),
+        (status = 403, description = "User is not a moderator", body = AlohaError)
+    )
+)]
+pub async fn get_reports_route(
+    session: Session,
+    query: QsQuery<DtoQuery<ReportFilterQuery>>,
+    pool: Data<PgPool>,
+) -> Result<HttpResponse, AlohaError> {
+    require_permission(&session, &pool, MODERATE_REPORTS_PERMISSION).await?;
+    let transaction = pool.begin().await.unwrap();
+    match get_reports(transaction, query.into_inner()).await {
+        Ok(result) => {
+            let data: Vec<ReportResponse> =
+                result.data.into_iter().map(ReportResponse::from).collect();
+            Ok(HttpResponse::Ok().json(DtoResponse::new(data, result.pagination)))
+        }
+        Err(e) => Err(AlohaError::DatabaseError(e.to_string())),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/reports/{id}",
+    params(
+        ("id" = Uuid, Path, description = "Report ID")
+    ),
+    responses(
+        (status = 200, description = "Report retrieved successfully", body = ReportResponse),
+        (status = 400, description = "Database error", body = AlohaError),
+        (status = 401, description = "User is not logged in", body = AlohaError),
+        (status = 403, description = "User is not a moderator", body = AlohaError),
+        (status = 404, description = "Report not found")
+    )
+)]
+pub async fn get_report_route(
+    session: Session,
+    id: web::Path<(Uuid,)>,
+    pool: Data<PgPool>,
+) -> Result<HttpResponse, AlohaError> {
+    require_permission(&session, &pool, MODERATE_REPORTS_PERMISSION).await?;
+    let transaction = pool.begin().await.unwrap();
+    match get_report_by_id(transaction, id.0).await {
+        Ok(Some(result)) => Ok(HttpResponse::Ok().json(ReportResponse::from(result))),
+        Ok(None) => Ok(HttpResponse::NotFound().finish()),
+        Err(e) => Err(AlohaError::DatabaseError(e.to_string())),
+    }
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/reports/{id}",
+    params(
+        ("id" = Uuid, Path, description = "Report ID")
+    ),
+    request_body = UpdateReportFormData,
+    responses(
+        (status = 200, description = "Report resolved successfully", body = ReportResponse),
+        (status = 400, description = "Report already resolved or database error", body = AlohaError),
+        (status = 401, description = "User is not logged in", body = AlohaError),
+        (status = 403, description = "User is not a moderator", body = AlohaError),
+        (status = 404, description = "Report not found")
+    )
+)]
+pub async fn update_report_route(
+    session: Session,
+    id: web::Path<(Uuid,)>,
+    body: Json<UpdateReportFormData>,
+    pool: Data<PgPool>,
+) -> Result<HttpResponse, AlohaError> {
+    let moderator_id = require_permission(&session, &pool, MODERATE_REPORTS_PERMISSION).await?;
+    let transaction = pool.begin().await.unwrap();
+    match update_report_status(
+        transaction,
+        id.0,
+        moderator_id,
+        body.status,
+        body.note.clone(),
+    )
+    .await
+    {
+        Ok(Some(result)) => Ok(HttpResponse::Ok().json(ReportResponse::from(result))),
+        Ok(None) => Ok(HttpResponse::NotFound().finish()),
+        Err(e) => Err(AlohaError::DatabaseError(e.to_string())),
+    }
+}
+
+pub fn report_routes(cfg: &mut web::ServiceConfig) {
+    let config = get_configuration().unwrap();
+    cfg.service(
+        web::scope(format!("/{}", config.routes.reports).as_str())
+            .route("", web::post().to(insert_report_route))
+            .route("", web::get().to(get_reports_route))
+            .route("/{id}", web::get().to(get_report_route))
+            .route("/{id}", web::put().to(update_report_route)),
+    );
+}
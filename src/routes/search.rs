@@ -0,0 +1,103 @@
+use crate::configuration::get_configuration;
+use crate::dto::query::SearchQuery;
+use crate::dto::response::DtoResponse;
+use crate::error::AlohaError;
+use crate::mappers::search::{search_tweets, search_users};
+use crate::models::search::{TweetSearchQuery, TweetSearchResponse};
+use crate::models::user::UserResponse;
+use crate::routes::auth::get_session_viewer_id;
+use crate::routes::tweet::build_tweet_responses;
+use actix_session::Session;
+use actix_web::web::{self, Data, Query};
+use actix_web::HttpResponse;
+use sqlx::PgPool;
+
+#[utoipa::path(
+    get,
+    path = "/api/search/tweets",
+    params(
+        ("q" = String, Query, description = "Search expression: words, `word*` prefixes, \"quoted phrases\", `from:username`, `#tag`, `since:YYYY-MM-DD` and `until:YYYY-MM-DD`"),
+        ("page" = Option<i32>, Query, description = "Page number"),
+        ("size" = Option<i32>, Query, description = "Page size")
+    ),
+    responses(
+        (status = 200, description = "Tweets found successfully", body = DtoResponse<Vec<TweetSearchResponse>>),
+        (status = 400, description = "Invalid search expression or database error", body = AlohaError)
+    )
+)]
+pub async fn search_tweets_route(
+    session: Session,
+    query: Query<SearchQuery>,
+    pool: Data<PgPool>,
+) -> Result<HttpResponse, AlohaError> {
+    let viewer_id = get_session_viewer_id(&session)?;
+    let search = TweetSearchQuery::parse(&query.q).map_err(AlohaError::RequestParameterInvalid)?;
+    let transaction = pool.begin().await.unwrap();
+    let result = match search_tweets(transaction, &search, &query, viewer_id).await {
+        Ok(result) => result,
+        Err(e) => return Err(AlohaError::DatabaseError(e.to_string())),
+    };
+
+    let (tweets, hits): (Vec<_>, Vec<_>) = result
+        .data
+        .into_iter()
+        .map(|hit| (hit.tweet, (hit.rank, hit.snippet)))
+        .unzip();
+    match build_tweet_responses(&pool, tweets, viewer_id).await {
+        Ok(tweets) => {
+            let response: Vec<TweetSearchResponse> = tweets
+                .into_iter()
+                .zip(hits)
+                .map(|(tweet, (rank, snippet))| TweetSearchResponse {
+                    tweet,
+                    rank,
+                    snippet,
+                })
+                .collect();
+            Ok(HttpResponse::Ok().json(DtoResponse::new(response, result.pagination)))
+        }
+        Err(e) => Err(AlohaError::DatabaseError(e.to_string())),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/search/users",
+    params(
+        ("q" = String, Query, description = "Part of a username; small typos are tolerated"),
+        ("page" = Option<i32>, Query, description = "Page number"),
+        ("size" = Option<i32>, Query, description = "Page size")
+    ),
+    responses(
+        (status = 200, description = "Users found successfully", body = DtoResponse<Vec<UserResponse>>),
+        (status = 400, description = "Empty search query or database error", body = AlohaError)
+    )
+)]
+pub async fn search_users_route(
+    query: Query<SearchQuery>,
+    pool: Data<PgPool>,
+) -> Result<HttpResponse, AlohaError> {
+    if query.q.trim().is_empty() {
+        return Err(AlohaError::RequestParameterInvalid(String::from(
+            "Search query is empty.",
+        )));
+    }
+    let transaction = pool.begin().await.unwrap();
+    match search_users(transaction, &query).await {
+        Ok(users) => {
+            let user_responses: Vec<UserResponse> =
+                users.data.into_iter().map(UserResponse::from).collect();
+            Ok(HttpResponse::Ok().json(DtoResponse::new(user_responses, users.pagination)))
+        }
+        Err(e) => Err(AlohaError::DatabaseError(e.to_string())),
+    }
+}
+
+pub fn search_routes(cfg: &mut web::ServiceConfig) {
+    let config = get_configuration().unwrap();
+    cfg.service(
+        web::scope(format!("/{}", config.routes.search).as_str())
+            .route("/tweets", web::get().to(search_tweets_route))
+            .route("/users", web::get().to(search_users_route)),
+    );
+}
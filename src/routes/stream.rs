@@ -0,0 +1,205 @@
+use crate::configuration::{get_configuration, StreamSettings};
+use crate::error::AlohaError;
+use crate::jobs::stream::{StreamBroker, StreamSubscription};
+use crate::mappers::notification::get_notification_by_id;
+use crate::mappers::stream::{get_stream_events_since, get_timeline_recipients};
+use crate::mappers::tweet::get_tweet_by_id;
+use crate::models::notification::NotificationResponse;
+use crate::models::stream::{
+    parse_last_event_id, StreamEvent, StreamEventKind, StreamMessage, STREAM_HEARTBEAT,
+};
+use crate::routes::auth::get_session_user_id;
+use crate::routes::tweet::build_tweet_responses;
+use actix_session::Session;
+use actix_web::http::header;
+use actix_web::rt::time::{timeout, Instant};
+use actix_web::web::{self, Bytes, Data};
+use actix_web::{HttpRequest, HttpResponse};
+use futures_util::{stream, StreamExt};
+use sqlx::PgPool;
+use std::collections::HashSet;
+use std::time::Duration;
+use tokio::sync::broadcast::error::RecvError;
+use uuid::Uuid;
+
+/// Renders `event` for whichever of `candidates` it is meant for: the owner of
+/// a notification, or the users whose home timeline a tweet reaches. Returns
+/// `None` when it concerns none of them or its tweet or notification is gone.
+/// Tweets are rendered without viewer-specific state.
+pub async fn render_stream_event(
+    pool: &PgPool,
+    event: &StreamEvent,
+    candidates: &[Uuid],
+) -> Result<Option<StreamMessage>, anyhow::Error> {
+    let (recipients, data) = match event.kind {
+        StreamEventKind::Tweet => {
+            let Some(tweet_id) = event.tweet_id else {
+                return Ok(None);
+            };
+            let transaction = pool.begin().await?;
+            let recipients = get_timeline_recipients(transaction, tweet_id, candidates).await?;
+            let Some(&viewer_id) = recipients.first() else {
+                return Ok(None);
+            };
+            let transaction = pool.begin().await?;
+            let Some(tweet) = get_tweet_by_id(transaction, tweet_id, Some(viewer_id)).await? else {
+                return Ok(None);
+            };
+            let response = build_tweet_responses(pool, vec![tweet], None).await?;
+            (recipients, serde_json::to_string(&response[0])?)
+        }
+        StreamEventKind::Notification => {
+            let Some(notification_id) = event.notification_id else {
+                return Ok(None);
+            };
+            let transaction = pool.begin().await?;
+            let Some(notification) = get_notification_by_id(transaction, notification_id).await?
+            else {
+                return Ok(None);
+            };
+            if !candidates.contains(&notification.user_id) {
+                return Ok(None);
+            }
+            let recipients = vec![notification.user_id];
+            let response = NotificationResponse::from(notification);
+            (recipients, serde_json::to_string(&response)?)
+        }
+    };
+    Ok(Some(StreamMessage {
+        id: event.id,
+        kind: event.kind,
+        recipients,
+        data,
+    }))
+}
+
+struct LiveStream {
+    subscription: StreamSubscription,
+    /// Events already sent while replaying.
+    replayed: HashSet<i64>,
+    heartbeat: Duration,
+    next_heartbeat: Instant,
+}
+
+/// Waits for the next message addressed to the subscriber, or for the
+/// heartbeat when none comes in time. Ends the stream when the subscriber
+/// fell behind, so the client reconnects and catches up from `Last-Event-ID`.
+async fn next_live_frame(
+    mut live: LiveStream,
+) -> Option<(Result<Bytes, actix_web::Error>, LiveStream)> {
+    loop {
+        let until_heartbeat = live
+            .next_heartbeat
+            .saturating_duration_since(Instant::now());
+        match timeout(until_heartbeat, live.subscription.receiver.recv()).await {
+            Err(_) => {
+                live.next_heartbeat = Instant::now() + live.heartbeat;
+                return Some((Ok(Bytes::from_static(STREAM_HEARTBEAT.as_bytes())), live));
+            }
+            Ok(Ok(message))
+                if message.recipients.contains(&live.subscription.user_id)
+                    && !live.replayed.contains(&message.id) =>
+            {
+                live.next_heartbeat = Instant::now() + live.heartbeat;
+                return Some((Ok(Bytes::from(message.to_sse())), live));
+            }
+            Ok(Ok(_)) => {}
+            Ok(Err(RecvError::Lagged(skipped))) => {
+                tracing::log::warn!(
+                    "Closing stream of user {} after missing {} events",
+                    live.subscription.user_id,
+                    skipped
+                );
+                return None;
+            }
+            Ok(Err(RecvError::Closed)) => return None,
+        }
+    }
+}
+
+/// Streams the events of the logged-in user, replaying the ones after
+/// `Last-Event-ID` first.
+///
+/// Event ids are handed out when an event is inserted, not when its
+/// transaction commits, so an event can become visible after one with a
+/// higher id was already sent. Such an event still reaches clients connected
+/// when it commits, but is not replayed to a client resuming from a later
+/// `Last-Event-ID`; clients that must not miss anything should reload their
+/// timeline and notifications after reconnecting.
+#[utoipa::path(
+    get,
+    path = "/api/stream",
+    params(
+        ("Last-Event-ID" = Option<i64>, Header, description = "Id of the last event received; later events are replayed first")
+    ),
+    responses(
+        (status = 200, description = "Server-Sent Events stream of `tweet` events carrying a TweetResponse and `notification` events carrying a NotificationResponse", content_type = "text/event-stream", body = String),
+        (status = 400, description = "Invalid Last-Event-ID or database error", body = AlohaError),
+        (status = 401, description = "User is not logged in", body = AlohaError)
+    )
+)]
+pub async fn stream_route(
+    request: HttpRequest,
+    session: Session,
+    pool: Data<PgPool>,
+    broker: Data<StreamBroker>,
+    settings: Data<StreamSettings>,
+) -> Result<HttpResponse, AlohaError> {
+    let user_id = get_session_user_id(&session)?;
+    let last_event_id = match request.headers().get("Last-Event-ID") {
+        Some(value) => {
+            let value = value
+                .to_str()
+                .map_err(|e| AlohaError::RequestParameterInvalid(e.to_string()))?;
+            Some(parse_last_event_id(value).map_err(AlohaError::RequestParameterInvalid)?)
+        }
+        None => None,
+    };
+
+    // Subscribe before replaying so events published meanwhile are not lost.
+    let subscription = broker.into_inner().subscribe(user_id);
+    let mut replayed = HashSet::new();
+    let mut frames: Vec<Result<Bytes, actix_web::Error>> = Vec::new();
+    if let Some(last_event_id) = last_event_id {
+        let transaction = pool.begin().await.unwrap();
+        let events = get_stream_events_since(
+            transaction,
+            user_id,
+            last_event_id,
+            settings.replay_limit as i64,
+        )
+        .await
+        .map_err(|e| AlohaError::DatabaseError(e.to_string()))?;
+        for event in events {
+            match render_stream_event(&pool, &event, &[user_id]).await {
+                Ok(Some(message)) => {
+                    replayed.insert(message.id);
+                    frames.push(Ok(Bytes::from(message.to_sse())));
+                }
+                Ok(None) => {}
+                Err(e) => return Err(AlohaError::DatabaseError(e.to_string())),
+            }
+        }
+    }
+
+    let heartbeat = Duration::from_secs(settings.heartbeat_seconds.max(1) as u64);
+    let live = LiveStream {
+        subscription,
+        replayed,
+        heartbeat,
+        next_heartbeat: Instant::now() + heartbeat,
+    };
+    let body = stream::iter(frames).chain(stream::unfold(live, next_live_frame));
+    Ok(HttpResponse::Ok()
+        .content_type("text/event-stream")
+        .insert_header((header::CACHE_CONTROL, "no-cache"))
+        .streaming(body))
+}
+
+pub fn stream_routes(cfg: &mut web::ServiceConfig) {
+    let config = get_configuration().unwrap();
+    cfg.service(
+        web::scope(format!("/{}", config.routes.stream).as_str())
+            .route("", web::get().to(stream_route)),
+    );
+}
@@ -0,0 +1,106 @@
+use crate::configuration::get_configuration;
+use crate::dto::response::DtoResponse;
+use crate::error::AlohaError;
+use crate::mappers::subscription::{
+    delete_subscription, get_subscriptions_by_subscriber_id, insert_subscription,
+};
+use crate::models::subscription::{Subscription, SubscriptionResponse};
+use crate::routes::auth::get_session_user_id;
+use actix_session::Session;
+use actix_web::web::{self, Data, Json, Path};
+use actix_web::HttpResponse;
+use serde::Deserialize;
+use sqlx::PgPool;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+#[derive(Deserialize, Clone, ToSchema)]
+pub struct CreateSubscriptionFormData {
+    pub target_id: Uuid,
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/subscriptions",
+    request_body = CreateSubscriptionFormData,
+    responses(
+        (status = 200, description = "Subscription created successfully", body = SubscriptionResponse),
+        (status = 400, description = "Database error", body = AlohaError),
+        (status = 401, description = "User is not logged in", body = AlohaError)
+    )
+)]
+pub async fn insert_subscription_route(
+    session: Session,
+    body: Json<CreateSubscriptionFormData>,
+    pool: Data<PgPool>,
+) -> Result<HttpResponse, AlohaError> {
+    let user_id = get_session_user_id(&session)?;
+    let transaction = pool.begin().await.unwrap();
+    let subscription = Subscription::new(user_id, body.target_id);
+    match insert_subscription(transaction, &subscription).await {
+        Ok(result) => Ok(HttpResponse::Ok().json(SubscriptionResponse::from(result))),
+        Err(e) => Err(AlohaError::DatabaseError(e.to_string())),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/subscriptions",
+    responses(
+        (status = 200, description = "Subscriptions retrieved successfully", body = DtoResponse<Vec<SubscriptionResponse>>),
+        (status = 400, description = "Database error", body = AlohaError),
+        (status = 401, description = "User is not logged in", body = AlohaError)
+    )
+)]
+pub async fn get_subscriptions_route(
+    session: Session,
+    pool: Data<PgPool>,
+) -> Result<HttpResponse, AlohaError> {
+    let user_id = get_session_user_id(&session)?;
+    let transaction = pool.begin().await.unwrap();
+    match get_subscriptions_by_subscriber_id(transaction, user_id).await {
+        Ok(subscriptions) => {
+            let result: Vec<SubscriptionResponse> = subscriptions
+                .into_iter()
+                .map(SubscriptionResponse::from)
+                .collect();
+            Ok(HttpResponse::Ok().json(DtoResponse::new(result, None)))
+        }
+        Err(e) => Err(AlohaError::DatabaseError(e.to_string())),
+    }
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/subscriptions/{target_id}",
+    params(
+        ("target_id" = Uuid, Path, description = "ID of the user to unsubscribe from")
+    ),
+    responses(
+        (status = 200, description = "Subscription deleted successfully", body = SubscriptionResponse),
+        (status = 400, description = "Database error", body = AlohaError),
+        (status = 401, description = "User is not logged in", body = AlohaError)
+    )
+)]
+pub async fn delete_subscription_route(
+    session: Session,
+    target_id: Path<Uuid>,
+    pool: Data<PgPool>,
+) -> Result<HttpResponse, AlohaError> {
+    let user_id = get_session_user_id(&session)?;
+    let transaction = pool.begin().await.unwrap();
+    match delete_subscription(transaction, user_id, *target_id).await {
+        Ok(result) => Ok(HttpResponse::Ok().json(SubscriptionResponse::from(result))),
+        Err(e) => Err(AlohaError::DatabaseError(e.to_string())),
+    }
+}
+
+pub fn subscription_routes(cfg: &mut web::ServiceConfig) {
+    let config = get_configuration().unwrap();
+    cfg.service(
+        web::scope(format!("/{}", config.routes.subscriptions).as_str())
+            .route("", web::post().to(insert_subscription_route))
+            .route("", web::get().to(get_subscriptions_route))
+            .route("/{target_id}", web::delete().to(delete_subscription_route)),
+    );
+}
@@ -0,0 +1,54 @@
+use crate::configuration::{get_configuration, PaginationSettings};
+use crate::dto::cursor::{CursorQuery, CursorResponse};
+use crate::error::AlohaError;
+use crate::mappers::timeline::get_home_timeline;
+use crate::models::tweet::TweetResponse;
+use crate::routes::auth::get_session_user_id;
+use crate::routes::tweet::build_tweet_responses;
+use actix_session::Session;
+use actix_web::web::{self, Data, Query};
+use actix_web::HttpResponse;
+use sqlx::PgPool;
+
+#[utoipa::path(
+    get,
+    path = "/api/timeline/home",
+    params(
+        ("cursor" = Option<String>, Query, description = "Opaque cursor returned as `next_cursor` by the previous page"),
+        ("size" = Option<i32>, Query, description = "Page size")
+    ),
+    responses(
+        (status = 200, description = "Home timeline retrieved successfully", body = CursorResponse<Vec<TweetResponse>>),
+        (status = 400, description = "Database error", body = AlohaError),
+        (status = 401, description = "User is not logged in", body = AlohaError)
+    )
+)]
+pub async fn get_home_timeline_route(
+    session: Session,
+    query: Query<CursorQuery>,
+    pool: Data<PgPool>,
+    pagination: Data<PaginationSettings>,
+) -> Result<HttpResponse, AlohaError> {
+    let user_id = get_session_user_id(&session)?;
+    let query = query
+        .into_inner()
+        .capped(pagination.max_cursor_size as usize);
+    let transaction = pool.begin().await.unwrap();
+    match get_home_timeline(transaction, user_id, query).await {
+        Ok(result) => match build_tweet_responses(&pool, result.data, Some(user_id)).await {
+            Ok(response) => {
+                Ok(HttpResponse::Ok().json(CursorResponse::new(response, result.next_cursor)))
+            }
+            Err(e) => Err(AlohaError::DatabaseError(e.to_string())),
+        },
+        Err(e) => Err(AlohaError::DatabaseError(e.to_string())),
+    }
+}
+
+pub fn timeline_routes(cfg: &mut web::ServiceConfig) {
+    let config = get_configuration().unwrap();
+    cfg.service(
+        web::scope(format!("/{}", config.routes.timeline).as_str())
+            .route("/home", web::get().to(get_home_timeline_route)),
+    );
+}
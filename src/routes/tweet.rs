@@ -1,24 +1,158 @@
-use crate::configuration::get_configuration;
+use crate::configuration::{get_configuration, MediaSettings, TweetSettings};
 use crate::dto::query::{DtoQuery, TweetFilterQuery};
 use crate::dto::response::DtoResponse;
 use crate::error::AlohaError;
+use crate::mappers::link_preview::get_link_cards_by_tweet_ids;
+use crate::mappers::media::get_media_by_tweet_ids;
+use crate::mappers::mention::get_mentions_by_tweet_ids;
+use crate::mappers::poll::{get_poll_tallies_by_tweet_ids, insert_poll_vote};
 use crate::mappers::tweet::{
-    delete_tweet_by_id, delete_tweets_by_ids, get_all_tweets, get_tweet_by_id, insert_tweet,
-    update_tweet,
+    delete_tweet_by_id, delete_tweets_by_ids, get_all_tweets, get_pinned_tweet_ids,
+    get_tweet_authors, get_tweet_by_id, get_tweet_status, insert_tweet_with_attachments, pin_tweet,
+    restore_tweet_by_id, unpin_tweet, update_tweet,
 };
-use crate::models::tweet::{Tweet, TweetResponse};
-use crate::routes::auth::check_login;
+use crate::mappers::tweet_revision::{get_revision_counts_by_tweet_ids, get_tweet_revisions};
+use crate::models::draft::TweetStatus;
+use crate::models::link_preview::LinkPreviewResponse;
+use crate::models::media::MediaResponse;
+use crate::models::poll::{Poll, PollResponse};
+use crate::models::tweet::{Tweet, TweetResponse, TweetVisibility};
+use crate::models::tweet_revision::TweetRevisionResponse;
+use crate::routes::auth::{check_login, get_session_user_id, get_session_viewer_id, require_admin};
+use crate::routes::bookmark::{delete_bookmark_route, insert_bookmark_route};
+use crate::routes::media::build_media_responses;
+use actix_session::Session;
 use actix_web::web::{Data, Json};
 use actix_web::{web, HttpResponse};
 use serde::Deserialize;
 use serde_qs::actix::QsQuery;
 use sqlx::PgPool;
+use std::collections::HashMap;
+use time::{Duration, OffsetDateTime};
 use utoipa::ToSchema;
 use uuid::Uuid;
 
+/// Converts tweets into responses, loading the entities stored alongside them
+/// with one query per entity kind for the whole batch. `viewer_id` decides
+/// whether poll results are revealed.
+pub async fn build_tweet_responses(
+    pool: &PgPool,
+    tweets: Vec<Tweet>,
+    viewer_id: Option<Uuid>,
+) -> Result<Vec<TweetResponse>, anyhow::Error> {
+    let tweet_ids: Vec<Uuid> = tweets.iter().map(|t| t.id).collect();
+    let mut author_ids: Vec<Uuid> = tweets.iter().map(|t| t.user_id).collect();
+    author_ids.sort();
+    author_ids.dedup();
+    // One read-only snapshot, so the entities of a page agree with each other.
+    let mut transaction = pool.begin().await?;
+    sqlx::query!("SET TRANSACTION ISOLATION LEVEL REPEATABLE READ, READ ONLY")
+        .execute(&mut *transaction)
+        .await?;
+    let authors = get_tweet_authors(&mut transaction, &author_ids).await?;
+    let mut mentions = get_mentions_by_tweet_ids(&mut transaction, &tweet_ids).await?;
+    let revision_counts = get_revision_counts_by_tweet_ids(&mut transaction, &tweet_ids).await?;
+    let pinned = get_pinned_tweet_ids(&mut transaction, &tweet_ids).await?;
+    let mut polls = get_poll_tallies_by_tweet_ids(&mut transaction, &tweet_ids, viewer_id).await?;
+    let mut cards = get_link_cards_by_tweet_ids(&mut transaction, &tweet_ids).await?;
+    let mut media = get_media_by_tweet_ids(&mut transaction, &tweet_ids).await?;
+    let media_responses: HashMap<Uuid, MediaResponse> = build_media_responses(
+        &mut transaction,
+        media.values().flatten().cloned().collect(),
+    )
+    .await?
+    .into_iter()
+    .map(|m| (m.id, m))
+    .collect();
+    transaction.commit().await?;
+    let now = OffsetDateTime::now_utc();
+
+    Ok(tweets
+        .into_iter()
+        .map(|tweet| {
+            let tweet_id = tweet.id;
+            let mut response = TweetResponse::from(tweet);
+            response.author = authors.get(&response.user_id).cloned();
+            response.mentions = mentions.remove(&tweet_id).unwrap_or_default();
+            response.media = media
+                .remove(&tweet_id)
+                .unwrap_or_default()
+                .iter()
+                .filter_map(|m| media_responses.get(&m.id).cloned())
+                .collect();
+            response.edited = revision_counts.contains_key(&tweet_id);
+            response.pinned = pinned.contains(&tweet_id);
+            response.poll = polls
+                .remove(&tweet_id)
+                .map(|tally| PollResponse::new(tally, now));
+            response.card = cards.remove(&tweet_id).map(LinkPreviewResponse::from);
+            response
+        })
+        .collect())
+}
+
+pub async fn build_tweet_response(
+    pool: &PgPool,
+    tweet: Tweet,
+    viewer_id: Option<Uuid>,
+) -> Result<TweetResponse, anyhow::Error> {
+    let mut responses = build_tweet_responses(pool, vec![tweet], viewer_id).await?;
+    Ok(responses.remove(0))
+}
+
+/// Answers a successful post or edit with `202 Accepted` when the content
+/// rules held the tweet for review, and `200 OK` otherwise.
+pub async fn written_tweet_response(
+    pool: &PgPool,
+    tweet: Tweet,
+    viewer_id: Option<Uuid>,
+) -> Result<HttpResponse, AlohaError> {
+    let transaction = pool.begin().await.unwrap();
+    let status = get_tweet_status(transaction, tweet.id)
+        .await
+        .map_err(|e| AlohaError::DatabaseError(e.to_string()))?;
+    let response = build_tweet_response(pool, tweet, viewer_id)
+        .await
+        .map_err(|e| AlohaError::DatabaseError(e.to_string()))?;
+    match status {
+        Some(TweetStatus::Held) => Ok(HttpResponse::Accepted().json(response)),
+        _ => Ok(HttpResponse::Ok().json(response)),
+    }
+}
+
 #[derive(Deserialize, Clone, ToSchema)]
 pub struct CreateTweetFormData {
     content: String,
+    /// Ids returned by `POST /api/media`, in display order.
+    #[serde(default)]
+    media_ids: Vec<Uuid>,
+    /// Who may read the tweet; public when omitted.
+    #[serde(default)]
+    visibility: TweetVisibility,
+    #[serde(default)]
+    poll: Option<CreatePollFormData>,
+}
+
+#[derive(Deserialize, Clone, ToSchema)]
+pub struct CreatePollFormData {
+    /// Between two and four option labels, in display order.
+    options: Vec<String>,
+    /// RFC 3339 timestamp after which votes are no longer accepted.
+    #[serde(with = "time::serde::rfc3339")]
+    #[schema(value_type = String)]
+    closes_at: OffsetDateTime,
+}
+
+impl CreatePollFormData {
+    fn to_poll(&self, tweet_id: Uuid) -> Result<Poll, AlohaError> {
+        if self.closes_at <= OffsetDateTime::now_utc() {
+            return Err(AlohaError::RequestParameterInvalid(
+                "closes_at must be in the future.".to_string(),
+            ));
+        }
+        Poll::new(tweet_id, &self.options, self.closes_at)
+            .map_err(AlohaError::RequestParameterInvalid)
+    }
 }
 
 #[utoipa::path(
@@ -27,14 +161,23 @@ pub struct CreateTweetFormData {
     request_body = CreateTweetFormData,
     responses(
         (status = 200, description = "Tweet created successfully", body = TweetResponse),
+        (status = 202, description = "Tweet held for review by the content rules", body = TweetResponse),
+        (status = 400, description = "Invalid poll, too many attachments, or content rejected by the content rules", body = AlohaError),
         (status = 500, description = "Database error", body = AlohaError)
     )
 )]
 pub async fn insert_tweet_route(
     session: actix_session::Session,
     body: Json<CreateTweetFormData>,
+    media_settings: Data<MediaSettings>,
     pool: Data<PgPool>,
 ) -> Result<HttpResponse, AlohaError> {
+    if body.media_ids.len() > media_settings.max_attachments as usize {
+        return Err(AlohaError::RequestParameterInvalid(format!(
+            "A tweet can have at most {} media attachments.",
+            media_settings.max_attachments
+        )));
+    }
     let is_login = check_login(&session).await;
     tracing::log::debug!("{:?}", session.entries());
 
@@ -44,10 +187,24 @@ pub async fn insert_tweet_route(
         Ok(Some(user_id)) => match is_login {
             Ok(true) => {
                 let transaction = pool.begin().await.unwrap();
-                let tweet = Tweet::new(body.content.clone(), user_id);
+                let tweet = Tweet {
+                    visibility: body.visibility,
+                    ..Tweet::new(body.content.clone(), user_id)
+                };
+                let poll = match &body.poll {
+                    Some(poll) => Some(poll.to_poll(tweet.id)?),
+                    None => None,
+                };
                 tracing::log::info!("CREATE TWEET: {:?}", tweet);
-                match insert_tweet(transaction, &tweet).await {
-                    Ok(result) => Ok(HttpResponse::Ok().json(TweetResponse::from(result))),
+                match insert_tweet_with_attachments(
+                    transaction,
+                    &tweet,
+                    &body.media_ids,
+                    poll.as_ref(),
+                )
+                .await
+                {
+                    Ok(result) => written_tweet_response(&pool, result, Some(user_id)).await,
                     Err(e) => Err(AlohaError::DatabaseError(e.to_string())),
                 }
             }
@@ -73,16 +230,19 @@ pub async fn insert_tweet_route(
     )
 )]
 pub async fn get_all_tweets_route(
+    session: Session,
     query: QsQuery<DtoQuery<TweetFilterQuery>>,
     pool: Data<PgPool>,
 ) -> Result<HttpResponse, AlohaError> {
+    let viewer_id = get_session_viewer_id(&session)?;
     let transaction = pool.begin().await.unwrap();
-    match get_all_tweets(transaction, query.into_inner()).await {
-        Ok(result) => {
-            let response: Vec<TweetResponse> =
-                result.data.into_iter().map(TweetResponse::from).collect();
-            Ok(HttpResponse::Ok().json(DtoResponse::new(response, result.pagination)))
-        }
+    match get_all_tweets(transaction, query.into_inner(), viewer_id).await {
+        Ok(result) => match build_tweet_responses(&pool, result.data, viewer_id).await {
+            Ok(response) => {
+                Ok(HttpResponse::Ok().json(DtoResponse::new(response, result.pagination)))
+            }
+            Err(e) => Err(AlohaError::DatabaseError(e.to_string())),
+        },
         Err(e) => Err(AlohaError::DatabaseError(e.to_string())),
     }
 }
@@ -95,17 +255,22 @@ pub async fn get_all_tweets_route(
     ),
     responses(
         (status = 200, description = "Tweet retrieved successfully", body = TweetResponse),
-        (status = 404, description = "Tweet not found"),
+        (status = 404, description = "Tweet not found or not visible to the viewer"),
         (status = 500, description = "Database error", body = AlohaError)
     )
 )]
 pub async fn get_tweet_route(
+    session: Session,
     id: web::Path<(Uuid,)>,
     pool: Data<PgPool>,
 ) -> Result<HttpResponse, AlohaError> {
+    let viewer_id = get_session_viewer_id(&session)?;
     let transaction = pool.begin().await.unwrap();
-    match get_tweet_by_id(transaction, id.0).await {
-        Ok(Some(result)) => Ok(HttpResponse::Ok().json(TweetResponse::from(result))),
+    match get_tweet_by_id(transaction, id.0, viewer_id).await {
+        Ok(Some(result)) => match build_tweet_response(&pool, result, viewer_id).await {
+            Ok(response) => Ok(HttpResponse::Ok().json(response)),
+            Err(e) => Err(AlohaError::DatabaseError(e.to_string())),
+        },
         Ok(None) => Ok(HttpResponse::NotFound().finish()),
         Err(e) => Err(AlohaError::DatabaseError(e.to_string())),
     }
@@ -113,33 +278,62 @@ pub async fn get_tweet_route(
 
 #[derive(Deserialize, Clone, ToSchema)]
 pub struct PutTweetFormData {
-    pub id: Uuid,
     pub content: String,
 }
 
 #[utoipa::path(
     put,
     path = "/api/tweets/{id}",
+    params(
+        ("id" = Uuid, Path, description = "Tweet ID")
+    ),
     request_body = PutTweetFormData,
     responses(
         (status = 200, description = "Tweet updated successfully", body = TweetResponse),
+        (status = 202, description = "Tweet held for review by the content rules", body = TweetResponse),
+        (status = 400, description = "Tweet not found, edit window has passed or content rejected by the content rules", body = AlohaError),
+        (status = 401, description = "User is not logged in", body = AlohaError),
+        (status = 403, description = "User is not the author of the tweet", body = AlohaError),
         (status = 500, description = "Database error", body = AlohaError)
     )
 )]
 pub async fn update_tweet_route(
+    session: Session,
+    id: web::Path<(Uuid,)>,
     body: Json<PutTweetFormData>,
+    tweet_settings: Data<TweetSettings>,
     pool: Data<PgPool>,
 ) -> Result<HttpResponse, AlohaError> {
+    let user_id = get_session_user_id(&session)?;
+    let id = id.into_inner().0;
+    let transaction = pool.begin().await.unwrap();
+    let existing = match get_tweet_by_id(transaction, id, Some(user_id)).await {
+        Ok(Some(existing)) => existing,
+        Ok(None) => return Err(AlohaError::DatabaseError("Tweet not found".to_string())),
+        Err(e) => return Err(AlohaError::DatabaseError(e.to_string())),
+    };
+    if existing.user_id != user_id {
+        return Err(AlohaError::PermissionDenied);
+    }
+    let edit_window = Duration::minutes(tweet_settings.edit_window_minutes as i64);
+    if !existing.is_editable(edit_window, OffsetDateTime::now_utc()) {
+        return Err(AlohaError::RequestParameterInvalid(format!(
+            "Tweets can only be edited within {} minutes of posting.",
+            tweet_settings.edit_window_minutes
+        )));
+    }
+
     let transaction = pool.begin().await.unwrap();
     let tweet = Tweet {
-        id: body.id,
+        id,
         content: body.content.clone(),
         created_at: None,
         updated_at: None,
-        user_id: Uuid::nil(), // This will be ignored in the update query
+        user_id,
+        visibility: existing.visibility,
     };
     match update_tweet(transaction, &tweet).await {
-        Ok(result) => Ok(HttpResponse::Ok().json(TweetResponse::from(result))),
+        Ok(result) => written_tweet_response(&pool, result, Some(user_id)).await,
         Err(e) => Err(AlohaError::DatabaseError(e.to_string())),
     }
 }
@@ -191,6 +385,177 @@ pub async fn delete_tweet_route(
     }
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/tweets/{id}/restore",
+    params(
+        ("id" = Uuid, Path, description = "Tweet ID")
+    ),
+    responses(
+        (status = 200, description = "Tweet restored successfully", body = TweetResponse),
+        (status = 401, description = "User is not logged in", body = AlohaError),
+        (status = 403, description = "User is not an admin", body = AlohaError),
+        (status = 404, description = "No restorable deleted tweet with this ID"),
+        (status = 500, description = "Database error", body = AlohaError)
+    )
+)]
+pub async fn restore_tweet_route(
+    session: Session,
+    id: web::Path<(Uuid,)>,
+    pool: Data<PgPool>,
+) -> Result<HttpResponse, AlohaError> {
+    let admin_id = require_admin(&session, &pool).await?;
+    let transaction = pool.begin().await.unwrap();
+    match restore_tweet_by_id(transaction, id.0).await {
+        Ok(Some(result)) => match build_tweet_response(&pool, result, Some(admin_id)).await {
+            Ok(response) => Ok(HttpResponse::Ok().json(response)),
+            Err(e) => Err(AlohaError::DatabaseError(e.to_string())),
+        },
+        Ok(None) => Ok(HttpResponse::NotFound().finish()),
+        Err(e) => Err(AlohaError::DatabaseError(e.to_string())),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/tweets/{id}/revisions",
+    params(
+        ("id" = Uuid, Path, description = "Tweet ID")
+    ),
+    responses(
+        (status = 200, description = "Previous versions of the tweet, oldest first", body = DtoResponse<Vec<TweetRevisionResponse>>),
+        (status = 404, description = "Tweet not found or not visible to the viewer"),
+        (status = 500, description = "Database error", body = AlohaError)
+    )
+)]
+pub async fn get_tweet_revisions_route(
+    session: Session,
+    id: web::Path<(Uuid,)>,
+    pool: Data<PgPool>,
+) -> Result<HttpResponse, AlohaError> {
+    let viewer_id = get_session_viewer_id(&session)?;
+    let transaction = pool.begin().await.unwrap();
+    match get_tweet_by_id(transaction, id.0, viewer_id).await {
+        Ok(Some(_)) => {}
+        Ok(None) => return Ok(HttpResponse::NotFound().finish()),
+        Err(e) => return Err(AlohaError::DatabaseError(e.to_string())),
+    }
+
+    let transaction = pool.begin().await.unwrap();
+    match get_tweet_revisions(transaction, id.0).await {
+        Ok(result) => {
+            let response: Vec<TweetRevisionResponse> = result
+                .into_iter()
+                .map(TweetRevisionResponse::from)
+                .collect();
+            Ok(HttpResponse::Ok().json(DtoResponse::new(response, None)))
+        }
+        Err(e) => Err(AlohaError::DatabaseError(e.to_string())),
+    }
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/tweets/{id}/pin",
+    params(
+        ("id" = Uuid, Path, description = "Tweet ID")
+    ),
+    responses(
+        (status = 200, description = "Tweet pinned to the author's profile", body = TweetResponse),
+        (status = 400, description = "Database error", body = AlohaError),
+        (status = 401, description = "User is not logged in", body = AlohaError),
+        (status = 404, description = "Tweet not found or written by another user")
+    )
+)]
+pub async fn pin_tweet_route(
+    session: Session,
+    id: web::Path<(Uuid,)>,
+    pool: Data<PgPool>,
+) -> Result<HttpResponse, AlohaError> {
+    let user_id = get_session_user_id(&session)?;
+    let transaction = pool.begin().await.unwrap();
+    match pin_tweet(transaction, user_id, id.0).await {
+        Ok(Some(result)) => match build_tweet_response(&pool, result, Some(user_id)).await {
+            Ok(response) => Ok(HttpResponse::Ok().json(response)),
+            Err(e) => Err(AlohaError::DatabaseError(e.to_string())),
+        },
+        Ok(None) => Ok(HttpResponse::NotFound().finish()),
+        Err(e) => Err(AlohaError::DatabaseError(e.to_string())),
+    }
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/tweets/{id}/pin",
+    params(
+        ("id" = Uuid, Path, description = "Tweet ID")
+    ),
+    responses(
+        (status = 200, description = "Tweet unpinned"),
+        (status = 400, description = "Database error", body = AlohaError),
+        (status = 401, description = "User is not logged in", body = AlohaError),
+        (status = 404, description = "Tweet is not pinned by the user")
+    )
+)]
+pub async fn unpin_tweet_route(
+    session: Session,
+    id: web::Path<(Uuid,)>,
+    pool: Data<PgPool>,
+) -> Result<HttpResponse, AlohaError> {
+    let user_id = get_session_user_id(&session)?;
+    let transaction = pool.begin().await.unwrap();
+    match unpin_tweet(transaction, user_id, id.0).await {
+        Ok(true) => Ok(HttpResponse::Ok().finish()),
+        Ok(false) => Ok(HttpResponse::NotFound().finish()),
+        Err(e) => Err(AlohaError::DatabaseError(e.to_string())),
+    }
+}
+
+#[derive(Deserialize, Clone, ToSchema)]
+pub struct VotePollFormData {
+    option_id: Uuid,
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/tweets/{id}/poll/vote",
+    params(
+        ("id" = Uuid, Path, description = "Tweet ID")
+    ),
+    request_body = VotePollFormData,
+    responses(
+        (status = 200, description = "Vote recorded; the tweet with the poll results", body = TweetResponse),
+        (status = 400, description = "Poll closed, unknown option or already voted", body = AlohaError),
+        (status = 401, description = "User is not logged in", body = AlohaError),
+        (status = 404, description = "Tweet not found, not visible to the user or without a poll")
+    )
+)]
+pub async fn vote_poll_route(
+    session: Session,
+    id: web::Path<(Uuid,)>,
+    body: Json<VotePollFormData>,
+    pool: Data<PgPool>,
+) -> Result<HttpResponse, AlohaError> {
+    let user_id = get_session_user_id(&session)?;
+    let transaction = pool.begin().await.unwrap();
+    let tweet = match get_tweet_by_id(transaction, id.0, Some(user_id)).await {
+        Ok(Some(tweet)) => tweet,
+        Ok(None) => return Ok(HttpResponse::NotFound().finish()),
+        Err(e) => return Err(AlohaError::DatabaseError(e.to_string())),
+    };
+
+    let transaction = pool.begin().await.unwrap();
+    let now = OffsetDateTime::now_utc();
+    match insert_poll_vote(transaction, tweet.id, user_id, body.option_id, now).await {
+        Ok(Some(_)) => match build_tweet_response(&pool, tweet, Some(user_id)).await {
+            Ok(response) => Ok(HttpResponse::Ok().json(response)),
+            Err(e) => Err(AlohaError::DatabaseError(e.to_string())),
+        },
+        Ok(None) => Ok(HttpResponse::NotFound().finish()),
+        Err(e) => Err(AlohaError::DatabaseError(e.to_string())),
+    }
+}
+
 pub fn tweet_routes(cfg: &mut web::ServiceConfig) {
     let config = get_configuration().unwrap();
 
@@ -200,6 +565,13 @@ pub fn tweet_routes(cfg: &mut web::ServiceConfig) {
             .route("", web::get().to(get_all_tweets_route))
             .route("/{id}", web::get().to(get_tweet_route))
             .route("/{id}", web::put().to(update_tweet_route))
+            .route("/{id}/revisions", web::get().to(get_tweet_revisions_route))
+            .route("/{id}/restore", web::post().to(restore_tweet_route))
+            .route("/{id}/pin", web::put().to(pin_tweet_route))
+            .route("/{id}/pin", web::delete().to(unpin_tweet_route))
+            .route("/{id}/poll/vote", web::post().to(vote_poll_route))
+            .route("/{id}/bookmark", web::put().to(insert_bookmark_route))
+            .route("/{id}/bookmark", web::delete().to(delete_bookmark_route))
             .route("", web::delete().to(delete_tweets_route))
             .route("/{id}", web::delete().to(delete_tweet_route)),
     );
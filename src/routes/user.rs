@@ -1,16 +1,31 @@
 use crate::configuration::get_configuration;
-use crate::dto::query::{DtoQuery, UserFilterQuery};
+use crate::dto::query::{DtoQuery, TweetFilterQuery, UserFilterQuery};
 use crate::dto::response::DtoResponse;
 use crate::error::AlohaError;
+use crate::mappers::account::get_account_state;
+use crate::mappers::mention::get_tweets_mentioning_user;
+use crate::mappers::moderation::change_account_status;
 use crate::mappers::user::{
-    delete_user_by_id, delete_users_by_ids, get_all_users, get_user_by_id, insert_user, update_user,
+    delete_user_by_id, delete_users_by_ids, get_all_users, get_user_by_id, insert_user,
+    restore_user_by_id, update_user,
 };
+use crate::models::account::{
+    AccountStateResponse, AccountStatus, MAX_ACCOUNT_STATUS_REASON_LENGTH,
+};
+use crate::models::moderation::{ModerationAction, ModerationActionKind};
+use crate::models::tweet::TweetResponse;
 use crate::models::user::{User, UserResponse};
+use crate::routes::auth::{get_session_viewer_id, require_admin};
+use crate::routes::block::{delete_block_route, insert_block_route};
+use crate::routes::mute::{delete_mute_route, insert_mute_route};
+use crate::routes::tweet::build_tweet_responses;
+use actix_session::Session;
 use actix_web::web::{Data, Json};
 use actix_web::{web, HttpResponse};
 use serde::Deserialize;
 use serde_qs::actix::QsQuery;
 use sqlx::PgPool;
+use time::OffsetDateTime;
 use utoipa::ToSchema;
 use uuid::Uuid;
 
@@ -27,13 +42,20 @@ pub struct CreateUserFormData {
     request_body = CreateUserFormData,
     responses(
         (status = 200, description = "User created successfully", body = UserResponse),
+        (status = 401, description = "A user group is given and the user is not logged in", body = AlohaError),
+        (status = 403, description = "A user group is given and the user is not an admin", body = AlohaError),
         (status = 500, description = "Database error", body = AlohaError)
     )
 )]
 pub async fn insert_user_route(
+    session: Session,
     body: Json<CreateUserFormData>,
     pool: Data<PgPool>,
 ) -> Result<HttpResponse, AlohaError> {
+    // Groups carry permissions, so only admins may place users in one.
+    if body.user_group_id.is_some() {
+        require_admin(&session, &pool).await?;
+    }
     // In a real application, you would hash the password here
     let password_hash = body.password.clone(); // This should be properly hashed in production
     let transaction = pool.begin().await.unwrap();
@@ -113,13 +135,17 @@ pub struct PutUserFormData {
     request_body = PutUserFormData,
     responses(
         (status = 200, description = "User updated successfully", body = UserResponse),
+        (status = 401, description = "User is not logged in", body = AlohaError),
+        (status = 403, description = "User is not an admin", body = AlohaError),
         (status = 500, description = "Database error", body = AlohaError)
     )
 )]
 pub async fn update_user_route(
+    session: Session,
     body: Json<PutUserFormData>,
     pool: Data<PgPool>,
 ) -> Result<HttpResponse, AlohaError> {
+    require_admin(&session, &pool).await?;
     let transaction = pool.begin().await.unwrap();
 
     let find_user = match get_user_by_id(transaction, body.0.id).await {
@@ -199,12 +225,187 @@ pub async fn delete_user_route(
         Err(e) => Err(AlohaError::DatabaseError(e.to_string())),
     }
 }
+
+#[utoipa::path(
+    post,
+    path = "/api/users/{id}/restore",
+    params(
+        ("id" = Uuid, Path, description = "User ID")
+    ),
+    responses(
+        (status = 200, description = "User and the tweets deleted with them restored successfully", body = UserResponse),
+        (status = 401, description = "User is not logged in", body = AlohaError),
+        (status = 403, description = "User is not an admin", body = AlohaError),
+        (status = 404, description = "No deleted user with this ID"),
+        (status = 500, description = "Database error", body = AlohaError)
+    )
+)]
+pub async fn restore_user_route(
+    session: Session,
+    id: web::Path<(Uuid,)>,
+    pool: Data<PgPool>,
+) -> Result<HttpResponse, AlohaError> {
+    require_admin(&session, &pool).await?;
+    let transaction = pool.begin().await.unwrap();
+    match restore_user_by_id(transaction, id.0).await {
+        Ok(Some(result)) => Ok(HttpResponse::Ok().json(UserResponse::from(result))),
+        Ok(None) => Ok(HttpResponse::NotFound().finish()),
+        Err(e) => Err(AlohaError::DatabaseError(e.to_string())),
+    }
+}
+
+#[derive(Deserialize, Clone, ToSchema)]
+pub struct UpdateAccountStatusFormData {
+    status: AccountStatus,
+    /// Shown to the user when they try to log in.
+    #[serde(default)]
+    reason: Option<String>,
+    /// RFC 3339 timestamp ending a suspension; required for `suspended` and
+    /// ignored otherwise.
+    #[serde(default, with = "time::serde::rfc3339::option")]
+    #[schema(value_type = Option<String>)]
+    suspended_until: Option<OffsetDateTime>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/users/{id}/status",
+    params(
+        ("id" = Uuid, Path, description = "User ID")
+    ),
+    responses(
+        (status = 200, description = "Account status of the user", body = AccountStateResponse),
+        (status = 400, description = "Database error", body = AlohaError),
+        (status = 401, description = "User is not logged in", body = AlohaError),
+        (status = 403, description = "User is not an admin", body = AlohaError),
+        (status = 404, description = "User not found")
+    )
+)]
+pub async fn get_account_status_route(
+    session: Session,
+    id: web::Path<(Uuid,)>,
+    pool: Data<PgPool>,
+) -> Result<HttpResponse, AlohaError> {
+    require_admin(&session, &pool).await?;
+    let mut transaction = pool.begin().await.unwrap();
+    match get_account_state(&mut transaction, id.0).await {
+        Ok(Some(result)) => {
+            Ok(HttpResponse::Ok()
+                .json(AccountStateResponse::new(result, OffsetDateTime::now_utc())))
+        }
+        Ok(None) => Ok(HttpResponse::NotFound().finish()),
+        Err(e) => Err(AlohaError::DatabaseError(e.to_string())),
+    }
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/users/{id}/status",
+    params(
+        ("id" = Uuid, Path, description = "User ID")
+    ),
+    request_body = UpdateAccountStatusFormData,
+    responses(
+        (status = 200, description = "Account status changed; the change is logged as a moderation action", body = AccountStateResponse),
+        (status = 400, description = "Invalid transition, suspension end or reason, or database error", body = AlohaError),
+        (status = 401, description = "User is not logged in", body = AlohaError),
+        (status = 403, description = "User is not an admin", body = AlohaError),
+        (status = 404, description = "User not found")
+    )
+)]
+pub async fn update_account_status_route(
+    session: Session,
+    id: web::Path<(Uuid,)>,
+    body: Json<UpdateAccountStatusFormData>,
+    pool: Data<PgPool>,
+) -> Result<HttpResponse, AlohaError> {
+    let admin_id = require_admin(&session, &pool).await?;
+    if id.0 == admin_id {
+        return Err(AlohaError::RequestParameterInvalid(
+            "You cannot change the status of your own account.".to_string(),
+        ));
+    }
+    let now = OffsetDateTime::now_utc();
+    if body.status == AccountStatus::Suspended && body.suspended_until.is_none_or(|u| u <= now) {
+        return Err(AlohaError::RequestParameterInvalid(
+            "Suspensions need a suspended_until in the future.".to_string(),
+        ));
+    }
+    let reason = body
+        .reason
+        .as_deref()
+        .map(str::trim)
+        .filter(|r| !r.is_empty());
+    if reason.is_some_and(|r| r.chars().count() > MAX_ACCOUNT_STATUS_REASON_LENGTH) {
+        return Err(AlohaError::RequestParameterInvalid(format!(
+            "Reasons must be at most {} characters long.",
+            MAX_ACCOUNT_STATUS_REASON_LENGTH
+        )));
+    }
+    let action = ModerationAction {
+        user_id: Some(id.0),
+        note: reason.map(str::to_string),
+        ..ModerationAction::new(
+            admin_id,
+            ModerationActionKind::for_account_status(body.status),
+        )
+    };
+    let transaction = pool.begin().await.unwrap();
+    match change_account_status(transaction, &action, body.status, body.suspended_until).await {
+        Ok(Some(result)) => Ok(HttpResponse::Ok().json(AccountStateResponse::new(result, now))),
+        Ok(None) => Ok(HttpResponse::NotFound().finish()),
+        Err(e) => Err(AlohaError::DatabaseError(e.to_string())),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/users/{id}/mentions",
+    params(
+        ("id" = Uuid, Path, description = "User ID"),
+        ("page" = Option<i32>, Query, description = "Page number"),
+        ("size" = Option<i32>, Query, description = "Page size"),
+        ("user_id" = Option<Uuid>, Query, description = "Filter by tweet author ID")
+    ),
+    responses(
+        (status = 200, description = "Tweets mentioning the user retrieved successfully", body = DtoResponse<Vec<TweetResponse>>),
+        (status = 500, description = "Database error", body = AlohaError)
+    )
+)]
+pub async fn get_user_mentions_route(
+    session: Session,
+    id: web::Path<(Uuid,)>,
+    query: QsQuery<DtoQuery<TweetFilterQuery>>,
+    pool: Data<PgPool>,
+) -> Result<HttpResponse, AlohaError> {
+    let viewer_id = get_session_viewer_id(&session)?;
+    let user_id = id.0;
+    let transaction = pool.begin().await.unwrap();
+    match get_tweets_mentioning_user(transaction, user_id, query.into_inner(), viewer_id).await {
+        Ok(result) => match build_tweet_responses(&pool, result.data, viewer_id).await {
+            Ok(response) => {
+                Ok(HttpResponse::Ok().json(DtoResponse::new(response, result.pagination)))
+            }
+            Err(e) => Err(AlohaError::DatabaseError(e.to_string())),
+        },
+        Err(e) => Err(AlohaError::DatabaseError(e.to_string())),
+    }
+}
+
 pub fn user_routes(cfg: &mut web::ServiceConfig) {
     let config = get_configuration().unwrap();
     cfg.service(
         web::scope(format!("/{}", config.routes.users).as_str())
             .route("", web::post().to(insert_user_route))
             .route("/{id}", web::get().to(get_user_route))
+            .route("/{id}/mentions", web::get().to(get_user_mentions_route))
+            .route("/{id}/block", web::put().to(insert_block_route))
+            .route("/{id}/block", web::delete().to(delete_block_route))
+            .route("/{id}/mute", web::put().to(insert_mute_route))
+            .route("/{id}/mute", web::delete().to(delete_mute_route))
+            .route("/{id}/restore", web::post().to(restore_user_route))
+            .route("/{id}/status", web::get().to(get_account_status_route))
+            .route("/{id}/status", web::put().to(update_account_status_route))
             .route("", web::put().to(update_user_route))
             .route("", web::get().to(get_all_users_route))
             .route("/{id}", web::delete().to(delete_user_route))
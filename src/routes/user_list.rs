@@ -0,0 +1,341 @@
+use crate::configuration::get_configuration;
+use crate::dto::query::{DtoQuery, TweetFilterQuery, UserListFilterQuery};
+use crate::dto::response::DtoResponse;
+use crate::error::AlohaError;
+use crate::mappers::user_list::{
+    delete_user_list, delete_user_list_member, get_user_list_by_id, get_user_list_members,
+    get_user_list_timeline, get_user_lists_by_owner_id, insert_user_list, insert_user_list_member,
+    update_user_list,
+};
+use crate::models::tweet::TweetResponse;
+use crate::models::user::UserResponse;
+use crate::models::user_list::{UserList, UserListMemberResponse, UserListResponse};
+use crate::routes::auth::get_session_user_id;
+use crate::routes::tweet::build_tweet_responses;
+use actix_session::Session;
+use actix_web::web::{self, Data, Json};
+use actix_web::HttpResponse;
+use serde::Deserialize;
+use serde_qs::actix::QsQuery;
+use sqlx::PgPool;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+const MAX_LIST_NAME_LENGTH: usize = 64;
+
+#[derive(Deserialize, Clone, ToSchema)]
+pub struct UserListFormData {
+    name: String,
+    #[serde(default)]
+    description: Option<String>,
+}
+
+impl UserListFormData {
+    /// Trims the name and rejects empty or overly long ones.
+    fn name(&self) -> Result<String, AlohaError> {
+        let name = self.name.trim();
+        if name.is_empty() || name.chars().count() > MAX_LIST_NAME_LENGTH {
+            return Err(AlohaError::RequestParameterInvalid(format!(
+                "List names must be between 1 and {} characters long.",
+                MAX_LIST_NAME_LENGTH
+            )));
+        }
+        Ok(name.to_string())
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/lists",
+    request_body = UserListFormData,
+    responses(
+        (status = 200, description = "List created successfully", body = UserListResponse),
+        (status = 400, description = "Invalid name or database error", body = AlohaError),
+        (status = 401, description = "User is not logged in", body = AlohaError)
+    )
+)]
+pub async fn insert_user_list_route(
+    session: Session,
+    body: Json<UserListFormData>,
+    pool: Data<PgPool>,
+) -> Result<HttpResponse, AlohaError> {
+    let user_id = get_session_user_id(&session)?;
+    let list = UserList::new(user_id, body.name()?, body.description.clone());
+    let transaction = pool.begin().await.unwrap();
+    match insert_user_list(transaction, &list).await {
+        Ok(result) => Ok(HttpResponse::Ok().json(UserListResponse::from(result))),
+        Err(e) => Err(AlohaError::DatabaseError(e.to_string())),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/lists",
+    params(
+        ("page" = Option<i32>, Query, description = "Page number"),
+        ("size" = Option<i32>, Query, description = "Page size")
+    ),
+    responses(
+        (status = 200, description = "Lists of the logged-in user", body = DtoResponse<Vec<UserListResponse>>),
+        (status = 400, description = "Database error", body = AlohaError),
+        (status = 401, description = "User is not logged in", body = AlohaError)
+    )
+)]
+pub async fn get_user_lists_route(
+    session: Session,
+    query: QsQuery<DtoQuery<UserListFilterQuery>>,
+    pool: Data<PgPool>,
+) -> Result<HttpResponse, AlohaError> {
+    let user_id = get_session_user_id(&session)?;
+    let transaction = pool.begin().await.unwrap();
+    match get_user_lists_by_owner_id(transaction, user_id, query.into_inner()).await {
+        Ok(result) => {
+            let data: Vec<UserListResponse> = result
+                .data
+                .into_iter()
+                .map(UserListResponse::from)
+                .collect();
+            Ok(HttpResponse::Ok().json(DtoResponse::new(data, result.pagination)))
+        }
+        Err(e) => Err(AlohaError::DatabaseError(e.to_string())),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/lists/{id}",
+    params(
+        ("id" = Uuid, Path, description = "List ID")
+    ),
+    responses(
+        (status = 200, description = "List retrieved successfully", body = UserListResponse),
+        (status = 400, description = "Database error", body = AlohaError),
+        (status = 401, description = "User is not logged in", body = AlohaError),
+        (status = 404, description = "List not found or owned by another user")
+    )
+)]
+pub async fn get_user_list_route(
+    session: Session,
+    id: web::Path<(Uuid,)>,
+    pool: Data<PgPool>,
+) -> Result<HttpResponse, AlohaError> {
+    let user_id = get_session_user_id(&session)?;
+    let transaction = pool.begin().await.unwrap();
+    match get_user_list_by_id(transaction, user_id, id.0).await {
+        Ok(Some(result)) => Ok(HttpResponse::Ok().json(UserListResponse::from(result))),
+        Ok(None) => Ok(HttpResponse::NotFound().finish()),
+        Err(e) => Err(AlohaError::DatabaseError(e.to_string())),
+    }
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/lists/{id}",
+    params(
+        ("id" = Uuid, Path, description = "List ID")
+    ),
+    request_body = UserListFormData,
+    responses(
+        (status = 200, description = "List updated successfully", body = UserListResponse),
+        (status = 400, description = "Invalid name or database error", body = AlohaError),
+        (status = 401, description = "User is not logged in", body = AlohaError),
+        (status = 404, description = "List not found or owned by another user")
+    )
+)]
+pub async fn update_user_list_route(
+    session: Session,
+    id: web::Path<(Uuid,)>,
+    body: Json<UserListFormData>,
+    pool: Data<PgPool>,
+) -> Result<HttpResponse, AlohaError> {
+    let user_id = get_session_user_id(&session)?;
+    let list = UserList {
+        id: id.0,
+        ..UserList::new(user_id, body.name()?, body.description.clone())
+    };
+    let transaction = pool.begin().await.unwrap();
+    match update_user_list(transaction, &list).await {
+        Ok(Some(result)) => Ok(HttpResponse::Ok().json(UserListResponse::from(result))),
+        Ok(None) => Ok(HttpResponse::NotFound().finish()),
+        Err(e) => Err(AlohaError::DatabaseError(e.to_string())),
+    }
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/lists/{id}",
+    params(
+        ("id" = Uuid, Path, description = "List ID")
+    ),
+    responses(
+        (status = 200, description = "List deleted successfully", body = UserListResponse),
+        (status = 400, description = "Database error", body = AlohaError),
+        (status = 401, description = "User is not logged in", body = AlohaError),
+        (status = 404, description = "List not found or owned by another user")
+    )
+)]
+pub async fn delete_user_list_route(
+    session: Session,
+    id: web::Path<(Uuid,)>,
+    pool: Data<PgPool>,
+) -> Result<HttpResponse, AlohaError> {
+    let user_id = get_session_user_id(&session)?;
+    let transaction = pool.begin().await.unwrap();
+    match delete_user_list(transaction, user_id, id.0).await {
+        Ok(Some(result)) => Ok(HttpResponse::Ok().json(UserListResponse::from(result))),
+        Ok(None) => Ok(HttpResponse::NotFound().finish()),
+        Err(e) => Err(AlohaError::DatabaseError(e.to_string())),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/lists/{id}/members",
+    params(
+        ("id" = Uuid, Path, description = "List ID"),
+        ("page" = Option<i32>, Query, description = "Page number"),
+        ("size" = Option<i32>, Query, description = "Page size")
+    ),
+    responses(
+        (status = 200, description = "List members retrieved successfully", body = DtoResponse<Vec<UserResponse>>),
+        (status = 400, description = "Database error", body = AlohaError),
+        (status = 401, description = "User is not logged in", body = AlohaError),
+        (status = 404, description = "List not found or owned by another user")
+    )
+)]
+pub async fn get_user_list_members_route(
+    session: Session,
+    id: web::Path<(Uuid,)>,
+    query: QsQuery<DtoQuery<UserListFilterQuery>>,
+    pool: Data<PgPool>,
+) -> Result<HttpResponse, AlohaError> {
+    let user_id = get_session_user_id(&session)?;
+    let transaction = pool.begin().await.unwrap();
+    match get_user_list_members(transaction, user_id, id.0, query.into_inner()).await {
+        Ok(Some(result)) => {
+            let data: Vec<UserResponse> = result.data.into_iter().map(UserResponse::from).collect();
+            Ok(HttpResponse::Ok().json(DtoResponse::new(data, result.pagination)))
+        }
+        Ok(None) => Ok(HttpResponse::NotFound().finish()),
+        Err(e) => Err(AlohaError::DatabaseError(e.to_string())),
+    }
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/lists/{id}/members/{user_id}",
+    params(
+        ("id" = Uuid, Path, description = "List ID"),
+        ("user_id" = Uuid, Path, description = "ID of the user to add")
+    ),
+    responses(
+        (status = 200, description = "Member added successfully", body = UserListMemberResponse),
+        (status = 400, description = "Unknown user or database error", body = AlohaError),
+        (status = 401, description = "User is not logged in", body = AlohaError),
+        (status = 404, description = "List not found or owned by another user")
+    )
+)]
+pub async fn insert_user_list_member_route(
+    session: Session,
+    path: web::Path<(Uuid, Uuid)>,
+    pool: Data<PgPool>,
+) -> Result<HttpResponse, AlohaError> {
+    let user_id = get_session_user_id(&session)?;
+    let (list_id, member_id) = path.into_inner();
+    let transaction = pool.begin().await.unwrap();
+    match insert_user_list_member(transaction, user_id, list_id, member_id).await {
+        Ok(Some(result)) => Ok(HttpResponse::Ok().json(UserListMemberResponse::from(result))),
+        Ok(None) => Ok(HttpResponse::NotFound().finish()),
+        Err(e) => Err(AlohaError::DatabaseError(e.to_string())),
+    }
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/lists/{id}/members/{user_id}",
+    params(
+        ("id" = Uuid, Path, description = "List ID"),
+        ("user_id" = Uuid, Path, description = "ID of the user to remove")
+    ),
+    responses(
+        (status = 200, description = "Member removed successfully", body = UserListMemberResponse),
+        (status = 400, description = "Database error", body = AlohaError),
+        (status = 401, description = "User is not logged in", body = AlohaError),
+        (status = 404, description = "List not found, owned by another user, or user is not a member")
+    )
+)]
+pub async fn delete_user_list_member_route(
+    session: Session,
+    path: web::Path<(Uuid, Uuid)>,
+    pool: Data<PgPool>,
+) -> Result<HttpResponse, AlohaError> {
+    let user_id = get_session_user_id(&session)?;
+    let (list_id, member_id) = path.into_inner();
+    let transaction = pool.begin().await.unwrap();
+    match delete_user_list_member(transaction, user_id, list_id, member_id).await {
+        Ok(Some(result)) => Ok(HttpResponse::Ok().json(UserListMemberResponse::from(result))),
+        Ok(None) => Ok(HttpResponse::NotFound().finish()),
+        Err(e) => Err(AlohaError::DatabaseError(e.to_string())),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/lists/{id}/timeline",
+    params(
+        ("id" = Uuid, Path, description = "List ID"),
+        ("page" = Option<i32>, Query, description = "Page number"),
+        ("size" = Option<i32>, Query, description = "Page size"),
+        ("user_id" = Option<Uuid>, Query, description = "Filter by tweet author ID")
+    ),
+    responses(
+        (status = 200, description = "Tweets of the list members, newest first", body = DtoResponse<Vec<TweetResponse>>),
+        (status = 400, description = "Database error", body = AlohaError),
+        (status = 401, description = "User is not logged in", body = AlohaError),
+        (status = 404, description = "List not found or owned by another user")
+    )
+)]
+pub async fn get_user_list_timeline_route(
+    session: Session,
+    id: web::Path<(Uuid,)>,
+    query: QsQuery<DtoQuery<TweetFilterQuery>>,
+    pool: Data<PgPool>,
+) -> Result<HttpResponse, AlohaError> {
+    let user_id = get_session_user_id(&session)?;
+    let transaction = pool.begin().await.unwrap();
+    match get_user_list_timeline(transaction, user_id, id.0, query.into_inner()).await {
+        Ok(Some(result)) => match build_tweet_responses(&pool, result.data, Some(user_id)).await {
+            Ok(response) => {
+                Ok(HttpResponse::Ok().json(DtoResponse::new(response, result.pagination)))
+            }
+            Err(e) => Err(AlohaError::DatabaseError(e.to_string())),
+        },
+        Ok(None) => Ok(HttpResponse::NotFound().finish()),
+        Err(e) => Err(AlohaError::DatabaseError(e.to_string())),
+    }
+}
+
+pub fn user_list_routes(cfg: &mut web::ServiceConfig) {
+    let config = get_configuration().unwrap();
+    cfg.service(
+        web::scope(format!("/{}", config.routes.lists).as_str())
+            .route("", web::post().to(insert_user_list_route))
+            .route("", web::get().to(get_user_lists_route))
+            .route("/{id}", web::get().to(get_user_list_route))
+            .route("/{id}", web::put().to(update_user_list_route))
+            .route("/{id}", web::delete().to(delete_user_list_route))
+            .route("/{id}/members", web::get().to(get_user_list_members_route))
+            .route(
+                "/{id}/members/{user_id}",
+                web::put().to(insert_user_list_member_route),
+            )
+            .route(
+                "/{id}/members/{user_id}",
+                web::delete().to(delete_user_list_member_route),
+            )
+            .route(
+                "/{id}/timeline",
+                web::get().to(get_user_list_timeline_route),
+            ),
+    );
+}
@@ -8,6 +8,8 @@ use crate::mappers::user_permission::{
     get_user_permissions_by_permission_id, get_user_permissions_by_user_id, insert_user_permission,
 };
 use crate::models::user_permission::{UserPermission, UserPermissionResponse};
+use crate::routes::auth::require_admin;
+use actix_session::Session;
 use actix_web::web::{self, Data, Json, Path, Query};
 use actix_web::HttpResponse;
 use serde::Deserialize;
@@ -27,13 +29,17 @@ pub struct CreateUserPermissionFormData {
     request_body = CreateUserPermissionFormData,
     responses(
         (status = 200, description = "User permission created successfully", body = UserPermission),
-        (status = 400, description = "Database error", body = AlohaError)
+        (status = 400, description = "Database error", body = AlohaError),
+        (status = 401, description = "User is not logged in", body = AlohaError),
+        (status = 403, description = "User is not an admin", body = AlohaError)
     )
 )]
 pub async fn insert_user_permission_route(
+    session: Session,
     body: Json<CreateUserPermissionFormData>,
     pool: Data<PgPool>,
 ) -> Result<HttpResponse, AlohaError> {
+    require_admin(&session, &pool).await?;
     let transaction = pool.begin().await.unwrap();
     let user_permission = UserPermission::from(body.0);
     match insert_user_permission(transaction, &user_permission).await {
@@ -140,13 +146,17 @@ pub struct DeleteUserPermissionFormData {
     request_body = DeleteUserPermissionFormData,
     responses(
         (status = 200, description = "User permission deleted successfully", body = UserPermission),
-        (status = 400, description = "Database error", body = AlohaError)
+        (status = 400, description = "Database error", body = AlohaError),
+        (status = 401, description = "User is not logged in", body = AlohaError),
+        (status = 403, description = "User is not an admin", body = AlohaError)
     )
 )]
 pub async fn delete_user_permission_route(
+    session: Session,
     body: Json<DeleteUserPermissionFormData>,
     pool: Data<PgPool>,
 ) -> Result<HttpResponse, AlohaError> {
+    require_admin(&session, &pool).await?;
     let transaction = pool.begin().await.unwrap();
     match delete_user_permission(transaction, body.user_id, body.permission_id).await {
         Ok(result) => Ok(HttpResponse::Ok().json(UserPermissionResponse::from(result))),
@@ -162,13 +172,17 @@ pub async fn delete_user_permission_route(
     ),
     responses(
         (status = 200, description = "User permissions deleted successfully", body = Vec<UserPermission>),
-        (status = 400, description = "Database error", body = AlohaError)
+        (status = 400, description = "Database error", body = AlohaError),
+        (status = 401, description = "User is not logged in", body = AlohaError),
+        (status = 403, description = "User is not an admin", body = AlohaError)
     )
 )]
 pub async fn delete_user_permissions_by_user_id_route(
+    session: Session,
     user_id: Path<Uuid>,
     pool: Data<PgPool>,
 ) -> Result<HttpResponse, AlohaError> {
+    require_admin(&session, &pool).await?;
     let transaction = pool.begin().await.unwrap();
     match delete_user_permissions_by_user_id(transaction, *user_id).await {
         Ok(user_permissions) => {
@@ -190,13 +204,17 @@ pub async fn delete_user_permissions_by_user_id_route(
     ),
     responses(
         (status = 200, description = "User permissions deleted successfully", body = Vec<UserPermission>),
-        (status = 400, description = "Database error", body = AlohaError)
+        (status = 400, description = "Database error", body = AlohaError),
+        (status = 401, description = "User is not logged in", body = AlohaError),
+        (status = 403, description = "User is not an admin", body = AlohaError)
     )
 )]
 pub async fn delete_user_permissions_by_permission_id_route(
+    session: Session,
     permission_id: Path<Uuid>,
     pool: Data<PgPool>,
 ) -> Result<HttpResponse, AlohaError> {
+    require_admin(&session, &pool).await?;
     let transaction = pool.begin().await.unwrap();
     match delete_user_permissions_by_permission_id(transaction, *permission_id).await {
         Ok(user_permissions) => {
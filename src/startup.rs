@@ -1,6 +1,13 @@
 use crate::api_doc::ApiDoc;
 use crate::configuration::{DatabaseSettings, Settings};
+use crate::jobs::gateway::Gateway;
+use crate::jobs::link_preview::run_link_preview_job;
+use crate::jobs::retention::run_retention_job;
+use crate::jobs::scheduler::run_scheduler_job;
+use crate::jobs::stream::{run_stream_job, StreamBroker};
 use crate::routes::api_routes;
+use crate::routes::auth::end_inactive_sessions;
+use crate::storage::{build_media_store, MediaStore};
 use utoipa::OpenApi;
 
 use crate::routes::health_check::health_check;
@@ -10,6 +17,7 @@ use actix_session::storage::RedisSessionStore;
 use actix_session::SessionMiddleware;
 use actix_web::cookie::Key;
 use actix_web::dev::Server;
+use actix_web::middleware::from_fn;
 use actix_web::web::Data;
 use actix_web::{web, App, HttpServer};
 use actix_web_flash_messages::storage::CookieMessageStore;
@@ -42,19 +50,12 @@ impl Application {
         );
         let listener = TcpListener::bind(address)?;
         let port = listener.local_addr()?.port();
-        match run(
-            listener,
-            connection_pool,
-            configuration.application.base_url,
-            configuration.application.hmac_secret,
-            configuration.redis_uri,
-        )
-        .await
-        {
+        let endpoint = configuration.application.endpoint.clone();
+        match run(listener, connection_pool, configuration).await {
             Ok(server) => Ok(Self {
                 port,
                 server,
-                endpoint: configuration.application.endpoint,
+                endpoint,
             }),
             Err(e) => {
                 tracing::log::error!("Failed to start server: {}", e);
@@ -99,18 +100,46 @@ pub fn get_connection_pool(configuration: &DatabaseSettings) -> PgPool {
 pub async fn run(
     listener: TcpListener,
     db_pool: PgPool,
-    base_url: String,
-    hmac_secret: SecretString,
-    redis_uri: SecretString,
+    configuration: Settings,
 ) -> Result<Server, anyhow::Error> {
+    let hmac_secret = configuration.application.hmac_secret;
+    let redis_uri = configuration.redis_uri;
+    tokio::spawn(run_retention_job(db_pool.clone(), configuration.retention));
+    tokio::spawn(run_scheduler_job(
+        db_pool.clone(),
+        configuration.tweets.clone(),
+    ));
+    if configuration.link_previews.enabled {
+        tokio::spawn(run_link_preview_job(
+            db_pool.clone(),
+            configuration.link_previews.clone(),
+        ));
+    }
+    let stream_broker = Data::new(StreamBroker::new(
+        configuration.stream.channel_capacity as usize,
+    ));
+    let gateway = Data::new(Gateway::new(configuration.gateway.send_queue as usize));
+    tokio::spawn(run_stream_job(
+        db_pool.clone(),
+        stream_broker.clone().into_inner(),
+        gateway.clone().into_inner(),
+    ));
+    let stream_settings = Data::new(configuration.stream);
+    let gateway_settings = Data::new(configuration.gateway);
+    let pagination_settings = Data::new(configuration.pagination);
     let db_pool = web::Data::new(db_pool);
-    let base_url = Data::new(ApplicationBaseUrl(base_url));
+    let hashtag_settings = Data::new(configuration.hashtags);
+    let media_store: Data<dyn MediaStore> = Data::from(build_media_store(&configuration.media)?);
+    let media_settings = Data::new(configuration.media);
+    let tweet_settings = Data::new(configuration.tweets);
+    let base_url = Data::new(ApplicationBaseUrl(configuration.application.base_url));
     let secret_key = Key::from(hmac_secret.expose_secret().as_bytes());
     let message_store = CookieMessageStore::builder(secret_key.clone()).build();
     let redis_store = RedisSessionStore::new(redis_uri.expose_secret()).await?;
     let message_framework = FlashMessagesFramework::builder(message_store).build();
     match HttpServer::new(move || {
         App::new()
+            .wrap(from_fn(end_inactive_sessions))
             .wrap(message_framework.clone())
             .wrap(SessionMiddleware::new(
                 redis_store.clone(),
@@ -133,6 +162,15 @@ pub async fn run(
             .app_data(db_pool.clone())
             .app_data(base_url.clone())
             .app_data(Data::new(HmacSecret(hmac_secret.clone())))
+            .app_data(hashtag_settings.clone())
+            .app_data(media_settings.clone())
+            .app_data(media_store.clone())
+            .app_data(tweet_settings.clone())
+            .app_data(stream_broker.clone())
+            .app_data(stream_settings.clone())
+            .app_data(gateway.clone())
+            .app_data(gateway_settings.clone())
+            .app_data(pagination_settings.clone())
     })
     .listen(listener)
     {
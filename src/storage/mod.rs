@@ -0,0 +1,34 @@
+use crate::configuration::{MediaSettings, MediaStorage};
+use async_trait::async_trait;
+use std::sync::Arc;
+
+pub mod local;
+pub mod s3;
+
+pub use local::LocalMediaStore;
+pub use s3::S3MediaStore;
+
+/// Blob storage for uploaded media. Keys are generated by the application
+/// (the hex SHA-256 of the content), never taken from user input.
+#[async_trait]
+pub trait MediaStore: Send + Sync {
+    async fn put(&self, key: &str, content_type: &str, bytes: Vec<u8>)
+        -> Result<(), anyhow::Error>;
+
+    /// Returns `Ok(None)` when nothing is stored under `key`.
+    async fn get(&self, key: &str) -> Result<Option<Vec<u8>>, anyhow::Error>;
+
+    async fn delete(&self, key: &str) -> Result<(), anyhow::Error>;
+}
+
+pub fn build_media_store(settings: &MediaSettings) -> Result<Arc<dyn MediaStore>, anyhow::Error> {
+    match settings.storage {
+        MediaStorage::Local => Ok(Arc::new(LocalMediaStore::new(&settings.local_path))),
+        MediaStorage::S3 => {
+            let s3 = settings.s3.as_ref().ok_or_else(|| {
+                anyhow::anyhow!("`media.s3` settings are required for S3 storage")
+            })?;
+            Ok(Arc::new(S3MediaStore::new(s3.clone())?))
+        }
+    }
+}
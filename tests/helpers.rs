@@ -1,15 +1,31 @@
 use aloha_backend::configuration::{get_configuration, DatabaseSettings};
+use aloha_backend::dto::cursor::{CursorQuery, CursorResponse};
 use aloha_backend::dto::query::{
-    DtoQuery, GroupPermissionFilterQuery, PermissionFilterQuery, TweetFilterQuery, UserFilterQuery,
+    BlockFilterQuery, ContentRuleFilterQuery, DraftFilterQuery, DtoQuery,
+    GroupPermissionFilterQuery, ModerationActionFilterQuery, MuteFilterQuery,
+    PermissionFilterQuery, ReportFilterQuery, SearchQuery, TweetFilterQuery, UserFilterQuery,
     UserGroupFilterQuery, UserPermissionFilterQuery,
 };
 use aloha_backend::dto::response::DtoResponse;
+use aloha_backend::mappers::permission::insert_permission;
+use aloha_backend::mappers::user::insert_user;
+use aloha_backend::mappers::user_permission::insert_user_permission;
+use aloha_backend::models::conversation::ConversationResponse;
+use aloha_backend::models::draft::TweetDraftResponse;
 use aloha_backend::models::group_permission::GroupPermissionResponse;
-use aloha_backend::models::permission::PermissionResponse;
+use aloha_backend::models::hashtag::TrendingHashtag;
+use aloha_backend::models::media::MediaResponse;
+use aloha_backend::models::notification::{NotificationCountResponse, NotificationResponse};
+use aloha_backend::models::permission::{Permission, PermissionResponse};
+use aloha_backend::models::report::ReportStatus;
+use aloha_backend::models::subscription::SubscriptionResponse;
 use aloha_backend::models::tweet::TweetResponse;
-use aloha_backend::models::user::UserResponse;
+use aloha_backend::models::tweet_revision::TweetRevisionResponse;
+use aloha_backend::models::user::{User, UserResponse};
 use aloha_backend::models::user_group::UserGroupResponse;
-use aloha_backend::models::user_permission::UserPermissionResponse;
+use aloha_backend::models::user_list::UserListResponse;
+use aloha_backend::models::user_permission::{UserPermission, UserPermissionResponse};
+use aloha_backend::routes::auth::ADMIN_PERMISSION;
 use aloha_backend::startup::{get_connection_pool, Application};
 use argon2::password_hash::rand_core::OsRng;
 use argon2::password_hash::SaltString;
@@ -18,9 +34,13 @@ use once_cell::sync::Lazy;
 use sqlx::{Connection, Executor, PgConnection, PgPool};
 use std::collections::HashMap;
 use std::net::TcpStream;
+use tokio_tungstenite::tungstenite::client::IntoClientRequest;
 use tracing::info;
 use uuid::Uuid;
 
+pub type GatewaySocket =
+    tokio_tungstenite::WebSocketStream<tokio_tungstenite::MaybeTlsStream<tokio::net::TcpStream>>;
+
 static TRACING: Lazy<()> = Lazy::new(|| {
     let _default_filter_level = "info".to_string();
 });
@@ -500,9 +520,91 @@ impl TestApp {
             .await
     }
 
+    pub async fn get_user_tweets(
+        &self,
+        user_id: Uuid,
+    ) -> reqwest::Result<DtoResponse<Vec<TweetResponse>>> {
+        let query = DtoQuery {
+            filter: Some(TweetFilterQuery {
+                user_id: Some(user_id),
+            }),
+            ..DtoQuery::default_query()
+        };
+        self.api_client
+            .get(format!(
+                "{}/tweets?{}",
+                self.address,
+                serde_qs::to_string(&query).unwrap()
+            ))
+            .send()
+            .await
+            .expect("Failed to execute request.")
+            .json::<DtoResponse<Vec<TweetResponse>>>()
+            .await
+    }
+
+    pub async fn pin_tweet(&self, id: Uuid) -> reqwest::Response {
+        self.api_client
+            .put(format!("{}/tweets/{}/pin", self.address, id))
+            .send()
+            .await
+            .expect("Failed to execute request.")
+    }
+
+    pub async fn unpin_tweet(&self, id: Uuid) -> reqwest::Response {
+        self.api_client
+            .delete(format!("{}/tweets/{}/pin", self.address, id))
+            .send()
+            .await
+            .expect("Failed to execute request.")
+    }
+
+    pub async fn get_tweet_response(&self, id: Uuid) -> reqwest::Response {
+        self.api_client
+            .get(format!("{}/tweets/{}", self.address, id))
+            .send()
+            .await
+            .expect("Failed to execute request.")
+    }
+
+    pub async fn post_tweet_response(&self, body: &serde_json::Value) -> reqwest::Response {
+        self.api_client
+            .post(format!("{}/tweets", self.address))
+            .json(body)
+            .send()
+            .await
+            .expect("Failed to execute request.")
+    }
+
+    pub async fn vote_poll(&self, id: Uuid, body: &serde_json::Value) -> reqwest::Response {
+        self.api_client
+            .post(format!("{}/tweets/{}/poll/vote", self.address, id))
+            .json(body)
+            .send()
+            .await
+            .expect("Failed to execute request.")
+    }
+
+    pub async fn put_tweet_response(&self, body: &serde_json::Value) -> reqwest::Response {
+        self.api_client
+            .put(format!(
+                "{}/tweets/{}",
+                self.address,
+                body["id"].as_str().unwrap()
+            ))
+            .json(body)
+            .send()
+            .await
+            .expect("Failed to execute request.")
+    }
+
     pub async fn put_tweet(&self, body: &serde_json::Value) -> reqwest::Result<TweetResponse> {
         self.api_client
-            .put(format!("{}/tweets/{}", self.address, body["id"]))
+            .put(format!(
+                "{}/tweets/{}",
+                self.address,
+                body["id"].as_str().unwrap()
+            ))
             .header("Content-Type", "application/json")
             .json(body)
             .send()
@@ -512,6 +614,19 @@ impl TestApp {
             .await
     }
 
+    pub async fn get_tweet_revisions(
+        &self,
+        id: Uuid,
+    ) -> reqwest::Result<DtoResponse<Vec<TweetRevisionResponse>>> {
+        self.api_client
+            .get(format!("{}/tweets/{}/revisions", self.address, id))
+            .send()
+            .await
+            .expect("Failed to execute request.")
+            .json::<DtoResponse<Vec<TweetRevisionResponse>>>()
+            .await
+    }
+
     pub async fn delete_tweets(&self, ids: &[Uuid]) -> reqwest::Result<Vec<TweetResponse>> {
         self.api_client
             .delete(format!("{}/tweets", self.address))
@@ -533,6 +648,741 @@ impl TestApp {
             .json::<TweetResponse>()
             .await
     }
+
+    pub async fn restore_tweet(&self, id: Uuid) -> reqwest::Response {
+        self.api_client
+            .post(format!("{}/tweets/{}/restore", self.address, id))
+            .send()
+            .await
+            .expect("Failed to execute request.")
+    }
+
+    pub async fn restore_user(&self, id: Uuid) -> reqwest::Response {
+        self.api_client
+            .post(format!("{}/users/{}/restore", self.address, id))
+            .send()
+            .await
+            .expect("Failed to execute request.")
+    }
+
+    pub async fn post_draft(&self, body: &serde_json::Value) -> reqwest::Response {
+        self.api_client
+            .post(format!("{}/drafts", self.address))
+            .json(body)
+            .send()
+            .await
+            .expect("Failed to execute request.")
+    }
+
+    pub async fn get_drafts(
+        &self,
+        query: &DtoQuery<DraftFilterQuery>,
+    ) -> reqwest::Result<DtoResponse<Vec<TweetDraftResponse>>> {
+        self.api_client
+            .get(format!(
+                "{}/drafts?{}",
+                self.address,
+                serde_qs::to_string(query).unwrap()
+            ))
+            .send()
+            .await
+            .expect("Failed to execute request.")
+            .json::<DtoResponse<Vec<TweetDraftResponse>>>()
+            .await
+    }
+
+    pub async fn put_draft(&self, id: Uuid, body: &serde_json::Value) -> reqwest::Response {
+        self.api_client
+            .put(format!("{}/drafts/{}", self.address, id))
+            .json(body)
+            .send()
+            .await
+            .expect("Failed to execute request.")
+    }
+
+    pub async fn publish_draft(&self, id: Uuid) -> reqwest::Response {
+        self.api_client
+            .post(format!("{}/drafts/{}/publish", self.address, id))
+            .send()
+            .await
+            .expect("Failed to execute request.")
+    }
+
+    pub async fn put_bookmark(&self, tweet_id: Uuid) -> reqwest::Response {
+        self.api_client
+            .put(format!("{}/tweets/{}/bookmark", self.address, tweet_id))
+            .send()
+            .await
+            .expect("Failed to execute request.")
+    }
+
+    pub async fn delete_bookmark(&self, tweet_id: Uuid) -> reqwest::Response {
+        self.api_client
+            .delete(format!("{}/tweets/{}/bookmark", self.address, tweet_id))
+            .send()
+            .await
+            .expect("Failed to execute request.")
+    }
+
+    pub async fn get_bookmarks(&self) -> reqwest::Result<DtoResponse<Vec<TweetResponse>>> {
+        self.api_client
+            .get(format!("{}/bookmarks", self.address))
+            .query(&DtoQuery::<TweetFilterQuery>::default_query())
+            .send()
+            .await
+            .expect("Failed to execute request.")
+            .json::<DtoResponse<Vec<TweetResponse>>>()
+            .await
+    }
+
+    pub async fn post_list(&self, body: &serde_json::Value) -> reqwest::Response {
+        self.api_client
+            .post(format!("{}/lists", self.address))
+            .json(body)
+            .send()
+            .await
+            .expect("Failed to execute request.")
+    }
+
+    pub async fn get_lists(&self) -> reqwest::Result<DtoResponse<Vec<UserListResponse>>> {
+        self.api_client
+            .get(format!("{}/lists", self.address))
+            .send()
+            .await
+            .expect("Failed to execute request.")
+            .json::<DtoResponse<Vec<UserListResponse>>>()
+            .await
+    }
+
+    pub async fn get_list(&self, id: Uuid) -> reqwest::Response {
+        self.api_client
+            .get(format!("{}/lists/{}", self.address, id))
+            .send()
+            .await
+            .expect("Failed to execute request.")
+    }
+
+    pub async fn put_list(&self, id: Uuid, body: &serde_json::Value) -> reqwest::Response {
+        self.api_client
+            .put(format!("{}/lists/{}", self.address, id))
+            .json(body)
+            .send()
+            .await
+            .expect("Failed to execute request.")
+    }
+
+    pub async fn delete_list(&self, id: Uuid) -> reqwest::Response {
+        self.api_client
+            .delete(format!("{}/lists/{}", self.address, id))
+            .send()
+            .await
+            .expect("Failed to execute request.")
+    }
+
+    pub async fn put_list_member(&self, id: Uuid, user_id: Uuid) -> reqwest::Response {
+        self.api_client
+            .put(format!("{}/lists/{}/members/{}", self.address, id, user_id))
+            .send()
+            .await
+            .expect("Failed to execute request.")
+    }
+
+    pub async fn delete_list_member(&self, id: Uuid, user_id: Uuid) -> reqwest::Response {
+        self.api_client
+            .delete(format!("{}/lists/{}/members/{}", self.address, id, user_id))
+            .send()
+            .await
+            .expect("Failed to execute request.")
+    }
+
+    pub async fn get_list_members(&self, id: Uuid) -> reqwest::Response {
+        self.api_client
+            .get(format!("{}/lists/{}/members", self.address, id))
+            .send()
+            .await
+            .expect("Failed to execute request.")
+    }
+
+    pub async fn get_list_timeline(&self, id: Uuid) -> reqwest::Response {
+        self.api_client
+            .get(format!("{}/lists/{}/timeline", self.address, id))
+            .send()
+            .await
+            .expect("Failed to execute request.")
+    }
+
+    pub async fn put_block(&self, user_id: Uuid) -> reqwest::Response {
+        self.api_client
+            .put(format!("{}/users/{}/block", self.address, user_id))
+            .send()
+            .await
+            .expect("Failed to execute request.")
+    }
+
+    pub async fn delete_block(&self, user_id: Uuid) -> reqwest::Response {
+        self.api_client
+            .delete(format!("{}/users/{}/block", self.address, user_id))
+            .send()
+            .await
+            .expect("Failed to execute request.")
+    }
+
+    pub async fn get_blocks(&self) -> reqwest::Result<DtoResponse<Vec<UserResponse>>> {
+        self.api_client
+            .get(format!("{}/blocks", self.address))
+            .query(&DtoQuery::<BlockFilterQuery>::default_query())
+            .send()
+            .await
+            .expect("Failed to execute request.")
+            .json::<DtoResponse<Vec<UserResponse>>>()
+            .await
+    }
+
+    pub async fn put_mute(&self, user_id: Uuid) -> reqwest::Response {
+        self.api_client
+            .put(format!("{}/users/{}/mute", self.address, user_id))
+            .send()
+            .await
+            .expect("Failed to execute request.")
+    }
+
+    pub async fn delete_mute(&self, user_id: Uuid) -> reqwest::Response {
+        self.api_client
+            .delete(format!("{}/users/{}/mute", self.address, user_id))
+            .send()
+            .await
+            .expect("Failed to execute request.")
+    }
+
+    pub async fn get_mutes(&self) -> reqwest::Result<DtoResponse<Vec<UserResponse>>> {
+        self.api_client
+            .get(format!("{}/mutes", self.address))
+            .query(&DtoQuery::<MuteFilterQuery>::default_query())
+            .send()
+            .await
+            .expect("Failed to execute request.")
+            .json::<DtoResponse<Vec<UserResponse>>>()
+            .await
+    }
+
+    pub async fn post_report(&self, body: &serde_json::Value) -> reqwest::Response {
+        self.api_client
+            .post(format!("{}/reports", self.address))
+            .json(body)
+            .send()
+            .await
+            .expect("Failed to execute request.")
+    }
+
+    pub async fn get_reports(&self, status: Option<ReportStatus>) -> reqwest::Response {
+        let query = DtoQuery {
+            filter: Some(ReportFilterQuery { status }),
+            ..DtoQuery::default_query()
+        };
+        self.api_client
+            .get(format!(
+                "{}/reports?{}",
+                self.address,
+                serde_qs::to_string(&query).unwrap()
+            ))
+            .send()
+            .await
+            .expect("Failed to execute request.")
+    }
+
+    pub async fn put_report(&self, id: Uuid, body: &serde_json::Value) -> reqwest::Response {
+        self.api_client
+            .put(format!("{}/reports/{}", self.address, id))
+            .json(body)
+            .send()
+            .await
+            .expect("Failed to execute request.")
+    }
+
+    pub async fn hide_tweet(&self, id: Uuid, body: &serde_json::Value) -> reqwest::Response {
+        self.api_client
+            .post(format!("{}/moderation/tweets/{}/hide", self.address, id))
+            .json(body)
+            .send()
+            .await
+            .expect("Failed to execute request.")
+    }
+
+    /// Grants `user_id` the named permissions, creating them first.
+    pub async fn grant_permissions(&self, user_id: Uuid, names: &[&str]) {
+        for name in names {
+            let transaction = self.db_pool.begin().await.unwrap();
+            let permission =
+                insert_permission(transaction, &Permission::new(name.to_string(), None))
+                    .await
+                    .unwrap();
+            let transaction = self.db_pool.begin().await.unwrap();
+            insert_user_permission(transaction, &UserPermission::new(user_id, permission.id))
+                .await
+                .unwrap();
+        }
+    }
+
+    /// Creates a user holding the admin permission and logs `api_client` in
+    /// as them.
+    pub async fn login_admin(&self) -> User {
+        let admin = User::new(Uuid::new_v4().to_string(), String::from("password"), None);
+        let transaction = self.db_pool.begin().await.unwrap();
+        insert_user(transaction, &admin).await.unwrap();
+        self.grant_permissions(admin.id, &[ADMIN_PERMISSION]).await;
+        self.login(&serde_json::json!({
+            "username": admin.username,
+            "password": admin.password_hash
+        }))
+        .await
+        .unwrap();
+        admin
+    }
+
+    pub async fn release_tweet(&self, id: Uuid, body: &serde_json::Value) -> reqwest::Response {
+        self.api_client
+            .post(format!("{}/moderation/tweets/{}/release", self.address, id))
+            .json(body)
+            .send()
+            .await
+            .expect("Failed to execute request.")
+    }
+
+    pub async fn get_held_tweets(&self) -> reqwest::Response {
+        self.api_client
+            .get(format!("{}/moderation/held-tweets", self.address))
+            .query(&DtoQuery::<TweetFilterQuery>::default_query())
+            .send()
+            .await
+            .expect("Failed to execute request.")
+    }
+
+    pub async fn post_content_rule(&self, body: &serde_json::Value) -> reqwest::Response {
+        self.api_client
+            .post(format!("{}/content_rules", self.address))
+            .json(body)
+            .send()
+            .await
+            .expect("Failed to execute request.")
+    }
+
+    pub async fn get_content_rules(&self) -> reqwest::Response {
+        self.api_client
+            .get(format!("{}/content_rules", self.address))
+            .query(&DtoQuery::<ContentRuleFilterQuery>::default_query())
+            .send()
+            .await
+            .expect("Failed to execute request.")
+    }
+
+    pub async fn put_content_rule(&self, id: Uuid, body: &serde_json::Value) -> reqwest::Response {
+        self.api_client
+            .put(format!("{}/content_rules/{}", self.address, id))
+            .json(body)
+            .send()
+            .await
+            .expect("Failed to execute request.")
+    }
+
+    pub async fn test_content_rules(&self, body: &serde_json::Value) -> reqwest::Response {
+        self.api_client
+            .post(format!("{}/content_rules/test", self.address))
+            .json(body)
+            .send()
+            .await
+            .expect("Failed to execute request.")
+    }
+
+    pub async fn suspend_user(&self, id: Uuid, body: &serde_json::Value) -> reqwest::Response {
+        self.api_client
+            .post(format!("{}/moderation/users/{}/suspend", self.address, id))
+            .json(body)
+            .send()
+            .await
+            .expect("Failed to execute request.")
+    }
+
+    pub async fn get_account_status(&self, id: Uuid) -> reqwest::Response {
+        self.api_client
+            .get(format!("{}/users/{}/status", self.address, id))
+            .send()
+            .await
+            .expect("Failed to execute request.")
+    }
+
+    pub async fn put_account_status(
+        &self,
+        id: Uuid,
+        body: &serde_json::Value,
+    ) -> reqwest::Response {
+        self.api_client
+            .put(format!("{}/users/{}/status", self.address, id))
+            .json(body)
+            .send()
+            .await
+            .expect("Failed to execute request.")
+    }
+
+    pub async fn deactivate_account(&self, body: &serde_json::Value) -> reqwest::Response {
+        self.api_client
+            .post(format!("{}/auth/deactivate", self.address))
+            .json(body)
+            .send()
+            .await
+            .expect("Failed to execute request.")
+    }
+
+    pub async fn get_profile(&self) -> reqwest::Response {
+        self.api_client
+            .get(format!("{}/profile", self.address))
+            .send()
+            .await
+            .expect("Failed to execute request.")
+    }
+
+    pub async fn put_profile(&self, body: &serde_json::Value) -> reqwest::Response {
+        self.api_client
+            .put(format!("{}/profile", self.address))
+            .json(body)
+            .send()
+            .await
+            .expect("Failed to execute request.")
+    }
+
+    pub async fn get_notifications(
+        &self,
+        query: &CursorQuery,
+    ) -> reqwest::Result<CursorResponse<Vec<NotificationResponse>>> {
+        self.api_client
+            .get(format!("{}/notifications", self.address))
+            .query(query)
+            .send()
+            .await
+            .expect("Failed to execute request.")
+            .json::<CursorResponse<Vec<NotificationResponse>>>()
+            .await
+    }
+
+    pub async fn get_unread_notification_count(
+        &self,
+    ) -> reqwest::Result<NotificationCountResponse> {
+        self.api_client
+            .get(format!("{}/notifications/unread_count", self.address))
+            .send()
+            .await
+            .expect("Failed to execute request.")
+            .json::<NotificationCountResponse>()
+            .await
+    }
+
+    pub async fn mark_notification_read(&self, id: Uuid) -> reqwest::Response {
+        self.api_client
+            .put(format!("{}/notifications/{}/read", self.address, id))
+            .send()
+            .await
+            .expect("Failed to execute request.")
+    }
+
+    pub async fn mark_all_notifications_read(&self) -> reqwest::Result<NotificationCountResponse> {
+        self.api_client
+            .put(format!("{}/notifications/read", self.address))
+            .send()
+            .await
+            .expect("Failed to execute request.")
+            .json::<NotificationCountResponse>()
+            .await
+    }
+
+    pub async fn post_conversation(&self, body: &serde_json::Value) -> reqwest::Response {
+        self.api_client
+            .post(format!("{}/conversations", self.address))
+            .json(body)
+            .send()
+            .await
+            .expect("Failed to execute request.")
+    }
+
+    pub async fn get_conversations(
+        &self,
+        query: &CursorQuery,
+    ) -> reqwest::Result<CursorResponse<Vec<ConversationResponse>>> {
+        self.api_client
+            .get(format!("{}/conversations", self.address))
+            .query(query)
+            .send()
+            .await
+            .expect("Failed to execute request.")
+            .json::<CursorResponse<Vec<ConversationResponse>>>()
+            .await
+    }
+
+    pub async fn get_conversation(&self, id: Uuid) -> reqwest::Response {
+        self.api_client
+            .get(format!("{}/conversations/{}", self.address, id))
+            .send()
+            .await
+            .expect("Failed to execute request.")
+    }
+
+    pub async fn mark_conversation_read(&self, id: Uuid) -> reqwest::Response {
+        self.api_client
+            .put(format!("{}/conversations/{}/read", self.address, id))
+            .send()
+            .await
+            .expect("Failed to execute request.")
+    }
+
+    pub async fn get_messages(&self, id: Uuid, query: &CursorQuery) -> reqwest::Response {
+        self.api_client
+            .get(format!("{}/conversations/{}/messages", self.address, id))
+            .query(query)
+            .send()
+            .await
+            .expect("Failed to execute request.")
+    }
+
+    pub async fn post_message(&self, id: Uuid, body: &serde_json::Value) -> reqwest::Response {
+        self.api_client
+            .post(format!("{}/conversations/{}/messages", self.address, id))
+            .json(body)
+            .send()
+            .await
+            .expect("Failed to execute request.")
+    }
+
+    pub async fn delete_message(&self, id: Uuid, message_id: Uuid) -> reqwest::Response {
+        self.api_client
+            .delete(format!(
+                "{}/conversations/{}/messages/{}",
+                self.address, id, message_id
+            ))
+            .send()
+            .await
+            .expect("Failed to execute request.")
+    }
+
+    pub async fn get_messaging_settings(&self) -> reqwest::Response {
+        self.api_client
+            .get(format!("{}/profile/messaging", self.address))
+            .send()
+            .await
+            .expect("Failed to execute request.")
+    }
+
+    pub async fn put_messaging_settings(&self, body: &serde_json::Value) -> reqwest::Response {
+        self.api_client
+            .put(format!("{}/profile/messaging", self.address))
+            .json(body)
+            .send()
+            .await
+            .expect("Failed to execute request.")
+    }
+
+    pub async fn open_stream(&self, last_event_id: Option<&str>) -> reqwest::Response {
+        let mut request = self.api_client.get(format!("{}/stream", self.address));
+        if let Some(last_event_id) = last_event_id {
+            request = request.header("Last-Event-ID", last_event_id);
+        }
+        request.send().await.expect("Failed to execute request.")
+    }
+
+    /// Logs in and returns the session cookies, for clients outside
+    /// `api_client` such as WebSocket connections.
+    pub async fn login_cookie(&self, body: &serde_json::Value) -> String {
+        let response = self.login_response(body).await;
+        response
+            .headers()
+            .get_all(reqwest::header::SET_COOKIE)
+            .iter()
+            .filter_map(|value| value.to_str().ok()?.split(';').next())
+            .collect::<Vec<_>>()
+            .join("; ")
+    }
+
+    pub async fn connect_gateway(
+        &self,
+        cookie: Option<&str>,
+    ) -> Result<GatewaySocket, tokio_tungstenite::tungstenite::Error> {
+        let url = format!("{}/gateway", self.address).replacen("http", "ws", 1);
+        let mut request = url.into_client_request()?;
+        if let Some(cookie) = cookie {
+            request
+                .headers_mut()
+                .insert("Cookie", cookie.parse().expect("Invalid cookie"));
+        }
+        let (socket, _) = tokio_tungstenite::connect_async(request).await?;
+        Ok(socket)
+    }
+
+    pub async fn get_moderation_actions(&self) -> reqwest::Response {
+        self.api_client
+            .get(format!("{}/moderation/actions", self.address))
+            .query(&DtoQuery::<ModerationActionFilterQuery>::default_query())
+            .send()
+            .await
+            .expect("Failed to execute request.")
+    }
+
+    pub async fn post_subscription(
+        &self,
+        body: &serde_json::Value,
+    ) -> reqwest::Result<SubscriptionResponse> {
+        self.api_client
+            .post(format!("{}/subscriptions", self.address))
+            .header("Content-Type", "application/json")
+            .json(body)
+            .send()
+            .await
+            .expect("Failed to execute request.")
+            .json::<SubscriptionResponse>()
+            .await
+    }
+
+    pub async fn get_subscriptions(
+        &self,
+    ) -> reqwest::Result<DtoResponse<Vec<SubscriptionResponse>>> {
+        self.api_client
+            .get(format!("{}/subscriptions", self.address))
+            .send()
+            .await
+            .expect("Failed to execute request.")
+            .json::<DtoResponse<Vec<SubscriptionResponse>>>()
+            .await
+    }
+
+    pub async fn delete_subscription(
+        &self,
+        target_id: Uuid,
+    ) -> reqwest::Result<SubscriptionResponse> {
+        self.api_client
+            .delete(format!("{}/subscriptions/{}", self.address, target_id))
+            .send()
+            .await
+            .expect("Failed to execute request.")
+            .json::<SubscriptionResponse>()
+            .await
+    }
+
+    pub async fn get_home_timeline(
+        &self,
+        query: &CursorQuery,
+    ) -> reqwest::Result<CursorResponse<Vec<TweetResponse>>> {
+        self.api_client
+            .get(format!("{}/timeline/home", self.address))
+            .query(query)
+            .send()
+            .await
+            .expect("Failed to execute request.")
+            .json::<CursorResponse<Vec<TweetResponse>>>()
+            .await
+    }
+
+    pub async fn get_hashtag_tweets(
+        &self,
+        tag: &str,
+    ) -> reqwest::Result<DtoResponse<Vec<TweetResponse>>> {
+        self.api_client
+            .get(format!("{}/hashtags/{}/tweets", self.address, tag))
+            .query(&DtoQuery::<TweetFilterQuery>::default_query())
+            .send()
+            .await
+            .expect("Failed to execute request.")
+            .json::<DtoResponse<Vec<TweetResponse>>>()
+            .await
+    }
+
+    pub async fn get_trending_hashtags(
+        &self,
+    ) -> reqwest::Result<DtoResponse<Vec<TrendingHashtag>>> {
+        self.api_client
+            .get(format!("{}/hashtags/trending", self.address))
+            .send()
+            .await
+            .expect("Failed to execute request.")
+            .json::<DtoResponse<Vec<TrendingHashtag>>>()
+            .await
+    }
+
+    pub async fn get_user_mentions(
+        &self,
+        user_id: Uuid,
+    ) -> reqwest::Result<DtoResponse<Vec<TweetResponse>>> {
+        self.api_client
+            .get(format!("{}/users/{}/mentions", self.address, user_id))
+            .query(&DtoQuery::<TweetFilterQuery>::default_query())
+            .send()
+            .await
+            .expect("Failed to execute request.")
+            .json::<DtoResponse<Vec<TweetResponse>>>()
+            .await
+    }
+
+    pub async fn search_tweets(&self, query: &SearchQuery) -> reqwest::Response {
+        self.api_client
+            .get(format!("{}/search/tweets", self.address))
+            .query(query)
+            .send()
+            .await
+            .expect("Failed to execute request.")
+    }
+
+    pub async fn search_users(&self, query: &SearchQuery) -> reqwest::Response {
+        self.api_client
+            .get(format!("{}/search/users", self.address))
+            .query(query)
+            .send()
+            .await
+            .expect("Failed to execute request.")
+    }
+
+    pub async fn upload_media(&self, bytes: Vec<u8>) -> reqwest::Response {
+        let form = reqwest::multipart::Form::new().part(
+            "file",
+            reqwest::multipart::Part::bytes(bytes).file_name("upload"),
+        );
+        self.api_client
+            .post(format!("{}/media", self.address))
+            .multipart(form)
+            .send()
+            .await
+            .expect("Failed to execute request.")
+    }
+
+    pub async fn get_media(&self, id: Uuid) -> reqwest::Result<MediaResponse> {
+        self.api_client
+            .get(format!("{}/media/{}", self.address, id))
+            .send()
+            .await
+            .expect("Failed to execute request.")
+            .json::<MediaResponse>()
+            .await
+    }
+
+    pub async fn get_media_variant_content(&self, id: Uuid, name: &str) -> reqwest::Response {
+        self.api_client
+            .get(format!("{}/media/{}/variants/{}", self.address, id, name))
+            .send()
+            .await
+            .expect("Failed to execute request.")
+    }
+
+    pub async fn get_media_content(&self, id: Uuid) -> reqwest::Response {
+        self.api_client
+            .get(format!("{}/media/{}/content", self.address, id))
+            .send()
+            .await
+            .expect("Failed to execute request.")
+    }
+
+    pub async fn login_response(&self, body: &serde_json::Value) -> reqwest::Response {
+        self.api_client
+            .post(format!("{}/auth/login", self.address))
+            .json(body)
+            .send()
+            .await
+            .expect("Failed to execute request.")
+    }
+
     pub async fn login(
         &self,
         body: &serde_json::Value,
@@ -572,6 +1422,12 @@ pub async fn spawn_app() -> TestApp {
         let mut c = get_configuration().expect("Failed to read configuration");
         c.database.database_name = Uuid::new_v4().to_string();
         c.application.port = 0;
+        // Link previews are fetched by the tests themselves
+        c.link_previews.enabled = false;
+        c.media.local_path = std::env::temp_dir()
+            .join("aloha-media")
+            .to_string_lossy()
+            .to_string();
         c
     };
     configure_database(&configuration.database).await;
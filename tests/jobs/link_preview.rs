@@ -0,0 +1,207 @@
+use crate::helpers::spawn_app;
+use aloha_backend::configuration::{get_configuration, LinkPreviewSettings};
+use aloha_backend::jobs::link_preview::{
+    run_link_preview_once, LinkPreviewCounts, LinkPreviewFetcher, PreviewFetchError,
+};
+use aloha_backend::mappers::link_preview::get_link_preview;
+use aloha_backend::mappers::user::insert_user;
+use aloha_backend::models::link_preview::LinkPreviewStatus;
+use aloha_backend::models::user::User;
+use serde_json::json;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+const PAGE: &str = r#"<html><head>
+<title>Ignored</title>
+<meta property="og:title" content="A test page">
+<meta property="og:description" content="Served by wiremock">
+<meta property="og:image" content="/card.png">
+</head><body>Hello</body></html>"#;
+
+/// Settings letting the fetcher reach the mock server on the loopback
+/// address.
+fn test_settings() -> LinkPreviewSettings {
+    let mut settings = get_configuration().unwrap().link_previews;
+    settings.allow_private_networks = true;
+    settings
+}
+
+fn html(body: &str) -> ResponseTemplate {
+    ResponseTemplate::new(200).set_body_raw(body.to_string(), "text/html; charset=utf-8")
+}
+
+#[tokio::test]
+async fn fetcher_reads_open_graph_tags_through_redirects() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/article"))
+        .respond_with(html(PAGE))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/short"))
+        .respond_with(ResponseTemplate::new(301).insert_header("Location", "/article"))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/loop"))
+        .respond_with(ResponseTemplate::new(302).insert_header("Location", "/loop"))
+        .mount(&server)
+        .await;
+    let fetcher = LinkPreviewFetcher::new(&test_settings());
+
+    let metadata = fetcher
+        .fetch(&format!("{}/short", server.uri()))
+        .await
+        .unwrap();
+    assert_eq!(metadata.title.as_deref(), Some("A test page"));
+    assert_eq!(metadata.description.as_deref(), Some("Served by wiremock"));
+    assert_eq!(
+        metadata.image_url,
+        Some(format!("{}/card.png", server.uri()))
+    );
+    assert!(matches!(
+        fetcher.fetch(&format!("{}/loop", server.uri())).await,
+        Err(PreviewFetchError::Refused(_))
+    ));
+}
+
+#[tokio::test]
+async fn fetcher_refuses_what_it_cannot_preview() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/image.png"))
+        .respond_with(ResponseTemplate::new(200).set_body_raw(b"\x89PNG".to_vec(), "image/png"))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/missing"))
+        .respond_with(ResponseTemplate::new(404))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/broken"))
+        .respond_with(ResponseTemplate::new(503))
+        .mount(&server)
+        .await;
+    // The tags come after the part of the page that is read
+    let mut settings = test_settings();
+    settings.max_body_bytes = 1024;
+    Mock::given(method("GET"))
+        .and(path("/huge"))
+        .respond_with(html(&format!("<!-- {} -->{}", "x".repeat(4096), PAGE)))
+        .mount(&server)
+        .await;
+    let fetcher = LinkPreviewFetcher::new(&settings);
+
+    for refused in ["/image.png", "/missing", "/huge"] {
+        let result = fetcher.fetch(&format!("{}{}", server.uri(), refused)).await;
+        assert!(
+            matches!(result, Err(PreviewFetchError::Refused(_))),
+            "{} gave {:?}",
+            refused,
+            result
+        );
+    }
+    assert!(matches!(
+        fetcher.fetch(&format!("{}/broken", server.uri())).await,
+        Err(PreviewFetchError::Failed(_))
+    ));
+}
+
+#[tokio::test]
+async fn fetcher_never_connects_to_private_addresses() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(html(PAGE))
+        .expect(0)
+        .mount(&server)
+        .await;
+    let mut settings = test_settings();
+    settings.allow_private_networks = false;
+    let fetcher = LinkPreviewFetcher::new(&settings);
+
+    for url in [
+        format!("{}/article", server.uri()),
+        format!("http://localhost:{}/article", server.address().port()),
+        "http://169.254.169.254/latest/meta-data/".to_string(),
+        "http://[::1]/".to_string(),
+        "http://10.0.0.1/".to_string(),
+    ] {
+        let result = fetcher.fetch(&url).await;
+        assert!(
+            matches!(result, Err(PreviewFetchError::Refused(_))),
+            "{} gave {:?}",
+            url,
+            result
+        );
+    }
+}
+
+#[tokio::test]
+async fn link_preview_job_attaches_cards_to_tweets() {
+    let app = spawn_app().await;
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/article"))
+        .respond_with(html(PAGE))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/down"))
+        .respond_with(ResponseTemplate::new(500))
+        .mount(&server)
+        .await;
+    let transaction = app.db_pool.begin().await.unwrap();
+    let author = insert_user(transaction, &User::default_test())
+        .await
+        .unwrap();
+    app.login(&json!({"username": author.username, "password": author.password_hash}))
+        .await
+        .unwrap();
+
+    let article = format!("{}/article", server.uri());
+    let down = format!("{}/down", server.uri());
+    let tweet = app
+        .post_tweet(&json!({ "content": format!("Look {}#comments and {}.", article, down) }))
+        .await
+        .unwrap();
+    assert_eq!(tweet.card, None);
+    // A second tweet with the same link shares the cached preview
+    let again = app
+        .post_tweet(&json!({ "content": format!("Again: {}", article) }))
+        .await
+        .unwrap();
+
+    let mut settings = test_settings();
+    settings.retry_seconds = 0;
+    settings.max_attempts = 2;
+    let fetcher = LinkPreviewFetcher::new(&settings);
+    let counts = run_link_preview_once(&app.db_pool, &fetcher, &settings)
+        .await
+        .unwrap();
+    assert_eq!(
+        counts,
+        LinkPreviewCounts {
+            ready: 1,
+            failed: 0,
+            retrying: 1
+        }
+    );
+    // The broken link is retried until it runs out of attempts
+    let counts = run_link_preview_once(&app.db_pool, &fetcher, &settings)
+        .await
+        .unwrap();
+    assert_eq!(counts.failed, 1);
+    let transaction = app.db_pool.begin().await.unwrap();
+    let failed = get_link_preview(transaction, &down).await.unwrap().unwrap();
+    assert_eq!(failed.status, LinkPreviewStatus::Failed);
+
+    for id in [tweet.id, again.id] {
+        let card = app.get_tweet_by_id(id).await.unwrap().card.unwrap();
+        assert_eq!(card.url, article);
+        assert_eq!(card.title.as_deref(), Some("A test page"));
+        assert_eq!(card.image_url, Some(format!("{}/card.png", server.uri())));
+    }
+}
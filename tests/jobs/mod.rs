@@ -0,0 +1 @@
+mod link_preview;
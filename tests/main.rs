@@ -1,3 +1,5 @@
 pub mod helpers;
+mod jobs;
 mod mappers;
 mod routes;
+mod storage;
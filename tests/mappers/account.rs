@@ -0,0 +1,136 @@
+use crate::helpers::spawn_app;
+use aloha_backend::dto::query::{DtoQuery, TweetFilterQuery};
+use aloha_backend::mappers::account::{get_account_state, set_account_status};
+use aloha_backend::mappers::moderation::change_account_status;
+use aloha_backend::mappers::tweet::{get_all_tweets, get_tweet_by_id, insert_tweet};
+use aloha_backend::mappers::user::insert_user;
+use aloha_backend::models::account::AccountStatus;
+use aloha_backend::models::moderation::{ModerationAction, ModerationActionKind};
+use aloha_backend::models::tweet::Tweet;
+use aloha_backend::models::user::User;
+use uuid::Uuid;
+
+#[tokio::test]
+async fn account_status_follows_allowed_transitions() {
+    let app = spawn_app().await;
+    let user = User::default_test();
+    let transaction = app.db_pool.begin().await.unwrap();
+    insert_user(transaction, &user).await.unwrap();
+
+    let mut transaction = app.db_pool.begin().await.unwrap();
+    let state = get_account_state(&mut transaction, user.id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(state.status, AccountStatus::Active);
+    assert_eq!(state.reason, None);
+
+    let state = set_account_status(
+        &mut transaction,
+        user.id,
+        AccountStatus::Deactivated,
+        Some("Taking a break".to_string()),
+        None,
+    )
+    .await
+    .unwrap()
+    .unwrap();
+    assert_eq!(state.status, AccountStatus::Deactivated);
+    assert_eq!(state.reason.as_deref(), Some("Taking a break"));
+    assert!(state.changed_at.is_some());
+
+    // Deactivated accounts cannot be suspended
+    assert!(set_account_status(
+        &mut transaction,
+        user.id,
+        AccountStatus::Suspended,
+        None,
+        None
+    )
+    .await
+    .is_err());
+
+    let mut transaction = app.db_pool.begin().await.unwrap();
+    assert!(set_account_status(
+        &mut transaction,
+        Uuid::new_v4(),
+        AccountStatus::Banned,
+        None,
+        None
+    )
+    .await
+    .unwrap()
+    .is_none());
+}
+
+#[tokio::test]
+async fn banned_users_content_is_hidden_until_reactivated() {
+    let app = spawn_app().await;
+    let users = User::default_vec_test(Some(3));
+    for user in &users {
+        let transaction = app.db_pool.begin().await.unwrap();
+        insert_user(transaction, user).await.unwrap();
+    }
+    let (author, viewer, moderator) = (&users[0], &users[1], &users[2]);
+    let transaction = app.db_pool.begin().await.unwrap();
+    let tweet = insert_tweet(transaction, &Tweet::new("Hello".to_string(), author.id))
+        .await
+        .unwrap();
+
+    let ban = ModerationAction {
+        user_id: Some(author.id),
+        note: Some("Spam network".to_string()),
+        ..ModerationAction::new(moderator.id, ModerationActionKind::BanUser)
+    };
+    let transaction = app.db_pool.begin().await.unwrap();
+    let state = change_account_status(transaction, &ban, AccountStatus::Banned, None)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(state.status, AccountStatus::Banned);
+    assert_eq!(state.reason.as_deref(), Some("Spam network"));
+
+    let transaction = app.db_pool.begin().await.unwrap();
+    assert!(get_tweet_by_id(transaction, tweet.id, Some(viewer.id))
+        .await
+        .unwrap()
+        .is_none());
+    let transaction = app.db_pool.begin().await.unwrap();
+    let tweets = get_all_tweets(
+        transaction,
+        DtoQuery::<TweetFilterQuery>::default_query(),
+        None,
+    )
+    .await
+    .unwrap();
+    assert!(tweets.data.iter().all(|t| t.id != tweet.id));
+
+    // Banned accounts can only be reactivated
+    let suspend = ModerationAction {
+        user_id: Some(author.id),
+        ..ModerationAction::new(moderator.id, ModerationActionKind::SuspendUser)
+    };
+    let transaction = app.db_pool.begin().await.unwrap();
+    assert!(
+        change_account_status(transaction, &suspend, AccountStatus::Suspended, None)
+            .await
+            .is_err()
+    );
+
+    let reactivate = ModerationAction {
+        user_id: Some(author.id),
+        ..ModerationAction::new(moderator.id, ModerationActionKind::ReactivateUser)
+    };
+    let transaction = app.db_pool.begin().await.unwrap();
+    let state = change_account_status(transaction, &reactivate, AccountStatus::Active, None)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(state.status, AccountStatus::Active);
+    assert_eq!(state.reason, None);
+    let transaction = app.db_pool.begin().await.unwrap();
+    assert!(get_tweet_by_id(transaction, tweet.id, Some(viewer.id))
+        .await
+        .unwrap()
+        .is_some());
+}
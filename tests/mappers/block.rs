@@ -0,0 +1,142 @@
+use crate::helpers::spawn_app;
+use aloha_backend::dto::query::{BlockFilterQuery, DtoQuery, TweetFilterQuery};
+use aloha_backend::mappers::block::{delete_block, get_blocked_users, insert_block};
+use aloha_backend::mappers::mention::get_mentions_by_tweet_ids;
+use aloha_backend::mappers::subscription::{
+    get_subscriptions_by_subscriber_id, insert_subscription,
+};
+use aloha_backend::mappers::tweet::{get_all_tweets, get_tweet_by_id, insert_tweet};
+use aloha_backend::mappers::user::insert_user;
+use aloha_backend::models::block::Block;
+use aloha_backend::models::subscription::Subscription;
+use aloha_backend::models::tweet::Tweet;
+use aloha_backend::models::user::User;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+async fn visible_tweet_ids(pool: &PgPool, viewer_id: Uuid) -> Vec<Uuid> {
+    let transaction = pool.begin().await.unwrap();
+    get_all_tweets(
+        transaction,
+        DtoQuery::<TweetFilterQuery>::default_query(),
+        Some(viewer_id),
+    )
+    .await
+    .unwrap()
+    .data
+    .into_iter()
+    .map(|t| t.id)
+    .collect()
+}
+
+#[tokio::test]
+async fn blocked_users_cannot_see_or_mention_each_other() {
+    let app = spawn_app().await;
+    let users = User::default_vec_test(Some(3));
+    for user in &users {
+        let transaction = app.db_pool.begin().await.unwrap();
+        insert_user(transaction, user).await.unwrap();
+    }
+    let (blocker, blocked, bystander) = (&users[0], &users[1], &users[2]);
+    for (subscriber, target) in [(blocker, blocked), (blocked, blocker)] {
+        let transaction = app.db_pool.begin().await.unwrap();
+        insert_subscription(transaction, &Subscription::new(subscriber.id, target.id))
+            .await
+            .unwrap();
+    }
+    let transaction = app.db_pool.begin().await.unwrap();
+    let blocker_tweet = insert_tweet(transaction, &Tweet::new("mine".to_string(), blocker.id))
+        .await
+        .unwrap();
+    let transaction = app.db_pool.begin().await.unwrap();
+    let blocked_tweet = insert_tweet(transaction, &Tweet::new("theirs".to_string(), blocked.id))
+        .await
+        .unwrap();
+
+    let transaction = app.db_pool.begin().await.unwrap();
+    let block = insert_block(transaction, &Block::new(blocker.id, blocked.id))
+        .await
+        .unwrap();
+    assert_eq!(block.blocked_id, blocked.id);
+    // Blocking twice is a no-op
+    let transaction = app.db_pool.begin().await.unwrap();
+    insert_block(transaction, &Block::new(blocker.id, blocked.id))
+        .await
+        .unwrap();
+    let transaction = app.db_pool.begin().await.unwrap();
+    assert!(
+        insert_block(transaction, &Block::new(blocker.id, Uuid::new_v4()))
+            .await
+            .is_err()
+    );
+
+    // Both directions are hidden, other users still see both tweets
+    assert_eq!(
+        visible_tweet_ids(&app.db_pool, blocker.id).await,
+        vec![blocker_tweet.id]
+    );
+    assert_eq!(
+        visible_tweet_ids(&app.db_pool, blocked.id).await,
+        vec![blocked_tweet.id]
+    );
+    assert_eq!(visible_tweet_ids(&app.db_pool, bystander.id).await.len(), 2);
+    let transaction = app.db_pool.begin().await.unwrap();
+    assert!(
+        get_tweet_by_id(transaction, blocker_tweet.id, Some(blocked.id))
+            .await
+            .unwrap()
+            .is_none()
+    );
+
+    // Blocking drops the subscriptions between the two users
+    for user in [blocker, blocked] {
+        let transaction = app.db_pool.begin().await.unwrap();
+        let subscriptions = get_subscriptions_by_subscriber_id(transaction, user.id)
+            .await
+            .unwrap();
+        assert!(subscriptions.is_empty());
+    }
+
+    // Mentions between blocked users are left as plain text
+    let content = format!("@{} @{}", blocker.username, bystander.username);
+    let transaction = app.db_pool.begin().await.unwrap();
+    let tweet = insert_tweet(transaction, &Tweet::new(content, blocked.id))
+        .await
+        .unwrap();
+    let mut transaction = app.db_pool.begin().await.unwrap();
+    let mentions = get_mentions_by_tweet_ids(&mut transaction, &[tweet.id])
+        .await
+        .unwrap();
+    assert_eq!(
+        mentions[&tweet.id]
+            .iter()
+            .map(|m| m.user_id)
+            .collect::<Vec<_>>(),
+        vec![bystander.id]
+    );
+
+    let transaction = app.db_pool.begin().await.unwrap();
+    let blocks = get_blocked_users(
+        transaction,
+        blocker.id,
+        DtoQuery::<BlockFilterQuery>::default_query(),
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        blocks.data.iter().map(|u| u.id).collect::<Vec<_>>(),
+        vec![blocked.id]
+    );
+
+    let transaction = app.db_pool.begin().await.unwrap();
+    let removed = delete_block(transaction, blocker.id, blocked.id)
+        .await
+        .unwrap();
+    assert!(removed.is_some());
+    let transaction = app.db_pool.begin().await.unwrap();
+    assert!(delete_block(transaction, blocker.id, blocked.id)
+        .await
+        .unwrap()
+        .is_none());
+    assert_eq!(visible_tweet_ids(&app.db_pool, blocked.id).await.len(), 3);
+}
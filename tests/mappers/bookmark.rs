@@ -0,0 +1,128 @@
+use crate::helpers::spawn_app;
+use aloha_backend::dto::query::{DtoQuery, TweetFilterQuery};
+use aloha_backend::mappers::bookmark::{delete_bookmark, get_bookmarked_tweets, insert_bookmark};
+use aloha_backend::mappers::tweet::{delete_tweet_by_id, insert_tweet};
+use aloha_backend::mappers::user::insert_user;
+use aloha_backend::models::bookmark::Bookmark;
+use aloha_backend::models::tweet::{Tweet, TweetVisibility};
+use aloha_backend::models::user::User;
+use uuid::Uuid;
+
+#[tokio::test]
+async fn bookmarks_are_listed_most_recent_first() {
+    let app = spawn_app().await;
+    let users = User::default_vec_test(Some(2));
+    for user in &users {
+        let transaction = app.db_pool.begin().await.unwrap();
+        insert_user(transaction, user).await.unwrap();
+    }
+    let (reader, author) = (&users[0], &users[1]);
+    let mut tweets = Vec::new();
+    for tweet in Tweet::default_vec_test(Some(3), author.id) {
+        let transaction = app.db_pool.begin().await.unwrap();
+        tweets.push(insert_tweet(transaction, &tweet).await.unwrap());
+    }
+
+    for tweet in [&tweets[2], &tweets[0]] {
+        let transaction = app.db_pool.begin().await.unwrap();
+        let bookmark = insert_bookmark(transaction, &Bookmark::new(reader.id, tweet.id))
+            .await
+            .unwrap();
+        assert_eq!(bookmark.map(|b| b.tweet_id), Some(tweet.id));
+    }
+    // Bookmarking twice is idempotent
+    let transaction = app.db_pool.begin().await.unwrap();
+    insert_bookmark(transaction, &Bookmark::new(reader.id, tweets[0].id))
+        .await
+        .unwrap();
+
+    let transaction = app.db_pool.begin().await.unwrap();
+    let bookmarks = get_bookmarked_tweets(
+        transaction,
+        reader.id,
+        DtoQuery::<TweetFilterQuery>::default_query(),
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        bookmarks.data.iter().map(|t| t.id).collect::<Vec<_>>(),
+        vec![tweets[0].id, tweets[2].id]
+    );
+    assert_eq!(bookmarks.pagination.unwrap().total, Some(2));
+
+    // Other users' bookmarks are separate
+    let transaction = app.db_pool.begin().await.unwrap();
+    let bookmarks = get_bookmarked_tweets(
+        transaction,
+        author.id,
+        DtoQuery::<TweetFilterQuery>::default_query(),
+    )
+    .await
+    .unwrap();
+    assert!(bookmarks.data.is_empty());
+
+    // Deleted tweets drop out of the list
+    let transaction = app.db_pool.begin().await.unwrap();
+    delete_tweet_by_id(transaction, tweets[2].id).await.unwrap();
+    let transaction = app.db_pool.begin().await.unwrap();
+    let bookmarks = get_bookmarked_tweets(
+        transaction,
+        reader.id,
+        DtoQuery::<TweetFilterQuery>::default_query(),
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        bookmarks.data.iter().map(|t| t.id).collect::<Vec<_>>(),
+        vec![tweets[0].id]
+    );
+
+    let transaction = app.db_pool.begin().await.unwrap();
+    let removed = delete_bookmark(transaction, reader.id, tweets[0].id)
+        .await
+        .unwrap();
+    assert_eq!(removed.map(|b| b.tweet_id), Some(tweets[0].id));
+    let transaction = app.db_pool.begin().await.unwrap();
+    assert!(delete_bookmark(transaction, reader.id, tweets[0].id)
+        .await
+        .unwrap()
+        .is_none());
+}
+
+#[tokio::test]
+async fn insert_bookmark_rejects_unknown_and_unreadable_tweets() {
+    let app = spawn_app().await;
+    let users = User::default_vec_test(Some(2));
+    for user in &users {
+        let transaction = app.db_pool.begin().await.unwrap();
+        insert_user(transaction, user).await.unwrap();
+    }
+    let private = Tweet {
+        visibility: TweetVisibility::Private,
+        ..Tweet::new("Note to self".to_string(), users[1].id)
+    };
+    let transaction = app.db_pool.begin().await.unwrap();
+    insert_tweet(transaction, &private).await.unwrap();
+
+    let transaction = app.db_pool.begin().await.unwrap();
+    assert!(
+        insert_bookmark(transaction, &Bookmark::new(users[0].id, Uuid::new_v4()))
+            .await
+            .unwrap()
+            .is_none()
+    );
+    let transaction = app.db_pool.begin().await.unwrap();
+    assert!(
+        insert_bookmark(transaction, &Bookmark::new(users[0].id, private.id))
+            .await
+            .unwrap()
+            .is_none()
+    );
+    let transaction = app.db_pool.begin().await.unwrap();
+    assert!(
+        insert_bookmark(transaction, &Bookmark::new(users[1].id, private.id))
+            .await
+            .unwrap()
+            .is_some()
+    );
+}
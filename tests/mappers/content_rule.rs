@@ -0,0 +1,294 @@
+use crate::helpers::spawn_app;
+use aloha_backend::configuration::get_configuration;
+use aloha_backend::dto::query::{
+    ContentRuleFilterQuery, DtoQuery, ModerationActionFilterQuery, TweetFilterQuery,
+};
+use aloha_backend::mappers::content_rule::{
+    get_content_rule_by_id, get_content_rules, insert_content_rule, test_content_rules,
+    update_content_rule,
+};
+use aloha_backend::mappers::hashtag::{get_trending_hashtags, get_tweets_by_hashtag};
+use aloha_backend::mappers::link_preview::get_link_cards_by_tweet_ids;
+use aloha_backend::mappers::mention::get_tweets_mentioning_user;
+use aloha_backend::mappers::moderation::{get_held_tweets, get_moderation_actions, release_tweet};
+use aloha_backend::mappers::tweet::{
+    get_tweet_by_id, get_tweet_status, insert_tweet, update_tweet,
+};
+use aloha_backend::mappers::user::insert_user;
+use aloha_backend::models::content_rule::{ContentRule, ContentRuleAction, ContentRuleKind};
+use aloha_backend::models::draft::TweetStatus;
+use aloha_backend::models::moderation::{ModerationAction, ModerationActionKind};
+use aloha_backend::models::tweet::Tweet;
+use aloha_backend::models::user::User;
+use sqlx::PgPool;
+
+async fn hit_count(pool: &PgPool, rule: &ContentRule) -> i64 {
+    let transaction = pool.begin().await.unwrap();
+    get_content_rule_by_id(transaction, rule.id)
+        .await
+        .unwrap()
+        .unwrap()
+        .hit_count
+}
+
+#[tokio::test]
+async fn content_rules_screen_posted_and_edited_tweets() {
+    let app = spawn_app().await;
+    let users = User::default_vec_test(Some(2));
+    for user in &users {
+        let transaction = app.db_pool.begin().await.unwrap();
+        insert_user(transaction, user).await.unwrap();
+    }
+    let (author, moderator) = (&users[0], &users[1]);
+    let mut rules = Vec::new();
+    for rule in [
+        ContentRule::new(ContentRuleKind::Word, "darn", ContentRuleAction::Mask),
+        ContentRule::new(
+            ContentRuleKind::Regex,
+            r"fr[e3]{2} money",
+            ContentRuleAction::Hold,
+        ),
+        ContentRule::new(
+            ContentRuleKind::Domain,
+            "spam.example",
+            ContentRuleAction::Reject,
+        ),
+    ] {
+        let transaction = app.db_pool.begin().await.unwrap();
+        rules.push(insert_content_rule(transaction, &rule).await.unwrap());
+    }
+    let (mask, hold, reject) = (&rules[0], &rules[1], &rules[2]);
+
+    // Dry runs do not count hits
+    let transaction = app.db_pool.begin().await.unwrap();
+    let evaluation = test_content_rules(transaction, "darn, free money")
+        .await
+        .unwrap();
+    assert_eq!(evaluation.action, Some(ContentRuleAction::Hold));
+    assert_eq!(evaluation.content, "****, free money");
+    assert_eq!(hit_count(&app.db_pool, mask).await, 0);
+
+    let transaction = app.db_pool.begin().await.unwrap();
+    let masked = insert_tweet(transaction, &Tweet::new("Darn it".to_string(), author.id))
+        .await
+        .unwrap();
+    assert_eq!(masked.content, "**** it");
+    assert_eq!(hit_count(&app.db_pool, mask).await, 1);
+
+    // Rejected tweets are not stored but still count as hits
+    let rejected = Tweet::new("Visit spam.example/deal".to_string(), author.id);
+    let transaction = app.db_pool.begin().await.unwrap();
+    assert!(insert_tweet(transaction, &rejected).await.is_err());
+    let transaction = app.db_pool.begin().await.unwrap();
+    assert!(get_tweet_status(transaction, rejected.id)
+        .await
+        .unwrap()
+        .is_none());
+    assert_eq!(hit_count(&app.db_pool, reject).await, 1);
+
+    let transaction = app.db_pool.begin().await.unwrap();
+    let held = insert_tweet(
+        transaction,
+        &Tweet::new("FREE money #deal".to_string(), author.id),
+    )
+    .await
+    .unwrap();
+    let transaction = app.db_pool.begin().await.unwrap();
+    assert_eq!(
+        get_tweet_status(transaction, held.id).await.unwrap(),
+        Some(TweetStatus::Held)
+    );
+    let transaction = app.db_pool.begin().await.unwrap();
+    assert!(get_tweet_by_id(transaction, held.id, Some(author.id))
+        .await
+        .unwrap()
+        .is_none());
+
+    // Edits are screened as well and can send a tweet back to review
+    let transaction = app.db_pool.begin().await.unwrap();
+    let edited = update_tweet(
+        transaction,
+        &Tweet {
+            content: "Fr33 money, darn".to_string(),
+            ..masked.clone()
+        },
+    )
+    .await
+    .unwrap();
+    assert_eq!(edited.content, "Fr33 money, ****");
+    assert_eq!(hit_count(&app.db_pool, hold).await, 2);
+
+    let transaction = app.db_pool.begin().await.unwrap();
+    let queue = get_held_tweets(transaction, DtoQuery::<TweetFilterQuery>::default_query())
+        .await
+        .unwrap();
+    assert_eq!(
+        queue.data.iter().map(|t| t.id).collect::<Vec<_>>(),
+        vec![masked.id, held.id]
+    );
+
+    let release = ModerationAction {
+        tweet_id: Some(held.id),
+        ..ModerationAction::new(moderator.id, ModerationActionKind::ReleaseTweet)
+    };
+    let transaction = app.db_pool.begin().await.unwrap();
+    assert!(release_tweet(transaction, &release)
+        .await
+        .unwrap()
+        .is_some());
+    let transaction = app.db_pool.begin().await.unwrap();
+    assert!(release_tweet(transaction, &release)
+        .await
+        .unwrap()
+        .is_none());
+    let transaction = app.db_pool.begin().await.unwrap();
+    assert!(get_tweet_by_id(transaction, held.id, None)
+        .await
+        .unwrap()
+        .is_some());
+
+    let transaction = app.db_pool.begin().await.unwrap();
+    let log = get_moderation_actions(
+        transaction,
+        DtoQuery::<ModerationActionFilterQuery>::default_query(),
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        log.data
+            .iter()
+            .map(|a| (a.action, a.moderator_id))
+            .collect::<Vec<_>>(),
+        vec![
+            (ModerationActionKind::ReleaseTweet, Some(moderator.id)),
+            (ModerationActionKind::HoldTweet, None),
+            (ModerationActionKind::HoldTweet, None),
+        ]
+    );
+
+    // Disabled rules are skipped
+    let transaction = app.db_pool.begin().await.unwrap();
+    update_content_rule(
+        transaction,
+        &ContentRule {
+            enabled: false,
+            ..reject.clone()
+        },
+    )
+    .await
+    .unwrap()
+    .unwrap();
+    let transaction = app.db_pool.begin().await.unwrap();
+    insert_tweet(transaction, &rejected).await.unwrap();
+    assert_eq!(hit_count(&app.db_pool, reject).await, 1);
+
+    let transaction = app.db_pool.begin().await.unwrap();
+    let enabled = get_content_rules(
+        transaction,
+        DtoQuery {
+            filter: Some(ContentRuleFilterQuery {
+                kind: None,
+                action: None,
+                enabled: Some(true),
+            }),
+            ..DtoQuery::default_query()
+        },
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        enabled.data.iter().map(|r| r.id).collect::<Vec<_>>(),
+        vec![mask.id, hold.id]
+    );
+}
+
+#[tokio::test]
+async fn held_edits_leave_hashtag_feeds_trends_and_mentions() {
+    let app = spawn_app().await;
+    let users = User::default_vec_test(Some(2));
+    for user in &users {
+        let transaction = app.db_pool.begin().await.unwrap();
+        insert_user(transaction, user).await.unwrap();
+    }
+    let (author, mentioned) = (&users[0], &users[1]);
+    let transaction = app.db_pool.begin().await.unwrap();
+    let tweet = insert_tweet(
+        transaction,
+        &Tweet::new(
+            format!("#launch day @{} https://example.com/a", mentioned.username),
+            author.id,
+        ),
+    )
+    .await
+    .unwrap();
+    let transaction = app.db_pool.begin().await.unwrap();
+    let rule = ContentRule::new(ContentRuleKind::Word, "casino", ContentRuleAction::Hold);
+    insert_content_rule(transaction, &rule).await.unwrap();
+    sqlx::query!("UPDATE link_previews SET status = 'ready' WHERE url = 'https://example.com/a'")
+        .execute(&app.db_pool)
+        .await
+        .unwrap();
+
+    let transaction = app.db_pool.begin().await.unwrap();
+    update_tweet(
+        transaction,
+        &Tweet {
+            content: format!(
+                "#launch casino @{} https://example.com/a",
+                mentioned.username
+            ),
+            ..tweet.clone()
+        },
+    )
+    .await
+    .unwrap();
+
+    let transaction = app.db_pool.begin().await.unwrap();
+    let tagged = get_tweets_by_hashtag(
+        transaction,
+        "launch",
+        DtoQuery::<TweetFilterQuery>::default_query(),
+        None,
+    )
+    .await
+    .unwrap();
+    assert!(tagged.data.is_empty());
+    let transaction = app.db_pool.begin().await.unwrap();
+    let trending = get_trending_hashtags(transaction, &get_configuration().unwrap().hashtags)
+        .await
+        .unwrap();
+    assert!(trending.is_empty());
+    let transaction = app.db_pool.begin().await.unwrap();
+    let mentions = get_tweets_mentioning_user(
+        transaction,
+        mentioned.id,
+        DtoQuery::<TweetFilterQuery>::default_query(),
+        None,
+    )
+    .await
+    .unwrap();
+    assert!(mentions.data.is_empty());
+    let mut transaction = app.db_pool.begin().await.unwrap();
+    assert!(get_link_cards_by_tweet_ids(&mut transaction, &[tweet.id])
+        .await
+        .unwrap()
+        .is_empty());
+
+    // Releasing the tweet brings them back
+    let release = ModerationAction {
+        tweet_id: Some(tweet.id),
+        ..ModerationAction::new(author.id, ModerationActionKind::ReleaseTweet)
+    };
+    let transaction = app.db_pool.begin().await.unwrap();
+    release_tweet(transaction, &release).await.unwrap().unwrap();
+    let transaction = app.db_pool.begin().await.unwrap();
+    let tagged = get_tweets_by_hashtag(
+        transaction,
+        "launch",
+        DtoQuery::<TweetFilterQuery>::default_query(),
+        None,
+    )
+    .await
+    .unwrap();
+    assert_eq!(tagged.data.len(), 1);
+}
@@ -0,0 +1,269 @@
+use crate::helpers::spawn_app;
+use aloha_backend::dto::cursor::CursorQuery;
+use aloha_backend::mappers::block::insert_block;
+use aloha_backend::mappers::conversation::{
+    delete_message, get_conversation_by_id, get_conversations, get_messages, insert_conversation,
+    insert_message, mark_conversation_read,
+};
+use aloha_backend::mappers::subscription::insert_subscription;
+use aloha_backend::mappers::user::{get_dm_policy, insert_user, update_dm_policy};
+use aloha_backend::models::block::Block;
+use aloha_backend::models::conversation::{DirectMessage, DmPolicy};
+use aloha_backend::models::subscription::Subscription;
+use aloha_backend::models::user::User;
+use uuid::Uuid;
+
+#[tokio::test]
+async fn one_to_one_conversations_are_reused_and_track_reads() {
+    let app = spawn_app().await;
+    let users = User::default_vec_test(Some(3));
+    for user in &users {
+        let transaction = app.db_pool.begin().await.unwrap();
+        insert_user(transaction, user).await.unwrap();
+    }
+    let (alice, bob, carol) = (&users[0], &users[1], &users[2]);
+
+    let transaction = app.db_pool.begin().await.unwrap();
+    let conversation = insert_conversation(transaction, alice.id, &[bob.id])
+        .await
+        .unwrap();
+    assert!(!conversation.is_group);
+    assert_eq!(conversation.creator_id, Some(alice.id));
+    assert_eq!(conversation.participant_ids.len(), 2);
+    // Either side starting it again lands in the same conversation
+    let transaction = app.db_pool.begin().await.unwrap();
+    let again = insert_conversation(transaction, bob.id, &[alice.id])
+        .await
+        .unwrap();
+    assert_eq!(again.id, conversation.id);
+    let transaction = app.db_pool.begin().await.unwrap();
+    assert!(
+        insert_conversation(transaction, alice.id, &[Uuid::new_v4()])
+            .await
+            .is_err()
+    );
+
+    for content in ["hi", "how are you?"] {
+        let transaction = app.db_pool.begin().await.unwrap();
+        let message = DirectMessage::new(conversation.id, alice.id, content.to_string());
+        insert_message(transaction, &message)
+            .await
+            .unwrap()
+            .unwrap();
+    }
+    // Outsiders can neither write nor read
+    let transaction = app.db_pool.begin().await.unwrap();
+    let intruder = DirectMessage::new(conversation.id, carol.id, "hey".to_string());
+    assert!(insert_message(transaction, &intruder)
+        .await
+        .unwrap()
+        .is_none());
+    let transaction = app.db_pool.begin().await.unwrap();
+    assert!(get_messages(
+        transaction,
+        carol.id,
+        conversation.id,
+        CursorQuery::default_query()
+    )
+    .await
+    .unwrap()
+    .is_none());
+    let transaction = app.db_pool.begin().await.unwrap();
+    assert!(
+        get_conversation_by_id(transaction, carol.id, conversation.id)
+            .await
+            .unwrap()
+            .is_none()
+    );
+
+    let transaction = app.db_pool.begin().await.unwrap();
+    let unread = get_conversation_by_id(transaction, bob.id, conversation.id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(unread.unread_count, 2);
+    let transaction = app.db_pool.begin().await.unwrap();
+    let own = get_conversation_by_id(transaction, alice.id, conversation.id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(own.unread_count, 0);
+
+    let transaction = app.db_pool.begin().await.unwrap();
+    let read = mark_conversation_read(transaction, bob.id, conversation.id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(read.unread_count, 0);
+    let transaction = app.db_pool.begin().await.unwrap();
+    let page = get_messages(
+        transaction,
+        alice.id,
+        conversation.id,
+        CursorQuery::default_query(),
+    )
+    .await
+    .unwrap()
+    .unwrap();
+    assert_eq!(page.data.len(), 2);
+    assert_eq!(page.data[0].content, "how are you?");
+    assert!(page.data.iter().all(|m| m.read_by == vec![bob.id]));
+
+    // Only the sender deletes a message
+    let message_id = page.data[0].id;
+    let transaction = app.db_pool.begin().await.unwrap();
+    assert!(
+        delete_message(transaction, bob.id, conversation.id, message_id)
+            .await
+            .unwrap()
+            .is_none()
+    );
+    let transaction = app.db_pool.begin().await.unwrap();
+    assert!(
+        delete_message(transaction, alice.id, conversation.id, message_id)
+            .await
+            .unwrap()
+            .is_some()
+    );
+}
+
+#[tokio::test]
+async fn dm_policy_and_blocks_restrict_who_can_be_messaged() {
+    let app = spawn_app().await;
+    let users = User::default_vec_test(Some(3));
+    for user in &users {
+        let transaction = app.db_pool.begin().await.unwrap();
+        insert_user(transaction, user).await.unwrap();
+    }
+    let (sender, recipient, blocker) = (&users[0], &users[1], &users[2]);
+
+    let transaction = app.db_pool.begin().await.unwrap();
+    assert_eq!(
+        get_dm_policy(transaction, recipient.id).await.unwrap(),
+        Some(DmPolicy::Everyone)
+    );
+    let transaction = app.db_pool.begin().await.unwrap();
+    update_dm_policy(transaction, recipient.id, DmPolicy::Nobody)
+        .await
+        .unwrap();
+    let transaction = app.db_pool.begin().await.unwrap();
+    let error = insert_conversation(transaction, sender.id, &[recipient.id])
+        .await
+        .unwrap_err();
+    assert!(error.to_string().contains(&recipient.username));
+
+    // Subscriptions only admits the accounts the recipient subscribes to
+    let transaction = app.db_pool.begin().await.unwrap();
+    update_dm_policy(transaction, recipient.id, DmPolicy::Subscriptions)
+        .await
+        .unwrap();
+    let transaction = app.db_pool.begin().await.unwrap();
+    assert!(insert_conversation(transaction, sender.id, &[recipient.id])
+        .await
+        .is_err());
+    let transaction = app.db_pool.begin().await.unwrap();
+    insert_subscription(transaction, &Subscription::new(recipient.id, sender.id))
+        .await
+        .unwrap();
+    let transaction = app.db_pool.begin().await.unwrap();
+    let conversation = insert_conversation(transaction, sender.id, &[recipient.id])
+        .await
+        .unwrap();
+
+    let transaction = app.db_pool.begin().await.unwrap();
+    let group = insert_conversation(transaction, sender.id, &[recipient.id, blocker.id])
+        .await
+        .unwrap();
+    assert!(group.is_group);
+    assert_ne!(group.id, conversation.id);
+    let transaction = app.db_pool.begin().await.unwrap();
+    insert_block(transaction, &Block::new(blocker.id, sender.id))
+        .await
+        .unwrap();
+    let transaction = app.db_pool.begin().await.unwrap();
+    assert!(insert_conversation(transaction, sender.id, &[blocker.id])
+        .await
+        .is_err());
+
+    // Group messages still go through, but the blocker never sees them
+    let transaction = app.db_pool.begin().await.unwrap();
+    let message = DirectMessage::new(group.id, sender.id, "hello all".to_string());
+    insert_message(transaction, &message)
+        .await
+        .unwrap()
+        .unwrap();
+    let transaction = app.db_pool.begin().await.unwrap();
+    let page = get_messages(
+        transaction,
+        blocker.id,
+        group.id,
+        CursorQuery::default_query(),
+    )
+    .await
+    .unwrap()
+    .unwrap();
+    assert!(page.data.is_empty());
+    let transaction = app.db_pool.begin().await.unwrap();
+    let seen = get_conversation_by_id(transaction, blocker.id, group.id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(seen.unread_count, 0);
+
+    // Blocking between the two ends a one-to-one conversation
+    let transaction = app.db_pool.begin().await.unwrap();
+    insert_block(transaction, &Block::new(recipient.id, sender.id))
+        .await
+        .unwrap();
+    let transaction = app.db_pool.begin().await.unwrap();
+    let message = DirectMessage::new(conversation.id, sender.id, "still there?".to_string());
+    assert!(insert_message(transaction, &message).await.is_err());
+}
+
+#[tokio::test]
+async fn conversations_are_paginated_by_latest_activity() {
+    let app = spawn_app().await;
+    let users = User::default_vec_test(Some(4));
+    for user in &users {
+        let transaction = app.db_pool.begin().await.unwrap();
+        insert_user(transaction, user).await.unwrap();
+    }
+    let owner = &users[0];
+    let mut ids = Vec::new();
+    for other in &users[1..] {
+        let transaction = app.db_pool.begin().await.unwrap();
+        let conversation = insert_conversation(transaction, owner.id, &[other.id])
+            .await
+            .unwrap();
+        ids.push(conversation.id);
+    }
+    // A new message moves the oldest conversation to the top
+    let transaction = app.db_pool.begin().await.unwrap();
+    let message = DirectMessage::new(ids[0], users[1].id, "bump".to_string());
+    insert_message(transaction, &message)
+        .await
+        .unwrap()
+        .unwrap();
+
+    let mut seen = Vec::new();
+    let mut cursor = None;
+    loop {
+        let transaction = app.db_pool.begin().await.unwrap();
+        let page = get_conversations(
+            transaction,
+            owner.id,
+            CursorQuery {
+                cursor,
+                size: Some(2),
+            },
+        )
+        .await
+        .unwrap();
+        seen.extend(page.data.iter().map(|c| c.id));
+        match page.next_cursor {
+            Some(next) => cursor = Some(next),
+            None => break,
+        }
+    }
+    assert_eq!(seen, vec![ids[0], ids[2], ids[1]]);
+}
@@ -0,0 +1,424 @@
+use crate::helpers::spawn_app;
+use aloha_backend::dto::query::{DraftFilterQuery, DtoQuery, TweetFilterQuery};
+use aloha_backend::mappers::content_rule::insert_content_rule;
+use aloha_backend::mappers::draft::{
+    delete_draft, get_draft_by_id, get_drafts_by_user_id, insert_draft, publish_draft,
+    publish_due_tweets, update_draft,
+};
+use aloha_backend::mappers::hashtag::get_tweets_by_hashtag;
+use aloha_backend::mappers::moderation::get_held_tweets;
+use aloha_backend::mappers::tweet::{get_all_tweets, get_tweet_by_id, get_tweet_status};
+use aloha_backend::mappers::user::insert_user;
+use aloha_backend::models::content_rule::{ContentRule, ContentRuleAction, ContentRuleKind};
+use aloha_backend::models::draft::{TweetDraft, TweetStatus};
+use aloha_backend::models::tweet::TweetVisibility;
+use aloha_backend::models::user::User;
+use sqlx::PgPool;
+use time::{Duration, OffsetDateTime};
+use uuid::Uuid;
+
+#[tokio::test]
+async fn drafts_stay_private_until_published() {
+    let app = spawn_app().await;
+    let users = User::default_vec_test(Some(2));
+    let transaction = app.db_pool.begin().await.unwrap();
+    let author = insert_user(transaction, &users[0]).await.unwrap();
+    let transaction = app.db_pool.begin().await.unwrap();
+    let other = insert_user(transaction, &users[1]).await.unwrap();
+
+    let later = OffsetDateTime::now_utc() + Duration::hours(1);
+    let transaction = app.db_pool.begin().await.unwrap();
+    let draft = insert_draft(
+        transaction,
+        &TweetDraft::new("Half-written #idea".to_string(), None, author.id),
+        &[],
+    )
+    .await
+    .unwrap();
+    assert_eq!(draft.status, TweetStatus::Draft);
+    let transaction = app.db_pool.begin().await.unwrap();
+    let scheduled = insert_draft(
+        transaction,
+        &TweetDraft::new("See you soon".to_string(), Some(later), author.id),
+        &[],
+    )
+    .await
+    .unwrap();
+    assert_eq!(scheduled.status, TweetStatus::Scheduled);
+
+    let transaction = app.db_pool.begin().await.unwrap();
+    let all = get_all_tweets(
+        transaction,
+        DtoQuery::<TweetFilterQuery>::default_query(),
+        None,
+    )
+    .await
+    .unwrap();
+    assert!(all.data.is_empty());
+    let transaction = app.db_pool.begin().await.unwrap();
+    assert!(get_tweet_by_id(transaction, draft.id, None)
+        .await
+        .unwrap()
+        .is_none());
+    let transaction = app.db_pool.begin().await.unwrap();
+    assert!(get_draft_by_id(transaction, other.id, draft.id)
+        .await
+        .unwrap()
+        .is_none());
+
+    let transaction = app.db_pool.begin().await.unwrap();
+    let listed = get_drafts_by_user_id(
+        transaction,
+        author.id,
+        DtoQuery::<DraftFilterQuery>::default_query(),
+    )
+    .await
+    .unwrap();
+    let ids: Vec<_> = listed.data.iter().map(|d| d.id).collect();
+    assert_eq!(ids, vec![scheduled.id, draft.id]);
+
+    let mut query = DtoQuery::<DraftFilterQuery>::default_query();
+    query.filter = Some(DraftFilterQuery {
+        status: Some(TweetStatus::Draft),
+    });
+    let transaction = app.db_pool.begin().await.unwrap();
+    let drafts_only = get_drafts_by_user_id(transaction, author.id, query)
+        .await
+        .unwrap();
+    assert_eq!(drafts_only.data.len(), 1);
+    assert_eq!(drafts_only.data[0].id, draft.id);
+
+    // Clearing `publish_at` turns a scheduled tweet back into a draft.
+    let transaction = app.db_pool.begin().await.unwrap();
+    let unscheduled = update_draft(
+        transaction,
+        &TweetDraft {
+            id: scheduled.id,
+            ..TweetDraft::new("See you later".to_string(), None, author.id)
+        },
+    )
+    .await
+    .unwrap()
+    .unwrap();
+    assert_eq!(unscheduled.status, TweetStatus::Draft);
+    assert_eq!(unscheduled.content, "See you later");
+
+    let transaction = app.db_pool.begin().await.unwrap();
+    let published = publish_draft(transaction, author.id, draft.id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(published.id, draft.id);
+    let transaction = app.db_pool.begin().await.unwrap();
+    assert!(publish_draft(transaction, author.id, draft.id)
+        .await
+        .unwrap()
+        .is_none());
+    let transaction = app.db_pool.begin().await.unwrap();
+    let tagged = get_tweets_by_hashtag(
+        transaction,
+        "idea",
+        DtoQuery::<TweetFilterQuery>::default_query(),
+        None,
+    )
+    .await
+    .unwrap();
+    assert_eq!(tagged.data.len(), 1);
+
+    // Published tweets are no longer drafts.
+    let transaction = app.db_pool.begin().await.unwrap();
+    assert!(delete_draft(transaction, author.id, draft.id)
+        .await
+        .unwrap()
+        .is_none());
+    let transaction = app.db_pool.begin().await.unwrap();
+    assert!(delete_draft(transaction, author.id, scheduled.id)
+        .await
+        .unwrap()
+        .is_some());
+}
+
+#[tokio::test]
+async fn publish_due_tweets_publishes_each_tweet_once() {
+    let app = spawn_app().await;
+    let transaction = app.db_pool.begin().await.unwrap();
+    let user = insert_user(transaction, &User::default_test())
+        .await
+        .unwrap();
+
+    let now = OffsetDateTime::now_utc();
+    let mut due = Vec::new();
+    for i in 0..6 {
+        let transaction = app.db_pool.begin().await.unwrap();
+        let draft = TweetDraft::new(
+            format!("Due tweet {}", i),
+            Some(now - Duration::minutes(i)),
+            user.id,
+        );
+        due.push(insert_draft(transaction, &draft, &[]).await.unwrap().id);
+    }
+    let transaction = app.db_pool.begin().await.unwrap();
+    let future = insert_draft(
+        transaction,
+        &TweetDraft::new(
+            "Not yet".to_string(),
+            Some(now + Duration::hours(1)),
+            user.id,
+        ),
+        &[],
+    )
+    .await
+    .unwrap();
+
+    // A row held by another instance is skipped rather than waited for.
+    let mut holder = app.db_pool.begin().await.unwrap();
+    sqlx::query!("SELECT id FROM tweet WHERE id = $1 FOR UPDATE", due[0])
+        .fetch_one(&mut *holder)
+        .await
+        .unwrap();
+
+    let first = app.db_pool.begin().await.unwrap();
+    let second = app.db_pool.begin().await.unwrap();
+    let (first, second) = tokio::join!(
+        publish_due_tweets(first, now, 3),
+        publish_due_tweets(second, now, 3)
+    );
+    let mut published: Vec<_> = first
+        .unwrap()
+        .into_iter()
+        .chain(second.unwrap())
+        .map(|t| t.id)
+        .collect();
+    assert_eq!(published.len(), 5);
+    assert!(!published.contains(&due[0]));
+
+    holder.rollback().await.unwrap();
+    let transaction = app.db_pool.begin().await.unwrap();
+    let rest = publish_due_tweets(transaction, now, 3).await.unwrap();
+    published.extend(rest.into_iter().map(|t| t.id));
+    published.sort();
+    due.sort();
+    assert_eq!(published, due);
+
+    let transaction = app.db_pool.begin().await.unwrap();
+    assert!(publish_due_tweets(transaction, now, 3)
+        .await
+        .unwrap()
+        .is_empty());
+    let transaction = app.db_pool.begin().await.unwrap();
+    assert!(get_tweet_by_id(transaction, future.id, None)
+        .await
+        .unwrap()
+        .is_none());
+}
+
+#[tokio::test]
+async fn publish_due_tweets_skips_tweets_that_fail_to_publish() {
+    let app = spawn_app().await;
+    let transaction = app.db_pool.begin().await.unwrap();
+    let user = insert_user(transaction, &User::default_test())
+        .await
+        .unwrap();
+    sqlx::query(
+        r#"
+        CREATE FUNCTION fail_broken_tweets() RETURNS trigger AS $$
+        BEGIN
+            IF NEW.content = 'broken' AND NEW.status = 'published' THEN
+                RAISE EXCEPTION 'broken tweet';
+            END IF;
+            RETURN NEW;
+        END;
+        $$ LANGUAGE plpgsql
+        "#,
+    )
+    .execute(&app.db_pool)
+    .await
+    .unwrap();
+    sqlx::query(
+        "CREATE TRIGGER fail_broken_tweets BEFORE UPDATE ON tweet
+         FOR EACH ROW EXECUTE FUNCTION fail_broken_tweets()",
+    )
+    .execute(&app.db_pool)
+    .await
+    .unwrap();
+
+    let now = OffsetDateTime::now_utc();
+    let mut ids = Vec::new();
+    for (content, minutes) in [("broken", 2), ("fine", 1)] {
+        let transaction = app.db_pool.begin().await.unwrap();
+        let draft = TweetDraft::new(
+            content.to_string(),
+            Some(now - Duration::minutes(minutes)),
+            user.id,
+        );
+        ids.push(insert_draft(transaction, &draft, &[]).await.unwrap().id);
+    }
+
+    let transaction = app.db_pool.begin().await.unwrap();
+    let published = publish_due_tweets(transaction, now, 10).await.unwrap();
+    assert_eq!(published.len(), 1);
+    assert_eq!(published[0].id, ids[1]);
+    let transaction = app.db_pool.begin().await.unwrap();
+    assert_eq!(
+        get_tweet_status(transaction, ids[0]).await.unwrap(),
+        Some(TweetStatus::Scheduled)
+    );
+}
+
+#[tokio::test]
+async fn scheduled_tweets_keep_their_visibility() {
+    let app = spawn_app().await;
+    let transaction = app.db_pool.begin().await.unwrap();
+    let user = insert_user(transaction, &User::default_test())
+        .await
+        .unwrap();
+
+    let now = OffsetDateTime::now_utc();
+    let transaction = app.db_pool.begin().await.unwrap();
+    let draft = insert_draft(
+        transaction,
+        &TweetDraft {
+            visibility: TweetVisibility::Private,
+            ..TweetDraft::new("Only for me".to_string(), Some(now), user.id)
+        },
+        &[],
+    )
+    .await
+    .unwrap();
+    assert_eq!(draft.visibility, TweetVisibility::Private);
+
+    let transaction = app.db_pool.begin().await.unwrap();
+    let published = publish_due_tweets(transaction, now, 10).await.unwrap();
+    assert_eq!(published.len(), 1);
+    assert_eq!(published[0].visibility, TweetVisibility::Private);
+
+    let transaction = app.db_pool.begin().await.unwrap();
+    assert!(get_tweet_by_id(transaction, draft.id, None)
+        .await
+        .unwrap()
+        .is_none());
+    let transaction = app.db_pool.begin().await.unwrap();
+    assert!(get_tweet_by_id(transaction, draft.id, Some(user.id))
+        .await
+        .unwrap()
+        .is_some());
+}
+
+/// Schedules `content` as already due under a single content rule matching
+/// "darn" with `action`, then runs the scheduler once.
+async fn schedule_under_rule(
+    pool: &PgPool,
+    action: ContentRuleAction,
+    content: &str,
+) -> (Uuid, Vec<Uuid>) {
+    let transaction = pool.begin().await.unwrap();
+    let user = insert_user(transaction, &User::default_test())
+        .await
+        .unwrap();
+    let transaction = pool.begin().await.unwrap();
+    let rule = ContentRule::new(ContentRuleKind::Word, "darn", action);
+    insert_content_rule(transaction, &rule).await.unwrap();
+
+    let now = OffsetDateTime::now_utc();
+    let transaction = pool.begin().await.unwrap();
+    let draft = TweetDraft::new(content.to_string(), Some(now), user.id);
+    let draft = insert_draft(transaction, &draft, &[]).await.unwrap();
+    let transaction = pool.begin().await.unwrap();
+    let published = publish_due_tweets(transaction, now, 10)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|t| t.id)
+        .collect();
+    (draft.id, published)
+}
+
+#[tokio::test]
+async fn publish_due_tweets_masks_matched_words() {
+    let app = spawn_app().await;
+    let (id, published) =
+        schedule_under_rule(&app.db_pool, ContentRuleAction::Mask, "darn #bugs").await;
+    assert_eq!(published, vec![id]);
+
+    let transaction = app.db_pool.begin().await.unwrap();
+    let tweet = get_tweet_by_id(transaction, id, None)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(tweet.content, "**** #bugs");
+    let transaction = app.db_pool.begin().await.unwrap();
+    let tagged = get_tweets_by_hashtag(
+        transaction,
+        "bugs",
+        DtoQuery::<TweetFilterQuery>::default_query(),
+        None,
+    )
+    .await
+    .unwrap();
+    assert_eq!(tagged.data.len(), 1);
+}
+
+#[tokio::test]
+async fn publish_due_tweets_holds_tweets_for_moderators() {
+    let app = spawn_app().await;
+    let (id, published) =
+        schedule_under_rule(&app.db_pool, ContentRuleAction::Hold, "darn #bugs").await;
+    assert!(published.is_empty());
+
+    let transaction = app.db_pool.begin().await.unwrap();
+    assert_eq!(
+        get_tweet_status(transaction, id).await.unwrap(),
+        Some(TweetStatus::Held)
+    );
+    let transaction = app.db_pool.begin().await.unwrap();
+    let queue = get_held_tweets(transaction, DtoQuery::<TweetFilterQuery>::default_query())
+        .await
+        .unwrap();
+    assert_eq!(
+        queue.data.iter().map(|t| t.id).collect::<Vec<_>>(),
+        vec![id]
+    );
+    let transaction = app.db_pool.begin().await.unwrap();
+    let tagged = get_tweets_by_hashtag(
+        transaction,
+        "bugs",
+        DtoQuery::<TweetFilterQuery>::default_query(),
+        None,
+    )
+    .await
+    .unwrap();
+    assert!(tagged.data.is_empty());
+}
+
+#[tokio::test]
+async fn publish_due_tweets_turns_rejected_tweets_back_into_drafts() {
+    let app = spawn_app().await;
+    let (id, published) =
+        schedule_under_rule(&app.db_pool, ContentRuleAction::Reject, "darn #bugs").await;
+    assert!(published.is_empty());
+
+    let transaction = app.db_pool.begin().await.unwrap();
+    assert_eq!(
+        get_tweet_status(transaction, id).await.unwrap(),
+        Some(TweetStatus::Draft)
+    );
+    let transaction = app.db_pool.begin().await.unwrap();
+    assert!(get_tweet_by_id(transaction, id, None)
+        .await
+        .unwrap()
+        .is_none());
+    // Publishing it by hand fails the same way and keeps the draft
+    let author_id = sqlx::query!("SELECT user_id FROM tweet WHERE id = $1", id)
+        .fetch_one(&app.db_pool)
+        .await
+        .unwrap()
+        .user_id;
+    let transaction = app.db_pool.begin().await.unwrap();
+    assert!(publish_draft(transaction, author_id, id).await.is_err());
+    let transaction = app.db_pool.begin().await.unwrap();
+    let draft = get_draft_by_id(transaction, author_id, id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(draft.content, "darn #bugs");
+}
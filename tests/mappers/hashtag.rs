@@ -0,0 +1,135 @@
+use crate::helpers::spawn_app;
+use aloha_backend::configuration::HashtagSettings;
+use aloha_backend::dto::query::{DtoQuery, TweetFilterQuery};
+use aloha_backend::mappers::hashtag::{get_trending_hashtags, get_tweets_by_hashtag};
+use aloha_backend::mappers::tweet::{delete_tweet_by_id, insert_tweet, update_tweet};
+use aloha_backend::mappers::user::insert_user;
+use aloha_backend::models::tweet::Tweet;
+use aloha_backend::models::user::User;
+
+fn trending_settings() -> HashtagSettings {
+    HashtagSettings {
+        trending_window_hours: 24,
+        trending_half_life_hours: 6.0,
+        trending_limit: 10,
+    }
+}
+
+#[tokio::test]
+async fn insert_tweet_links_hashtags() {
+    let app = spawn_app().await;
+    let transaction = app.db_pool.begin().await.unwrap();
+    let user = insert_user(transaction, &User::default_test())
+        .await
+        .unwrap();
+
+    let transaction = app.db_pool.begin().await.unwrap();
+    let tweet = Tweet::new(String::from("Shipping #Rust and #SQLx today"), user.id);
+    let tweet = insert_tweet(transaction, &tweet).await.unwrap();
+    let transaction = app.db_pool.begin().await.unwrap();
+    insert_tweet(transaction, &Tweet::default_test(user.id))
+        .await
+        .unwrap();
+
+    let transaction = app.db_pool.begin().await.unwrap();
+    let result = get_tweets_by_hashtag(
+        transaction,
+        "#RUST",
+        DtoQuery::<TweetFilterQuery>::default_query(),
+        None,
+    )
+    .await
+    .unwrap();
+    assert_eq!(result.data.len(), 1);
+    assert_eq!(result.data[0].id, tweet.id);
+    assert_eq!(result.pagination.unwrap().total(), 1);
+
+    let transaction = app.db_pool.begin().await.unwrap();
+    let result = get_tweets_by_hashtag(
+        transaction,
+        "sqlx",
+        DtoQuery::<TweetFilterQuery>::default_query(),
+        None,
+    )
+    .await
+    .unwrap();
+    assert_eq!(result.data.len(), 1);
+}
+
+#[tokio::test]
+async fn update_and_delete_tweet_resync_hashtags() {
+    let app = spawn_app().await;
+    let transaction = app.db_pool.begin().await.unwrap();
+    let user = insert_user(transaction, &User::default_test())
+        .await
+        .unwrap();
+
+    let transaction = app.db_pool.begin().await.unwrap();
+    let mut tweet = insert_tweet(transaction, &Tweet::new(String::from("#before"), user.id))
+        .await
+        .unwrap();
+
+    tweet.content = String::from("now about #after");
+    let transaction = app.db_pool.begin().await.unwrap();
+    update_tweet(transaction, &tweet).await.unwrap();
+
+    let transaction = app.db_pool.begin().await.unwrap();
+    let before = get_tweets_by_hashtag(transaction, "before", DtoQuery::default_query(), None)
+        .await
+        .unwrap();
+    assert!(before.data.is_empty());
+    let transaction = app.db_pool.begin().await.unwrap();
+    let after = get_tweets_by_hashtag(transaction, "after", DtoQuery::default_query(), None)
+        .await
+        .unwrap();
+    assert_eq!(after.data.len(), 1);
+
+    let transaction = app.db_pool.begin().await.unwrap();
+    delete_tweet_by_id(transaction, tweet.id).await.unwrap();
+    let transaction = app.db_pool.begin().await.unwrap();
+    let after = get_tweets_by_hashtag(transaction, "after", DtoQuery::default_query(), None)
+        .await
+        .unwrap();
+    assert!(after.data.is_empty());
+}
+
+#[tokio::test]
+async fn get_trending_hashtags_decays_older_tweets() {
+    let app = spawn_app().await;
+    let transaction = app.db_pool.begin().await.unwrap();
+    let user = insert_user(transaction, &User::default_test())
+        .await
+        .unwrap();
+
+    // Two uses of #steady twenty hours ago, one fresh use of #burst, and a
+    // use of #stale that has fallen out of the window.
+    for (content, age_hours) in [
+        ("#steady", 20),
+        ("#steady", 20),
+        ("#burst", 0),
+        ("#stale", 30),
+    ] {
+        let transaction = app.db_pool.begin().await.unwrap();
+        let tweet = insert_tweet(transaction, &Tweet::new(content.to_string(), user.id))
+            .await
+            .unwrap();
+        sqlx::query!(
+            "UPDATE tweet SET created_at = now() - make_interval(hours => $1) WHERE id = $2",
+            age_hours,
+            tweet.id
+        )
+        .execute(&app.db_pool)
+        .await
+        .unwrap();
+    }
+
+    let transaction = app.db_pool.begin().await.unwrap();
+    let trending = get_trending_hashtags(transaction, &trending_settings())
+        .await
+        .unwrap();
+
+    let tags: Vec<&str> = trending.iter().map(|t| t.tag.as_str()).collect();
+    assert_eq!(tags, vec!["burst", "steady"]);
+    assert_eq!(trending[1].uses, 2);
+    assert!(trending[0].score > trending[1].score);
+}
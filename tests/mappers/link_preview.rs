@@ -0,0 +1,78 @@
+use crate::helpers::spawn_app;
+use aloha_backend::mappers::link_preview::{
+    claim_link_previews, get_link_cards_by_tweet_ids, get_link_preview, save_link_preview,
+};
+use aloha_backend::mappers::tweet::{insert_tweet, update_tweet};
+use aloha_backend::mappers::user::insert_user;
+use aloha_backend::models::link_preview::{LinkPreviewStatus, PreviewMetadata};
+use aloha_backend::models::tweet::Tweet;
+use aloha_backend::models::user::User;
+
+#[tokio::test]
+async fn tweet_links_are_queued_once_and_resynced_on_edit() {
+    let app = spawn_app().await;
+    let transaction = app.db_pool.begin().await.unwrap();
+    let author = insert_user(transaction, &User::default_test())
+        .await
+        .unwrap();
+    let content = "See https://example.com/a?utm_source=feed and https://example.org/b";
+    let transaction = app.db_pool.begin().await.unwrap();
+    let mut tweet = insert_tweet(transaction, &Tweet::new(content.to_string(), author.id))
+        .await
+        .unwrap();
+
+    let transaction = app.db_pool.begin().await.unwrap();
+    let preview = get_link_preview(transaction, "https://example.com/a")
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(preview.status, LinkPreviewStatus::Pending);
+
+    let transaction = app.db_pool.begin().await.unwrap();
+    let mut claimed = claim_link_previews(transaction, 10, 60).await.unwrap();
+    claimed.sort_by(|a, b| a.url.cmp(&b.url));
+    let urls: Vec<&str> = claimed.iter().map(|c| c.url.as_str()).collect();
+    assert_eq!(urls, vec!["https://example.com/a", "https://example.org/b"]);
+    assert!(claimed.iter().all(|c| c.attempts == 1));
+    // Claimed previews wait for their retry delay
+    let transaction = app.db_pool.begin().await.unwrap();
+    assert!(claim_link_previews(transaction, 10, 60)
+        .await
+        .unwrap()
+        .is_empty());
+
+    for url in ["https://example.com/a", "https://example.org/b"] {
+        let metadata = PreviewMetadata {
+            title: Some(url.to_string()),
+            ..PreviewMetadata::default()
+        };
+        let transaction = app.db_pool.begin().await.unwrap();
+        save_link_preview(transaction, url, &metadata)
+            .await
+            .unwrap()
+            .unwrap();
+    }
+    let mut transaction = app.db_pool.begin().await.unwrap();
+    let cards = get_link_cards_by_tweet_ids(&mut transaction, &[tweet.id])
+        .await
+        .unwrap();
+    assert_eq!(cards[&tweet.id].url, "https://example.com/a");
+
+    tweet.content = "Only https://example.org/b now".to_string();
+    let transaction = app.db_pool.begin().await.unwrap();
+    update_tweet(transaction, &tweet).await.unwrap();
+    let mut transaction = app.db_pool.begin().await.unwrap();
+    let cards = get_link_cards_by_tweet_ids(&mut transaction, &[tweet.id])
+        .await
+        .unwrap();
+    assert_eq!(cards[&tweet.id].url, "https://example.org/b");
+
+    tweet.content = "No links at all".to_string();
+    let transaction = app.db_pool.begin().await.unwrap();
+    update_tweet(transaction, &tweet).await.unwrap();
+    let mut transaction = app.db_pool.begin().await.unwrap();
+    assert!(get_link_cards_by_tweet_ids(&mut transaction, &[tweet.id])
+        .await
+        .unwrap()
+        .is_empty());
+}
@@ -0,0 +1,69 @@
+use crate::helpers::spawn_app;
+use aloha_backend::mappers::media::{get_media_by_tweet_ids, insert_media};
+use aloha_backend::mappers::tweet::insert_tweet_with_media;
+use aloha_backend::mappers::user::insert_user;
+use aloha_backend::models::media::Media;
+use aloha_backend::models::tweet::Tweet;
+use aloha_backend::models::user::User;
+
+#[tokio::test]
+async fn insert_media_deduplicates_by_hash() {
+    let app = spawn_app().await;
+    let transaction = app.db_pool.begin().await.unwrap();
+    let user = insert_user(transaction, &User::default_test())
+        .await
+        .unwrap();
+
+    let transaction = app.db_pool.begin().await.unwrap();
+    let first = insert_media(transaction, &Media::new(user.id, "image/png", b"same"))
+        .await
+        .unwrap();
+    let transaction = app.db_pool.begin().await.unwrap();
+    let second = insert_media(transaction, &Media::new(user.id, "image/png", b"same"))
+        .await
+        .unwrap();
+    assert_eq!(first.id, second.id);
+    assert_eq!(first.size_bytes, 4);
+}
+
+#[tokio::test]
+async fn insert_tweet_with_media_keeps_order_and_checks_owner() {
+    let app = spawn_app().await;
+    let mut users = Vec::new();
+    for user in User::default_vec_test(Some(2)) {
+        let transaction = app.db_pool.begin().await.unwrap();
+        users.push(insert_user(transaction, &user).await.unwrap());
+    }
+    let mut media = Vec::new();
+    for content in [b"first".as_slice(), b"second".as_slice()] {
+        let transaction = app.db_pool.begin().await.unwrap();
+        media.push(
+            insert_media(transaction, &Media::new(users[0].id, "image/png", content))
+                .await
+                .unwrap(),
+        );
+    }
+
+    let media_ids = vec![media[1].id, media[0].id];
+    let transaction = app.db_pool.begin().await.unwrap();
+    let tweet = insert_tweet_with_media(transaction, &Tweet::default_test(users[0].id), &media_ids)
+        .await
+        .unwrap();
+
+    let mut transaction = app.db_pool.begin().await.unwrap();
+    let mut attached = get_media_by_tweet_ids(&mut transaction, &[tweet.id])
+        .await
+        .unwrap();
+    let attached_ids: Vec<_> = attached
+        .remove(&tweet.id)
+        .unwrap()
+        .into_iter()
+        .map(|m| m.id)
+        .collect();
+    assert_eq!(attached_ids, media_ids);
+
+    let transaction = app.db_pool.begin().await.unwrap();
+    let result =
+        insert_tweet_with_media(transaction, &Tweet::default_test(users[1].id), &media_ids).await;
+    assert!(result.is_err());
+}
@@ -0,0 +1,82 @@
+use crate::helpers::spawn_app;
+use aloha_backend::dto::query::{DtoQuery, TweetFilterQuery};
+use aloha_backend::mappers::mention::{get_mentions_by_tweet_ids, get_tweets_mentioning_user};
+use aloha_backend::mappers::tweet::{insert_tweet, update_tweet};
+use aloha_backend::mappers::user::insert_user;
+use aloha_backend::models::tweet::Tweet;
+use aloha_backend::models::user::User;
+
+#[tokio::test]
+async fn insert_tweet_resolves_known_mentions() {
+    let app = spawn_app().await;
+    let mut users = Vec::new();
+    for user in User::default_vec_test(Some(2)) {
+        let transaction = app.db_pool.begin().await.unwrap();
+        users.push(insert_user(transaction, &user).await.unwrap());
+    }
+
+    let content = format!("hi @{} and @nobody", users[1].username);
+    let transaction = app.db_pool.begin().await.unwrap();
+    let tweet = insert_tweet(transaction, &Tweet::new(content, users[0].id))
+        .await
+        .unwrap();
+
+    let mut transaction = app.db_pool.begin().await.unwrap();
+    let mut mentions = get_mentions_by_tweet_ids(&mut transaction, &[tweet.id])
+        .await
+        .unwrap();
+    let mentions = mentions.remove(&tweet.id).unwrap();
+    assert_eq!(mentions.len(), 1);
+    assert_eq!(mentions[0].user_id, users[1].id);
+    assert_eq!(mentions[0].start, 3);
+    assert_eq!(
+        mentions[0].end,
+        4 + users[1].username.chars().count() as i32
+    );
+}
+
+#[tokio::test]
+async fn update_tweet_resyncs_mentions() {
+    let app = spawn_app().await;
+    let mut users = Vec::new();
+    for user in User::default_vec_test(Some(3)) {
+        let transaction = app.db_pool.begin().await.unwrap();
+        users.push(insert_user(transaction, &user).await.unwrap());
+    }
+
+    let transaction = app.db_pool.begin().await.unwrap();
+    let mut tweet = insert_tweet(
+        transaction,
+        &Tweet::new(format!("@{}", users[1].username), users[0].id),
+    )
+    .await
+    .unwrap();
+
+    tweet.content = format!("now @{}", users[2].username);
+    let transaction = app.db_pool.begin().await.unwrap();
+    update_tweet(transaction, &tweet).await.unwrap();
+
+    let transaction = app.db_pool.begin().await.unwrap();
+    let before = get_tweets_mentioning_user(
+        transaction,
+        users[1].id,
+        DtoQuery::<TweetFilterQuery>::default_query(),
+        None,
+    )
+    .await
+    .unwrap();
+    assert!(before.data.is_empty());
+
+    let transaction = app.db_pool.begin().await.unwrap();
+    let after = get_tweets_mentioning_user(
+        transaction,
+        users[2].id,
+        DtoQuery::<TweetFilterQuery>::default_query(),
+        None,
+    )
+    .await
+    .unwrap();
+    assert_eq!(after.data.len(), 1);
+    assert_eq!(after.data[0].id, tweet.id);
+    assert_eq!(after.pagination.unwrap().total(), 1);
+}
@@ -1,5 +1,27 @@
+mod account;
+mod block;
+mod bookmark;
+mod content_rule;
+mod conversation;
+mod draft;
+mod hashtag;
+mod link_preview;
+mod media;
+mod mention;
+mod moderation;
+mod mute;
+mod notification;
 mod permission;
+mod poll;
+mod report;
+mod retention;
+mod search;
+mod stream;
+mod subscription;
+mod timeline;
 mod tweet;
+mod tweet_revision;
 mod user;
 mod user_group;
+mod user_list;
 mod user_permission;
@@ -0,0 +1,126 @@
+use crate::helpers::spawn_app;
+use aloha_backend::dto::query::{DtoQuery, ModerationActionFilterQuery};
+use aloha_backend::mappers::account::get_account_state;
+use aloha_backend::mappers::moderation::{get_moderation_actions, hide_tweet, suspend_user};
+use aloha_backend::mappers::report::{get_report_by_id, insert_report};
+use aloha_backend::mappers::tweet::{get_tweet_by_id, insert_tweet};
+use aloha_backend::mappers::user::insert_user;
+use aloha_backend::models::account::AccountStatus;
+use aloha_backend::models::moderation::{ModerationAction, ModerationActionKind};
+use aloha_backend::models::report::{Report, ReportStatus};
+use aloha_backend::models::tweet::Tweet;
+use aloha_backend::models::user::User;
+use time::{Duration, OffsetDateTime};
+use uuid::Uuid;
+
+#[tokio::test]
+async fn moderator_actions_resolve_reports_and_are_logged() {
+    let app = spawn_app().await;
+    let users = User::default_vec_test(Some(3));
+    for user in &users {
+        let transaction = app.db_pool.begin().await.unwrap();
+        insert_user(transaction, user).await.unwrap();
+    }
+    let (author, reporter, moderator) = (&users[0], &users[1], &users[2]);
+    let transaction = app.db_pool.begin().await.unwrap();
+    let tweet = insert_tweet(transaction, &Tweet::new("rude".to_string(), author.id))
+        .await
+        .unwrap();
+    let transaction = app.db_pool.begin().await.unwrap();
+    let report = insert_report(
+        transaction,
+        &Report::new(reporter.id, Some(tweet.id), None, "rude".to_string()),
+    )
+    .await
+    .unwrap()
+    .unwrap();
+
+    let hide = ModerationAction {
+        tweet_id: Some(tweet.id),
+        report_id: Some(report.id),
+        ..ModerationAction::new(moderator.id, ModerationActionKind::HideTweet)
+    };
+    let transaction = app.db_pool.begin().await.unwrap();
+    let hidden = hide_tweet(transaction, &hide).await.unwrap().unwrap();
+    assert_eq!(hidden.tweet_id, Some(tweet.id));
+    let transaction = app.db_pool.begin().await.unwrap();
+    assert!(get_tweet_by_id(transaction, tweet.id, Some(author.id))
+        .await
+        .unwrap()
+        .is_none());
+    let transaction = app.db_pool.begin().await.unwrap();
+    let report = get_report_by_id(transaction, report.id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(report.status, ReportStatus::Actioned);
+
+    // Hidden tweets cannot be hidden again
+    let transaction = app.db_pool.begin().await.unwrap();
+    let again = ModerationAction {
+        report_id: None,
+        ..hide.clone()
+    };
+    assert!(hide_tweet(transaction, &again).await.unwrap().is_none());
+
+    let now = OffsetDateTime::now_utc();
+    let suspend = ModerationAction {
+        user_id: Some(author.id),
+        note: Some("Repeated abuse".to_string()),
+        ..ModerationAction::new(moderator.id, ModerationActionKind::SuspendUser)
+    };
+    let transaction = app.db_pool.begin().await.unwrap();
+    suspend_user(transaction, &suspend, now + Duration::days(7))
+        .await
+        .unwrap()
+        .unwrap();
+    let mut transaction = app.db_pool.begin().await.unwrap();
+    let state = get_account_state(&mut transaction, author.id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(state.effective_status(now), AccountStatus::Suspended);
+    assert_eq!(state.reason.as_deref(), Some("Repeated abuse"));
+    assert_eq!(
+        state.effective_status(now + Duration::days(8)),
+        AccountStatus::Active
+    );
+    drop(transaction);
+
+    // Actions answering unknown reports are rolled back
+    let transaction = app.db_pool.begin().await.unwrap();
+    let unknown = ModerationAction {
+        report_id: Some(Uuid::new_v4()),
+        ..ModerationAction::new(moderator.id, ModerationActionKind::SuspendUser)
+    };
+    let unknown = ModerationAction {
+        user_id: Some(reporter.id),
+        ..unknown
+    };
+    assert!(suspend_user(transaction, &unknown, now + Duration::days(1))
+        .await
+        .is_err());
+    let mut transaction = app.db_pool.begin().await.unwrap();
+    let state = get_account_state(&mut transaction, reporter.id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(state.status, AccountStatus::Active);
+    drop(transaction);
+
+    let transaction = app.db_pool.begin().await.unwrap();
+    let log = get_moderation_actions(
+        transaction,
+        DtoQuery::<ModerationActionFilterQuery>::default_query(),
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        log.data.iter().map(|a| a.action).collect::<Vec<_>>(),
+        vec![
+            ModerationActionKind::SuspendUser,
+            ModerationActionKind::HideTweet
+        ]
+    );
+    assert_eq!(log.data[1].report_id, Some(report.id));
+}
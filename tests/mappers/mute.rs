@@ -0,0 +1,123 @@
+use crate::helpers::spawn_app;
+use aloha_backend::dto::cursor::CursorQuery;
+use aloha_backend::dto::query::{DtoQuery, MuteFilterQuery, TweetFilterQuery};
+use aloha_backend::mappers::mute::{delete_mute, get_muted_users, insert_mute};
+use aloha_backend::mappers::subscription::insert_subscription;
+use aloha_backend::mappers::timeline::get_home_timeline;
+use aloha_backend::mappers::tweet::{get_all_tweets, insert_tweet};
+use aloha_backend::mappers::user::insert_user;
+use aloha_backend::models::mute::Mute;
+use aloha_backend::models::subscription::Subscription;
+use aloha_backend::models::tweet::Tweet;
+use aloha_backend::models::user::User;
+use uuid::Uuid;
+
+#[tokio::test]
+async fn muted_users_are_hidden_from_the_muter_timelines_only() {
+    let app = spawn_app().await;
+    let users = User::default_vec_test(Some(2));
+    for user in &users {
+        let transaction = app.db_pool.begin().await.unwrap();
+        insert_user(transaction, user).await.unwrap();
+    }
+    let (muter, muted) = (&users[0], &users[1]);
+    let transaction = app.db_pool.begin().await.unwrap();
+    insert_subscription(transaction, &Subscription::new(muter.id, muted.id))
+        .await
+        .unwrap();
+    let transaction = app.db_pool.begin().await.unwrap();
+    let muter_tweet = insert_tweet(transaction, &Tweet::new("mine".to_string(), muter.id))
+        .await
+        .unwrap();
+    let transaction = app.db_pool.begin().await.unwrap();
+    let muted_tweet = insert_tweet(transaction, &Tweet::new("noise".to_string(), muted.id))
+        .await
+        .unwrap();
+
+    let transaction = app.db_pool.begin().await.unwrap();
+    insert_mute(transaction, &Mute::new(muter.id, muted.id))
+        .await
+        .unwrap();
+
+    let transaction = app.db_pool.begin().await.unwrap();
+    let timeline = get_home_timeline(transaction, muter.id, CursorQuery::default_query())
+        .await
+        .unwrap();
+    assert_eq!(
+        timeline.data.iter().map(|t| t.id).collect::<Vec<_>>(),
+        vec![muter_tweet.id]
+    );
+
+    let transaction = app.db_pool.begin().await.unwrap();
+    let all = get_all_tweets(
+        transaction,
+        DtoQuery::<TweetFilterQuery>::default_query(),
+        Some(muter.id),
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        all.data.iter().map(|t| t.id).collect::<Vec<_>>(),
+        vec![muter_tweet.id]
+    );
+
+    // The muted user's profile can still be browsed explicitly
+    let transaction = app.db_pool.begin().await.unwrap();
+    let profile = get_all_tweets(
+        transaction,
+        DtoQuery {
+            filter: Some(TweetFilterQuery {
+                user_id: Some(muted.id),
+            }),
+            ..DtoQuery::default_query()
+        },
+        Some(muter.id),
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        profile.data.iter().map(|t| t.id).collect::<Vec<_>>(),
+        vec![muted_tweet.id]
+    );
+
+    // Muting is one-way
+    let transaction = app.db_pool.begin().await.unwrap();
+    let all = get_all_tweets(
+        transaction,
+        DtoQuery::<TweetFilterQuery>::default_query(),
+        Some(muted.id),
+    )
+    .await
+    .unwrap();
+    assert_eq!(all.data.len(), 2);
+
+    let transaction = app.db_pool.begin().await.unwrap();
+    let mutes = get_muted_users(
+        transaction,
+        muter.id,
+        DtoQuery::<MuteFilterQuery>::default_query(),
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        mutes.data.iter().map(|u| u.id).collect::<Vec<_>>(),
+        vec![muted.id]
+    );
+    let transaction = app.db_pool.begin().await.unwrap();
+    assert!(
+        insert_mute(transaction, &Mute::new(muter.id, Uuid::new_v4()))
+            .await
+            .is_err()
+    );
+
+    let transaction = app.db_pool.begin().await.unwrap();
+    assert!(delete_mute(transaction, muter.id, muted.id)
+        .await
+        .unwrap()
+        .is_some());
+    let transaction = app.db_pool.begin().await.unwrap();
+    let timeline = get_home_timeline(transaction, muter.id, CursorQuery::default_query())
+        .await
+        .unwrap();
+    assert_eq!(timeline.data.len(), 2);
+}
@@ -0,0 +1,221 @@
+use crate::helpers::spawn_app;
+use aloha_backend::dto::cursor::CursorQuery;
+use aloha_backend::mappers::notification::{
+    get_notifications, get_unread_notification_count, mark_all_notifications_read,
+    mark_notification_read,
+};
+use aloha_backend::mappers::permission::insert_permission;
+use aloha_backend::mappers::tweet::{insert_tweet, update_tweet};
+use aloha_backend::mappers::user::{insert_user, update_user};
+use aloha_backend::mappers::user_group::insert_user_group;
+use aloha_backend::mappers::user_permission::{delete_user_permission, insert_user_permission};
+use aloha_backend::models::notification::NotificationKind;
+use aloha_backend::models::permission::Permission;
+use aloha_backend::models::tweet::{Tweet, TweetVisibility};
+use aloha_backend::models::user::User;
+use aloha_backend::models::user_group::UserGroup;
+use aloha_backend::models::user_permission::UserPermission;
+
+#[tokio::test]
+async fn mentions_from_several_users_are_grouped_until_read() {
+    let app = spawn_app().await;
+    let users = User::default_vec_test(Some(3));
+    for user in &users {
+        let transaction = app.db_pool.begin().await.unwrap();
+        insert_user(transaction, user).await.unwrap();
+    }
+    let (recipient, first, second) = (&users[0], &users[1], &users[2]);
+    let content = format!("hi @{}", recipient.username);
+    let transaction = app.db_pool.begin().await.unwrap();
+    let first_tweet = insert_tweet(transaction, &Tweet::new(content.clone(), first.id))
+        .await
+        .unwrap();
+    // Editing a tweet does not notify users it already mentioned
+    let transaction = app.db_pool.begin().await.unwrap();
+    update_tweet(
+        transaction,
+        &Tweet {
+            content: format!("{} again", content),
+            ..first_tweet.clone()
+        },
+    )
+    .await
+    .unwrap();
+    let transaction = app.db_pool.begin().await.unwrap();
+    let second_tweet = insert_tweet(transaction, &Tweet::new(content.clone(), second.id))
+        .await
+        .unwrap();
+
+    let transaction = app.db_pool.begin().await.unwrap();
+    let page = get_notifications(transaction, recipient.id, CursorQuery::default_query())
+        .await
+        .unwrap();
+    assert_eq!(page.data.len(), 1);
+    let notification = &page.data[0];
+    assert_eq!(notification.kind, NotificationKind::Mention);
+    assert_eq!(notification.actor_ids, vec![second.id, first.id]);
+    assert_eq!(notification.event_count, 2);
+    assert_eq!(notification.tweet_id, Some(second_tweet.id));
+    assert_eq!(
+        notification.message(),
+        format!("{} and 1 other mentioned you", second.username)
+    );
+
+    let transaction = app.db_pool.begin().await.unwrap();
+    let read = mark_notification_read(transaction, recipient.id, notification.id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(read.read_at.is_some());
+    let transaction = app.db_pool.begin().await.unwrap();
+    assert_eq!(
+        get_unread_notification_count(transaction, recipient.id)
+            .await
+            .unwrap(),
+        0
+    );
+
+    // Mentions after reading start a new group
+    let transaction = app.db_pool.begin().await.unwrap();
+    insert_tweet(transaction, &Tweet::new(content, first.id))
+        .await
+        .unwrap();
+    let transaction = app.db_pool.begin().await.unwrap();
+    let page = get_notifications(transaction, recipient.id, CursorQuery::default_query())
+        .await
+        .unwrap();
+    assert_eq!(page.data.len(), 2);
+    assert_eq!(page.data[0].actor_ids, vec![first.id]);
+    assert!(page.data[0].read_at.is_none());
+}
+
+#[tokio::test]
+async fn mentions_in_tweets_the_user_cannot_read_are_not_notified() {
+    let app = spawn_app().await;
+    let users = User::default_vec_test(Some(2));
+    for user in &users {
+        let transaction = app.db_pool.begin().await.unwrap();
+        insert_user(transaction, user).await.unwrap();
+    }
+    let private = Tweet {
+        visibility: TweetVisibility::Private,
+        ..Tweet::new(
+            format!("@{} @{}", users[1].username, users[0].username),
+            users[0].id,
+        )
+    };
+    let transaction = app.db_pool.begin().await.unwrap();
+    insert_tweet(transaction, &private).await.unwrap();
+
+    for user in &users {
+        let transaction = app.db_pool.begin().await.unwrap();
+        assert_eq!(
+            get_unread_notification_count(transaction, user.id)
+                .await
+                .unwrap(),
+            0
+        );
+    }
+}
+
+#[tokio::test]
+async fn permission_and_group_changes_are_notified() {
+    let app = spawn_app().await;
+    let transaction = app.db_pool.begin().await.unwrap();
+    let group = insert_user_group(transaction, &UserGroup::default_vec_test(Some(1))[0])
+        .await
+        .unwrap();
+    let transaction = app.db_pool.begin().await.unwrap();
+    let permission = insert_permission(transaction, &Permission::default_vec_test(Some(1))[0])
+        .await
+        .unwrap();
+    let transaction = app.db_pool.begin().await.unwrap();
+    let user = insert_user(transaction, &User::default_test())
+        .await
+        .unwrap();
+
+    let transaction = app.db_pool.begin().await.unwrap();
+    insert_user_permission(transaction, &UserPermission::new(user.id, permission.id))
+        .await
+        .unwrap();
+    let transaction = app.db_pool.begin().await.unwrap();
+    delete_user_permission(transaction, user.id, permission.id)
+        .await
+        .unwrap();
+    let transaction = app.db_pool.begin().await.unwrap();
+    update_user(
+        transaction,
+        &User {
+            user_group_id: Some(group.id),
+            ..user.clone()
+        },
+    )
+    .await
+    .unwrap();
+    // Saving a user without changing the group is not notified
+    let transaction = app.db_pool.begin().await.unwrap();
+    update_user(
+        transaction,
+        &User {
+            user_group_id: Some(group.id),
+            ..user
+        },
+    )
+    .await
+    .unwrap();
+
+    let transaction = app.db_pool.begin().await.unwrap();
+    let first_page = get_notifications(
+        transaction,
+        user.id,
+        CursorQuery {
+            cursor: None,
+            size: Some(2),
+        },
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        first_page
+            .data
+            .iter()
+            .map(|n| n.message())
+            .collect::<Vec<_>>(),
+        vec![
+            format!("You were moved to the {} group", group.group_name),
+            format!("Your {} permission was revoked", permission.name),
+        ]
+    );
+    let transaction = app.db_pool.begin().await.unwrap();
+    let second_page = get_notifications(
+        transaction,
+        user.id,
+        CursorQuery {
+            cursor: first_page.next_cursor,
+            size: Some(2),
+        },
+    )
+    .await
+    .unwrap();
+    assert_eq!(second_page.data.len(), 1);
+    assert_eq!(
+        second_page.data[0].kind,
+        NotificationKind::PermissionGranted
+    );
+    assert!(second_page.next_cursor.is_none());
+
+    let transaction = app.db_pool.begin().await.unwrap();
+    assert_eq!(
+        mark_all_notifications_read(transaction, user.id)
+            .await
+            .unwrap(),
+        3
+    );
+    let transaction = app.db_pool.begin().await.unwrap();
+    assert_eq!(
+        get_unread_notification_count(transaction, user.id)
+            .await
+            .unwrap(),
+        0
+    );
+}
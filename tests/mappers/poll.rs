@@ -0,0 +1,85 @@
+use crate::helpers::spawn_app;
+use aloha_backend::mappers::poll::{get_poll_tallies_by_tweet_ids, insert_poll_vote};
+use aloha_backend::mappers::tweet::insert_tweet_with_attachments;
+use aloha_backend::mappers::user::insert_user;
+use aloha_backend::models::poll::Poll;
+use aloha_backend::models::tweet::Tweet;
+use aloha_backend::models::user::User;
+use time::{Duration, OffsetDateTime};
+use uuid::Uuid;
+
+#[tokio::test]
+async fn poll_accepts_one_vote_per_user_until_it_closes() {
+    let app = spawn_app().await;
+    let users = User::default_vec_test(Some(3));
+    for user in &users {
+        let transaction = app.db_pool.begin().await.unwrap();
+        insert_user(transaction, user).await.unwrap();
+    }
+    let now = OffsetDateTime::now_utc();
+    let tweet = Tweet::new("Tabs or spaces?".to_string(), users[0].id);
+    let labels = vec!["Tabs".to_string(), "Spaces".to_string()];
+    let poll = Poll::new(tweet.id, &labels, now + Duration::hours(1)).unwrap();
+    let transaction = app.db_pool.begin().await.unwrap();
+    insert_tweet_with_attachments(transaction, &tweet, &[], Some(&poll))
+        .await
+        .unwrap();
+    let (tabs, spaces) = (poll.options[0].id, poll.options[1].id);
+
+    let transaction = app.db_pool.begin().await.unwrap();
+    let vote = insert_poll_vote(transaction, tweet.id, users[1].id, spaces, now)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(vote.option_id, spaces);
+
+    // A second vote, even for another option, is rejected
+    let transaction = app.db_pool.begin().await.unwrap();
+    let result = insert_poll_vote(transaction, tweet.id, users[1].id, tabs, now).await;
+    assert!(result.is_err());
+
+    // Options of other polls are unknown
+    let transaction = app.db_pool.begin().await.unwrap();
+    let result = insert_poll_vote(transaction, tweet.id, users[2].id, Uuid::new_v4(), now).await;
+    assert!(result.is_err());
+
+    // Votes are refused once the poll has closed
+    let transaction = app.db_pool.begin().await.unwrap();
+    let result = insert_poll_vote(
+        transaction,
+        tweet.id,
+        users[2].id,
+        tabs,
+        now + Duration::hours(2),
+    )
+    .await;
+    assert!(result.is_err());
+
+    // Tweets without a poll cannot be voted on
+    let plain = Tweet::new("No poll here".to_string(), users[0].id);
+    let transaction = app.db_pool.begin().await.unwrap();
+    insert_tweet_with_attachments(transaction, &plain, &[], None)
+        .await
+        .unwrap();
+    let transaction = app.db_pool.begin().await.unwrap();
+    let result = insert_poll_vote(transaction, plain.id, users[2].id, tabs, now)
+        .await
+        .unwrap();
+    assert!(result.is_none());
+
+    let mut transaction = app.db_pool.begin().await.unwrap();
+    let tallies =
+        get_poll_tallies_by_tweet_ids(&mut transaction, &[tweet.id, plain.id], Some(users[1].id))
+            .await
+            .unwrap();
+    assert_eq!(tallies.len(), 1);
+    let tally = &tallies[&tweet.id];
+    assert_eq!(tally.votes, vec![0, 1]);
+    assert_eq!(tally.voted_option_id, Some(spaces));
+
+    let mut transaction = app.db_pool.begin().await.unwrap();
+    let tallies = get_poll_tallies_by_tweet_ids(&mut transaction, &[tweet.id], Some(users[2].id))
+        .await
+        .unwrap();
+    assert_eq!(tallies[&tweet.id].voted_option_id, None);
+}
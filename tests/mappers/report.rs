@@ -0,0 +1,126 @@
+use crate::helpers::spawn_app;
+use aloha_backend::dto::query::{DtoQuery, ReportFilterQuery};
+use aloha_backend::mappers::report::{
+    get_report_by_id, get_reports, insert_report, update_report_status,
+};
+use aloha_backend::mappers::tweet::insert_tweet;
+use aloha_backend::mappers::user::insert_user;
+use aloha_backend::models::report::{Report, ReportStatus};
+use aloha_backend::models::tweet::{Tweet, TweetVisibility};
+use aloha_backend::models::user::User;
+use uuid::Uuid;
+
+#[tokio::test]
+async fn reports_are_queued_until_resolved_once() {
+    let app = spawn_app().await;
+    let users = User::default_vec_test(Some(3));
+    for user in &users {
+        let transaction = app.db_pool.begin().await.unwrap();
+        insert_user(transaction, user).await.unwrap();
+    }
+    let (author, reporter, moderator) = (&users[0], &users[1], &users[2]);
+    let transaction = app.db_pool.begin().await.unwrap();
+    let tweet = insert_tweet(transaction, &Tweet::new("rude".to_string(), author.id))
+        .await
+        .unwrap();
+    let private = Tweet {
+        visibility: TweetVisibility::Private,
+        ..Tweet::new("secret".to_string(), author.id)
+    };
+    let transaction = app.db_pool.begin().await.unwrap();
+    let private = insert_tweet(transaction, &private).await.unwrap();
+
+    let transaction = app.db_pool.begin().await.unwrap();
+    let tweet_report = insert_report(
+        transaction,
+        &Report::new(reporter.id, Some(tweet.id), None, "spam".to_string()),
+    )
+    .await
+    .unwrap()
+    .unwrap();
+    assert_eq!(tweet_report.status, ReportStatus::Open);
+    let transaction = app.db_pool.begin().await.unwrap();
+    let user_report = insert_report(
+        transaction,
+        &Report::new(reporter.id, None, Some(author.id), "abuse".to_string()),
+    )
+    .await
+    .unwrap()
+    .unwrap();
+
+    // Tweets the reporter cannot read cannot be reported
+    let transaction = app.db_pool.begin().await.unwrap();
+    let result = insert_report(
+        transaction,
+        &Report::new(reporter.id, Some(private.id), None, "?".to_string()),
+    )
+    .await
+    .unwrap();
+    assert!(result.is_none());
+    let transaction = app.db_pool.begin().await.unwrap();
+    assert!(insert_report(
+        transaction,
+        &Report::new(reporter.id, None, Some(Uuid::new_v4()), "?".to_string()),
+    )
+    .await
+    .is_err());
+
+    let transaction = app.db_pool.begin().await.unwrap();
+    let queue = get_reports(transaction, DtoQuery::<ReportFilterQuery>::default_query())
+        .await
+        .unwrap();
+    assert_eq!(
+        queue.data.iter().map(|r| r.id).collect::<Vec<_>>(),
+        vec![tweet_report.id, user_report.id]
+    );
+
+    let transaction = app.db_pool.begin().await.unwrap();
+    let dismissed = update_report_status(
+        transaction,
+        tweet_report.id,
+        moderator.id,
+        ReportStatus::Dismissed,
+        Some("Not spam".to_string()),
+    )
+    .await
+    .unwrap()
+    .unwrap();
+    assert_eq!(dismissed.status, ReportStatus::Dismissed);
+    assert_eq!(dismissed.resolved_by, Some(moderator.id));
+    assert!(dismissed.resolved_at.is_some());
+
+    // Resolved reports cannot change status again
+    let transaction = app.db_pool.begin().await.unwrap();
+    assert!(update_report_status(
+        transaction,
+        tweet_report.id,
+        moderator.id,
+        ReportStatus::Actioned,
+        None,
+    )
+    .await
+    .is_err());
+    let transaction = app.db_pool.begin().await.unwrap();
+    let report = get_report_by_id(transaction, tweet_report.id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(report.status, ReportStatus::Dismissed);
+
+    let transaction = app.db_pool.begin().await.unwrap();
+    let queue = get_reports(
+        transaction,
+        DtoQuery {
+            filter: Some(ReportFilterQuery {
+                status: Some(ReportStatus::Dismissed),
+            }),
+            ..DtoQuery::default_query()
+        },
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        queue.data.iter().map(|r| r.id).collect::<Vec<_>>(),
+        vec![tweet_report.id]
+    );
+}
@@ -0,0 +1,189 @@
+use crate::helpers::spawn_app;
+use aloha_backend::dto::query::{DtoQuery, TweetFilterQuery};
+use aloha_backend::mappers::retention::{purge_deleted, PurgeCounts};
+use aloha_backend::mappers::tweet::{
+    delete_tweet_by_id, get_all_tweets, get_tweet_by_id, insert_tweet, restore_tweet_by_id,
+};
+use aloha_backend::mappers::user::{
+    delete_user_by_id, get_user_by_id, insert_user, restore_user_by_id,
+};
+use aloha_backend::models::tweet::Tweet;
+use aloha_backend::models::user::User;
+use time::{Duration, OffsetDateTime};
+
+#[tokio::test]
+async fn deleted_tweets_are_hidden_until_restored() {
+    let app = spawn_app().await;
+    let transaction = app.db_pool.begin().await.unwrap();
+    let user = insert_user(transaction, &User::default_test())
+        .await
+        .unwrap();
+    let transaction = app.db_pool.begin().await.unwrap();
+    let tweet = insert_tweet(transaction, &Tweet::default_test(user.id))
+        .await
+        .unwrap();
+
+    let transaction = app.db_pool.begin().await.unwrap();
+    delete_tweet_by_id(transaction, tweet.id).await.unwrap();
+
+    let transaction = app.db_pool.begin().await.unwrap();
+    assert!(get_tweet_by_id(transaction, tweet.id, None)
+        .await
+        .unwrap()
+        .is_none());
+    let transaction = app.db_pool.begin().await.unwrap();
+    let all = get_all_tweets(
+        transaction,
+        DtoQuery::<TweetFilterQuery>::default_query(),
+        None,
+    )
+    .await
+    .unwrap();
+    assert!(all.data.iter().all(|t| t.id != tweet.id));
+
+    let transaction = app.db_pool.begin().await.unwrap();
+    assert!(delete_tweet_by_id(transaction, tweet.id).await.is_err());
+
+    let transaction = app.db_pool.begin().await.unwrap();
+    let restored = restore_tweet_by_id(transaction, tweet.id).await.unwrap();
+    assert_eq!(restored.map(|t| t.id), Some(tweet.id));
+    let transaction = app.db_pool.begin().await.unwrap();
+    assert!(get_tweet_by_id(transaction, tweet.id, None)
+        .await
+        .unwrap()
+        .is_some());
+
+    let transaction = app.db_pool.begin().await.unwrap();
+    assert!(restore_tweet_by_id(transaction, tweet.id)
+        .await
+        .unwrap()
+        .is_none());
+}
+
+#[tokio::test]
+async fn restoring_a_user_brings_back_only_tweets_deleted_with_them() {
+    let app = spawn_app().await;
+    let transaction = app.db_pool.begin().await.unwrap();
+    let user = insert_user(transaction, &User::default_test())
+        .await
+        .unwrap();
+    let transaction = app.db_pool.begin().await.unwrap();
+    let kept = insert_tweet(transaction, &Tweet::default_test(user.id))
+        .await
+        .unwrap();
+    let transaction = app.db_pool.begin().await.unwrap();
+    let removed = insert_tweet(transaction, &Tweet::default_test(user.id))
+        .await
+        .unwrap();
+    let transaction = app.db_pool.begin().await.unwrap();
+    delete_tweet_by_id(transaction, removed.id).await.unwrap();
+
+    let transaction = app.db_pool.begin().await.unwrap();
+    delete_user_by_id(transaction, user.id).await.unwrap();
+    let transaction = app.db_pool.begin().await.unwrap();
+    assert!(get_user_by_id(transaction, user.id)
+        .await
+        .unwrap()
+        .is_none());
+    let transaction = app.db_pool.begin().await.unwrap();
+    assert!(get_tweet_by_id(transaction, kept.id, None)
+        .await
+        .unwrap()
+        .is_none());
+
+    // A tweet cannot come back while its author is deleted.
+    let transaction = app.db_pool.begin().await.unwrap();
+    assert!(restore_tweet_by_id(transaction, kept.id)
+        .await
+        .unwrap()
+        .is_none());
+
+    let transaction = app.db_pool.begin().await.unwrap();
+    let restored = restore_user_by_id(transaction, user.id).await.unwrap();
+    assert_eq!(restored.map(|u| u.id), Some(user.id));
+
+    let transaction = app.db_pool.begin().await.unwrap();
+    assert!(get_tweet_by_id(transaction, kept.id, None)
+        .await
+        .unwrap()
+        .is_some());
+    let transaction = app.db_pool.begin().await.unwrap();
+    assert!(get_tweet_by_id(transaction, removed.id, None)
+        .await
+        .unwrap()
+        .is_none());
+}
+
+#[tokio::test]
+async fn purge_deleted_removes_rows_past_the_cutoff() {
+    let app = spawn_app().await;
+    let users = User::default_vec_test(Some(2));
+    let transaction = app.db_pool.begin().await.unwrap();
+    let old_user = insert_user(transaction, &users[0]).await.unwrap();
+    let transaction = app.db_pool.begin().await.unwrap();
+    let recent_user = insert_user(transaction, &users[1]).await.unwrap();
+    let transaction = app.db_pool.begin().await.unwrap();
+    let old_tweet = insert_tweet(transaction, &Tweet::default_test(recent_user.id))
+        .await
+        .unwrap();
+    let transaction = app.db_pool.begin().await.unwrap();
+    let recent_tweet = insert_tweet(transaction, &Tweet::default_test(recent_user.id))
+        .await
+        .unwrap();
+
+    let transaction = app.db_pool.begin().await.unwrap();
+    delete_user_by_id(transaction, old_user.id).await.unwrap();
+    let transaction = app.db_pool.begin().await.unwrap();
+    delete_tweet_by_id(transaction, old_tweet.id).await.unwrap();
+    let transaction = app.db_pool.begin().await.unwrap();
+    delete_tweet_by_id(transaction, recent_tweet.id)
+        .await
+        .unwrap();
+    sqlx::query!(
+        "UPDATE users SET deleted_at = now() - interval '40 days' WHERE id = $1",
+        old_user.id
+    )
+    .execute(&app.db_pool)
+    .await
+    .unwrap();
+    sqlx::query!(
+        "UPDATE tweet SET deleted_at = now() - interval '40 days' WHERE id = $1",
+        old_tweet.id
+    )
+    .execute(&app.db_pool)
+    .await
+    .unwrap();
+
+    let cutoff = OffsetDateTime::now_utc() - Duration::days(30);
+    let transaction = app.db_pool.begin().await.unwrap();
+    let counts = purge_deleted(transaction, cutoff).await.unwrap();
+    assert_eq!(
+        counts,
+        PurgeCounts {
+            users: 1,
+            tweets: 1
+        }
+    );
+
+    let remaining = sqlx::query!(
+        "SELECT id FROM tweet WHERE id = ANY($1)",
+        &[old_tweet.id, recent_tweet.id]
+    )
+    .fetch_all(&app.db_pool)
+    .await
+    .unwrap();
+    assert_eq!(remaining.len(), 1);
+    assert_eq!(remaining[0].id, recent_tweet.id);
+
+    // The recently deleted tweet is still within its grace period.
+    let transaction = app.db_pool.begin().await.unwrap();
+    assert!(restore_tweet_by_id(transaction, recent_tweet.id)
+        .await
+        .unwrap()
+        .is_some());
+    let transaction = app.db_pool.begin().await.unwrap();
+    assert!(restore_user_by_id(transaction, old_user.id)
+        .await
+        .unwrap()
+        .is_none());
+}
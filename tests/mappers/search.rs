@@ -0,0 +1,222 @@
+use crate::helpers::spawn_app;
+use aloha_backend::dto::query::SearchQuery;
+use aloha_backend::mappers::search::{search_tweets, search_users};
+use aloha_backend::mappers::tweet::insert_tweet;
+use aloha_backend::mappers::user::insert_user;
+use aloha_backend::models::search::TweetSearchQuery;
+use aloha_backend::models::tweet::Tweet;
+use aloha_backend::models::user::User;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+async fn search(pool: &PgPool, q: &str) -> Vec<Uuid> {
+    let transaction = pool.begin().await.unwrap();
+    let search = TweetSearchQuery::parse(q).unwrap();
+    search_tweets(transaction, &search, &SearchQuery::new(q), None)
+        .await
+        .unwrap()
+        .data
+        .into_iter()
+        .map(|hit| hit.tweet.id)
+        .collect()
+}
+
+#[tokio::test]
+async fn search_tweets_matches_words_phrases_and_prefixes() {
+    let app = spawn_app().await;
+    let transaction = app.db_pool.begin().await.unwrap();
+    let user = insert_user(transaction, &User::default_test())
+        .await
+        .unwrap();
+
+    let mut ids = Vec::new();
+    for content in [
+        "Tokio is an async runtime for Rust",
+        "The runtime of this async job is long",
+        "Learning Rust with #SQLx",
+    ] {
+        let transaction = app.db_pool.begin().await.unwrap();
+        let tweet = insert_tweet(transaction, &Tweet::new(content.to_string(), user.id))
+            .await
+            .unwrap();
+        ids.push(tweet.id);
+    }
+
+    assert_eq!(
+        search(&app.db_pool, r#""async runtime""#).await,
+        vec![ids[0]]
+    );
+    assert_eq!(search(&app.db_pool, "runtime").await.len(), 2);
+    assert_eq!(search(&app.db_pool, "learn*").await, vec![ids[2]]);
+    assert_eq!(search(&app.db_pool, "rust #sqlx").await, vec![ids[2]]);
+    assert!(search(&app.db_pool, "python").await.is_empty());
+
+    let transaction = app.db_pool.begin().await.unwrap();
+    let search_query = SearchQuery::new("runtime");
+    let result = search_tweets(
+        transaction,
+        &TweetSearchQuery::parse(&search_query.q).unwrap(),
+        &search_query,
+        None,
+    )
+    .await
+    .unwrap();
+    assert_eq!(result.pagination.unwrap().total(), 2);
+    assert!(result.data[0].snippet.contains("<mark>runtime</mark>"));
+}
+
+#[tokio::test]
+async fn search_tweets_applies_author_and_date_filters() {
+    let app = spawn_app().await;
+    let mut users = Vec::new();
+    for user in User::default_vec_test(Some(2)) {
+        let transaction = app.db_pool.begin().await.unwrap();
+        users.push(insert_user(transaction, &user).await.unwrap());
+    }
+
+    let mut ids = Vec::new();
+    for user in &users {
+        let transaction = app.db_pool.begin().await.unwrap();
+        let tweet = insert_tweet(transaction, &Tweet::new(String::from("hello"), user.id))
+            .await
+            .unwrap();
+        ids.push(tweet.id);
+    }
+    sqlx::query!(
+        "UPDATE tweet SET created_at = '2024-06-15 12:00:00+00' WHERE id = $1",
+        ids[1]
+    )
+    .execute(&app.db_pool)
+    .await
+    .unwrap();
+
+    let q = format!("hello from:{}", users[0].username);
+    assert_eq!(search(&app.db_pool, &q).await, vec![ids[0]]);
+    assert_eq!(
+        search(&app.db_pool, "since:2024-06-15 until:2024-06-15").await,
+        vec![ids[1]]
+    );
+    assert_eq!(
+        search(&app.db_pool, "hello since:2024-06-16").await,
+        vec![ids[0]]
+    );
+    assert!(search(&app.db_pool, "from:nobody").await.is_empty());
+}
+
+#[tokio::test]
+async fn search_users_ranks_exact_and_prefix_matches_first() {
+    let app = spawn_app().await;
+    for username in ["alice", "alicia", "malice", "bob", "al_ice"] {
+        let transaction = app.db_pool.begin().await.unwrap();
+        let user = User::new(username.to_string(), String::from("password"), None);
+        insert_user(transaction, &user).await.unwrap();
+    }
+
+    let transaction = app.db_pool.begin().await.unwrap();
+    let result = search_users(transaction, &SearchQuery::new("alice"))
+        .await
+        .unwrap();
+    let usernames: Vec<&str> = result.data.iter().map(|u| u.username.as_str()).collect();
+    assert_eq!(usernames[0], "alice");
+    assert_eq!(usernames[1], "malice");
+    assert!(!usernames.contains(&"bob"));
+
+    let transaction = app.db_pool.begin().await.unwrap();
+    let result = search_users(transaction, &SearchQuery::new("ali"))
+        .await
+        .unwrap();
+    let usernames: Vec<&str> = result.data.iter().map(|u| u.username.as_str()).collect();
+    assert_eq!(&usernames[..2], &["alice", "alicia"]);
+
+    let transaction = app.db_pool.begin().await.unwrap();
+    let result = search_users(transaction, &SearchQuery::new("l_i"))
+        .await
+        .unwrap();
+    assert_eq!(result.data.len(), 1);
+    assert_eq!(result.data[0].username, "al_ice");
+
+    let transaction = app.db_pool.begin().await.unwrap();
+    let result = search_users(transaction, &SearchQuery::new("alicee"))
+        .await
+        .unwrap();
+    assert_eq!(result.data[0].username, "alice");
+}
+
+#[tokio::test]
+async fn search_tweets_orders_better_matches_before_newer_ones() {
+    let app = spawn_app().await;
+    let transaction = app.db_pool.begin().await.unwrap();
+    let user = insert_user(transaction, &User::default_test())
+        .await
+        .unwrap();
+
+    let mut ids = Vec::new();
+    for content in [
+        "Rust, rust and more rust: rust everywhere",
+        "Some rust on an old bike",
+    ] {
+        let transaction = app.db_pool.begin().await.unwrap();
+        let tweet = insert_tweet(transaction, &Tweet::new(content.to_string(), user.id))
+            .await
+            .unwrap();
+        ids.push(tweet.id);
+    }
+    sqlx::query!(
+        "UPDATE tweet SET created_at = '2024-06-15 12:00:00+00' WHERE id = $1",
+        ids[0]
+    )
+    .execute(&app.db_pool)
+    .await
+    .unwrap();
+
+    assert_eq!(search(&app.db_pool, "rust").await, ids);
+}
+
+#[tokio::test]
+async fn search_snippets_escape_tweet_content() {
+    let app = spawn_app().await;
+    let transaction = app.db_pool.begin().await.unwrap();
+    let user = insert_user(transaction, &User::default_test())
+        .await
+        .unwrap();
+    let transaction = app.db_pool.begin().await.unwrap();
+    insert_tweet(
+        transaction,
+        &Tweet::new(
+            String::from("Rust <script>alert('x')</script> & <img src=x onerror=alert(1)>"),
+            user.id,
+        ),
+    )
+    .await
+    .unwrap();
+
+    for q in ["rust", "since:2000-01-01"] {
+        let transaction = app.db_pool.begin().await.unwrap();
+        let search_query = SearchQuery::new(q);
+        let result = search_tweets(
+            transaction,
+            &TweetSearchQuery::parse(q).unwrap(),
+            &search_query,
+            None,
+        )
+        .await
+        .unwrap();
+        let snippet = &result.data[0].snippet;
+        assert!(!snippet.contains("<script"), "{}", snippet);
+        assert!(!snippet.contains("<img"), "{}", snippet);
+        assert!(snippet.contains("&lt;script&gt;"), "{}", snippet);
+    }
+    let transaction = app.db_pool.begin().await.unwrap();
+    let search_query = SearchQuery::new("rust");
+    let result = search_tweets(
+        transaction,
+        &TweetSearchQuery::parse("rust").unwrap(),
+        &search_query,
+        None,
+    )
+    .await
+    .unwrap();
+    assert!(result.data[0]
+        .snippet
+        .starts_with("<mark>Rust</mark> &lt;script&gt;"));
+}
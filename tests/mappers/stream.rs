@@ -0,0 +1,108 @@
+use crate::helpers::spawn_app;
+use aloha_backend::mappers::stream::{
+    get_stream_events_since, get_timeline_recipients, purge_stream_events,
+};
+use aloha_backend::mappers::subscription::insert_subscription;
+use aloha_backend::mappers::tweet::insert_tweet;
+use aloha_backend::mappers::user::insert_user;
+use aloha_backend::models::stream::StreamEventKind;
+use aloha_backend::models::subscription::Subscription;
+use aloha_backend::models::tweet::{Tweet, TweetVisibility};
+use aloha_backend::models::user::User;
+use time::{Duration, OffsetDateTime};
+
+#[tokio::test]
+async fn stream_events_reach_subscribers_and_mentioned_users() {
+    let app = spawn_app().await;
+    let users = User::default_vec_test(Some(3));
+    for user in &users {
+        let transaction = app.db_pool.begin().await.unwrap();
+        insert_user(transaction, user).await.unwrap();
+    }
+    let (author, subscriber, stranger) = (&users[0], &users[1], &users[2]);
+    let transaction = app.db_pool.begin().await.unwrap();
+    insert_subscription(transaction, &Subscription::new(subscriber.id, author.id))
+        .await
+        .unwrap();
+
+    let transaction = app.db_pool.begin().await.unwrap();
+    let tweet = insert_tweet(
+        transaction,
+        &Tweet::new(format!("hi @{}", stranger.username), author.id),
+    )
+    .await
+    .unwrap();
+    let private = Tweet {
+        visibility: TweetVisibility::Private,
+        ..Tweet::new("secret".to_string(), author.id)
+    };
+    let transaction = app.db_pool.begin().await.unwrap();
+    insert_tweet(transaction, &private).await.unwrap();
+
+    let transaction = app.db_pool.begin().await.unwrap();
+    let events = get_stream_events_since(transaction, subscriber.id, 0, 100)
+        .await
+        .unwrap();
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].kind, StreamEventKind::Tweet);
+    assert_eq!(events[0].tweet_id, Some(tweet.id));
+
+    let transaction = app.db_pool.begin().await.unwrap();
+    let events = get_stream_events_since(transaction, stranger.id, 0, 100)
+        .await
+        .unwrap();
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].kind, StreamEventKind::Notification);
+
+    // Nothing is replayed after the last event seen
+    let transaction = app.db_pool.begin().await.unwrap();
+    assert!(
+        get_stream_events_since(transaction, stranger.id, events[0].id, 100)
+            .await
+            .unwrap()
+            .is_empty()
+    );
+
+    let transaction = app.db_pool.begin().await.unwrap();
+    let mut recipients = get_timeline_recipients(
+        transaction,
+        tweet.id,
+        &[author.id, subscriber.id, stranger.id],
+    )
+    .await
+    .unwrap();
+    recipients.sort();
+    let mut expected = vec![author.id, subscriber.id];
+    expected.sort();
+    assert_eq!(recipients, expected);
+}
+
+#[tokio::test]
+async fn purge_stream_events_removes_old_events() {
+    let app = spawn_app().await;
+    let transaction = app.db_pool.begin().await.unwrap();
+    let user = insert_user(transaction, &User::default_test())
+        .await
+        .unwrap();
+    let transaction = app.db_pool.begin().await.unwrap();
+    insert_tweet(transaction, &Tweet::default_test(user.id))
+        .await
+        .unwrap();
+
+    let transaction = app.db_pool.begin().await.unwrap();
+    let purged = purge_stream_events(transaction, OffsetDateTime::now_utc() - Duration::hours(1))
+        .await
+        .unwrap();
+    assert_eq!(purged, 0);
+    let transaction = app.db_pool.begin().await.unwrap();
+    let purged = purge_stream_events(transaction, OffsetDateTime::now_utc() + Duration::hours(1))
+        .await
+        .unwrap();
+    assert_eq!(purged, 1);
+
+    let transaction = app.db_pool.begin().await.unwrap();
+    assert!(get_stream_events_since(transaction, user.id, 0, 100)
+        .await
+        .unwrap()
+        .is_empty());
+}
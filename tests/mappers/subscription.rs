@@ -0,0 +1,82 @@
+use crate::helpers::spawn_app;
+use aloha_backend::mappers::subscription::{
+    delete_subscription, get_subscriptions_by_subscriber_id, insert_subscription,
+};
+use aloha_backend::mappers::user::insert_user;
+use aloha_backend::models::subscription::Subscription;
+use aloha_backend::models::user::User;
+use uuid::Uuid;
+
+#[tokio::test]
+async fn insert_subscription_works() {
+    let app = spawn_app().await;
+    let users = User::default_vec_test(Some(2));
+    for user in &users {
+        let transaction = app.db_pool.begin().await.unwrap();
+        insert_user(transaction, user).await.unwrap();
+    }
+
+    let transaction = app.db_pool.begin().await.unwrap();
+    let subscription = Subscription::new(users[0].id, users[1].id);
+    let result = insert_subscription(transaction, &subscription)
+        .await
+        .unwrap();
+
+    assert_eq!(result.subscriber_id, users[0].id);
+    assert_eq!(result.target_id, users[1].id);
+
+    // Subscribing twice is idempotent
+    let transaction = app.db_pool.begin().await.unwrap();
+    insert_subscription(transaction, &subscription)
+        .await
+        .unwrap();
+    let transaction = app.db_pool.begin().await.unwrap();
+    let subscriptions = get_subscriptions_by_subscriber_id(transaction, users[0].id)
+        .await
+        .unwrap();
+    assert_eq!(subscriptions.len(), 1);
+}
+
+#[tokio::test]
+async fn insert_subscription_rejects_unknown_target_and_self() {
+    let app = spawn_app().await;
+    let transaction = app.db_pool.begin().await.unwrap();
+    let user = insert_user(transaction, &User::default_test())
+        .await
+        .unwrap();
+
+    let transaction = app.db_pool.begin().await.unwrap();
+    let result =
+        insert_subscription(transaction, &Subscription::new(user.id, Uuid::new_v4())).await;
+    assert!(result.is_err());
+
+    let transaction = app.db_pool.begin().await.unwrap();
+    let result = insert_subscription(transaction, &Subscription::new(user.id, user.id)).await;
+    assert!(result.is_err());
+}
+
+#[tokio::test]
+async fn delete_subscription_works() {
+    let app = spawn_app().await;
+    let users = User::default_vec_test(Some(2));
+    for user in &users {
+        let transaction = app.db_pool.begin().await.unwrap();
+        insert_user(transaction, user).await.unwrap();
+    }
+    let transaction = app.db_pool.begin().await.unwrap();
+    insert_subscription(transaction, &Subscription::new(users[0].id, users[1].id))
+        .await
+        .unwrap();
+
+    let transaction = app.db_pool.begin().await.unwrap();
+    let result = delete_subscription(transaction, users[0].id, users[1].id)
+        .await
+        .unwrap();
+    assert_eq!(result.target_id, users[1].id);
+
+    let transaction = app.db_pool.begin().await.unwrap();
+    let subscriptions = get_subscriptions_by_subscriber_id(transaction, users[0].id)
+        .await
+        .unwrap();
+    assert!(subscriptions.is_empty());
+}
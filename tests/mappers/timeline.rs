@@ -0,0 +1,121 @@
+use crate::helpers::spawn_app;
+use aloha_backend::dto::cursor::CursorQuery;
+use aloha_backend::mappers::subscription::insert_subscription;
+use aloha_backend::mappers::timeline::get_home_timeline;
+use aloha_backend::mappers::tweet::insert_tweet;
+use aloha_backend::mappers::user::insert_user;
+use aloha_backend::mappers::user_group::insert_user_group;
+use aloha_backend::models::subscription::Subscription;
+use aloha_backend::models::tweet::Tweet;
+use aloha_backend::models::user::User;
+use aloha_backend::models::user_group::UserGroup;
+use std::collections::HashSet;
+use uuid::Uuid;
+
+#[tokio::test]
+async fn get_home_timeline_includes_self_group_and_subscriptions() {
+    let app = spawn_app().await;
+
+    let transaction = app.db_pool.begin().await.unwrap();
+    let group = insert_user_group(transaction, &UserGroup::default_test())
+        .await
+        .unwrap();
+
+    // viewer and group_mate share a group, subscribed is followed, stranger is neither
+    let mut users = User::default_vec_test(Some(4));
+    users[0].user_group_id = Some(group.id);
+    users[1].user_group_id = Some(group.id);
+    for user in &users {
+        let transaction = app.db_pool.begin().await.unwrap();
+        insert_user(transaction, user).await.unwrap();
+    }
+    let (viewer, group_mate, subscribed, stranger) = (&users[0], &users[1], &users[2], &users[3]);
+
+    let transaction = app.db_pool.begin().await.unwrap();
+    insert_subscription(transaction, &Subscription::new(viewer.id, subscribed.id))
+        .await
+        .unwrap();
+
+    for user in &users {
+        let transaction = app.db_pool.begin().await.unwrap();
+        insert_tweet(transaction, &Tweet::default_test(user.id))
+            .await
+            .unwrap();
+    }
+
+    let transaction = app.db_pool.begin().await.unwrap();
+    let result = get_home_timeline(transaction, viewer.id, CursorQuery::default_query())
+        .await
+        .unwrap();
+
+    let authors: HashSet<Uuid> = result.data.iter().map(|t| t.user_id).collect();
+    assert_eq!(result.data.len(), 3);
+    assert!(authors.contains(&viewer.id));
+    assert!(authors.contains(&group_mate.id));
+    assert!(authors.contains(&subscribed.id));
+    assert!(!authors.contains(&stranger.id));
+    assert!(result.next_cursor.is_none());
+}
+
+#[tokio::test]
+async fn get_home_timeline_pages_with_cursor() {
+    let app = spawn_app().await;
+
+    let transaction = app.db_pool.begin().await.unwrap();
+    let user = insert_user(transaction, &User::default_test())
+        .await
+        .unwrap();
+
+    for tweet in Tweet::default_vec_test(Some(5), user.id) {
+        let transaction = app.db_pool.begin().await.unwrap();
+        insert_tweet(transaction, &tweet).await.unwrap();
+    }
+
+    let mut query = CursorQuery {
+        cursor: None,
+        size: Some(2),
+    };
+    let mut seen = Vec::new();
+    let mut first_page = true;
+    loop {
+        let transaction = app.db_pool.begin().await.unwrap();
+        let page = get_home_timeline(transaction, user.id, query.clone())
+            .await
+            .unwrap();
+        assert!(page.data.len() <= 2);
+        seen.extend(page.data.iter().map(|t| (t.created_at.unwrap(), t.id)));
+
+        // A tweet posted after the first page must not shift later pages.
+        if first_page {
+            first_page = false;
+            let transaction = app.db_pool.begin().await.unwrap();
+            insert_tweet(transaction, &Tweet::default_test(user.id))
+                .await
+                .unwrap();
+        }
+
+        match page.next_cursor {
+            Some(cursor) => query.cursor = Some(cursor),
+            None => break,
+        }
+    }
+
+    assert_eq!(seen.len(), 5);
+    let unique: HashSet<Uuid> = seen.iter().map(|(_, id)| *id).collect();
+    assert_eq!(unique.len(), 5);
+    assert!(seen.windows(2).all(|w| w[0] > w[1]));
+}
+
+#[tokio::test]
+async fn get_home_timeline_rejects_invalid_cursor() {
+    let app = spawn_app().await;
+
+    let transaction = app.db_pool.begin().await.unwrap();
+    let query = CursorQuery {
+        cursor: Some(String::from("garbage")),
+        size: None,
+    };
+    let result = get_home_timeline(transaction, Uuid::new_v4(), query).await;
+
+    assert!(result.is_err());
+}
@@ -1,11 +1,14 @@
 use crate::helpers::spawn_app;
 use aloha_backend::dto::query::{DtoQuery, TweetFilterQuery};
 use aloha_backend::mappers::tweet::{
-    delete_tweet_by_id, get_all_tweets, get_tweet_by_id, insert_tweet, update_tweet,
+    delete_tweet_by_id, get_all_tweets, get_pinned_tweet_ids, get_tweet_authors, get_tweet_by_id,
+    insert_tweet, pin_tweet, update_tweet,
 };
 use aloha_backend::mappers::user::insert_user;
-use aloha_backend::models::tweet::Tweet;
+use aloha_backend::mappers::user_group::insert_user_group;
+use aloha_backend::models::tweet::{Tweet, TweetVisibility};
 use aloha_backend::models::user::User;
+use aloha_backend::models::user_group::UserGroup;
 use uuid::Uuid;
 
 #[tokio::test]
@@ -48,7 +51,7 @@ async fn get_tweet_by_id_success() {
 
     // Get the tweet by ID
     let transaction = app.db_pool.begin().await.unwrap();
-    let get_result = get_tweet_by_id(transaction, insert_result.id)
+    let get_result = get_tweet_by_id(transaction, insert_result.id, None)
         .await
         .unwrap();
 
@@ -66,7 +69,9 @@ async fn get_tweet_by_id_not_found() {
 
     // Try to get a non-existent tweet
     let non_existent_id = Uuid::new_v4();
-    let result = get_tweet_by_id(transaction, non_existent_id).await.unwrap();
+    let result = get_tweet_by_id(transaction, non_existent_id, None)
+        .await
+        .unwrap();
 
     assert!(result.is_none());
 }
@@ -100,7 +105,7 @@ async fn update_tweet_success() {
 
     // Verify the tweet was updated
     let transaction = app.db_pool.begin().await.unwrap();
-    let get_result = get_tweet_by_id(transaction, insert_result.id)
+    let get_result = get_tweet_by_id(transaction, insert_result.id, None)
         .await
         .unwrap()
         .unwrap();
@@ -134,7 +139,7 @@ async fn delete_tweet_by_id_success() {
 
     // Verify tweet exists before deletion
     let transaction = app.db_pool.begin().await.unwrap();
-    let get_before_delete = get_tweet_by_id(transaction, insert_result.id)
+    let get_before_delete = get_tweet_by_id(transaction, insert_result.id, None)
         .await
         .unwrap();
     assert!(
@@ -154,7 +159,7 @@ async fn delete_tweet_by_id_success() {
 
     // Verify it's deleted
     let transaction = app.db_pool.begin().await.unwrap();
-    let get_result = get_tweet_by_id(transaction, insert_result.id)
+    let get_result = get_tweet_by_id(transaction, insert_result.id, None)
         .await
         .unwrap();
     assert!(
@@ -176,6 +181,7 @@ async fn get_all_tweets_no_filter() {
             created_at: Some(time::OffsetDateTime::now_utc()),
             updated_at: Some(time::OffsetDateTime::now_utc()),
             user_id: test_user.id,
+            visibility: TweetVisibility::Public,
         },
         Tweet {
             id: Uuid::new_v4(),
@@ -183,6 +189,7 @@ async fn get_all_tweets_no_filter() {
             created_at: Some(time::OffsetDateTime::now_utc()),
             updated_at: Some(time::OffsetDateTime::now_utc()),
             user_id: test_user.id,
+            visibility: TweetVisibility::Public,
         },
         Tweet {
             id: Uuid::new_v4(),
@@ -190,6 +197,7 @@ async fn get_all_tweets_no_filter() {
             created_at: Some(time::OffsetDateTime::now_utc()),
             updated_at: Some(time::OffsetDateTime::now_utc()),
             user_id: test_user.id,
+            visibility: TweetVisibility::Public,
         },
     ]);
 
@@ -242,7 +250,7 @@ async fn get_all_tweets_no_filter() {
     let transaction = pool.begin().await.expect("Failed to begin transaction");
     let query = DtoQuery::<TweetFilterQuery>::default_query();
     println!("query: {:?}", &query);
-    let result = get_all_tweets(transaction, query)
+    let result = get_all_tweets(transaction, query, None)
         .await
         .expect("Failed to get tweets");
 
@@ -299,7 +307,7 @@ async fn get_all_tweets_with_user_filter() {
         user_id: Some(user1_result.id),
     });
 
-    let result = get_all_tweets(transaction, query).await.unwrap();
+    let result = get_all_tweets(transaction, query, None).await.unwrap();
 
     // Should only have tweets from user1
     assert_eq!(result.data.len(), 2);
@@ -399,6 +407,7 @@ async fn delete_tweets_by_ids_success() {
             created_at: row.created_at,
             updated_at: row.updated_at,
             user_id: row.user_id,
+            visibility: TweetVisibility::Public,
         })
         .collect::<Vec<_>>();
 
@@ -452,6 +461,7 @@ async fn delete_tweets_by_ids_success() {
             created_at: row.created_at,
             updated_at: row.updated_at,
             user_id: row.user_id,
+            visibility: TweetVisibility::Public,
         })
         .collect::<Vec<_>>();
 
@@ -490,6 +500,7 @@ async fn delete_tweets_by_ids_success() {
             created_at: row.created_at,
             updated_at: row.updated_at,
             user_id: row.user_id,
+            visibility: TweetVisibility::Public,
         })
         .collect::<Vec<_>>();
 
@@ -507,3 +518,163 @@ async fn delete_tweets_by_ids_success() {
         .await
         .expect("Failed to commit final transaction");
 }
+
+#[tokio::test]
+async fn tweet_visibility_depends_on_the_viewer() {
+    let app = spawn_app().await;
+    let group = UserGroup::default_test();
+    let transaction = app.db_pool.begin().await.unwrap();
+    insert_user_group(transaction, &group).await.unwrap();
+
+    let users = User::default_vec_test(Some(3));
+    let author = User {
+        user_group_id: Some(group.id),
+        ..users[0].clone()
+    };
+    let teammate = User {
+        user_group_id: Some(group.id),
+        ..users[1].clone()
+    };
+    let outsider = users[2].clone();
+    for user in [&author, &teammate, &outsider] {
+        let transaction = app.db_pool.begin().await.unwrap();
+        insert_user(transaction, user).await.unwrap();
+    }
+
+    let mut tweets = Vec::new();
+    for visibility in [
+        TweetVisibility::Public,
+        TweetVisibility::Group,
+        TweetVisibility::Private,
+    ] {
+        let tweet = Tweet {
+            visibility,
+            ..Tweet::new(format!("{:?} tweet", visibility), author.id)
+        };
+        let transaction = app.db_pool.begin().await.unwrap();
+        tweets.push(insert_tweet(transaction, &tweet).await.unwrap());
+    }
+
+    for (viewer, expected) in [
+        (None, vec![TweetVisibility::Public]),
+        (Some(outsider.id), vec![TweetVisibility::Public]),
+        (
+            Some(teammate.id),
+            vec![TweetVisibility::Public, TweetVisibility::Group],
+        ),
+        (
+            Some(author.id),
+            vec![
+                TweetVisibility::Public,
+                TweetVisibility::Group,
+                TweetVisibility::Private,
+            ],
+        ),
+    ] {
+        let transaction = app.db_pool.begin().await.unwrap();
+        let all = get_all_tweets(
+            transaction,
+            DtoQuery::<TweetFilterQuery>::default_query(),
+            viewer,
+        )
+        .await
+        .unwrap();
+        let mut visible: Vec<TweetVisibility> = all.data.iter().map(|t| t.visibility).collect();
+        visible.sort_by_key(|v| *v as u8);
+        assert_eq!(visible, expected);
+        assert_eq!(all.pagination.unwrap().total, Some(expected.len() as i64));
+
+        for tweet in &tweets {
+            let transaction = app.db_pool.begin().await.unwrap();
+            let found = get_tweet_by_id(transaction, tweet.id, viewer)
+                .await
+                .unwrap();
+            assert_eq!(found.is_some(), expected.contains(&tweet.visibility));
+        }
+    }
+}
+
+#[tokio::test]
+async fn pinned_tweet_comes_first_until_deleted() {
+    let app = spawn_app().await;
+    let users = User::default_vec_test(Some(2));
+    for user in &users {
+        let transaction = app.db_pool.begin().await.unwrap();
+        insert_user(transaction, user).await.unwrap();
+    }
+    let (author, other) = (&users[0], &users[1]);
+    let mut tweets = Vec::new();
+    for tweet in Tweet::default_vec_test(Some(3), author.id) {
+        let transaction = app.db_pool.begin().await.unwrap();
+        tweets.push(insert_tweet(transaction, &tweet).await.unwrap());
+    }
+    let by_author = || DtoQuery {
+        filter: Some(TweetFilterQuery {
+            user_id: Some(author.id),
+        }),
+        ..DtoQuery::<TweetFilterQuery>::default_query()
+    };
+
+    // Only the author can pin their tweet
+    let transaction = app.db_pool.begin().await.unwrap();
+    assert!(pin_tweet(transaction, other.id, tweets[0].id)
+        .await
+        .unwrap()
+        .is_none());
+    let oldest = &tweets[0];
+    let transaction = app.db_pool.begin().await.unwrap();
+    let pinned = pin_tweet(transaction, author.id, oldest.id).await.unwrap();
+    assert_eq!(pinned.map(|t| t.id), Some(oldest.id));
+
+    let transaction = app.db_pool.begin().await.unwrap();
+    let listed = get_all_tweets(transaction, by_author(), None)
+        .await
+        .unwrap();
+    assert_eq!(listed.data[0].id, oldest.id);
+    assert_eq!(listed.data.len(), 3);
+    let mut transaction = app.db_pool.begin().await.unwrap();
+    let pinned_ids = get_pinned_tweet_ids(
+        &mut transaction,
+        &tweets.iter().map(|t| t.id).collect::<Vec<_>>(),
+    )
+    .await
+    .unwrap();
+    assert_eq!(pinned_ids.into_iter().collect::<Vec<_>>(), vec![oldest.id]);
+
+    let transaction = app.db_pool.begin().await.unwrap();
+    delete_tweet_by_id(transaction, oldest.id).await.unwrap();
+    let mut transaction = app.db_pool.begin().await.unwrap();
+    assert!(get_pinned_tweet_ids(&mut transaction, &[oldest.id])
+        .await
+        .unwrap()
+        .is_empty());
+    let transaction = app.db_pool.begin().await.unwrap();
+    let listed = get_all_tweets(transaction, by_author(), None)
+        .await
+        .unwrap();
+    assert_eq!(
+        listed.data.iter().map(|t| t.id).collect::<Vec<_>>(),
+        vec![tweets[2].id, tweets[1].id]
+    );
+}
+
+#[tokio::test]
+async fn get_tweet_authors_loads_each_author_once() {
+    let app = spawn_app().await;
+    let users = User::default_vec_test(Some(2));
+    for user in &users {
+        let transaction = app.db_pool.begin().await.unwrap();
+        insert_user(transaction, user).await.unwrap();
+    }
+
+    let mut transaction = app.db_pool.begin().await.unwrap();
+    let authors = get_tweet_authors(
+        &mut transaction,
+        &[users[0].id, users[1].id, Uuid::new_v4()],
+    )
+    .await
+    .unwrap();
+    assert_eq!(authors.len(), 2);
+    assert_eq!(authors[&users[0].id].username, users[0].username);
+    assert_eq!(authors[&users[1].id].username, users[1].username);
+}
@@ -0,0 +1,47 @@
+use crate::helpers::spawn_app;
+use aloha_backend::mappers::tweet::{insert_tweet, update_tweet};
+use aloha_backend::mappers::tweet_revision::{
+    get_revision_counts_by_tweet_ids, get_tweet_revisions,
+};
+use aloha_backend::mappers::user::insert_user;
+use aloha_backend::models::tweet::Tweet;
+use aloha_backend::models::user::User;
+
+#[tokio::test]
+async fn update_tweet_records_previous_versions() {
+    let app = spawn_app().await;
+    let transaction = app.db_pool.begin().await.unwrap();
+    let user = insert_user(transaction, &User::default_test())
+        .await
+        .unwrap();
+
+    let transaction = app.db_pool.begin().await.unwrap();
+    let mut tweet = insert_tweet(transaction, &Tweet::new(String::from("first"), user.id))
+        .await
+        .unwrap();
+    let transaction = app.db_pool.begin().await.unwrap();
+    let untouched = insert_tweet(transaction, &Tweet::default_test(user.id))
+        .await
+        .unwrap();
+
+    for content in ["second", "third", "third"] {
+        tweet.content = content.to_string();
+        let transaction = app.db_pool.begin().await.unwrap();
+        update_tweet(transaction, &tweet).await.unwrap();
+    }
+
+    let transaction = app.db_pool.begin().await.unwrap();
+    let revisions = get_tweet_revisions(transaction, tweet.id).await.unwrap();
+    let history: Vec<_> = revisions
+        .iter()
+        .map(|r| (r.revision, r.content.as_str()))
+        .collect();
+    assert_eq!(history, vec![(1, "first"), (2, "second")]);
+
+    let mut transaction = app.db_pool.begin().await.unwrap();
+    let counts = get_revision_counts_by_tweet_ids(&mut transaction, &[tweet.id, untouched.id])
+        .await
+        .unwrap();
+    assert_eq!(counts.get(&tweet.id), Some(&2));
+    assert!(!counts.contains_key(&untouched.id));
+}
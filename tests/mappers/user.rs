@@ -1,6 +1,8 @@
 use aloha_backend::dto::query::DtoQuery;
+use aloha_backend::mappers::media::insert_media;
 use aloha_backend::mappers::user::*;
-use aloha_backend::models::user::User;
+use aloha_backend::models::media::Media;
+use aloha_backend::models::user::{User, UserProfile};
 use sqlx::{postgres::PgPoolOptions, PgPool};
 use std::env;
 use uuid::Uuid;
@@ -42,6 +44,7 @@ async fn test_user_crud_operations() {
         password_hash: "hashed_password".to_string(),
         created_at: None,
         user_group_id: None,
+        ..User::default_test()
     };
 
     let transaction = pool.begin().await.expect("Failed to begin transaction");
@@ -106,6 +109,7 @@ async fn test_get_all_users() {
             password_hash: "hash1".to_string(),
             created_at: None,
             user_group_id: None,
+            ..User::default_test()
         },
         User {
             id: Uuid::new_v4(),
@@ -113,6 +117,7 @@ async fn test_get_all_users() {
             password_hash: "hash2".to_string(),
             created_at: None,
             user_group_id: None,
+            ..User::default_test()
         },
     ];
 
@@ -155,6 +160,7 @@ async fn test_delete_users_by_ids() {
             password_hash: "hash1".to_string(),
             created_at: None,
             user_group_id: None,
+            ..User::default_test()
         },
         User {
             id: Uuid::new_v4(),
@@ -162,6 +168,7 @@ async fn test_delete_users_by_ids() {
             password_hash: "hash2".to_string(),
             created_at: None,
             user_group_id: None,
+            ..User::default_test()
         },
         User {
             id: Uuid::new_v4(),
@@ -169,6 +176,7 @@ async fn test_delete_users_by_ids() {
             password_hash: "hash3".to_string(),
             created_at: None,
             user_group_id: None,
+            ..User::default_test()
         },
     ];
 
@@ -216,6 +224,7 @@ async fn test_check_user_is_valid() {
         password_hash: "hashed_password".to_string(),
         created_at: None,
         user_group_id: None,
+        ..User::default_test()
     };
     let transaction = pool.begin().await.expect("Failed to begin transaction");
     let inserted_user = insert_user(transaction, &user)
@@ -232,3 +241,62 @@ async fn test_check_user_is_valid() {
         .unwrap();
     assert!(result)
 }
+
+#[tokio::test]
+async fn test_update_user_profile() {
+    let app = crate::helpers::spawn_app().await;
+    let users = User::default_vec_test(Some(2));
+    for user in &users {
+        let transaction = app.db_pool.begin().await.unwrap();
+        insert_user(transaction, user).await.unwrap();
+    }
+    let (user, other) = (&users[0], &users[1]);
+    let mut media = Vec::new();
+    for (owner, mime_type) in [
+        (user.id, "image/png"),
+        (user.id, "video/mp4"),
+        (other.id, "image/png"),
+    ] {
+        let transaction = app.db_pool.begin().await.unwrap();
+        let item = Media::new(owner, mime_type, mime_type.as_bytes());
+        media.push(insert_media(transaction, &item).await.unwrap());
+    }
+
+    let profile = UserProfile {
+        display_name: Some("Test User".to_string()),
+        website: Some("https://example.com".to_string()),
+        avatar_media_id: Some(media[0].id),
+        banner_media_id: Some(media[0].id),
+        ..UserProfile::default()
+    };
+    let transaction = app.db_pool.begin().await.unwrap();
+    let updated = update_user_profile(transaction, user.id, &profile)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(updated.display_name.as_deref(), Some("Test User"));
+    assert_eq!(updated.avatar_media_id, Some(media[0].id));
+    let transaction = app.db_pool.begin().await.unwrap();
+    let fetched = get_user_by_id(transaction, user.id).await.unwrap().unwrap();
+    assert_eq!(fetched, updated);
+
+    // Avatars must be images the user uploaded
+    for media_id in [media[1].id, media[2].id] {
+        let transaction = app.db_pool.begin().await.unwrap();
+        let profile = UserProfile {
+            avatar_media_id: Some(media_id),
+            ..UserProfile::default()
+        };
+        assert!(update_user_profile(transaction, user.id, &profile)
+            .await
+            .is_err());
+    }
+
+    let transaction = app.db_pool.begin().await.unwrap();
+    assert!(
+        update_user_profile(transaction, Uuid::new_v4(), &UserProfile::default())
+            .await
+            .unwrap()
+            .is_none()
+    );
+}
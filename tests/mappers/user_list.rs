@@ -0,0 +1,195 @@
+use crate::helpers::spawn_app;
+use aloha_backend::dto::query::{DtoQuery, TweetFilterQuery, UserListFilterQuery};
+use aloha_backend::mappers::tweet::insert_tweet;
+use aloha_backend::mappers::user::insert_user;
+use aloha_backend::mappers::user_list::{
+    delete_user_list, delete_user_list_member, get_user_list_by_id, get_user_list_members,
+    get_user_list_timeline, get_user_lists_by_owner_id, insert_user_list, insert_user_list_member,
+    update_user_list,
+};
+use aloha_backend::models::tweet::{Tweet, TweetVisibility};
+use aloha_backend::models::user::User;
+use aloha_backend::models::user_list::UserList;
+use uuid::Uuid;
+
+#[tokio::test]
+async fn user_lists_are_only_managed_by_their_owner() {
+    let app = spawn_app().await;
+    let users = User::default_vec_test(Some(2));
+    for user in &users {
+        let transaction = app.db_pool.begin().await.unwrap();
+        insert_user(transaction, user).await.unwrap();
+    }
+    let (owner, other) = (&users[0], &users[1]);
+
+    let transaction = app.db_pool.begin().await.unwrap();
+    let list = insert_user_list(
+        transaction,
+        &UserList::new(owner.id, "Friends".to_string(), None),
+    )
+    .await
+    .unwrap();
+
+    let transaction = app.db_pool.begin().await.unwrap();
+    let lists = get_user_lists_by_owner_id(
+        transaction,
+        owner.id,
+        DtoQuery::<UserListFilterQuery>::default_query(),
+    )
+    .await
+    .unwrap();
+    assert_eq!(lists.data, vec![list.clone()]);
+    let transaction = app.db_pool.begin().await.unwrap();
+    assert!(get_user_list_by_id(transaction, other.id, list.id)
+        .await
+        .unwrap()
+        .is_none());
+
+    let renamed = UserList {
+        id: list.id,
+        ..UserList::new(
+            owner.id,
+            "Close friends".to_string(),
+            Some("<3".to_string()),
+        )
+    };
+    let transaction = app.db_pool.begin().await.unwrap();
+    let updated = update_user_list(transaction, &renamed)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(updated.name, "Close friends");
+    assert_eq!(updated.description.as_deref(), Some("<3"));
+    let transaction = app.db_pool.begin().await.unwrap();
+    let stolen = UserList {
+        owner_id: other.id,
+        ..renamed.clone()
+    };
+    assert!(update_user_list(transaction, &stolen)
+        .await
+        .unwrap()
+        .is_none());
+
+    let transaction = app.db_pool.begin().await.unwrap();
+    assert!(
+        insert_user_list_member(transaction, other.id, list.id, owner.id)
+            .await
+            .unwrap()
+            .is_none()
+    );
+    let transaction = app.db_pool.begin().await.unwrap();
+    assert!(
+        insert_user_list_member(transaction, owner.id, list.id, Uuid::new_v4())
+            .await
+            .is_err()
+    );
+
+    let transaction = app.db_pool.begin().await.unwrap();
+    assert!(delete_user_list(transaction, other.id, list.id)
+        .await
+        .unwrap()
+        .is_none());
+    let transaction = app.db_pool.begin().await.unwrap();
+    assert!(delete_user_list(transaction, owner.id, list.id)
+        .await
+        .unwrap()
+        .is_some());
+}
+
+#[tokio::test]
+async fn user_list_timeline_contains_readable_member_tweets() {
+    let app = spawn_app().await;
+    let users = User::default_vec_test(Some(3));
+    for user in &users {
+        let transaction = app.db_pool.begin().await.unwrap();
+        insert_user(transaction, user).await.unwrap();
+    }
+    let (owner, member, stranger) = (&users[0], &users[1], &users[2]);
+
+    let transaction = app.db_pool.begin().await.unwrap();
+    let list = insert_user_list(
+        transaction,
+        &UserList::new(owner.id, "Reading".to_string(), None),
+    )
+    .await
+    .unwrap();
+    for _ in 0..2 {
+        let transaction = app.db_pool.begin().await.unwrap();
+        let added = insert_user_list_member(transaction, owner.id, list.id, member.id)
+            .await
+            .unwrap();
+        assert_eq!(added.map(|m| m.user_id), Some(member.id));
+    }
+
+    let public = Tweet::new("Hello list".to_string(), member.id);
+    let private = Tweet {
+        visibility: TweetVisibility::Private,
+        ..Tweet::new("Hidden".to_string(), member.id)
+    };
+    let unrelated = Tweet::new("Not in the list".to_string(), stranger.id);
+    for tweet in [&public, &private, &unrelated] {
+        let transaction = app.db_pool.begin().await.unwrap();
+        insert_tweet(transaction, tweet).await.unwrap();
+    }
+
+    let transaction = app.db_pool.begin().await.unwrap();
+    let members = get_user_list_members(
+        transaction,
+        owner.id,
+        list.id,
+        DtoQuery::<UserListFilterQuery>::default_query(),
+    )
+    .await
+    .unwrap()
+    .unwrap();
+    assert_eq!(
+        members.data.iter().map(|u| u.id).collect::<Vec<_>>(),
+        vec![member.id]
+    );
+
+    let transaction = app.db_pool.begin().await.unwrap();
+    let timeline = get_user_list_timeline(
+        transaction,
+        owner.id,
+        list.id,
+        DtoQuery::<TweetFilterQuery>::default_query(),
+    )
+    .await
+    .unwrap()
+    .unwrap();
+    assert_eq!(
+        timeline.data.iter().map(|t| t.id).collect::<Vec<_>>(),
+        vec![public.id]
+    );
+    assert_eq!(timeline.pagination.unwrap().total, Some(1));
+
+    let transaction = app.db_pool.begin().await.unwrap();
+    assert!(get_user_list_timeline(
+        transaction,
+        stranger.id,
+        list.id,
+        DtoQuery::<TweetFilterQuery>::default_query(),
+    )
+    .await
+    .unwrap()
+    .is_none());
+
+    let transaction = app.db_pool.begin().await.unwrap();
+    assert!(
+        delete_user_list_member(transaction, owner.id, list.id, member.id)
+            .await
+            .unwrap()
+            .is_some()
+    );
+    let transaction = app.db_pool.begin().await.unwrap();
+    let timeline = get_user_list_timeline(
+        transaction,
+        owner.id,
+        list.id,
+        DtoQuery::<TweetFilterQuery>::default_query(),
+    )
+    .await
+    .unwrap()
+    .unwrap();
+    assert!(timeline.data.is_empty());
+}
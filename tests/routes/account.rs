@@ -0,0 +1,144 @@
+use crate::helpers::spawn_app;
+use aloha_backend::mappers::user::insert_user;
+use aloha_backend::models::account::{AccountStateResponse, AccountStatus};
+use aloha_backend::models::user::User;
+use aloha_backend::routes::auth::ADMIN_PERMISSION;
+use serde_json::json;
+
+#[tokio::test]
+async fn admins_ban_and_reactivate_accounts() {
+    let app = spawn_app().await;
+    let users = User::default_vec_test(Some(2));
+    for user in &users {
+        let transaction = app.db_pool.begin().await.unwrap();
+        insert_user(transaction, user).await.unwrap();
+    }
+    let (author, admin) = (&users[0], &users[1]);
+    app.grant_permissions(admin.id, &[ADMIN_PERMISSION]).await;
+    let author_login = json!({"username": author.username, "password": author.password_hash});
+
+    app.login(&author_login).await.unwrap();
+    let tweet = app
+        .post_tweet(&json!({ "content": "Buy followers here" }))
+        .await
+        .unwrap();
+    assert_eq!(
+        app.put_account_status(admin.id, &json!({ "status": "banned" }))
+            .await
+            .status()
+            .as_u16(),
+        403
+    );
+
+    // The admin works from a second session so the author's stays open
+    let admin_client = reqwest::Client::builder()
+        .cookie_store(true)
+        .build()
+        .unwrap();
+    admin_client
+        .post(format!("{}/auth/login", app.address))
+        .json(&json!({"username": admin.username, "password": admin.password_hash}))
+        .send()
+        .await
+        .unwrap();
+    let status_url = format!("{}/users/{}/status", app.address, author.id);
+    let response = admin_client
+        .put(&status_url)
+        .json(&json!({ "status": "suspended" }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status().as_u16(), 400);
+    let response = admin_client
+        .put(format!("{}/users/{}/status", app.address, admin.id))
+        .json(&json!({ "status": "banned" }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status().as_u16(), 400);
+    let response = admin_client
+        .put(&status_url)
+        .json(&json!({ "status": "banned", "reason": "Spam" }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status().as_u16(), 200);
+    let state = response.json::<AccountStateResponse>().await.unwrap();
+    assert_eq!(state.status, AccountStatus::Banned);
+    assert_eq!(state.reason.as_deref(), Some("Spam"));
+
+    // The author's open session ends and their tweets disappear
+    assert_eq!(
+        app.deactivate_account(&json!({})).await.status().as_u16(),
+        401
+    );
+    assert_eq!(
+        app.get_tweet_response(tweet.id).await.status().as_u16(),
+        404
+    );
+    let response = app.login_response(&author_login).await;
+    assert_eq!(response.status().as_u16(), 403);
+    assert_eq!(
+        response.text().await.unwrap(),
+        "This account is banned. Reason: Spam"
+    );
+
+    let response = admin_client
+        .put(&status_url)
+        .json(&json!({ "status": "active" }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status().as_u16(), 200);
+    assert_eq!(
+        app.login_response(&author_login).await.status().as_u16(),
+        200
+    );
+    assert_eq!(
+        app.get_tweet_response(tweet.id).await.status().as_u16(),
+        200
+    );
+}
+
+#[tokio::test]
+async fn users_can_deactivate_their_own_account() {
+    let app = spawn_app().await;
+    let users = User::default_vec_test(Some(2));
+    for user in &users {
+        let transaction = app.db_pool.begin().await.unwrap();
+        insert_user(transaction, user).await.unwrap();
+    }
+    let (user, admin) = (&users[0], &users[1]);
+    app.grant_permissions(admin.id, &[ADMIN_PERMISSION]).await;
+    let user_login = json!({"username": user.username, "password": user.password_hash});
+
+    app.login(&user_login).await.unwrap();
+    let response = app
+        .deactivate_account(&json!({ "reason": "Taking a break" }))
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+    let state = response.json::<AccountStateResponse>().await.unwrap();
+    assert_eq!(state.status, AccountStatus::Deactivated);
+    assert_eq!(
+        app.deactivate_account(&json!({})).await.status().as_u16(),
+        401
+    );
+    let response = app.login_response(&user_login).await;
+    assert_eq!(response.status().as_u16(), 403);
+    assert_eq!(
+        response.text().await.unwrap(),
+        "This account is deactivated. Reason: Taking a break"
+    );
+
+    app.login(&json!({"username": admin.username, "password": admin.password_hash}))
+        .await
+        .unwrap();
+    let state = app
+        .get_account_status(user.id)
+        .await
+        .json::<AccountStateResponse>()
+        .await
+        .unwrap();
+    assert_eq!(state.status, AccountStatus::Deactivated);
+    assert!(state.changed_at.is_some());
+}
@@ -0,0 +1,70 @@
+use crate::helpers::spawn_app;
+use aloha_backend::mappers::user::insert_user;
+use aloha_backend::models::block::BlockResponse;
+use aloha_backend::models::user::User;
+use serde_json::json;
+
+#[tokio::test]
+async fn blocking_hides_tweets_in_both_directions() {
+    let app = spawn_app().await;
+    let users = User::default_vec_test(Some(2));
+    for user in &users {
+        let transaction = app.db_pool.begin().await.unwrap();
+        insert_user(transaction, user).await.unwrap();
+    }
+    let (author, blocker) = (&users[0], &users[1]);
+
+    app.login(&json!({"username": author.username, "password": author.password_hash}))
+        .await
+        .unwrap();
+    let tweet = app
+        .post_tweet(&json!({ "content": "Hello #blocking" }))
+        .await
+        .unwrap();
+
+    app.login(&json!({"username": blocker.username, "password": blocker.password_hash}))
+        .await
+        .unwrap();
+    let own = app
+        .post_tweet(&json!({ "content": "Bye #blocking" }))
+        .await
+        .unwrap();
+    assert_eq!(app.put_block(blocker.id).await.status().as_u16(), 400);
+    let response = app.put_block(author.id).await;
+    assert_eq!(response.status().as_u16(), 200);
+    let block = response.json::<BlockResponse>().await.unwrap();
+    assert_eq!(block.blocker_id, blocker.id);
+    assert_eq!(block.blocked_id, author.id);
+
+    let blocks = app.get_blocks().await.unwrap();
+    assert_eq!(
+        blocks.data.iter().map(|u| u.id).collect::<Vec<_>>(),
+        vec![author.id]
+    );
+    assert_eq!(
+        app.get_tweet_response(tweet.id).await.status().as_u16(),
+        404
+    );
+    let tagged = app.get_hashtag_tweets("blocking").await.unwrap();
+    assert_eq!(
+        tagged.data.iter().map(|t| t.id).collect::<Vec<_>>(),
+        vec![own.id]
+    );
+
+    // The blocked author cannot read the blocker's tweets either
+    app.login(&json!({"username": author.username, "password": author.password_hash}))
+        .await
+        .unwrap();
+    assert_eq!(app.get_tweet_response(own.id).await.status().as_u16(), 404);
+    assert!(app.get_blocks().await.unwrap().data.is_empty());
+
+    app.login(&json!({"username": blocker.username, "password": blocker.password_hash}))
+        .await
+        .unwrap();
+    assert_eq!(app.delete_block(author.id).await.status().as_u16(), 200);
+    assert_eq!(app.delete_block(author.id).await.status().as_u16(), 404);
+    assert_eq!(
+        app.get_tweet_response(tweet.id).await.status().as_u16(),
+        200
+    );
+}
@@ -0,0 +1,79 @@
+use crate::helpers::spawn_app;
+use aloha_backend::mappers::user::insert_user;
+use aloha_backend::models::bookmark::BookmarkResponse;
+use aloha_backend::models::user::User;
+use serde_json::json;
+
+#[tokio::test]
+async fn bookmarks_are_private_to_the_logged_in_user() {
+    let app = spawn_app().await;
+    let users = User::default_vec_test(Some(2));
+    for user in &users {
+        let transaction = app.db_pool.begin().await.unwrap();
+        insert_user(transaction, user).await.unwrap();
+    }
+    let (author, reader) = (&users[0], &users[1]);
+
+    app.login(&json!({"username": author.username, "password": author.password_hash}))
+        .await
+        .unwrap();
+    let tweet = app
+        .post_tweet(&json!({ "content": "Worth keeping" }))
+        .await
+        .unwrap();
+    let private = app
+        .post_tweet(&json!({ "content": "Mine only", "visibility": "private" }))
+        .await
+        .unwrap();
+
+    app.login(&json!({"username": reader.username, "password": reader.password_hash}))
+        .await
+        .unwrap();
+    let response = app.put_bookmark(tweet.id).await;
+    assert_eq!(response.status().as_u16(), 200);
+    let bookmark = response.json::<BookmarkResponse>().await.unwrap();
+    assert_eq!(bookmark.user_id, reader.id);
+    assert_eq!(bookmark.tweet_id, tweet.id);
+    assert_eq!(app.put_bookmark(tweet.id).await.status().as_u16(), 200);
+    assert_eq!(app.put_bookmark(private.id).await.status().as_u16(), 404);
+
+    let bookmarks = app.get_bookmarks().await.unwrap();
+    assert_eq!(
+        bookmarks.data.iter().map(|t| t.id).collect::<Vec<_>>(),
+        vec![tweet.id]
+    );
+    assert_eq!(bookmarks.pagination.unwrap().total, Some(1));
+
+    // The author does not see the reader's bookmarks
+    app.login(&json!({"username": author.username, "password": author.password_hash}))
+        .await
+        .unwrap();
+    assert!(app.get_bookmarks().await.unwrap().data.is_empty());
+    assert_eq!(app.delete_bookmark(tweet.id).await.status().as_u16(), 404);
+
+    app.login(&json!({"username": reader.username, "password": reader.password_hash}))
+        .await
+        .unwrap();
+    assert_eq!(app.delete_bookmark(tweet.id).await.status().as_u16(), 200);
+    assert!(app.get_bookmarks().await.unwrap().data.is_empty());
+}
+
+#[tokio::test]
+async fn bookmark_routes_return_401_when_not_logged_in() {
+    let app = spawn_app().await;
+
+    let response = app
+        .api_client
+        .get(format!("{}/bookmarks", app.address))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+    assert_eq!(response.status().as_u16(), 401);
+    assert_eq!(
+        app.put_bookmark(uuid::Uuid::new_v4())
+            .await
+            .status()
+            .as_u16(),
+        401
+    );
+}
@@ -0,0 +1,175 @@
+use crate::helpers::spawn_app;
+use aloha_backend::dto::response::DtoResponse;
+use aloha_backend::mappers::user::insert_user;
+use aloha_backend::models::content_rule::{
+    ContentRuleAction, ContentRuleEvaluationResponse, ContentRuleResponse,
+};
+use aloha_backend::models::tweet::TweetResponse;
+use aloha_backend::models::user::User;
+use aloha_backend::routes::auth::{
+    ADMIN_PERMISSION, HIDE_TWEETS_PERMISSION, MODERATE_REPORTS_PERMISSION,
+};
+use serde_json::json;
+
+#[tokio::test]
+async fn admins_manage_content_rules_applied_to_new_tweets() {
+    let app = spawn_app().await;
+    let users = User::default_vec_test(Some(2));
+    for user in &users {
+        let transaction = app.db_pool.begin().await.unwrap();
+        insert_user(transaction, user).await.unwrap();
+    }
+    let (author, admin) = (&users[0], &users[1]);
+    app.grant_permissions(
+        admin.id,
+        &[
+            ADMIN_PERMISSION,
+            MODERATE_REPORTS_PERMISSION,
+            HIDE_TWEETS_PERMISSION,
+        ],
+    )
+    .await;
+
+    app.login(&json!({"username": author.username, "password": author.password_hash}))
+        .await
+        .unwrap();
+    let word_rule = json!({ "kind": "word", "pattern": "darn", "action": "mask" });
+    assert_eq!(
+        app.post_content_rule(&word_rule).await.status().as_u16(),
+        403
+    );
+    assert_eq!(
+        app.test_content_rules(&json!({ "content": "darn" }))
+            .await
+            .status()
+            .as_u16(),
+        403
+    );
+
+    app.login(&json!({"username": admin.username, "password": admin.password_hash}))
+        .await
+        .unwrap();
+    let response = app
+        .post_content_rule(&json!({ "kind": "regex", "pattern": "(oops", "action": "hold" }))
+        .await;
+    assert_eq!(response.status().as_u16(), 400);
+    let response = app
+        .post_content_rule(&json!({ "kind": "domain", "pattern": "nodot", "action": "reject" }))
+        .await;
+    assert_eq!(response.status().as_u16(), 400);
+    let response = app.post_content_rule(&word_rule).await;
+    assert_eq!(response.status().as_u16(), 200);
+    let mask = response.json::<ContentRuleResponse>().await.unwrap();
+    assert!(mask.enabled);
+    assert_eq!(
+        app.post_content_rule(&word_rule).await.status().as_u16(),
+        400
+    );
+    let hold = app
+        .post_content_rule(
+            &json!({ "kind": "regex", "pattern": "fr[e3]{2} money", "action": "hold" }),
+        )
+        .await
+        .json::<ContentRuleResponse>()
+        .await
+        .unwrap();
+    let reject = app
+        .post_content_rule(
+            &json!({ "kind": "domain", "pattern": "Spam.Example", "action": "reject" }),
+        )
+        .await
+        .json::<ContentRuleResponse>()
+        .await
+        .unwrap();
+    assert_eq!(reject.pattern, "spam.example");
+
+    let evaluation = app
+        .test_content_rules(&json!({ "content": "Darn, see http://spam.example" }))
+        .await
+        .json::<ContentRuleEvaluationResponse>()
+        .await
+        .unwrap();
+    assert_eq!(evaluation.action, Some(ContentRuleAction::Reject));
+    assert_eq!(evaluation.matched_rule_ids, vec![mask.id, reject.id]);
+
+    app.login(&json!({"username": author.username, "password": author.password_hash}))
+        .await
+        .unwrap();
+    let response = app
+        .post_tweet_response(&json!({ "content": "Darn weather" }))
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+    assert_eq!(
+        response.json::<TweetResponse>().await.unwrap().content,
+        "**** weather"
+    );
+    let response = app
+        .post_tweet_response(&json!({ "content": "Go to https://spam.example" }))
+        .await;
+    assert_eq!(response.status().as_u16(), 400);
+    let response = app
+        .post_tweet_response(&json!({ "content": "Free money inside" }))
+        .await;
+    assert_eq!(response.status().as_u16(), 202);
+    let held = response.json::<TweetResponse>().await.unwrap();
+    assert_eq!(app.get_tweet_response(held.id).await.status().as_u16(), 404);
+    assert_eq!(
+        app.get_all_tweets()
+            .await
+            .unwrap()
+            .pagination
+            .unwrap()
+            .total,
+        Some(1)
+    );
+
+    app.login(&json!({"username": admin.username, "password": admin.password_hash}))
+        .await
+        .unwrap();
+    let rules = app
+        .get_content_rules()
+        .await
+        .json::<DtoResponse<Vec<ContentRuleResponse>>>()
+        .await
+        .unwrap();
+    assert_eq!(
+        rules
+            .data
+            .iter()
+            .map(|r| (r.id, r.hit_count))
+            .collect::<Vec<_>>(),
+        vec![(mask.id, 1), (hold.id, 1), (reject.id, 1)]
+    );
+    assert!(rules.data.iter().all(|r| r.last_hit_at.is_some()));
+
+    let queue = app
+        .get_held_tweets()
+        .await
+        .json::<DtoResponse<Vec<TweetResponse>>>()
+        .await
+        .unwrap();
+    assert_eq!(
+        queue.data.iter().map(|t| t.id).collect::<Vec<_>>(),
+        vec![held.id]
+    );
+    let response = app
+        .release_tweet(held.id, &json!({ "note": "Fine in context" }))
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+    assert_eq!(app.get_tweet_response(held.id).await.status().as_u16(), 200);
+
+    let response = app
+        .put_content_rule(
+            reject.id,
+            &json!({ "kind": "domain", "pattern": "spam.example", "action": "reject", "enabled": false }),
+        )
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+    let disabled = response.json::<ContentRuleResponse>().await.unwrap();
+    assert!(!disabled.enabled);
+    assert_eq!(disabled.hit_count, 1);
+    let response = app
+        .post_tweet_response(&json!({ "content": "Go to https://spam.example" }))
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+}
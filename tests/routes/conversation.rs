@@ -0,0 +1,172 @@
+use crate::helpers::spawn_app;
+use aloha_backend::dto::cursor::{CursorQuery, CursorResponse};
+use aloha_backend::mappers::user::insert_user;
+use aloha_backend::models::conversation::{
+    ConversationResponse, DirectMessageResponse, DmPolicy, MessagingSettings,
+};
+use aloha_backend::models::user::User;
+use serde_json::json;
+
+#[tokio::test]
+async fn participants_exchange_messages_with_read_receipts() {
+    let app = spawn_app().await;
+    let users = User::default_vec_test(Some(3));
+    for user in &users {
+        let transaction = app.db_pool.begin().await.unwrap();
+        insert_user(transaction, user).await.unwrap();
+    }
+    let (alice, bob, carol) = (&users[0], &users[1], &users[2]);
+    let body = json!({ "participant_ids": [bob.id] });
+    assert_eq!(app.post_conversation(&body).await.status().as_u16(), 401);
+
+    app.login(&json!({"username": alice.username, "password": alice.password_hash}))
+        .await
+        .unwrap();
+    let only_self = json!({ "participant_ids": [alice.id] });
+    assert_eq!(
+        app.post_conversation(&only_self).await.status().as_u16(),
+        400
+    );
+    let response = app.post_conversation(&body).await;
+    assert_eq!(response.status().as_u16(), 200);
+    let conversation = response.json::<ConversationResponse>().await.unwrap();
+
+    assert_eq!(
+        app.post_message(conversation.id, &json!({ "content": "  " }))
+            .await
+            .status()
+            .as_u16(),
+        400
+    );
+    let response = app
+        .post_message(conversation.id, &json!({ "content": " hi bob " }))
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+    let message = response.json::<DirectMessageResponse>().await.unwrap();
+    assert_eq!(message.content, "hi bob");
+
+    app.login(&json!({"username": bob.username, "password": bob.password_hash}))
+        .await
+        .unwrap();
+    let inbox = app
+        .get_conversations(&CursorQuery::default_query())
+        .await
+        .unwrap();
+    assert_eq!(inbox.data.len(), 1);
+    assert_eq!(inbox.data[0].unread_count, 1);
+    let response = app.mark_conversation_read(conversation.id).await;
+    assert_eq!(response.status().as_u16(), 200);
+    assert_eq!(
+        response
+            .json::<ConversationResponse>()
+            .await
+            .unwrap()
+            .unread_count,
+        0
+    );
+    // Only the sender may delete the message
+    assert_eq!(
+        app.delete_message(conversation.id, message.id)
+            .await
+            .status()
+            .as_u16(),
+        404
+    );
+
+    app.login(&json!({"username": alice.username, "password": alice.password_hash}))
+        .await
+        .unwrap();
+    let page = app
+        .get_messages(conversation.id, &CursorQuery::default_query())
+        .await
+        .json::<CursorResponse<Vec<DirectMessageResponse>>>()
+        .await
+        .unwrap();
+    assert_eq!(page.data.len(), 1);
+    assert_eq!(page.data[0].read_by, vec![bob.id]);
+    assert_eq!(
+        app.delete_message(conversation.id, message.id)
+            .await
+            .status()
+            .as_u16(),
+        200
+    );
+
+    app.login(&json!({"username": carol.username, "password": carol.password_hash}))
+        .await
+        .unwrap();
+    assert_eq!(
+        app.get_conversation(conversation.id)
+            .await
+            .status()
+            .as_u16(),
+        404
+    );
+    assert_eq!(
+        app.get_messages(conversation.id, &CursorQuery::default_query())
+            .await
+            .status()
+            .as_u16(),
+        404
+    );
+    assert_eq!(
+        app.post_message(conversation.id, &json!({ "content": "let me in" }))
+            .await
+            .status()
+            .as_u16(),
+        404
+    );
+}
+
+#[tokio::test]
+async fn users_choose_who_can_message_them() {
+    let app = spawn_app().await;
+    let users = User::default_vec_test(Some(2));
+    for user in &users {
+        let transaction = app.db_pool.begin().await.unwrap();
+        insert_user(transaction, user).await.unwrap();
+    }
+    let (sender, recipient) = (&users[0], &users[1]);
+    assert_eq!(app.get_messaging_settings().await.status().as_u16(), 401);
+
+    app.login(&json!({"username": recipient.username, "password": recipient.password_hash}))
+        .await
+        .unwrap();
+    let settings = app
+        .get_messaging_settings()
+        .await
+        .json::<MessagingSettings>()
+        .await
+        .unwrap();
+    assert_eq!(settings.dm_policy, DmPolicy::Everyone);
+    assert_eq!(
+        app.put_messaging_settings(&json!({ "dm_policy": "strangers" }))
+            .await
+            .status()
+            .as_u16(),
+        400
+    );
+    let response = app
+        .put_messaging_settings(&json!({ "dm_policy": "nobody" }))
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+    assert_eq!(
+        response
+            .json::<MessagingSettings>()
+            .await
+            .unwrap()
+            .dm_policy,
+        DmPolicy::Nobody
+    );
+
+    app.login(&json!({"username": sender.username, "password": sender.password_hash}))
+        .await
+        .unwrap();
+    assert_eq!(
+        app.post_conversation(&json!({ "participant_ids": [recipient.id] }))
+            .await
+            .status()
+            .as_u16(),
+        400
+    );
+}
@@ -0,0 +1,114 @@
+use crate::helpers::spawn_app;
+use aloha_backend::configuration::get_configuration;
+use aloha_backend::dto::query::{DraftFilterQuery, DtoQuery};
+use aloha_backend::jobs::scheduler::run_scheduler_once;
+use aloha_backend::mappers::user::insert_user;
+use aloha_backend::models::draft::{TweetDraftResponse, TweetStatus};
+use aloha_backend::models::tweet::TweetResponse;
+use aloha_backend::models::user::User;
+use serde_json::json;
+use time::format_description::well_known::Rfc3339;
+use time::{Duration, OffsetDateTime};
+
+#[tokio::test]
+async fn drafts_can_be_scheduled_edited_and_published() {
+    let app = spawn_app().await;
+    let transaction = app.db_pool.begin().await.unwrap();
+    let user = insert_user(transaction, &User::default_test())
+        .await
+        .unwrap();
+
+    let response = app.post_draft(&json!({ "content": "Anonymous" })).await;
+    assert_eq!(response.status().as_u16(), 401);
+
+    app.login(&json!({"username": user.username, "password": user.password_hash}))
+        .await
+        .unwrap();
+
+    let past = (OffsetDateTime::now_utc() - Duration::minutes(1))
+        .format(&Rfc3339)
+        .unwrap();
+    let response = app
+        .post_draft(&json!({ "content": "Too late", "publish_at": past }))
+        .await;
+    assert_eq!(response.status().as_u16(), 400);
+
+    let later = (OffsetDateTime::now_utc() + Duration::hours(1))
+        .format(&Rfc3339)
+        .unwrap();
+    let scheduled: TweetDraftResponse = app
+        .post_draft(&json!({ "content": "Launch day", "publish_at": later }))
+        .await
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(scheduled.status, TweetStatus::Scheduled);
+    let draft: TweetDraftResponse = app
+        .post_draft(&json!({ "content": "Thinking out loud" }))
+        .await
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(draft.status, TweetStatus::Draft);
+
+    let mut query = DtoQuery::<DraftFilterQuery>::default_query();
+    query.filter = Some(DraftFilterQuery {
+        status: Some(TweetStatus::Scheduled),
+    });
+    let listed = app.get_drafts(&query).await.unwrap();
+    assert_eq!(listed.data.len(), 1);
+    assert_eq!(listed.data[0].id, scheduled.id);
+    assert_eq!(
+        app.get_all_tweets()
+            .await
+            .unwrap()
+            .pagination
+            .unwrap()
+            .total,
+        Some(0)
+    );
+
+    let edited: TweetDraftResponse = app
+        .put_draft(draft.id, &json!({ "content": "Thinking out loud, edited" }))
+        .await
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(edited.content, "Thinking out loud, edited");
+    assert_eq!(edited.status, TweetStatus::Draft);
+
+    let response = app.publish_draft(draft.id).await;
+    assert_eq!(response.status().as_u16(), 200);
+    let tweet: TweetResponse = response.json().await.unwrap();
+    assert_eq!(tweet.content, "Thinking out loud, edited");
+    assert_eq!(app.publish_draft(draft.id).await.status().as_u16(), 404);
+    assert_eq!(
+        app.put_draft(draft.id, &json!({ "content": "Rewrite" }))
+            .await
+            .status()
+            .as_u16(),
+        404
+    );
+
+    // The scheduler publishes the scheduled tweet once it is due.
+    let settings = get_configuration().unwrap().tweets;
+    let published = run_scheduler_once(
+        &app.db_pool,
+        &settings,
+        OffsetDateTime::now_utc() + Duration::hours(2),
+    )
+    .await
+    .unwrap();
+    assert_eq!(published.len(), 1);
+    assert_eq!(published[0].id, scheduled.id);
+    assert_eq!(
+        app.get_tweet_by_id(scheduled.id).await.unwrap().content,
+        "Launch day"
+    );
+    assert!(app
+        .get_drafts(&DtoQuery::<DraftFilterQuery>::default_query())
+        .await
+        .unwrap()
+        .data
+        .is_empty());
+}
@@ -0,0 +1,161 @@
+use crate::helpers::{spawn_app, GatewaySocket};
+use aloha_backend::mappers::tweet::insert_tweet;
+use aloha_backend::mappers::user::insert_user;
+use aloha_backend::models::tweet::Tweet;
+use aloha_backend::models::user::User;
+use futures_util::{SinkExt, StreamExt};
+use std::time::Duration;
+use tokio_tungstenite::tungstenite::{Error, Message};
+use uuid::Uuid;
+
+async fn send_event(socket: &mut GatewaySocket, event: serde_json::Value) {
+    socket
+        .send(Message::text(event.to_string()))
+        .await
+        .expect("Failed to send event");
+}
+
+/// Waits for the next JSON event, skipping control frames.
+async fn next_event(socket: &mut GatewaySocket) -> serde_json::Value {
+    actix_web::rt::time::timeout(Duration::from_secs(10), async {
+        loop {
+            let message = socket
+                .next()
+                .await
+                .expect("Gateway closed the connection")
+                .expect("Failed to read frame");
+            if let Message::Text(text) = message {
+                return serde_json::from_str(&text).expect("Frame is not JSON");
+            }
+        }
+    })
+    .await
+    .expect("No event received in time")
+}
+
+#[tokio::test]
+async fn gateway_returns_401_when_not_logged_in() {
+    let app = spawn_app().await;
+
+    match app.connect_gateway(None).await {
+        Err(Error::Http(response)) => assert_eq!(response.status().as_u16(), 401),
+        other => panic!("Unexpected handshake result: {:?}", other.map(|_| ())),
+    }
+}
+
+#[tokio::test]
+async fn gateway_delivers_tweets_on_subscribed_channels() {
+    let app = spawn_app().await;
+    let users = User::default_vec_test(Some(2));
+    for user in &users {
+        let transaction = app.db_pool.begin().await.unwrap();
+        insert_user(transaction, user).await.unwrap();
+    }
+    let (viewer, author) = (&users[0], &users[1]);
+    let cookie = app
+        .login_cookie(&serde_json::json!({
+            "username": viewer.username,
+            "password": viewer.password_hash
+        }))
+        .await;
+    let mut socket = app.connect_gateway(Some(&cookie)).await.unwrap();
+
+    send_event(
+        &mut socket,
+        serde_json::json!({"type": "subscribe", "channel": "timeline"}),
+    )
+    .await;
+    assert_eq!(next_event(&mut socket).await["type"], "error");
+    send_event(
+        &mut socket,
+        serde_json::json!({"type": "subscribe", "channel": format!("user:{}", Uuid::new_v4())}),
+    )
+    .await;
+    assert_eq!(next_event(&mut socket).await["type"], "error");
+
+    let user_channel = format!("user:{}", author.id);
+    for channel in [user_channel.as_str(), "hashtag:#Rust"] {
+        send_event(
+            &mut socket,
+            serde_json::json!({"type": "subscribe", "channel": channel}),
+        )
+        .await;
+        assert_eq!(next_event(&mut socket).await["type"], "subscribed");
+    }
+
+    let transaction = app.db_pool.begin().await.unwrap();
+    let tweet = insert_tweet(
+        transaction,
+        &Tweet::new("hello #rust".to_string(), author.id),
+    )
+    .await
+    .unwrap();
+
+    let mut channels = Vec::new();
+    for _ in 0..2 {
+        let event = next_event(&mut socket).await;
+        assert_eq!(event["type"], "tweet");
+        assert_eq!(event["tweet"]["id"], tweet.id.to_string());
+        channels.push(event["channel"].as_str().unwrap().to_string());
+    }
+    channels.sort();
+    assert_eq!(channels, vec!["hashtag:rust".to_string(), user_channel]);
+}
+
+#[tokio::test]
+async fn gateway_relays_typing_to_other_conversation_subscribers() {
+    let app = spawn_app().await;
+    let users = User::default_vec_test(Some(2));
+    for user in &users {
+        let transaction = app.db_pool.begin().await.unwrap();
+        insert_user(transaction, user).await.unwrap();
+    }
+    let transaction = app.db_pool.begin().await.unwrap();
+    let tweet = insert_tweet(transaction, &Tweet::default_test(users[0].id))
+        .await
+        .unwrap();
+    let channel = format!("conversation:{}", tweet.id);
+
+    let mut sockets = Vec::new();
+    for user in &users {
+        let cookie = app
+            .login_cookie(&serde_json::json!({
+                "username": user.username,
+                "password": user.password_hash
+            }))
+            .await;
+        let mut socket = app.connect_gateway(Some(&cookie)).await.unwrap();
+        send_event(
+            &mut socket,
+            serde_json::json!({"type": "subscribe", "channel": channel}),
+        )
+        .await;
+        assert_eq!(next_event(&mut socket).await["type"], "subscribed");
+        sockets.push(socket);
+    }
+
+    send_event(
+        &mut sockets[1],
+        serde_json::json!({"type": "typing", "channel": "hashtag:rust"}),
+    )
+    .await;
+    assert_eq!(next_event(&mut sockets[1]).await["type"], "error");
+
+    send_event(
+        &mut sockets[1],
+        serde_json::json!({"type": "typing", "channel": channel}),
+    )
+    .await;
+    let event = next_event(&mut sockets[0]).await;
+    assert_eq!(event["type"], "typing");
+    assert_eq!(event["channel"], channel);
+    assert_eq!(event["username"], users[1].username);
+
+    // The typist does not hear about it, only the ack of what follows
+    send_event(
+        &mut sockets[1],
+        serde_json::json!({"type": "unsubscribe", "channel": channel}),
+    )
+    .await;
+    assert_eq!(next_event(&mut sockets[1]).await["type"], "unsubscribed");
+}
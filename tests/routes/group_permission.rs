@@ -11,6 +11,7 @@ use wiremock::{Mock, MockServer, ResponseTemplate};
 #[tokio::test]
 async fn insert_group_permission_returns_a_200_for_valid_form_data() {
     let app = spawn_app().await;
+    app.login_admin().await;
     let mut transaction = app.db_pool.begin().await.unwrap();
 
     // Create a user group and permission first
@@ -143,6 +144,7 @@ async fn get_group_permissions_by_permission_id_returns_a_200() {
 #[tokio::test]
 async fn delete_group_permission_returns_a_200() {
     let app = spawn_app().await;
+    app.login_admin().await;
     let mut transaction = app.db_pool.begin().await.unwrap();
 
     // Create a user group and permission first
@@ -179,6 +181,7 @@ async fn delete_group_permission_returns_a_200() {
 #[tokio::test]
 async fn delete_group_permissions_by_group_id_returns_a_200() {
     let app = spawn_app().await;
+    app.login_admin().await;
     let mut transaction = app.db_pool.begin().await.unwrap();
 
     // Create a user group and permission first
@@ -215,6 +218,7 @@ async fn delete_group_permissions_by_group_id_returns_a_200() {
 #[tokio::test]
 async fn delete_group_permissions_by_permission_id_returns_a_200() {
     let app = spawn_app().await;
+    app.login_admin().await;
     let mut transaction = app.db_pool.begin().await.unwrap();
 
     // Create a user group and permission first
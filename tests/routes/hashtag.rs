@@ -0,0 +1,38 @@
+use crate::helpers::spawn_app;
+use aloha_backend::mappers::user::insert_user;
+use aloha_backend::models::user::User;
+
+#[tokio::test]
+async fn hashtag_routes_return_tagged_and_trending_tweets() {
+    let app = spawn_app().await;
+    let transaction = app.db_pool.begin().await.unwrap();
+    let user = insert_user(transaction, &User::default_test())
+        .await
+        .unwrap();
+    app.login(&serde_json::json!({
+        "username": user.username,
+        "password": user.password_hash
+    }))
+    .await
+    .unwrap();
+
+    let tweet = app
+        .post_tweet(&serde_json::json!({ "content": "Hello #Aloha" }))
+        .await
+        .unwrap();
+    app.post_tweet(&serde_json::json!({ "content": "Hello again #aloha #backend" }))
+        .await
+        .unwrap();
+
+    let response = app.get_hashtag_tweets("aloha").await.unwrap();
+    assert_eq!(response.data.len(), 2);
+    assert!(response.data.iter().any(|t| t.id == tweet.id));
+
+    let response = app.get_hashtag_tweets("unknown").await.unwrap();
+    assert!(response.data.is_empty());
+
+    let trending = app.get_trending_hashtags().await.unwrap();
+    assert_eq!(trending.data[0].tag, "aloha");
+    assert_eq!(trending.data[0].uses, 2);
+    assert_eq!(trending.data.len(), 2);
+}
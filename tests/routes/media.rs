@@ -0,0 +1,194 @@
+use crate::helpers::spawn_app;
+use aloha_backend::configuration::get_configuration;
+use aloha_backend::mappers::media::{insert_media, media_content_exists};
+use aloha_backend::mappers::user::insert_user;
+use aloha_backend::models::media::{sha256_hex, Media, MediaResponse};
+use aloha_backend::models::user::User;
+use aloha_backend::routes::media::process_uploaded_media;
+use aloha_backend::storage::{LocalMediaStore, MediaStore};
+use image::{DynamicImage, ImageFormat, RgbImage};
+use std::io::Cursor;
+use std::time::Duration;
+
+const PNG: &[u8] = b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR";
+
+#[tokio::test]
+async fn uploaded_media_can_be_attached_and_downloaded() {
+    let app = spawn_app().await;
+    let transaction = app.db_pool.begin().await.unwrap();
+    let user = insert_user(transaction, &User::default_test())
+        .await
+        .unwrap();
+
+    let response = app.upload_media(PNG.to_vec()).await;
+    assert_eq!(response.status().as_u16(), 401);
+
+    app.login(&serde_json::json!({
+        "username": user.username,
+        "password": user.password_hash
+    }))
+    .await
+    .unwrap();
+
+    let media = app
+        .upload_media(PNG.to_vec())
+        .await
+        .json::<MediaResponse>()
+        .await
+        .unwrap();
+    assert_eq!(media.mime_type, "image/png");
+    let again = app
+        .upload_media(PNG.to_vec())
+        .await
+        .json::<MediaResponse>()
+        .await
+        .unwrap();
+    assert_eq!(again.id, media.id);
+
+    let response = app.upload_media(b"plain text".to_vec()).await;
+    assert_eq!(response.status().as_u16(), 400);
+    let response = app.upload_media(vec![0; 5 * 1024 * 1024 + 1]).await;
+    assert_eq!(response.status().as_u16(), 400);
+
+    let response = app.get_media_content(media.id).await;
+    assert_eq!(response.headers()["content-type"], "image/png");
+    assert_eq!(response.bytes().await.unwrap().as_ref(), PNG);
+
+    let tweet = app
+        .post_tweet(&serde_json::json!({
+            "content": "With a picture",
+            "media_ids": [media.id]
+        }))
+        .await
+        .unwrap();
+    assert_eq!(tweet.media, vec![media.clone()]);
+    let fetched = app.get_tweet_by_id(tweet.id).await.unwrap();
+    assert_eq!(fetched.media, vec![media]);
+}
+
+fn encode_image(width: u32, height: u32, format: ImageFormat) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    DynamicImage::ImageRgb8(RgbImage::new(width, height))
+        .write_to(&mut Cursor::new(&mut bytes), format)
+        .unwrap();
+    bytes
+}
+
+#[tokio::test]
+async fn uploaded_images_get_resized_variants() {
+    let app = spawn_app().await;
+    let users = User::default_vec_test(Some(2));
+    for user in &users {
+        let transaction = app.db_pool.begin().await.unwrap();
+        insert_user(transaction, user).await.unwrap();
+    }
+    app.login(&serde_json::json!({
+        "username": users[0].username,
+        "password": users[0].password_hash
+    }))
+    .await
+    .unwrap();
+
+    let png = encode_image(400, 200, ImageFormat::Png);
+    let media = app
+        .upload_media(png.clone())
+        .await
+        .json::<MediaResponse>()
+        .await
+        .unwrap();
+
+    // Variants are generated in the background after the upload returns.
+    let mut variants = Vec::new();
+    for _ in 0..50 {
+        variants = app.get_media(media.id).await.unwrap().variants;
+        if variants.len() == 2 {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+    let dimensions: Vec<_> = variants
+        .iter()
+        .map(|v| (v.name.as_str(), v.width, v.height))
+        .collect();
+    assert_eq!(
+        dimensions,
+        vec![("thumbnail", 150, 75), ("medium", 400, 200)]
+    );
+
+    let response = app.get_media_variant_content(media.id, "thumbnail").await;
+    assert_eq!(response.headers()["content-type"], "image/png");
+    let thumbnail = image::load_from_memory(&response.bytes().await.unwrap()).unwrap();
+    assert_eq!((thumbnail.width(), thumbnail.height()), (150, 75));
+
+    // Originals are only cached for good once their metadata is stripped
+    let response = app.get_media_content(media.id).await;
+    assert_eq!(
+        response.headers()["cache-control"],
+        "public, max-age=31536000, immutable"
+    );
+    let transaction = app.db_pool.begin().await.unwrap();
+    let unprocessed = insert_media(transaction, &Media::new(users[1].id, "image/png", &png))
+        .await
+        .unwrap();
+    let response = app.get_media_content(unprocessed.id).await;
+    assert_eq!(response.headers()["cache-control"], "no-store");
+}
+
+#[tokio::test]
+async fn processing_strips_exif_from_the_original() {
+    let app = spawn_app().await;
+    let transaction = app.db_pool.begin().await.unwrap();
+    let user = insert_user(transaction, &User::default_test())
+        .await
+        .unwrap();
+
+    let jpeg = encode_image(40, 20, ImageFormat::Jpeg);
+    let exif = [&[0xFF, 0xE1, 0x00, 0x08][..], b"Exif\0\0"].concat();
+    let jpeg_with_exif = [&jpeg[..2], &exif, &jpeg[2..]].concat();
+
+    let configuration = get_configuration().unwrap();
+    let store = LocalMediaStore::new(
+        std::env::temp_dir().join(format!("aloha-media-{}", uuid::Uuid::new_v4())),
+    );
+    let media = Media::new(user.id, "image/jpeg", &jpeg_with_exif);
+    store
+        .put(&media.storage_key, "image/jpeg", jpeg_with_exif.clone())
+        .await
+        .unwrap();
+    let transaction = app.db_pool.begin().await.unwrap();
+    let media = insert_media(transaction, &media).await.unwrap();
+
+    let variants = process_uploaded_media(&app.db_pool, &store, &configuration.media, &media)
+        .await
+        .unwrap();
+    assert_eq!(variants.len(), 2);
+    assert!(variants.iter().all(|v| v.mime_type == "image/jpeg"));
+    assert_eq!(
+        store.get(&media.storage_key).await.unwrap(),
+        Some(jpeg.clone())
+    );
+    assert_eq!(media.sha256, sha256_hex(&jpeg_with_exif));
+
+    // A later upload of the same original is found by the hash of the upload
+    // and shares the stripped object instead of storing the original again.
+    let transaction = app.db_pool.begin().await.unwrap();
+    let other = insert_user(
+        transaction,
+        &User::new("other".into(), "password".into(), None),
+    )
+    .await
+    .unwrap();
+    let again = Media::new(other.id, "image/jpeg", &jpeg_with_exif);
+    let transaction = app.db_pool.begin().await.unwrap();
+    assert!(media_content_exists(transaction, &again.sha256)
+        .await
+        .unwrap());
+    let transaction = app.db_pool.begin().await.unwrap();
+    let again = insert_media(transaction, &again).await.unwrap();
+    assert_ne!(again.id, media.id);
+    assert_eq!(again.storage_key, media.storage_key);
+    process_uploaded_media(&app.db_pool, &store, &configuration.media, &again)
+        .await
+        .unwrap();
+    assert_eq!(store.get(&again.storage_key).await.unwrap(), Some(jpeg));
+}
@@ -0,0 +1,39 @@
+use crate::helpers::spawn_app;
+use aloha_backend::mappers::user::insert_user;
+use aloha_backend::models::user::User;
+
+#[tokio::test]
+async fn posted_tweet_exposes_mentions() {
+    let app = spawn_app().await;
+    let mut users = Vec::new();
+    for user in User::default_vec_test(Some(2)) {
+        let transaction = app.db_pool.begin().await.unwrap();
+        users.push(insert_user(transaction, &user).await.unwrap());
+    }
+    app.login(&serde_json::json!({
+        "username": users[0].username,
+        "password": users[0].password_hash
+    }))
+    .await
+    .unwrap();
+
+    let tweet = app
+        .post_tweet(&serde_json::json!({
+            "content": format!("ping @{}", users[1].username)
+        }))
+        .await
+        .unwrap();
+    assert_eq!(tweet.mentions.len(), 1);
+    assert_eq!(tweet.mentions[0].username, users[1].username);
+    assert_eq!(tweet.mentions[0].start, 5);
+
+    let fetched = app.get_tweet_by_id(tweet.id).await.unwrap();
+    assert_eq!(fetched.mentions, tweet.mentions);
+
+    let response = app.get_user_mentions(users[1].id).await.unwrap();
+    assert_eq!(response.data.len(), 1);
+    assert_eq!(response.data[0].id, tweet.id);
+
+    let response = app.get_user_mentions(users[0].id).await.unwrap();
+    assert!(response.data.is_empty());
+}
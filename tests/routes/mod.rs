@@ -1,8 +1,30 @@
+pub mod account;
 pub mod auth;
+pub mod block;
+pub mod bookmark;
+pub mod content_rule;
+pub mod conversation;
+pub mod draft;
+pub mod gateway;
 pub mod group_permission;
+pub mod hashtag;
 pub mod health_check;
+pub mod media;
+pub mod mention;
+pub mod moderation;
+pub mod mute;
+pub mod notification;
 pub mod permission;
+pub mod poll;
+pub mod profile;
+pub mod retention;
+pub mod search;
+pub mod stream;
+pub mod timeline;
 pub mod tweet;
+pub mod tweet_revision;
 pub mod user;
 pub mod user_group;
+pub mod user_list;
 pub mod user_permission;
+pub mod visibility;
@@ -0,0 +1,153 @@
+use crate::helpers::spawn_app;
+use aloha_backend::dto::response::DtoResponse;
+use aloha_backend::mappers::user::insert_user;
+use aloha_backend::models::moderation::{ModerationActionKind, ModerationActionResponse};
+use aloha_backend::models::report::{ReportResponse, ReportStatus};
+use aloha_backend::models::user::User;
+use aloha_backend::routes::auth::{
+    HIDE_TWEETS_PERMISSION, MODERATE_REPORTS_PERMISSION, SUSPEND_USERS_PERMISSION,
+};
+use serde_json::json;
+use time::format_description::well_known::Rfc3339;
+use time::{Duration, OffsetDateTime};
+use uuid::Uuid;
+
+#[tokio::test]
+async fn moderators_work_through_the_report_queue() {
+    let app = spawn_app().await;
+    let users = User::default_vec_test(Some(3));
+    for user in &users {
+        let transaction = app.db_pool.begin().await.unwrap();
+        insert_user(transaction, user).await.unwrap();
+    }
+    let (author, reporter, moderator) = (&users[0], &users[1], &users[2]);
+    app.grant_permissions(
+        moderator.id,
+        &[
+            MODERATE_REPORTS_PERMISSION,
+            HIDE_TWEETS_PERMISSION,
+            SUSPEND_USERS_PERMISSION,
+        ],
+    )
+    .await;
+
+    app.login(&json!({"username": author.username, "password": author.password_hash}))
+        .await
+        .unwrap();
+    let tweet = app
+        .post_tweet(&json!({ "content": "Something nasty" }))
+        .await
+        .unwrap();
+
+    app.login(&json!({"username": reporter.username, "password": reporter.password_hash}))
+        .await
+        .unwrap();
+    let response = app
+        .post_report(&json!({ "tweet_id": tweet.id, "user_id": author.id, "reason": "both" }))
+        .await;
+    assert_eq!(response.status().as_u16(), 400);
+    let response = app
+        .post_report(&json!({ "user_id": reporter.id, "reason": "me" }))
+        .await;
+    assert_eq!(response.status().as_u16(), 400);
+    let response = app
+        .post_report(&json!({ "tweet_id": tweet.id, "reason": "  " }))
+        .await;
+    assert_eq!(response.status().as_u16(), 400);
+    let response = app
+        .post_report(&json!({ "tweet_id": tweet.id, "reason": "Harassment" }))
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+    let tweet_report = response.json::<ReportResponse>().await.unwrap();
+    assert_eq!(tweet_report.status, ReportStatus::Open);
+    let user_report = app
+        .post_report(&json!({ "user_id": author.id, "reason": "Keeps harassing" }))
+        .await
+        .json::<ReportResponse>()
+        .await
+        .unwrap();
+    let response = app
+        .post_report(&json!({ "tweet_id": Uuid::new_v4(), "reason": "Gone" }))
+        .await;
+    assert_eq!(response.status().as_u16(), 404);
+
+    // Regular users have no access to the queue or moderator actions
+    assert_eq!(app.get_reports(None).await.status().as_u16(), 403);
+    assert_eq!(
+        app.hide_tweet(tweet.id, &json!({})).await.status().as_u16(),
+        403
+    );
+
+    app.login(&json!({"username": moderator.username, "password": moderator.password_hash}))
+        .await
+        .unwrap();
+    let queue = app
+        .get_reports(None)
+        .await
+        .json::<DtoResponse<Vec<ReportResponse>>>()
+        .await
+        .unwrap();
+    assert_eq!(
+        queue.data.iter().map(|r| r.id).collect::<Vec<_>>(),
+        vec![tweet_report.id, user_report.id]
+    );
+
+    let response = app
+        .hide_tweet(tweet.id, &json!({ "report_id": tweet_report.id }))
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+    assert_eq!(
+        app.get_tweet_response(tweet.id).await.status().as_u16(),
+        404
+    );
+    let response = app
+        .put_report(tweet_report.id, &json!({ "status": "dismissed" }))
+        .await;
+    assert_eq!(response.status().as_u16(), 400);
+
+    let until = (OffsetDateTime::now_utc() + Duration::days(3))
+        .format(&Rfc3339)
+        .unwrap();
+    let response = app
+        .suspend_user(author.id, &json!({ "until": "2001-01-01T00:00:00Z" }))
+        .await;
+    assert_eq!(response.status().as_u16(), 400);
+    let response = app
+        .suspend_user(
+            author.id,
+            &json!({ "until": until, "report_id": user_report.id, "note": "Harassment" }),
+        )
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    let queue = app
+        .get_reports(Some(ReportStatus::Actioned))
+        .await
+        .json::<DtoResponse<Vec<ReportResponse>>>()
+        .await
+        .unwrap();
+    assert_eq!(queue.pagination.unwrap().total, Some(2));
+    assert!(queue
+        .data
+        .iter()
+        .all(|r| r.resolved_by == Some(moderator.id)));
+    let log = app
+        .get_moderation_actions()
+        .await
+        .json::<DtoResponse<Vec<ModerationActionResponse>>>()
+        .await
+        .unwrap();
+    assert_eq!(
+        log.data.iter().map(|a| a.action).collect::<Vec<_>>(),
+        vec![
+            ModerationActionKind::SuspendUser,
+            ModerationActionKind::HideTweet
+        ]
+    );
+
+    // Suspended users cannot log in
+    let response = app
+        .login_response(&json!({"username": author.username, "password": author.password_hash}))
+        .await;
+    assert_eq!(response.status().as_u16(), 403);
+}
@@ -0,0 +1,47 @@
+use crate::helpers::spawn_app;
+use aloha_backend::mappers::user::insert_user;
+use aloha_backend::models::mute::MuteResponse;
+use aloha_backend::models::user::User;
+use serde_json::json;
+
+#[tokio::test]
+async fn muting_hides_tweets_from_the_muter_feed() {
+    let app = spawn_app().await;
+    let users = User::default_vec_test(Some(2));
+    for user in &users {
+        let transaction = app.db_pool.begin().await.unwrap();
+        insert_user(transaction, user).await.unwrap();
+    }
+    let (author, muter) = (&users[0], &users[1]);
+
+    app.login(&json!({"username": author.username, "password": author.password_hash}))
+        .await
+        .unwrap();
+    let tweet = app
+        .post_tweet(&json!({ "content": "Very loud" }))
+        .await
+        .unwrap();
+
+    app.login(&json!({"username": muter.username, "password": muter.password_hash}))
+        .await
+        .unwrap();
+    let response = app.put_mute(author.id).await;
+    assert_eq!(response.status().as_u16(), 200);
+    let mute = response.json::<MuteResponse>().await.unwrap();
+    assert_eq!(mute.muted_id, author.id);
+
+    let mutes = app.get_mutes().await.unwrap();
+    assert_eq!(
+        mutes.data.iter().map(|u| u.id).collect::<Vec<_>>(),
+        vec![author.id]
+    );
+    assert!(app.get_all_tweets().await.unwrap().data.is_empty());
+    // Muted tweets can still be opened directly
+    assert_eq!(
+        app.get_tweet_response(tweet.id).await.status().as_u16(),
+        200
+    );
+
+    assert_eq!(app.delete_mute(author.id).await.status().as_u16(), 200);
+    assert_eq!(app.get_all_tweets().await.unwrap().data.len(), 1);
+}
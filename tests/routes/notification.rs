@@ -0,0 +1,76 @@
+use crate::helpers::spawn_app;
+use aloha_backend::dto::cursor::CursorQuery;
+use aloha_backend::mappers::tweet::insert_tweet;
+use aloha_backend::mappers::user::insert_user;
+use aloha_backend::models::notification::NotificationKind;
+use aloha_backend::models::tweet::Tweet;
+use aloha_backend::models::user::User;
+use uuid::Uuid;
+
+#[tokio::test]
+async fn get_notifications_returns_401_when_not_logged_in() {
+    let app = spawn_app().await;
+
+    let response = app
+        .api_client
+        .get(format!("{}/notifications", app.address))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    assert_eq!(response.status().as_u16(), 401);
+}
+
+#[tokio::test]
+async fn mentioned_user_reads_grouped_notifications() {
+    let app = spawn_app().await;
+    let users = User::default_vec_test(Some(4));
+    for user in &users {
+        let transaction = app.db_pool.begin().await.unwrap();
+        insert_user(transaction, user).await.unwrap();
+    }
+    let recipient = &users[0];
+    for author in &users[1..] {
+        let transaction = app.db_pool.begin().await.unwrap();
+        insert_tweet(
+            transaction,
+            &Tweet::new(format!("hey @{}", recipient.username), author.id),
+        )
+        .await
+        .unwrap();
+    }
+
+    app.login(&serde_json::json!({
+        "username": recipient.username,
+        "password": recipient.password_hash
+    }))
+    .await
+    .unwrap();
+
+    assert_eq!(app.get_unread_notification_count().await.unwrap().count, 1);
+    let response = app
+        .get_notifications(&CursorQuery::default_query())
+        .await
+        .unwrap();
+    assert_eq!(response.data.len(), 1);
+    let notification = &response.data[0];
+    assert_eq!(notification.kind, NotificationKind::Mention);
+    assert_eq!(notification.event_count, 3);
+    assert_eq!(
+        notification.message,
+        format!("{} and 2 others mentioned you", users[3].username)
+    );
+    assert!(!notification.read);
+
+    assert_eq!(
+        app.mark_notification_read(Uuid::new_v4())
+            .await
+            .status()
+            .as_u16(),
+        404
+    );
+    let response = app.mark_notification_read(notification.id).await;
+    assert_eq!(response.status().as_u16(), 200);
+    assert_eq!(app.get_unread_notification_count().await.unwrap().count, 0);
+    assert_eq!(app.mark_all_notifications_read().await.unwrap().count, 0);
+}
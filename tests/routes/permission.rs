@@ -8,6 +8,7 @@ use wiremock::{Mock, MockServer, ResponseTemplate};
 #[tokio::test]
 async fn insert_permission_returns_a_200_for_valid_form_data() {
     let app = spawn_app().await;
+    app.login_admin().await;
     let body = serde_json::json!({
         "name": "Default Permission",
         "description": "Default permission description"
@@ -71,6 +72,7 @@ async fn get_permission_returns_a_200_for_valid_id() {
 #[tokio::test]
 async fn update_permission_returns_a_200_for_valid_form_data() {
     let app = spawn_app().await;
+    app.login_admin().await;
     let transaction = app.db_pool.begin().await.unwrap();
     let default_permission = Permission::default_test();
     let insert_result = insert_permission(transaction, &default_permission)
@@ -106,6 +108,7 @@ async fn update_permission_returns_a_200_for_valid_form_data() {
 #[tokio::test]
 async fn delete_permission_returns_a_200_for_valid_id() {
     let app = spawn_app().await;
+    app.login_admin().await;
     let transaction = app.db_pool.begin().await.unwrap();
     let default_permission = Permission::default_test();
     let insert_result = insert_permission(transaction, &default_permission)
@@ -0,0 +1,91 @@
+use crate::helpers::spawn_app;
+use aloha_backend::mappers::user::insert_user;
+use aloha_backend::models::tweet::TweetResponse;
+use aloha_backend::models::user::User;
+use serde_json::json;
+use time::format_description::well_known::Rfc3339;
+use time::{Duration, OffsetDateTime};
+
+#[tokio::test]
+async fn poll_results_are_revealed_after_voting() {
+    let app = spawn_app().await;
+    let users = User::default_vec_test(Some(3));
+    for user in &users {
+        let transaction = app.db_pool.begin().await.unwrap();
+        insert_user(transaction, user).await.unwrap();
+    }
+    let (author, voter, other) = (&users[0], &users[1], &users[2]);
+    let closes_at = (OffsetDateTime::now_utc() + Duration::days(1))
+        .format(&Rfc3339)
+        .unwrap();
+
+    app.login(&json!({"username": author.username, "password": author.password_hash}))
+        .await
+        .unwrap();
+    let tweet = app
+        .post_tweet(&json!({
+            "content": "Lunch?",
+            "poll": { "options": ["Pizza", " Sushi ", "Salad"], "closes_at": closes_at }
+        }))
+        .await
+        .unwrap();
+    let poll = tweet.poll.unwrap();
+    assert_eq!(
+        poll.options
+            .iter()
+            .map(|o| o.label.as_str())
+            .collect::<Vec<_>>(),
+        vec!["Pizza", "Sushi", "Salad"]
+    );
+    assert_eq!(poll.total_votes, None);
+    assert!(poll.options.iter().all(|o| o.votes.is_none()));
+
+    // Invalid polls are rejected
+    let response = app
+        .post_tweet_response(&json!({
+            "content": "Only one choice",
+            "poll": { "options": ["Yes"], "closes_at": closes_at }
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 400);
+    let response = app
+        .post_tweet_response(&json!({
+            "content": "Already over",
+            "poll": { "options": ["Yes", "No"], "closes_at": "2001-01-01T00:00:00Z" }
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 400);
+
+    app.login(&json!({"username": voter.username, "password": voter.password_hash}))
+        .await
+        .unwrap();
+    let sushi = poll.options[1].id;
+    let response = app
+        .vote_poll(tweet.id, &json!({ "option_id": sushi }))
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+    let voted = response
+        .json::<TweetResponse>()
+        .await
+        .unwrap()
+        .poll
+        .unwrap();
+    assert_eq!(voted.voted_option_id, Some(sushi));
+    assert_eq!(voted.total_votes, Some(1));
+    assert_eq!(
+        voted.options.iter().map(|o| o.votes).collect::<Vec<_>>(),
+        vec![Some(0), Some(1), Some(0)]
+    );
+    let response = app
+        .vote_poll(tweet.id, &json!({ "option_id": sushi }))
+        .await;
+    assert_eq!(response.status().as_u16(), 400);
+
+    // Users who have not voted still see the results hidden
+    app.login(&json!({"username": other.username, "password": other.password_hash}))
+        .await
+        .unwrap();
+    let hidden = app.get_tweet_by_id(tweet.id).await.unwrap().poll.unwrap();
+    assert_eq!(hidden.total_votes, None);
+    assert_eq!(hidden.voted_option_id, None);
+}
@@ -0,0 +1,76 @@
+use crate::helpers::spawn_app;
+use aloha_backend::mappers::user::insert_user;
+use aloha_backend::models::media::MediaResponse;
+use aloha_backend::models::user::{User, UserResponse};
+use serde_json::json;
+
+const PNG: &[u8] = b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR";
+
+#[tokio::test]
+async fn users_edit_their_own_profile() {
+    let app = spawn_app().await;
+    let transaction = app.db_pool.begin().await.unwrap();
+    let user = insert_user(transaction, &User::default_test())
+        .await
+        .unwrap();
+    let profile = json!({ "display_name": "Tester", "bio": "Writes tests" });
+    assert_eq!(app.put_profile(&profile).await.status().as_u16(), 401);
+
+    app.login(&json!({"username": user.username, "password": user.password_hash}))
+        .await
+        .unwrap();
+    let avatar = app
+        .upload_media(PNG.to_vec())
+        .await
+        .json::<MediaResponse>()
+        .await
+        .unwrap();
+
+    for invalid in [
+        json!({ "display_name": "x".repeat(51) }),
+        json!({ "website": "not a url" }),
+        json!({ "website": "ftp://example.com" }),
+        json!({ "banner_media_id": uuid::Uuid::new_v4() }),
+    ] {
+        assert_eq!(app.put_profile(&invalid).await.status().as_u16(), 400);
+    }
+
+    let response = app
+        .put_profile(&json!({
+            "display_name": "  Tester  ",
+            "bio": "Writes tests",
+            "location": "",
+            "website": "https://example.com/tester",
+            "avatar_media_id": avatar.id
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+    let updated = response.json::<UserResponse>().await.unwrap();
+    assert_eq!(updated.display_name.as_deref(), Some("Tester"));
+    assert_eq!(updated.location, None);
+    assert_eq!(updated.avatar_media_id, Some(avatar.id));
+
+    let own = app
+        .get_profile()
+        .await
+        .json::<UserResponse>()
+        .await
+        .unwrap();
+    assert_eq!(own, updated);
+    let public = app.get_user_by_id(user.id).await.unwrap();
+    assert_eq!(public.bio.as_deref(), Some("Writes tests"));
+    assert_eq!(
+        public.website.as_deref(),
+        Some("https://example.com/tester")
+    );
+
+    // Replacing the profile clears fields left out
+    let cleared = app
+        .put_profile(&json!({ "display_name": "Tester" }))
+        .await
+        .json::<UserResponse>()
+        .await
+        .unwrap();
+    assert_eq!(cleared.bio, None);
+    assert_eq!(cleared.avatar_media_id, None);
+}
@@ -0,0 +1,70 @@
+use crate::helpers::spawn_app;
+use aloha_backend::mappers::permission::insert_permission;
+use aloha_backend::mappers::user::insert_user;
+use aloha_backend::mappers::user_permission::insert_user_permission;
+use aloha_backend::models::permission::Permission;
+use aloha_backend::models::user::User;
+use aloha_backend::models::user_permission::UserPermission;
+use aloha_backend::routes::auth::ADMIN_PERMISSION;
+use serde_json::json;
+
+#[tokio::test]
+async fn only_admins_can_restore_deleted_tweets_and_users() {
+    let app = spawn_app().await;
+    let users = User::default_vec_test(Some(2));
+    let transaction = app.db_pool.begin().await.unwrap();
+    let author = insert_user(transaction, &users[0]).await.unwrap();
+    let transaction = app.db_pool.begin().await.unwrap();
+    let admin = insert_user(transaction, &users[1]).await.unwrap();
+    let transaction = app.db_pool.begin().await.unwrap();
+    let permission = insert_permission(
+        transaction,
+        &Permission::new(ADMIN_PERMISSION.to_string(), None),
+    )
+    .await
+    .unwrap();
+    let transaction = app.db_pool.begin().await.unwrap();
+    insert_user_permission(transaction, &UserPermission::new(admin.id, permission.id))
+        .await
+        .unwrap();
+
+    let response = app.restore_user(author.id).await;
+    assert_eq!(response.status().as_u16(), 401);
+
+    app.login(&json!({"username": author.username, "password": author.password_hash}))
+        .await
+        .unwrap();
+    let tweet = app
+        .post_tweet(&json!({ "content": "Gone for now" }))
+        .await
+        .unwrap();
+    app.delete_tweet(tweet.id).await.unwrap();
+    assert_eq!(
+        app.get_all_tweets()
+            .await
+            .unwrap()
+            .pagination
+            .unwrap()
+            .total,
+        Some(0)
+    );
+    assert_eq!(app.restore_tweet(tweet.id).await.status().as_u16(), 403);
+
+    app.delete_user(author.id).await.unwrap();
+    app.login(&json!({"username": admin.username, "password": admin.password_hash}))
+        .await
+        .unwrap();
+    assert_eq!(app.restore_tweet(tweet.id).await.status().as_u16(), 404);
+
+    let response = app.restore_user(author.id).await;
+    assert_eq!(response.status().as_u16(), 200);
+    assert_eq!(app.get_user_by_id(author.id).await.unwrap().id, author.id);
+
+    let response = app.restore_tweet(tweet.id).await;
+    assert_eq!(response.status().as_u16(), 200);
+    assert_eq!(
+        app.get_tweet_by_id(tweet.id).await.unwrap().content,
+        "Gone for now"
+    );
+    assert_eq!(app.restore_tweet(tweet.id).await.status().as_u16(), 404);
+}
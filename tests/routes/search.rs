@@ -0,0 +1,65 @@
+use crate::helpers::spawn_app;
+use aloha_backend::dto::query::SearchQuery;
+use aloha_backend::dto::response::DtoResponse;
+use aloha_backend::mappers::user::insert_user;
+use aloha_backend::models::search::TweetSearchResponse;
+use aloha_backend::models::user::{User, UserResponse};
+
+#[tokio::test]
+async fn search_tweets_route_returns_ranked_hits() {
+    let app = spawn_app().await;
+    let transaction = app.db_pool.begin().await.unwrap();
+    let user = insert_user(transaction, &User::default_test())
+        .await
+        .unwrap();
+    app.login(&serde_json::json!({
+        "username": user.username,
+        "password": user.password_hash
+    }))
+    .await
+    .unwrap();
+
+    let tweet = app
+        .post_tweet(&serde_json::json!({ "content": "Searching with Postgres" }))
+        .await
+        .unwrap();
+    app.post_tweet(&serde_json::json!({ "content": "Something else" }))
+        .await
+        .unwrap();
+
+    let response = app
+        .search_tweets(&SearchQuery::new("postgre*"))
+        .await
+        .json::<DtoResponse<Vec<TweetSearchResponse>>>()
+        .await
+        .unwrap();
+    assert_eq!(response.data.len(), 1);
+    assert_eq!(response.data[0].tweet.id, tweet.id);
+    assert!(response.data[0].rank > 0.0);
+
+    let response = app
+        .search_tweets(&SearchQuery::new("since:2024-99-01"))
+        .await;
+    assert_eq!(response.status().as_u16(), 400);
+}
+
+#[tokio::test]
+async fn search_users_route_returns_paginated_users() {
+    let app = spawn_app().await;
+    for user in User::default_vec_test(Some(3)) {
+        let transaction = app.db_pool.begin().await.unwrap();
+        insert_user(transaction, &user).await.unwrap();
+    }
+
+    let response = app
+        .search_users(&SearchQuery::new("test_user_1"))
+        .await
+        .json::<DtoResponse<Vec<UserResponse>>>()
+        .await
+        .unwrap();
+    assert_eq!(response.data[0].username, "test_user_1");
+    assert_eq!(response.pagination.unwrap().total(), 3);
+
+    let response = app.search_users(&SearchQuery::new(" ")).await;
+    assert_eq!(response.status().as_u16(), 400);
+}
@@ -0,0 +1,101 @@
+use crate::helpers::spawn_app;
+use aloha_backend::mappers::subscription::insert_subscription;
+use aloha_backend::mappers::tweet::insert_tweet;
+use aloha_backend::mappers::user::insert_user;
+use aloha_backend::models::subscription::Subscription;
+use aloha_backend::models::tweet::Tweet;
+use aloha_backend::models::user::User;
+
+/// Reads the stream until every one of `needles` has shown up.
+async fn read_until(response: &mut reqwest::Response, needles: &[&str]) -> String {
+    let mut body = String::new();
+    while !needles.iter().all(|needle| body.contains(needle)) {
+        let chunk = response
+            .chunk()
+            .await
+            .expect("Failed to read stream")
+            .expect("Stream ended early");
+        body.push_str(&String::from_utf8_lossy(&chunk));
+    }
+    body
+}
+
+#[tokio::test]
+async fn stream_returns_401_when_not_logged_in() {
+    let app = spawn_app().await;
+
+    let response = app.open_stream(None).await;
+
+    assert_eq!(response.status().as_u16(), 401);
+}
+
+#[tokio::test]
+async fn stream_replays_events_after_last_event_id() {
+    let app = spawn_app().await;
+    let users = User::default_vec_test(Some(2));
+    for user in &users {
+        let transaction = app.db_pool.begin().await.unwrap();
+        insert_user(transaction, user).await.unwrap();
+    }
+    let (viewer, author) = (&users[0], &users[1]);
+    let transaction = app.db_pool.begin().await.unwrap();
+    insert_subscription(transaction, &Subscription::new(viewer.id, author.id))
+        .await
+        .unwrap();
+    let transaction = app.db_pool.begin().await.unwrap();
+    let tweet = insert_tweet(
+        transaction,
+        &Tweet::new(format!("hi @{}", viewer.username), author.id),
+    )
+    .await
+    .unwrap();
+
+    app.login(&serde_json::json!({
+        "username": viewer.username,
+        "password": viewer.password_hash
+    }))
+    .await
+    .unwrap();
+
+    assert_eq!(app.open_stream(Some("abc")).await.status().as_u16(), 400);
+
+    let mut response = app.open_stream(Some("0")).await;
+    assert_eq!(response.status().as_u16(), 200);
+    assert_eq!(
+        response.headers()["content-type"].to_str().unwrap(),
+        "text/event-stream"
+    );
+    let body = read_until(&mut response, &["event: tweet", "event: notification"]).await;
+    assert!(body.contains(&tweet.id.to_string()));
+    assert!(body.contains("mentioned you"));
+}
+
+#[tokio::test]
+async fn stream_pushes_new_notifications() {
+    let app = spawn_app().await;
+    let users = User::default_vec_test(Some(2));
+    for user in &users {
+        let transaction = app.db_pool.begin().await.unwrap();
+        insert_user(transaction, user).await.unwrap();
+    }
+    let (viewer, author) = (&users[0], &users[1]);
+    app.login(&serde_json::json!({
+        "username": viewer.username,
+        "password": viewer.password_hash
+    }))
+    .await
+    .unwrap();
+
+    let mut response = app.open_stream(None).await;
+    assert_eq!(response.status().as_u16(), 200);
+    let transaction = app.db_pool.begin().await.unwrap();
+    insert_tweet(
+        transaction,
+        &Tweet::new(format!("hey @{}", viewer.username), author.id),
+    )
+    .await
+    .unwrap();
+
+    let body = read_until(&mut response, &["event: notification"]).await;
+    assert!(body.contains(&format!("{} mentioned you", author.username)));
+}
@@ -0,0 +1,83 @@
+use crate::helpers::spawn_app;
+use aloha_backend::dto::cursor::CursorQuery;
+use aloha_backend::mappers::tweet::insert_tweet;
+use aloha_backend::mappers::user::insert_user;
+use aloha_backend::models::tweet::Tweet;
+use aloha_backend::models::user::User;
+
+#[tokio::test]
+async fn get_home_timeline_returns_401_when_not_logged_in() {
+    let app = spawn_app().await;
+
+    let response = app
+        .api_client
+        .get(format!("{}/timeline/home", app.address))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    assert_eq!(response.status().as_u16(), 401);
+}
+
+#[tokio::test]
+async fn get_home_timeline_returns_subscribed_tweets() {
+    let app = spawn_app().await;
+    let users = User::default_vec_test(Some(2));
+    for user in &users {
+        let transaction = app.db_pool.begin().await.unwrap();
+        insert_user(transaction, user).await.unwrap();
+    }
+    let (viewer, author) = (&users[0], &users[1]);
+    for tweet in Tweet::default_vec_test(Some(3), author.id) {
+        let transaction = app.db_pool.begin().await.unwrap();
+        insert_tweet(transaction, &tweet).await.unwrap();
+    }
+
+    app.login(&serde_json::json!({
+        "username": viewer.username,
+        "password": viewer.password_hash
+    }))
+    .await
+    .unwrap();
+
+    // Nothing to show before subscribing
+    let response = app
+        .get_home_timeline(&CursorQuery::default_query())
+        .await
+        .unwrap();
+    assert!(response.data.is_empty());
+
+    let subscription = app
+        .post_subscription(&serde_json::json!({ "target_id": author.id }))
+        .await
+        .unwrap();
+    assert_eq!(subscription.subscriber_id, viewer.id);
+    assert_eq!(app.get_subscriptions().await.unwrap().data.len(), 1);
+
+    let first_page = app
+        .get_home_timeline(&CursorQuery {
+            cursor: None,
+            size: Some(2),
+        })
+        .await
+        .unwrap();
+    assert_eq!(first_page.data.len(), 2);
+    assert!(first_page.data.iter().all(|t| t.user_id == author.id));
+
+    let second_page = app
+        .get_home_timeline(&CursorQuery {
+            cursor: first_page.next_cursor,
+            size: Some(2),
+        })
+        .await
+        .unwrap();
+    assert_eq!(second_page.data.len(), 1);
+    assert!(second_page.next_cursor.is_none());
+
+    app.delete_subscription(author.id).await.unwrap();
+    let response = app
+        .get_home_timeline(&CursorQuery::default_query())
+        .await
+        .unwrap();
+    assert!(response.data.is_empty());
+}
@@ -2,7 +2,7 @@ use crate::helpers::spawn_app;
 use aloha_backend::dto::query::{DtoQuery, TweetFilterQuery};
 use aloha_backend::mappers::tweet::{get_all_tweets, insert_tweet};
 use aloha_backend::mappers::user::insert_user;
-use aloha_backend::models::tweet::Tweet;
+use aloha_backend::models::tweet::{Tweet, TweetResponse};
 use aloha_backend::models::user::User;
 use aloha_backend::routes::auth::LoginFormData;
 use wiremock::matchers::{method, path};
@@ -72,7 +72,7 @@ async fn get_all_tweets_returns_a_200() {
     // Verify the tweets exist in the database before testing the route
     let transaction = app.db_pool.begin().await.unwrap();
     let query = DtoQuery::<TweetFilterQuery>::default_query();
-    let db_result = get_all_tweets(transaction, query).await.unwrap();
+    let db_result = get_all_tweets(transaction, query, None).await.unwrap();
     assert!(
         db_result.data.len() >= 3,
         "Failed to create test tweets in the database"
@@ -140,6 +140,12 @@ async fn update_tweet_returns_a_200_for_valid_form_data() {
     let tweet = Tweet::default_test(user_result.id);
     let insert_result = insert_tweet(transaction, &tweet).await.unwrap();
 
+    app.login(&serde_json::json!({
+        "username": user.username,
+        "password": user.password_hash
+    }))
+    .await
+    .unwrap();
     let body = serde_json::json!({
         "id": insert_result.id,
         "content": "Updated tweet content"
@@ -220,3 +226,129 @@ async fn delete_tweet_returns_a_200_for_valid_id() {
     assert_eq!(response.content, insert_result.content);
     assert_eq!(response.user_id, user_result.id);
 }
+
+#[tokio::test]
+async fn pinned_tweet_is_listed_first_on_the_author_profile() {
+    let app = spawn_app().await;
+    let users = User::default_vec_test(Some(2));
+    for user in &users {
+        let transaction = app.db_pool.begin().await.unwrap();
+        insert_user(transaction, user).await.unwrap();
+    }
+    let (author, other) = (&users[0], &users[1]);
+
+    app.login(&serde_json::json!({"username": author.username, "password": author.password_hash}))
+        .await
+        .unwrap();
+    let first = app
+        .post_tweet(&serde_json::json!({ "content": "Read this first" }))
+        .await
+        .unwrap();
+    let second = app
+        .post_tweet(&serde_json::json!({ "content": "Newer tweet" }))
+        .await
+        .unwrap();
+    assert_eq!(app.unpin_tweet(first.id).await.status().as_u16(), 404);
+
+    let response = app.pin_tweet(first.id).await;
+    assert_eq!(response.status().as_u16(), 200);
+    assert!(response.json::<TweetResponse>().await.unwrap().pinned);
+    let listed = app.get_user_tweets(author.id).await.unwrap();
+    assert_eq!(
+        listed
+            .data
+            .iter()
+            .map(|t| (t.id, t.pinned))
+            .collect::<Vec<_>>(),
+        vec![(first.id, true), (second.id, false)]
+    );
+
+    app.login(&serde_json::json!({"username": other.username, "password": other.password_hash}))
+        .await
+        .unwrap();
+    assert_eq!(app.pin_tweet(second.id).await.status().as_u16(), 404);
+    assert_eq!(app.unpin_tweet(first.id).await.status().as_u16(), 404);
+
+    app.login(&serde_json::json!({"username": author.username, "password": author.password_hash}))
+        .await
+        .unwrap();
+    assert_eq!(app.unpin_tweet(first.id).await.status().as_u16(), 200);
+    assert!(!app.get_tweet_by_id(first.id).await.unwrap().pinned);
+    assert_eq!(
+        app.get_user_tweets(author.id).await.unwrap().data[0].id,
+        second.id
+    );
+
+    // Deleting the pinned tweet unpins it
+    assert_eq!(app.pin_tweet(first.id).await.status().as_u16(), 200);
+    app.delete_tweet(first.id).await.unwrap();
+    assert_eq!(app.unpin_tweet(first.id).await.status().as_u16(), 404);
+}
+
+#[tokio::test]
+async fn tweets_embed_their_author_profile() {
+    let app = spawn_app().await;
+    let users = User::default_vec_test(Some(2));
+    for user in &users {
+        let transaction = app.db_pool.begin().await.unwrap();
+        insert_user(transaction, user).await.unwrap();
+        let transaction = app.db_pool.begin().await.unwrap();
+        insert_tweet(transaction, &Tweet::default_test(user.id))
+            .await
+            .unwrap();
+    }
+    app.login(&serde_json::json!({
+        "username": users[0].username,
+        "password": users[0].password_hash
+    }))
+    .await
+    .unwrap();
+    app.put_profile(&serde_json::json!({ "display_name": "First User" }))
+        .await;
+
+    let response = app.get_all_tweets().await.unwrap();
+    assert_eq!(response.data.len(), 2);
+    for tweet in response.data {
+        let author = tweet.author.expect("Tweets should embed their author");
+        assert_eq!(author.id, tweet.user_id);
+        let user = users.iter().find(|u| u.id == tweet.user_id).unwrap();
+        assert_eq!(author.username, user.username);
+        let display_name = (user.id == users[0].id).then(|| "First User".to_string());
+        assert_eq!(author.display_name, display_name);
+    }
+}
+
+#[tokio::test]
+async fn only_authors_edit_their_tweets() {
+    let app = spawn_app().await;
+    let users = User::default_vec_test(Some(2));
+    for user in &users {
+        let transaction = app.db_pool.begin().await.unwrap();
+        insert_user(transaction, user).await.unwrap();
+    }
+    let transaction = app.db_pool.begin().await.unwrap();
+    let tweet = insert_tweet(transaction, &Tweet::default_test(users[0].id))
+        .await
+        .unwrap();
+    let body = serde_json::json!({ "id": tweet.id, "content": "Not my words" });
+
+    let response = reqwest::Client::new()
+        .put(format!("{}/tweets/{}", app.address, tweet.id))
+        .json(&body)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status().as_u16(), 401);
+
+    app.login(&serde_json::json!({
+        "username": users[1].username,
+        "password": users[1].password_hash
+    }))
+    .await
+    .unwrap();
+    assert_eq!(app.put_tweet_response(&body).await.status().as_u16(), 403);
+
+    let response = app.get_tweet_by_id(tweet.id).await.unwrap();
+    assert_eq!(response.content, tweet.content);
+    assert!(!response.edited);
+}
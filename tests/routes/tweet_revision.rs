@@ -0,0 +1,107 @@
+use crate::helpers::spawn_app;
+use aloha_backend::mappers::user::insert_user;
+use aloha_backend::mappers::user_group::insert_user_group;
+use aloha_backend::models::user::User;
+use aloha_backend::models::user_group::UserGroup;
+use serde_json::json;
+
+#[tokio::test]
+async fn edited_tweets_expose_revisions_until_the_window_closes() {
+    let app = spawn_app().await;
+    let transaction = app.db_pool.begin().await.unwrap();
+    let user = insert_user(transaction, &User::default_test())
+        .await
+        .unwrap();
+    app.login(&serde_json::json!({
+        "username": user.username,
+        "password": user.password_hash
+    }))
+    .await
+    .unwrap();
+
+    let tweet = app
+        .post_tweet(&serde_json::json!({ "content": "Helo world" }))
+        .await
+        .unwrap();
+    assert!(!tweet.edited);
+
+    let updated = app
+        .put_tweet(&serde_json::json!({ "id": tweet.id, "content": "Hello world" }))
+        .await
+        .unwrap();
+    assert!(updated.edited);
+    assert_eq!(updated.content, "Hello world");
+
+    let revisions = app.get_tweet_revisions(tweet.id).await.unwrap();
+    assert_eq!(revisions.data.len(), 1);
+    assert_eq!(revisions.data[0].content, "Helo world");
+
+    sqlx::query!(
+        "UPDATE tweet SET created_at = now() - interval '31 minutes' WHERE id = $1",
+        tweet.id
+    )
+    .execute(&app.db_pool)
+    .await
+    .unwrap();
+    let response = app
+        .api_client
+        .put(format!("{}/tweets/{}", app.address, tweet.id))
+        .json(&serde_json::json!({ "id": tweet.id, "content": "Too late" }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status().as_u16(), 400);
+    assert_eq!(
+        app.get_tweet_by_id(tweet.id).await.unwrap().content,
+        "Hello world"
+    );
+}
+
+#[tokio::test]
+async fn revisions_are_only_readable_by_viewers_of_the_tweet() {
+    let app = spawn_app().await;
+    let groups = UserGroup::default_vec_test(Some(2));
+    for group in &groups {
+        let transaction = app.db_pool.begin().await.unwrap();
+        insert_user_group(transaction, group).await.unwrap();
+    }
+    let users = User::default_vec_test(Some(2));
+    let author = User {
+        user_group_id: Some(groups[0].id),
+        ..users[0].clone()
+    };
+    let outsider = User {
+        user_group_id: Some(groups[1].id),
+        ..users[1].clone()
+    };
+    for user in [&author, &outsider] {
+        let transaction = app.db_pool.begin().await.unwrap();
+        insert_user(transaction, user).await.unwrap();
+    }
+
+    app.login(&json!({"username": author.username, "password": author.password_hash}))
+        .await
+        .unwrap();
+    let tweet = app
+        .post_tweet(&json!({ "content": "Team plan: tbd", "visibility": "group" }))
+        .await
+        .unwrap();
+    app.put_tweet(&json!({ "id": tweet.id, "content": "Team plan: ship" }))
+        .await
+        .unwrap();
+    assert_eq!(
+        app.get_tweet_revisions(tweet.id).await.unwrap().data.len(),
+        1
+    );
+
+    let url = format!("{}/tweets/{}/revisions", app.address, tweet.id);
+    let anonymous = reqwest::Client::new();
+    let response = anonymous.get(&url).send().await.unwrap();
+    assert_eq!(response.status().as_u16(), 404);
+
+    app.login(&json!({"username": outsider.username, "password": outsider.password_hash}))
+        .await
+        .unwrap();
+    let response = app.api_client.get(&url).send().await.unwrap();
+    assert_eq!(response.status().as_u16(), 404);
+}
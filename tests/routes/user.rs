@@ -1,14 +1,20 @@
 use crate::helpers::spawn_app;
-use aloha_backend::mappers::user::insert_user;
+use aloha_backend::mappers::group_permission::insert_group_permission;
+use aloha_backend::mappers::permission::insert_permission;
+use aloha_backend::mappers::user::{get_user_by_id, insert_user};
 use aloha_backend::mappers::user_group::insert_user_group;
+use aloha_backend::models::group_permission::GroupPermission;
+use aloha_backend::models::permission::Permission;
 use aloha_backend::models::user::User;
 use aloha_backend::models::user_group::UserGroup;
+use aloha_backend::routes::auth::ADMIN_PERMISSION;
 use wiremock::matchers::{method, path};
 use wiremock::{Mock, MockServer, ResponseTemplate};
 
 #[tokio::test]
 async fn insert_user_returns_a_200_for_valid_form_data() {
     let app = spawn_app().await;
+    app.login_admin().await;
 
     // First create a user group
     let transaction = app.db_pool.begin().await.unwrap();
@@ -92,6 +98,7 @@ async fn get_user_returns_a_200_for_valid_id() {
 #[tokio::test]
 async fn update_user_returns_a_200_for_valid_form_data() {
     let app = spawn_app().await;
+    app.login_admin().await;
 
     // First create a user group
     let mut transaction = app.db_pool.begin().await.unwrap();
@@ -184,3 +191,62 @@ async fn delete_user_returns_a_200_for_valid_id() {
     let response = app.delete_user(insert_result.id).await.unwrap();
     assert_eq!(response.id, insert_result.id);
 }
+
+#[tokio::test]
+async fn only_admins_place_users_in_groups() {
+    let app = spawn_app().await;
+    let transaction = app.db_pool.begin().await.unwrap();
+    let admins = insert_user_group(transaction, &UserGroup::default_test())
+        .await
+        .unwrap();
+    let transaction = app.db_pool.begin().await.unwrap();
+    let admin = insert_permission(
+        transaction,
+        &Permission::new(ADMIN_PERMISSION.to_string(), None),
+    )
+    .await
+    .unwrap();
+    let transaction = app.db_pool.begin().await.unwrap();
+    insert_group_permission(transaction, &GroupPermission::new(admins.id, admin.id))
+        .await
+        .unwrap();
+    let transaction = app.db_pool.begin().await.unwrap();
+    let user = insert_user(transaction, &User::default_test())
+        .await
+        .unwrap();
+
+    let response = reqwest::Client::new()
+        .post(format!("{}/users", app.address))
+        .json(&serde_json::json!({
+            "username": "intruder",
+            "password": "password",
+            "user_group_id": admins.id
+        }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status().as_u16(), 401);
+
+    app.login(&serde_json::json!({
+        "username": user.username,
+        "password": user.password_hash
+    }))
+    .await
+    .unwrap();
+    let response = app
+        .api_client
+        .put(format!("{}/users", app.address))
+        .json(&serde_json::json!({
+            "id": user.id,
+            "username": user.username,
+            "user_group_id": admins.id
+        }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status().as_u16(), 403);
+
+    let transaction = app.db_pool.begin().await.unwrap();
+    let stored = get_user_by_id(transaction, user.id).await.unwrap().unwrap();
+    assert_eq!(stored.user_group_id, None);
+}
@@ -0,0 +1,132 @@
+use crate::helpers::spawn_app;
+use aloha_backend::dto::response::DtoResponse;
+use aloha_backend::mappers::user::insert_user;
+use aloha_backend::models::tweet::TweetResponse;
+use aloha_backend::models::user::{User, UserResponse};
+use aloha_backend::models::user_list::UserListResponse;
+use serde_json::json;
+
+#[tokio::test]
+async fn user_lists_crud_members_and_timeline() {
+    let app = spawn_app().await;
+    let users = User::default_vec_test(Some(3));
+    for user in &users {
+        let transaction = app.db_pool.begin().await.unwrap();
+        insert_user(transaction, user).await.unwrap();
+    }
+    let (owner, member, other) = (&users[0], &users[1], &users[2]);
+
+    app.login(&json!({"username": member.username, "password": member.password_hash}))
+        .await
+        .unwrap();
+    let tweet = app
+        .post_tweet(&json!({ "content": "From a list member" }))
+        .await
+        .unwrap();
+
+    app.login(&json!({"username": owner.username, "password": owner.password_hash}))
+        .await
+        .unwrap();
+    assert_eq!(
+        app.post_list(&json!({ "name": "   " }))
+            .await
+            .status()
+            .as_u16(),
+        400
+    );
+    let response = app.post_list(&json!({ "name": " Friends " })).await;
+    assert_eq!(response.status().as_u16(), 200);
+    let list = response.json::<UserListResponse>().await.unwrap();
+    assert_eq!(list.name, "Friends");
+    assert_eq!(list.owner_id, owner.id);
+
+    let response = app
+        .put_list(
+            list.id,
+            &json!({ "name": "Close friends", "description": "<3" }),
+        )
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+    let list = response.json::<UserListResponse>().await.unwrap();
+    assert_eq!(list.name, "Close friends");
+    assert_eq!(app.get_lists().await.unwrap().data, vec![list.clone()]);
+
+    assert_eq!(
+        app.put_list_member(list.id, member.id)
+            .await
+            .status()
+            .as_u16(),
+        200
+    );
+    assert_eq!(
+        app.put_list_member(list.id, uuid::Uuid::new_v4())
+            .await
+            .status()
+            .as_u16(),
+        400
+    );
+    let members = app
+        .get_list_members(list.id)
+        .await
+        .json::<DtoResponse<Vec<UserResponse>>>()
+        .await
+        .unwrap();
+    assert_eq!(
+        members.data.iter().map(|u| u.id).collect::<Vec<_>>(),
+        vec![member.id]
+    );
+    let timeline = app
+        .get_list_timeline(list.id)
+        .await
+        .json::<DtoResponse<Vec<TweetResponse>>>()
+        .await
+        .unwrap();
+    assert_eq!(
+        timeline.data.iter().map(|t| t.id).collect::<Vec<_>>(),
+        vec![tweet.id]
+    );
+
+    // Lists are invisible to everyone but their owner
+    app.login(&json!({"username": other.username, "password": other.password_hash}))
+        .await
+        .unwrap();
+    assert!(app.get_lists().await.unwrap().data.is_empty());
+    assert_eq!(app.get_list(list.id).await.status().as_u16(), 404);
+    assert_eq!(
+        app.put_list(list.id, &json!({ "name": "Mine now" }))
+            .await
+            .status()
+            .as_u16(),
+        404
+    );
+    assert_eq!(
+        app.put_list_member(list.id, other.id)
+            .await
+            .status()
+            .as_u16(),
+        404
+    );
+    assert_eq!(
+        app.delete_list_member(list.id, member.id)
+            .await
+            .status()
+            .as_u16(),
+        404
+    );
+    assert_eq!(app.get_list_members(list.id).await.status().as_u16(), 404);
+    assert_eq!(app.get_list_timeline(list.id).await.status().as_u16(), 404);
+    assert_eq!(app.delete_list(list.id).await.status().as_u16(), 404);
+
+    app.login(&json!({"username": owner.username, "password": owner.password_hash}))
+        .await
+        .unwrap();
+    assert_eq!(
+        app.delete_list_member(list.id, member.id)
+            .await
+            .status()
+            .as_u16(),
+        200
+    );
+    assert_eq!(app.delete_list(list.id).await.status().as_u16(), 200);
+    assert_eq!(app.get_list(list.id).await.status().as_u16(), 404);
+}
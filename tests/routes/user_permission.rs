@@ -1,16 +1,20 @@
 use crate::helpers::spawn_app;
 use aloha_backend::mappers::permission::insert_permission;
 use aloha_backend::mappers::user::insert_user;
-use aloha_backend::mappers::user_permission::insert_user_permission;
+use aloha_backend::mappers::user_permission::{
+    get_user_permissions_by_user_id, insert_user_permission,
+};
 use aloha_backend::models::permission::Permission;
 use aloha_backend::models::user::User;
 use aloha_backend::models::user_permission::UserPermission;
+use aloha_backend::routes::auth::ADMIN_PERMISSION;
 use wiremock::matchers::{method, path};
 use wiremock::{Mock, MockServer, ResponseTemplate};
 
 #[tokio::test]
 async fn insert_user_permission_returns_a_200_for_valid_form_data() {
     let app = spawn_app().await;
+    app.login_admin().await;
     let mut transaction = app.db_pool.begin().await.unwrap();
 
     // Create a user and permission first
@@ -138,6 +142,7 @@ async fn get_user_permissions_by_permission_id_returns_a_200() {
 #[tokio::test]
 async fn delete_user_permission_returns_a_200() {
     let app = spawn_app().await;
+    app.login_admin().await;
     let mut transaction = app.db_pool.begin().await.unwrap();
 
     // Create a user and permission first
@@ -174,6 +179,7 @@ async fn delete_user_permission_returns_a_200() {
 #[tokio::test]
 async fn delete_user_permissions_by_user_id_returns_a_200() {
     let app = spawn_app().await;
+    app.login_admin().await;
     let mut transaction = app.db_pool.begin().await.unwrap();
 
     // Create a user and permission first
@@ -208,6 +214,7 @@ async fn delete_user_permissions_by_user_id_returns_a_200() {
 #[tokio::test]
 async fn delete_user_permissions_by_permission_id_returns_a_200() {
     let app = spawn_app().await;
+    app.login_admin().await;
     let mut transaction = app.db_pool.begin().await.unwrap();
 
     // Create a user and permission first
@@ -240,3 +247,43 @@ async fn delete_user_permissions_by_permission_id_returns_a_200() {
     assert!(!response.data.is_empty());
     assert_eq!(response.data[0].permission_id, permission.id);
 }
+
+#[tokio::test]
+async fn only_admins_grant_permissions() {
+    let app = spawn_app().await;
+    let transaction = app.db_pool.begin().await.unwrap();
+    let user = User::default_test();
+    insert_user(transaction, &user).await.unwrap();
+    let transaction = app.db_pool.begin().await.unwrap();
+    let admin = insert_permission(
+        transaction,
+        &Permission::new(ADMIN_PERMISSION.to_string(), None),
+    )
+    .await
+    .unwrap();
+    let body = serde_json::json!({ "user_id": user.id, "permission_id": admin.id });
+    let grant = |client: &reqwest::Client| {
+        client
+            .post(format!("{}/user_permissions", app.address))
+            .json(&body)
+            .send()
+    };
+
+    let response = grant(&reqwest::Client::new()).await.unwrap();
+    assert_eq!(response.status().as_u16(), 401);
+
+    app.login(&serde_json::json!({
+        "username": user.username,
+        "password": user.password_hash
+    }))
+    .await
+    .unwrap();
+    let response = grant(&app.api_client).await.unwrap();
+    assert_eq!(response.status().as_u16(), 403);
+
+    let transaction = app.db_pool.begin().await.unwrap();
+    assert!(get_user_permissions_by_user_id(transaction, user.id)
+        .await
+        .unwrap()
+        .is_empty());
+}
@@ -0,0 +1,119 @@
+use crate::helpers::spawn_app;
+use aloha_backend::dto::response::DtoResponse;
+use aloha_backend::mappers::user::insert_user;
+use aloha_backend::mappers::user_group::insert_user_group;
+use aloha_backend::models::tweet::{TweetResponse, TweetVisibility};
+use aloha_backend::models::user::User;
+use aloha_backend::models::user_group::UserGroup;
+use serde_json::json;
+
+#[tokio::test]
+async fn tweets_are_only_readable_by_allowed_viewers() {
+    let app = spawn_app().await;
+    let groups = UserGroup::default_vec_test(Some(2));
+    for group in &groups {
+        let transaction = app.db_pool.begin().await.unwrap();
+        insert_user_group(transaction, group).await.unwrap();
+    }
+    let users = User::default_vec_test(Some(3));
+    let author = User {
+        user_group_id: Some(groups[0].id),
+        ..users[0].clone()
+    };
+    let teammate = User {
+        user_group_id: Some(groups[0].id),
+        ..users[1].clone()
+    };
+    let outsider = User {
+        user_group_id: Some(groups[1].id),
+        ..users[2].clone()
+    };
+    for user in [&author, &teammate, &outsider] {
+        let transaction = app.db_pool.begin().await.unwrap();
+        insert_user(transaction, user).await.unwrap();
+    }
+
+    app.login(&json!({"username": author.username, "password": author.password_hash}))
+        .await
+        .unwrap();
+    let public = app
+        .post_tweet(&json!({ "content": "Hello world" }))
+        .await
+        .unwrap();
+    assert_eq!(public.visibility, TweetVisibility::Public);
+    let group = app
+        .post_tweet(&json!({ "content": "Hello team", "visibility": "group" }))
+        .await
+        .unwrap();
+    assert_eq!(group.visibility, TweetVisibility::Group);
+    let private = app
+        .post_tweet(&json!({ "content": "Note to self", "visibility": "private" }))
+        .await
+        .unwrap();
+    assert_eq!(private.visibility, TweetVisibility::Private);
+    assert_eq!(app.get_all_tweets().await.unwrap().data.len(), 3);
+    assert_eq!(
+        app.get_tweet_response(private.id).await.status().as_u16(),
+        200
+    );
+
+    // Anonymous viewers, without a session cookie, only see public tweets
+    let anonymous = reqwest::Client::new();
+    let all = anonymous
+        .get(format!("{}/tweets", app.address))
+        .send()
+        .await
+        .unwrap()
+        .json::<DtoResponse<Vec<TweetResponse>>>()
+        .await
+        .unwrap();
+    assert_eq!(
+        all.data.iter().map(|t| t.id).collect::<Vec<_>>(),
+        vec![public.id]
+    );
+    assert_eq!(all.pagination.unwrap().total, Some(1));
+    for (id, status) in [(public.id, 200), (group.id, 404), (private.id, 404)] {
+        let response = anonymous
+            .get(format!("{}/tweets/{}", app.address, id))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status().as_u16(), status);
+    }
+
+    // Members of the author's group also see group tweets
+    app.login(&json!({"username": teammate.username, "password": teammate.password_hash}))
+        .await
+        .unwrap();
+    let all = app.get_all_tweets().await.unwrap();
+    assert_eq!(
+        all.data.iter().map(|t| t.id).collect::<Vec<_>>(),
+        vec![group.id, public.id]
+    );
+    assert_eq!(
+        app.get_tweet_response(group.id).await.status().as_u16(),
+        200
+    );
+    assert_eq!(
+        app.get_tweet_response(private.id).await.status().as_u16(),
+        404
+    );
+
+    // Members of another group are treated like anonymous viewers
+    app.login(&json!({"username": outsider.username, "password": outsider.password_hash}))
+        .await
+        .unwrap();
+    let all = app.get_all_tweets().await.unwrap();
+    assert_eq!(
+        all.data.iter().map(|t| t.id).collect::<Vec<_>>(),
+        vec![public.id]
+    );
+    assert_eq!(
+        app.get_tweet_response(group.id).await.status().as_u16(),
+        404
+    );
+    assert_eq!(
+        app.get_tweet_response(private.id).await.status().as_u16(),
+        404
+    );
+}
@@ -0,0 +1,75 @@
+use aloha_backend::configuration::S3Settings;
+use aloha_backend::storage::{LocalMediaStore, MediaStore, S3MediaStore};
+use uuid::Uuid;
+use wiremock::matchers::{header_exists, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+#[tokio::test]
+async fn local_media_store_round_trips_files() {
+    let root = std::env::temp_dir().join(format!("aloha-media-{}", Uuid::new_v4()));
+    let store = LocalMediaStore::new(&root);
+
+    assert_eq!(store.get("abc123").await.unwrap(), None);
+    store
+        .put("abc123", "image/png", b"content".to_vec())
+        .await
+        .unwrap();
+    assert_eq!(
+        store.get("abc123").await.unwrap(),
+        Some(b"content".to_vec())
+    );
+    store.delete("abc123").await.unwrap();
+    assert_eq!(store.get("abc123").await.unwrap(), None);
+
+    assert!(store.get("../etc/passwd").await.is_err());
+    std::fs::remove_dir_all(root).unwrap();
+}
+
+#[tokio::test]
+async fn s3_media_store_signs_requests_to_an_s3_compatible_server() {
+    let server = MockServer::start().await;
+    Mock::given(method("PUT"))
+        .and(path("/media/abc123"))
+        .and(header_exists("authorization"))
+        .and(header_exists("x-amz-date"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/media/abc123"))
+        .respond_with(ResponseTemplate::new(200).set_body_bytes(b"content".to_vec()))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/media/missing"))
+        .respond_with(ResponseTemplate::new(404))
+        .mount(&server)
+        .await;
+    Mock::given(method("DELETE"))
+        .and(path("/media/abc123"))
+        .respond_with(ResponseTemplate::new(204))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let store = S3MediaStore::new(S3Settings {
+        endpoint: server.uri(),
+        bucket: String::from("media"),
+        region: String::from("us-east-1"),
+        access_key_id: String::from("access"),
+        secret_access_key: String::from("secret").into(),
+    })
+    .unwrap();
+
+    store
+        .put("abc123", "image/png", b"content".to_vec())
+        .await
+        .unwrap();
+    assert_eq!(
+        store.get("abc123").await.unwrap(),
+        Some(b"content".to_vec())
+    );
+    assert_eq!(store.get("missing").await.unwrap(), None);
+    store.delete("abc123").await.unwrap();
+}